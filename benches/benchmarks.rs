@@ -1,6 +1,6 @@
 use std::fs::read;
 
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
 use levenshtein_diff as levenshtein;
 
 pub fn distance(c: &mut Criterion) {
@@ -22,6 +22,23 @@ pub fn distance(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new("memoization", len), &input, |b, i| {
             b.iter(|| levenshtein::levenshtein_memoization(i.0, i.1))
         });
+        group.bench_with_input(BenchmarkId::new("two_rows", len), &input, |b, i| {
+            b.iter(|| levenshtein::levenshtein_two_rows(i.0, i.1))
+        });
+        group.bench_with_input(BenchmarkId::new("bit_parallel", len), &input, |b, i| {
+            let pattern = levenshtein::Pattern::new(i.0);
+            b.iter(|| pattern.distance_to(i.1))
+        });
+        group.bench_with_input(BenchmarkId::new("within_10", len), &input, |b, i| {
+            b.iter(|| levenshtein::is_within_distance(i.0, i.1, 10))
+        });
+        group.bench_with_input(BenchmarkId::new("banded_10", len), &input, |b, i| {
+            b.iter(|| levenshtein::levenshtein_banded(i.0, i.1, 10))
+        });
+        #[cfg(feature = "simd")]
+        group.bench_with_input(BenchmarkId::new("simd", len), &input, |b, i| {
+            b.iter(|| levenshtein::levenshtein_simd(i.0, i.1))
+        });
     }
     group.finish();
 }
@@ -57,9 +74,46 @@ pub fn apply_edits(c: &mut Criterion) {
         group.bench_with_input(len.to_string(), &input, |b, i| {
             b.iter(|| levenshtein::apply_edits(i.0, i.1))
         });
+        group.bench_with_input(BenchmarkId::new("in_place", len), &input, |b, i| {
+            b.iter_batched(
+                || i.0.to_vec(),
+                |mut v| levenshtein::apply_edits_in_place(&mut v, i.1),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    // An insert before every item, which is where applying edits used to be quadratic
+    for len in [1_000, 10_000, 100_000] {
+        let s1 = vec![0u8; len];
+        let edits: Vec<_> = (0..len)
+            .rev()
+            .map(|i| levenshtein::Edit::Insert(i, 1))
+            .collect();
+        let input = (s1.as_slice(), edits.as_slice());
+        group.bench_with_input(BenchmarkId::new("inserts", len), &input, |b, i| {
+            b.iter(|| levenshtein::apply_edits(i.0, i.1))
+        });
+    }
+    group.finish();
+}
+
+pub fn prefilters(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prefilters");
+    let bytes = read("benches/data/atoz-2k.bin").unwrap();
+    assert_eq!(bytes.len(), 2_000);
+
+    for len in [10, 100, 1000] {
+        let input = (&bytes[0..len], &bytes[len..2 * len]);
+        group.bench_with_input(BenchmarkId::new("length", len), &input, |b, i| {
+            b.iter(|| levenshtein::util::length_lower_bound(i.0, i.1))
+        });
+        group.bench_with_input(BenchmarkId::new("histogram", len), &input, |b, i| {
+            b.iter(|| levenshtein::util::histogram_lower_bound(i.0, i.1))
+        });
     }
     group.finish();
 }
 
-criterion_group!(benches, distance, generate_edits, apply_edits);
+criterion_group!(benches, distance, generate_edits, apply_edits, prefilters);
 criterion_main!(benches);
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::batch::pairwise_distances;
+use crate::edit::generate_edits;
+use crate::replace::coalesce_edits;
+
+// How a sequence lines up with the center: the item it has in place of every center item (None
+// if it was deleted), and the items it inserts in every gap between center items
+struct Pairwise<T> {
+    aligned: Vec<Option<T>>,
+    inserted: Vec<Vec<T>>,
+}
+
+fn align_to_center<T: Clone + PartialEq>(center: &[T], sequence: &[T]) -> Pairwise<T> {
+    let (_, matrix) = crate::distance(center, sequence);
+    let edits =
+        generate_edits(center, sequence, &matrix).expect("the matrix was computed for these");
+
+    let mut aligned: Vec<Option<T>> = center.iter().cloned().map(Some).collect();
+    let mut inserted = vec![Vec::new(); center.len() + 1];
+
+    // Within a replacement, items are substituted for as long as both sides last, and the rest
+    // of the new items are inserted after it, like `replacements_to_edits` does
+    for replacement in coalesce_edits(&edits) {
+        let start = replacement.range.start;
+
+        for idx in replacement.range.clone() {
+            aligned[idx] = replacement.with.get(idx - start).cloned();
+        }
+
+        if replacement.with.len() > replacement.range.len() {
+            inserted[replacement.range.end]
+                .extend_from_slice(&replacement.with[replacement.range.len()..]);
+        }
+    }
+
+    Pairwise { aligned, inserted }
+}
+
+/// Aligns several sequences with each other, and returns them as rows of equal length in which
+/// aligned items share a column and `None` marks a gap.
+///
+/// This uses the center star method: the sequence with the smallest total distance to all the
+/// others is chosen as the center, every other sequence is aligned to it with `generate_edits`,
+/// and the pairwise alignments are combined by opening the gaps each of them needs in all the
+/// rows. The result is a good approximation of the optimal alignment, with a sum of pairwise
+/// costs at most twice as large. The rows are in the same order as the sequences.
+///
+/// # Arguments
+///
+/// * `sequences` - The sequences to align
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let readings = ["SUNDAY".as_bytes(), "SUNDAE".as_bytes(), "SNDAY".as_bytes()];
+///
+/// let rows = levenshtein::multiple_alignment(&readings);
+///
+/// let as_text = |row: &Vec<Option<u8>>| -> String {
+///     row.iter().map(|item| item.map_or('-', char::from)).collect()
+/// };
+///
+/// assert_eq!(as_text(&rows[0]), "SUNDAY");
+/// assert_eq!(as_text(&rows[1]), "SUNDAE");
+/// assert_eq!(as_text(&rows[2]), "S-NDAY");
+/// ```
+pub fn multiple_alignment<T: Clone + Eq + Hash>(sequences: &[&[T]]) -> Vec<Vec<Option<T>>> {
+    if sequences.is_empty() {
+        return Vec::new();
+    }
+
+    let distances = pairwise_distances(sequences);
+    let center_idx = (0..sequences.len())
+        .min_by_key(|&i| distances[i].iter().sum::<usize>())
+        .unwrap();
+    let center = sequences[center_idx];
+
+    let pairwise: Vec<Pairwise<T>> = sequences
+        .iter()
+        .map(|sequence| align_to_center(center, sequence))
+        .collect();
+
+    // Every gap of the center gets as many columns as the longest insertion into it
+    let gap_widths: Vec<usize> = (0..center.len() + 1)
+        .map(|gap| {
+            pairwise
+                .iter()
+                .map(|alignment| alignment.inserted[gap].len())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    pairwise
+        .into_iter()
+        .map(|mut alignment| {
+            let mut row = Vec::new();
+
+            for (gap, &width) in gap_widths.iter().enumerate() {
+                let inserted = std::mem::take(&mut alignment.inserted[gap]);
+                let padding = width - inserted.len();
+
+                row.extend(inserted.into_iter().map(Some));
+                row.extend(std::iter::repeat_with(|| None).take(padding));
+
+                if gap < center.len() {
+                    row.push(alignment.aligned[gap].take());
+                }
+            }
+
+            row
+        })
+        .collect()
+}
+
+/// The consensus of several sequences, as returned by `consensus`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Consensus<T> {
+    /// The items most of the sequences agree on
+    pub sequence: Vec<T>,
+    /// For every item of the consensus, the fraction of the sequences that have it, from 0.0 to
+    /// 1.0
+    pub confidence: Vec<f64>,
+}
+
+/// Returns the consensus of an alignment: the item most rows have in every column, along with
+/// how many of the rows have it.
+///
+/// Gaps take part in the vote, and columns where a gap wins are left out of the consensus. Ties
+/// go to whichever option appears in the earliest row.
+///
+/// # Arguments
+///
+/// * `rows` - The aligned rows, as returned by `multiple_alignment`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let rows = vec![
+///     vec![Some(b'A'), Some(b'B'), None],
+///     vec![Some(b'A'), Some(b'C'), None],
+///     vec![Some(b'A'), Some(b'B'), Some(b'D')],
+/// ];
+///
+/// let consensus = levenshtein::consensus_of_alignment(&rows);
+///
+/// assert_eq!(consensus.sequence, "AB".as_bytes());
+/// assert_eq!(consensus.confidence, vec![1.0, 2.0 / 3.0]);
+/// ```
+pub fn consensus_of_alignment<T: Clone + Eq + Hash>(rows: &[Vec<Option<T>>]) -> Consensus<T> {
+    let columns = rows.first().map_or(0, |row| row.len());
+
+    let mut sequence = Vec::new();
+    let mut confidence = Vec::new();
+
+    for column in 0..columns {
+        // The number of votes for every option, and the first row that voted for it
+        let mut votes = HashMap::<Option<&T>, (usize, usize)>::new();
+
+        for (row_idx, row) in rows.iter().enumerate() {
+            votes.entry(row[column].as_ref()).or_insert((0, row_idx)).0 += 1;
+        }
+
+        let (winner, (count, _)) = votes
+            .into_iter()
+            .max_by(|(_, (a, a_row)), (_, (b, b_row))| a.cmp(b).then(b_row.cmp(a_row)))
+            .unwrap();
+
+        if let Some(item) = winner {
+            sequence.push(item.clone());
+            confidence.push(count as f64 / rows.len() as f64);
+        }
+    }
+
+    Consensus {
+        sequence,
+        confidence,
+    }
+}
+
+/// Returns the consensus of several similar sequences, e.g. noisy copies of the same record.
+///
+/// The sequences are aligned with `multiple_alignment`, and every column is then decided by a
+/// majority vote. See `consensus_of_alignment`.
+///
+/// # Arguments
+///
+/// * `sequences` - The sequences to take the consensus of
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let readings = ["SUNDAY".as_bytes(), "SUNDAE".as_bytes(), "SNDAY".as_bytes()];
+///
+/// let consensus = levenshtein::consensus(&readings);
+///
+/// assert_eq!(consensus.sequence, "SUNDAY".as_bytes());
+/// assert_eq!(consensus.confidence[1], 2.0 / 3.0); // One reading is missing the U
+/// ```
+pub fn consensus<T: Clone + Eq + Hash>(sequences: &[&[T]]) -> Consensus<T> {
+    consensus_of_alignment(&multiple_alignment(sequences))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alignment::*;
+
+    #[test]
+    fn multiple_alignment_test() {
+        let sequences = [
+            "ABC".as_bytes(),
+            "XABC".as_bytes(),
+            "ABYC".as_bytes(),
+            "AC".as_bytes(),
+        ];
+
+        let rows = multiple_alignment(&sequences);
+
+        assert_eq!(
+            rows,
+            vec![
+                vec![None, Some(b'A'), Some(b'B'), None, Some(b'C')],
+                vec![Some(b'X'), Some(b'A'), Some(b'B'), None, Some(b'C')],
+                vec![None, Some(b'A'), Some(b'B'), Some(b'Y'), Some(b'C')],
+                vec![None, Some(b'A'), None, None, Some(b'C')],
+            ]
+        );
+
+        assert!(multiple_alignment::<u8>(&[]).is_empty());
+    }
+
+    #[test]
+    fn consensus_test() {
+        let sequences = [
+            "ABC".as_bytes(),
+            "XABC".as_bytes(),
+            "ABYC".as_bytes(),
+            "AC".as_bytes(),
+        ];
+
+        let consensus = consensus(&sequences);
+
+        assert_eq!(consensus.sequence, "ABC".as_bytes());
+        assert_eq!(consensus.confidence, vec![1.0, 0.75, 1.0]);
+
+        // The tie between X and a gap goes to X, since the first row has it
+        let consensus = consensus_of_alignment(&[vec![Some(b'X')], vec![None]]);
+        assert_eq!(consensus.sequence, vec![b'X']);
+    }
+}
@@ -0,0 +1,165 @@
+use alloc::vec::Vec;
+use core::cmp::min;
+
+/// A Levenshtein automaton: it accepts the sequences within distance `k` of a query, and can be
+/// fed one item at a time.
+///
+/// A state is the last row of the distance matrix between the query and the items fed so far,
+/// with every distance over `k` stored as `k + 1`, so stepping a state costs O(m) for a query of
+/// m items. What makes the automaton useful is `can_match`, which says as soon as no sequence
+/// starting with the items fed so far can be accepted: walking a dictionary stored as a trie or
+/// an FST along with the automaton skips every branch that can't lead to a match, which is how
+/// fuzzy search over large dictionaries is done. See `Trie::fuzzy_search`.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::LevenshteinAutomaton;
+///
+/// let automaton = LevenshteinAutomaton::new("SATURDAY".as_bytes(), 3);
+///
+/// let mut state = automaton.start();
+/// for item in "SUNDAY".as_bytes() {
+///     state = automaton.step(&state, item);
+/// }
+/// assert_eq!(automaton.distance(&state), Some(3));
+///
+/// // Nothing that starts with XYZW is within 3 of SATURDAY
+/// let state = "XYZW".bytes().fold(automaton.start(), |s, item| automaton.step(&s, &item));
+/// assert!(!automaton.can_match(&state));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LevenshteinAutomaton<'a, T> {
+    query: &'a [T],
+    k: usize,
+}
+
+/// A state of a `LevenshteinAutomaton`, after it has been fed some items.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AutomatonState {
+    // The distances between every prefix of the query and the items fed so far, capped at k + 1
+    row: Vec<usize>,
+}
+
+impl<'a, T: PartialEq> LevenshteinAutomaton<'a, T> {
+    /// Returns an automaton that accepts the sequences within distance `k` of the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query sequence
+    /// * `k` - The maximum distance
+    pub fn new(query: &'a [T], k: usize) -> Self {
+        LevenshteinAutomaton { query, k }
+    }
+
+    /// Returns the query sequence.
+    pub fn query(&self) -> &'a [T] {
+        self.query
+    }
+
+    /// Returns the maximum distance.
+    pub fn max_distance(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the state before any items have been fed.
+    pub fn start(&self) -> AutomatonState {
+        let cap = self.k.saturating_add(1);
+
+        AutomatonState {
+            row: (0..self.query.len() + 1).map(|i| min(i, cap)).collect(),
+        }
+    }
+
+    /// Returns the state after feeding another item.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state after the items fed so far
+    /// * `item` - The next item
+    pub fn step(&self, state: &AutomatonState, item: &T) -> AutomatonState {
+        let cap = self.k.saturating_add(1);
+        let mut row = Vec::with_capacity(state.row.len());
+        row.push(min(state.row[0].saturating_add(1), cap));
+
+        for i in 1..state.row.len() {
+            let substitute = if self.query[i - 1] == *item {
+                state.row[i - 1]
+            } else {
+                state.row[i - 1].saturating_add(1)
+            };
+
+            let cell = min(min(state.row[i], row[i - 1]).saturating_add(1), substitute);
+            row.push(min(cell, cap));
+        }
+
+        AutomatonState { row }
+    }
+
+    /// Returns the distance between the query and the items fed so far if it's at most `k`, and
+    /// `None` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state after the items
+    pub fn distance(&self, state: &AutomatonState) -> Option<usize> {
+        state.row.last().copied().filter(|&d| d <= self.k)
+    }
+
+    /// Returns true if some sequence that starts with the items fed so far is within distance
+    /// `k` of the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state after the items
+    pub fn can_match(&self, state: &AutomatonState) -> bool {
+        state.row.iter().any(|&d| d <= self.k)
+    }
+
+    /// Returns the distance between the query and target if it's at most `k`, and `None`
+    /// otherwise, giving up as soon as no continuation of the target could be accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target sequence
+    pub fn matches(&self, target: &[T]) -> Option<usize> {
+        let mut state = self.start();
+
+        for item in target {
+            if !self.can_match(&state) {
+                return None;
+            }
+
+            state = self.step(&state, item);
+        }
+
+        self.distance(&state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::automaton::*;
+
+    #[test]
+    fn levenshtein_automaton_test() {
+        let automaton = LevenshteinAutomaton::new("kitten".as_bytes(), 2);
+
+        assert_eq!(automaton.matches("kitten".as_bytes()), Some(0));
+        assert_eq!(automaton.matches("mitten".as_bytes()), Some(1));
+        assert_eq!(automaton.matches("sitting".as_bytes()), None);
+        assert_eq!(automaton.matches("kit".as_bytes()), None);
+        assert_eq!(automaton.matches("kitt".as_bytes()), Some(2));
+
+        // A prefix that is too far off can't be completed to a match
+        let state = "xyz"
+            .bytes()
+            .fold(automaton.start(), |s, c| automaton.step(&s, &c));
+        assert!(!automaton.can_match(&state));
+
+        // Every distance is within an unbounded automaton
+        let automaton = LevenshteinAutomaton::new("kitten".as_bytes(), usize::MAX);
+        assert_eq!(automaton.matches("sitting".as_bytes()), Some(3));
+    }
+}
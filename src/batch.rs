@@ -0,0 +1,320 @@
+#[cfg(feature = "simd")]
+use std::cmp::max;
+use std::hash::Hash;
+
+use crate::pattern::Pattern;
+
+/// Returns the Levenshtein distances between source and each of the targets.
+///
+/// Work that only depends on the source (element histograms, and the bit-vectors used by the
+/// bit-parallel algorithm) is done once and shared by all the targets, which makes this
+/// much faster than calling `distance` in a loop.
+///
+/// If a cutoff is supplied, targets whose distance exceeds it are reported as `None`. Cheap lower
+/// bounds are used to discard such targets without computing their distance.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `targets` - The target sequences
+/// * `cutoff` - An optional maximum distance
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let query = "SATURDAY".as_bytes();
+/// let words = ["SUNDAY".as_bytes(), "SATURN".as_bytes(), "MONDAY".as_bytes()];
+///
+/// let distances = levenshtein::distances_one_to_many(query, &words, Some(3));
+/// assert_eq!(distances, vec![Some(3), Some(3), None]);
+/// ```
+pub fn distances_one_to_many<T: Eq + Hash>(
+    source: &[T],
+    targets: &[&[T]],
+    cutoff: Option<usize>,
+) -> Vec<Option<usize>> {
+    let pattern = Pattern::new(source);
+
+    targets
+        .iter()
+        .map(|target| match cutoff {
+            Some(k) => pattern.distance_within(target, k),
+            None => Some(pattern.distance_to(target)),
+        })
+        .collect()
+}
+
+/// Returns the Levenshtein distances between a byte string and each of the target byte strings,
+/// scoring many targets at once with SIMD instructions.
+///
+/// The targets are taken in groups, one target per SIMD lane, and the table of every target in a
+/// group is filled at the same time: the rows follow the source, and every cell holds the
+/// distances of all the targets in the group. This is the fastest way to score one query against
+/// many short strings, e.g. for autocompletion, as long as the targets are of similar lengths,
+/// since every group takes as long as its longest target. This requires a nightly compiler and
+/// the `simd` feature.
+///
+/// If a cutoff is supplied, targets whose distance exceeds it are reported as `None`, as with
+/// `distances_one_to_many`.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `targets` - The target sequences
+/// * `cutoff` - An optional maximum distance
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let query = "SATURDAY".as_bytes();
+/// let words = ["SUNDAY".as_bytes(), "SATURN".as_bytes(), "MONDAY".as_bytes()];
+///
+/// let distances = levenshtein::simd_distances_one_to_many(query, &words, Some(3));
+/// assert_eq!(distances, vec![Some(3), Some(3), None]);
+/// ```
+#[cfg(feature = "simd")]
+pub fn simd_distances_one_to_many(
+    source: &[u8],
+    targets: &[&[u8]],
+    cutoff: Option<usize>,
+) -> Vec<Option<usize>> {
+    use std::simd::cmp::{SimdOrd, SimdPartialEq};
+    use std::simd::{Select, Simd};
+
+    use crate::distance::SIMD_LANES;
+
+    type Cells = Simd<u32, SIMD_LANES>;
+    type Items = Simd<u8, SIMD_LANES>;
+
+    let one = Cells::splat(1);
+    let mut distances = Vec::with_capacity(targets.len());
+
+    for group in targets.chunks(SIMD_LANES) {
+        let longest = group.iter().map(|target| target.len()).max().unwrap_or(0);
+
+        assert!(
+            max(source.len(), longest) <= u32::MAX as usize,
+            "the sequences are too long for simd_distances_one_to_many"
+        );
+
+        // The items of every target at every position, one target per lane. Lanes past the end
+        // of a target are never read back, so what they hold doesn't matter.
+        let columns: Vec<Items> = (0..longest)
+            .map(|j| {
+                Items::from_array(std::array::from_fn(|lane| {
+                    group
+                        .get(lane)
+                        .and_then(|target| target.get(j))
+                        .copied()
+                        .unwrap_or(0)
+                }))
+            })
+            .collect();
+
+        let mut prev: Vec<Cells> = (0..longest + 1).map(|j| Cells::splat(j as u32)).collect();
+        let mut curr = vec![Cells::splat(0); longest + 1];
+
+        for (i, &item) in source.iter().enumerate() {
+            let item = Items::splat(item);
+            curr[0] = Cells::splat(i as u32 + 1);
+
+            for j in 1..longest + 1 {
+                let same = columns[j - 1].simd_eq(item);
+                let substitute = same.cast::<i32>().select(prev[j - 1], prev[j - 1] + one);
+
+                curr[j] = (prev[j].simd_min(curr[j - 1]) + one).simd_min(substitute);
+            }
+
+            std::mem::swap(&mut prev, &mut curr);
+        }
+
+        // Every target's distance is in the column of its length
+        distances.extend(group.iter().enumerate().map(|(lane, target)| {
+            let distance = prev[target.len()][lane] as usize;
+
+            match cutoff {
+                Some(k) if distance > k => None,
+                _ => Some(distance),
+            }
+        }));
+    }
+
+    distances
+}
+
+/// Returns the matrix of Levenshtein distances between every pair of items.
+///
+/// The distance is symmetric, so each pair is only computed once, and the preprocessing of every
+/// item is shared by all its comparisons (see `Pattern`). The diagonal is always 0.
+///
+/// # Arguments
+///
+/// * `items` - The sequences to compare
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let words = ["FLAW".as_bytes(), "LAWN".as_bytes(), "LAW".as_bytes()];
+///
+/// let distances = levenshtein::pairwise_distances(&words);
+/// assert_eq!(distances, vec![vec![0, 2, 1], vec![2, 0, 1], vec![1, 1, 0]]);
+/// ```
+pub fn pairwise_distances<T: Eq + Hash>(items: &[&[T]]) -> Vec<Vec<usize>> {
+    let upper = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| upper_triangle_row(items, i, item))
+        .collect();
+
+    mirror_upper_triangle(upper)
+}
+
+/// Returns the matrix of Levenshtein distances between every pair of items, computing the rows in
+/// parallel.
+///
+/// This is identical to `pairwise_distances`, but spreads the work over the rayon thread pool.
+///
+/// # Arguments
+///
+/// * `items` - The sequences to compare
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let words = ["FLAW".as_bytes(), "LAWN".as_bytes(), "LAW".as_bytes()];
+///
+/// let distances = levenshtein::par_pairwise_distances(&words);
+/// assert_eq!(distances, vec![vec![0, 2, 1], vec![2, 0, 1], vec![1, 1, 0]]);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_pairwise_distances<T: Eq + Hash + Sync>(items: &[&[T]]) -> Vec<Vec<usize>> {
+    use rayon::prelude::*;
+
+    let upper = items
+        .par_iter()
+        .enumerate()
+        .map(|(i, item)| upper_triangle_row(items, i, item))
+        .collect();
+
+    mirror_upper_triangle(upper)
+}
+
+// Returns the distances from items[i] to every item after it
+fn upper_triangle_row<T: Eq + Hash>(items: &[&[T]], i: usize, item: &[T]) -> Vec<usize> {
+    let pattern = Pattern::new(item);
+
+    items[i + 1..]
+        .iter()
+        .map(|target| pattern.distance_to(target))
+        .collect()
+}
+
+// Builds the full symmetric matrix out of the rows of its upper triangle
+fn mirror_upper_triangle(upper: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+    let n = upper.len();
+    let mut distances = vec![vec![0; n]; n];
+
+    for (i, row) in upper.iter().enumerate() {
+        for (offset, distance) in row.iter().enumerate() {
+            let j = i + 1 + offset;
+            distances[i][j] = *distance;
+            distances[j][i] = *distance;
+        }
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::*;
+
+    #[test]
+    fn distances_one_to_many_test() {
+        let s1 = "LAWN".as_bytes();
+        let targets = ["FFLAWANN".as_bytes(), "FLAW".as_bytes(), "".as_bytes()];
+
+        assert_eq!(
+            distances_one_to_many(s1, &targets, None),
+            vec![Some(4), Some(2), Some(4)]
+        );
+        assert_eq!(
+            distances_one_to_many(s1, &targets, Some(2)),
+            vec![None, Some(2), None]
+        );
+    }
+
+    #[test]
+    fn distances_one_to_many_long_source_test() {
+        // Longer than a machine word, so the bit-parallel path works on several blocks
+        let s1 = "LAWN".repeat(20);
+        let s2 = format!("F{}", &s1[..s1.len() - 1]);
+        let targets = [s2.as_bytes(), s1.as_bytes()];
+
+        assert_eq!(
+            distances_one_to_many(s1.as_bytes(), &targets, None),
+            vec![Some(2), Some(0)]
+        );
+        assert_eq!(
+            distances_one_to_many(s1.as_bytes(), &targets, Some(1)),
+            vec![None, Some(0)]
+        );
+    }
+
+    #[test]
+    fn pairwise_distances_test() {
+        let items = ["LAWN".as_bytes(), "FFLAWANN".as_bytes(), "FLAW".as_bytes()];
+
+        let expected = vec![vec![0, 4, 2], vec![4, 0, 4], vec![2, 4, 0]];
+
+        assert_eq!(pairwise_distances(&items), expected);
+        assert!(pairwise_distances::<u8>(&[]).is_empty());
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn simd_distances_one_to_many_test() {
+        let s1 = "LAWN".as_bytes();
+
+        // More targets than lanes, so there's a full group and a partial one
+        let words = [
+            "FFLAWANN", "FLAW", "", "LAWN", "LAWNS", "DAWN", "LA", "AWNING",
+        ];
+        let targets: Vec<&[u8]> = words
+            .iter()
+            .cycle()
+            .take(20)
+            .map(|w| w.as_bytes())
+            .collect();
+
+        assert_eq!(
+            simd_distances_one_to_many(s1, &targets, None),
+            distances_one_to_many(s1, &targets, None)
+        );
+        assert_eq!(
+            simd_distances_one_to_many(s1, &targets, Some(2)),
+            distances_one_to_many(s1, &targets, Some(2))
+        );
+        assert_eq!(
+            simd_distances_one_to_many(&[], &targets[..3], None),
+            vec![Some(8), Some(4), Some(0)]
+        );
+        assert!(simd_distances_one_to_many(s1, &[], None).is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_pairwise_distances_test() {
+        let items = ["LAWN".as_bytes(), "FFLAWANN".as_bytes(), "FLAW".as_bytes()];
+
+        assert_eq!(par_pairwise_distances(&items), pairwise_distances(&items));
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::hash::Hash;
+
+use crate::pattern::Pattern;
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    item: Vec<T>,
+    // The children, by their distance to this node's item. All the items under a child are at
+    // that distance from this node's item.
+    children: BTreeMap<usize, usize>,
+}
+
+/// A BK-tree of sequences, for finding the ones within a distance of a query.
+///
+/// The tree relies on the Levenshtein distance being a metric: by the triangle inequality, the
+/// items within distance k of the query can only be under the children of a node whose distance
+/// to it is within k of the query's, so only those are visited. Unlike `TrigramIndex`, this
+/// works as well for short sequences and large distances, but every node visited costs a
+/// distance computation.
+///
+/// Sequences that are already in the tree are not added again.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::BkTree;
+///
+/// let words = ["SATURDAY", "SUNDAY", "MONDAY", "SATURN"];
+/// let tree = BkTree::new(words.iter().map(|word| word.as_bytes().to_vec()));
+///
+/// // MONDAY is 3 away
+/// let found = tree.search("SUNDAE".as_bytes(), 2);
+/// assert_eq!(found, vec![("SUNDAY".as_bytes(), 1)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BkTree<T> {
+    // The root is the first node, if there is one
+    nodes: Vec<Node<T>>,
+}
+
+impl<T: Eq + Hash> BkTree<T> {
+    /// Returns a tree of the sequences.
+    ///
+    /// # Arguments
+    ///
+    /// * `corpus` - The sequences to add to the tree
+    pub fn new<I: IntoIterator<Item = Vec<T>>>(corpus: I) -> Self {
+        let mut tree = BkTree { nodes: Vec::new() };
+
+        for item in corpus {
+            tree.insert(item);
+        }
+
+        tree
+    }
+
+    /// Adds a sequence to the tree. Returns true if it was added, and false if it was already
+    /// there.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The sequence to add
+    pub fn insert(&mut self, item: Vec<T>) -> bool {
+        let new_node = self.nodes.len();
+
+        if new_node == 0 {
+            self.nodes.push(Node {
+                item,
+                children: BTreeMap::new(),
+            });
+            return true;
+        }
+
+        let pattern = Pattern::new(&item);
+        let mut current = 0;
+
+        // Follow the children at the new item's distance until there's none
+        let distance = loop {
+            let distance = pattern.distance_to(&self.nodes[current].item);
+
+            if distance == 0 {
+                return false;
+            }
+
+            match self.nodes[current].children.get(&distance) {
+                Some(&child) => current = child,
+                None => break distance,
+            }
+        };
+
+        self.nodes[current].children.insert(distance, new_node);
+        self.nodes.push(Node {
+            item,
+            children: BTreeMap::new(),
+        });
+
+        true
+    }
+
+    /// Returns the number of sequences in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns true if there are no sequences in the tree.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns true if the sequence is in the tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The sequence to look for
+    pub fn contains(&self, item: &[T]) -> bool {
+        !self.search(item, 0).is_empty()
+    }
+
+    /// Returns every sequence within distance `k` of the query, with its distance, closest
+    /// first.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The sequence to look for
+    /// * `k` - The maximum distance
+    pub fn search(&self, query: &[T], k: usize) -> Vec<(&[T], usize)> {
+        let mut found = Vec::new();
+
+        if self.nodes.is_empty() {
+            return found;
+        }
+
+        let pattern = Pattern::new(query);
+        let mut pending = vec![0];
+
+        while let Some(current) = pending.pop() {
+            let node = &self.nodes[current];
+            let distance = pattern.distance_to(&node.item);
+
+            if distance <= k {
+                found.push((node.item.as_slice(), distance));
+            }
+
+            let nearest = distance.saturating_sub(k)..=distance.saturating_add(k);
+            pending.extend(node.children.range(nearest).map(|(_, &child)| child));
+        }
+
+        found.sort_by_key(|&(_, distance)| distance);
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bk_tree::*;
+
+    #[test]
+    fn bk_tree_test() {
+        let words = ["kitten", "sitting", "mitten", "kit", "", "smitten"];
+        let mut tree = BkTree::new(words.iter().map(|word| word.as_bytes().to_vec()));
+        assert_eq!(tree.len(), 6);
+
+        assert!(!tree.insert("mitten".as_bytes().to_vec()));
+        assert!(tree.contains("smitten".as_bytes()));
+        assert!(!tree.contains("mittens".as_bytes()));
+
+        let found = tree.search("kitten".as_bytes(), 2);
+        assert_eq!(
+            found,
+            vec![
+                ("kitten".as_bytes(), 0),
+                ("mitten".as_bytes(), 1),
+                ("smitten".as_bytes(), 2)
+            ]
+        );
+
+        assert_eq!(tree.search("".as_bytes(), 3).len(), 2);
+        assert_eq!(tree.search("kitten".as_bytes(), usize::MAX).len(), 6);
+        assert!(BkTree::<u8>::new(Vec::new()).search(&[], 10).is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+use alloc::vec::Vec;
+use core::cmp::{min, Ordering};
+use core::ops::Range;
+
+use crate::edit::{apply_edits, generate_edits, Edit};
+
+// Regions between exact matches longer than this are inserted as they are, since running the
+// quadratic Levenshtein algorithm on them would take too long
+const MAX_FUZZY_LEN: usize = 1024;
+
+/// An operation of a binary delta, as returned by `binary_delta`. Each operation appends to the
+/// target, in order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DeltaOp {
+    /// Append `source[from..from + len]` as it is.
+    Copy { from: usize, len: usize },
+    /// Append `source[from..from + len]` with the edits applied to it. The edits are relative to
+    /// that region, as if it were the whole source.
+    Edit {
+        from: usize,
+        len: usize,
+        edits: Vec<Edit<u8>>,
+    },
+    /// Append new bytes.
+    Insert(Vec<u8>),
+}
+
+// Returns the suffix array of the bytes: the start of every suffix, in lexicographic order. This
+// uses prefix doubling, which takes O(n log^2 n) time.
+fn suffix_array(bytes: &[u8]) -> Vec<usize> {
+    let n = bytes.len();
+    let mut suffixes: Vec<usize> = (0..n).collect();
+    let mut rank: Vec<usize> = bytes.iter().map(|&b| b as usize).collect();
+    let mut next_rank = vec![0; n];
+    let mut k = 1;
+
+    while k < n {
+        // Suffixes shorter than k sort before all others with the same first k bytes
+        let key = |i: usize| (rank[i], rank.get(i + k).map_or(0, |r| r + 1));
+
+        suffixes.sort_by_key(|&i| key(i));
+
+        next_rank[suffixes[0]] = 0;
+        for w in 1..n {
+            let bump = (key(suffixes[w - 1]) != key(suffixes[w])) as usize;
+            next_rank[suffixes[w]] = next_rank[suffixes[w - 1]] + bump;
+        }
+
+        core::mem::swap(&mut rank, &mut next_rank);
+
+        if rank[suffixes[n - 1]] == n - 1 {
+            break;
+        }
+
+        k *= 2;
+    }
+
+    suffixes
+}
+
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+// Returns the start and length of the longest match of a prefix of `needle` in the source
+fn longest_match(source: &[u8], suffixes: &[usize], needle: &[u8]) -> (usize, usize) {
+    // The suffixes around the place where the needle would be sorted share its longest prefix
+    let at = suffixes.partition_point(|&i| source[i..].cmp(needle) == Ordering::Less);
+
+    [at.checked_sub(1), Some(at)]
+        .iter()
+        .flatten()
+        .filter_map(|&w| suffixes.get(w))
+        .map(|&i| (i, common_prefix_len(&source[i..], needle)))
+        .max_by_key(|&(_, len)| len)
+        .unwrap_or((0, 0))
+}
+
+// Returns the cheapest operation that appends the gap, using the source region that most likely
+// corresponds to it
+fn fuzzy_op(source: &[u8], region: Range<usize>, gap: &[u8]) -> DeltaOp {
+    let region_bytes = &source[region.clone()];
+
+    if !region_bytes.is_empty() && region_bytes.len() <= MAX_FUZZY_LEN && gap.len() <= MAX_FUZZY_LEN
+    {
+        let (distance, matrix) = crate::distance(region_bytes, gap);
+
+        // Each edit costs about as much to store as an inserted byte
+        if distance < gap.len() {
+            return DeltaOp::Edit {
+                from: region.start,
+                len: region.len(),
+                edits: generate_edits(region_bytes, gap, &matrix).unwrap(),
+            };
+        }
+    }
+
+    DeltaOp::Insert(gap.to_vec())
+}
+
+/// Returns a binary delta that transforms the source into the target, in the style of bsdiff.
+///
+/// Long exact matches are found with a suffix array of the source and copied. The regions of the
+/// target between them usually correspond to slightly changed regions of the source (think of an
+/// executable where only a few addresses differ), so these are expressed as Levenshtein edits of
+/// the source region that follows the previous match, falling back to inserting the bytes.
+///
+/// # Arguments
+///
+/// * `source` - The source bytes
+/// * `target` - The target bytes
+/// * `min_match` - The minimum length of an exact match to copy
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::DeltaOp;
+///
+/// let source = "the quick brown fox jumps over the lazy dog".as_bytes();
+/// let target = "the quick brown cat jumps over the lazy dog!".as_bytes();
+///
+/// let delta = levenshtein::binary_delta(source, target, 8);
+///
+/// assert!(matches!(delta[0], DeltaOp::Copy { from: 0, len: 16 }));
+/// assert_eq!(levenshtein::apply_binary_delta(source, &delta), target);
+/// ```
+pub fn binary_delta(source: &[u8], target: &[u8], min_match: usize) -> Vec<DeltaOp> {
+    let suffixes = suffix_array(source);
+    let min_match = core::cmp::max(min_match, 1);
+
+    let mut ops = Vec::new();
+
+    // Where the previous match ended, in the source and in the target
+    let mut source_end = 0;
+    let mut gap_start = 0;
+    let mut pos = 0;
+
+    while pos < target.len() {
+        let (from, len) = longest_match(source, &suffixes, &target[pos..]);
+
+        if len < min_match {
+            pos += 1;
+            continue;
+        }
+
+        if gap_start < pos {
+            ops.push(gap_op(
+                source,
+                source_end,
+                Some(from),
+                &target[gap_start..pos],
+            ));
+        }
+
+        ops.push(DeltaOp::Copy { from, len });
+
+        source_end = from + len;
+        pos += len;
+        gap_start = pos;
+    }
+
+    if gap_start < target.len() {
+        ops.push(gap_op(source, source_end, None, &target[gap_start..]));
+    }
+
+    ops
+}
+
+// Returns the operation for a gap between the match ending at `source_end` and the match
+// starting at `next_match`, if there is one. The gap is compared to the source between the two
+// matches if the next one is nearby, and otherwise to a region as long as the gap.
+pub(crate) fn gap_op(
+    source: &[u8],
+    source_end: usize,
+    next_match: Option<usize>,
+    gap: &[u8],
+) -> DeltaOp {
+    let region_end = match next_match {
+        Some(from) if from >= source_end && from - source_end <= 2 * gap.len() => from,
+        _ => min(source_end + gap.len(), source.len()),
+    };
+
+    fuzzy_op(source, source_end..region_end, gap)
+}
+
+/// Applies a binary delta to the source, and returns the target.
+///
+/// # Arguments
+///
+/// * `source` - The source bytes
+/// * `delta` - The delta, as returned by `binary_delta`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::DeltaOp;
+///
+/// let delta = vec![
+///     DeltaOp::Copy { from: 4, len: 4 },
+///     DeltaOp::Insert(" ".as_bytes().to_vec()),
+///     DeltaOp::Copy { from: 0, len: 3 },
+/// ];
+///
+/// let target = levenshtein::apply_binary_delta("SUN DAY!".as_bytes(), &delta);
+/// assert_eq!(target, "DAY! SUN".as_bytes());
+/// ```
+pub fn apply_binary_delta(source: &[u8], delta: &[DeltaOp]) -> Vec<u8> {
+    let mut target = Vec::with_capacity(source.len());
+
+    for op in delta {
+        match op {
+            DeltaOp::Copy { from, len } => target.extend_from_slice(&source[*from..from + len]),
+            DeltaOp::Edit { from, len, edits } => {
+                target.extend(apply_edits(&source[*from..from + len], edits))
+            }
+            DeltaOp::Insert(bytes) => target.extend_from_slice(bytes),
+        }
+    }
+
+    target
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bsdiff::*;
+
+    #[test]
+    fn suffix_array_test() {
+        let bytes = "banana".as_bytes();
+
+        // a, ana, anana, banana, na, nana
+        assert_eq!(suffix_array(bytes), vec![5, 3, 1, 0, 4, 2]);
+        assert!(suffix_array(&[]).is_empty());
+        assert_eq!(suffix_array(&[7, 7, 7]), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn binary_delta_test() {
+        // A header, and a table of little endian offsets that all changed by one
+        let mut source = b"HEADER\x00\x01".to_vec();
+        let mut target = source.clone();
+
+        for i in 0..64u32 {
+            source.extend_from_slice(&(1000 + i * 8).to_le_bytes());
+            target.extend_from_slice(&(1001 + i * 8).to_le_bytes());
+        }
+
+        source.extend_from_slice(b"TRAILER");
+        target.extend_from_slice(b"TRAILER");
+
+        let delta = binary_delta(&source, &target, 4);
+
+        assert!(matches!(delta[0], DeltaOp::Copy { from: 0, len: 8 }));
+        assert!(matches!(delta[1], DeltaOp::Edit { .. }));
+        assert_eq!(apply_binary_delta(&source, &delta), target);
+    }
+}
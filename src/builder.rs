@@ -0,0 +1,167 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::edit::Edit;
+use crate::patch::PatchError;
+use crate::replace::{replacements_to_edits, Replace};
+
+/// Builds a sequence of edits by hand, from changes to ranges of the source.
+///
+/// Ranges and positions are 0-indexed and half-open, like slice ranges, so there's no need to
+/// deal with the conventions of `Edit` directly. Every change is checked against the length of
+/// the source and the changes made so far, and `build` turns them into edits in the same form
+/// `generate_edits` returns. Changes can be made in any order.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::PatchBuilder;
+///
+/// let source = "SATURDAY".as_bytes();
+///
+/// let mut builder = PatchBuilder::new(source.len());
+/// builder
+///     .replace(4..5, "N".as_bytes().to_vec())
+///     .unwrap()
+///     .delete(1..3)
+///     .unwrap();
+///
+/// let edits = builder.build();
+/// assert_eq!(levenshtein::apply_edits(source, &edits), "SUNDAY".as_bytes());
+///
+/// // The A was already deleted
+/// assert!(builder.delete(1..2).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatchBuilder<T> {
+    source_len: usize,
+    changes: Vec<Replace<T>>,
+}
+
+impl<T: Clone + PartialEq> PatchBuilder<T> {
+    /// Returns a builder with no changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_len` - The length of the source the edits will apply to
+    pub fn new(source_len: usize) -> Self {
+        PatchBuilder {
+            source_len,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Inserts items before the item at `at`, or at the end if `at` is the length of the source.
+    ///
+    /// Items inserted at the same position end up in the order they were inserted in.
+    ///
+    /// Returns `PatchError::InvalidIndex` with `at` if it's past the end of the source.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` - Where to insert
+    /// * `items` - The items to insert
+    pub fn insert(&mut self, at: usize, items: Vec<T>) -> Result<&mut Self, PatchError> {
+        self.replace(at..at, items)
+    }
+
+    /// Deletes a range of the source.
+    ///
+    /// Returns an error if the range doesn't fit the source, or overlaps a range that was deleted
+    /// or replaced before. See `replace`.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - The range to delete
+    pub fn delete(&mut self, range: Range<usize>) -> Result<&mut Self, PatchError> {
+        self.replace(range, Vec::new())
+    }
+
+    /// Replaces a range of the source with new items.
+    ///
+    /// Returns `PatchError::InvalidIndex` with the end of the range if it's past the end of the
+    /// source or before its start, and `PatchError::ConflictingEdits` with the first index the
+    /// range shares with a range that was deleted or replaced before.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - The range to replace
+    /// * `items` - The items that take its place
+    pub fn replace(&mut self, range: Range<usize>, items: Vec<T>) -> Result<&mut Self, PatchError> {
+        if range.end > self.source_len || range.start > range.end {
+            return Err(PatchError::InvalidIndex(range.end));
+        }
+
+        if let Some(other) = self
+            .changes
+            .iter()
+            .find(|other| range.start < other.range.end && other.range.start < range.end)
+        {
+            return Err(PatchError::ConflictingEdits(
+                range.start.max(other.range.start),
+            ));
+        }
+
+        // Inserts at the same position are kept together, in the order they were made
+        if range.is_empty() {
+            if let Some(other) = self.changes.iter_mut().find(|other| other.range == range) {
+                other.with.extend(items);
+                return Ok(self);
+            }
+        }
+
+        self.changes.push(Replace { range, with: items });
+
+        Ok(self)
+    }
+
+    /// Returns the edits that make all the changes, in the same descending order as
+    /// `generate_edits` produces, so they can be applied with `apply_edits`.
+    pub fn build(&self) -> Vec<Edit<T>> {
+        let mut changes = self.changes.clone();
+
+        // An insert at the start of a replaced range goes before the items replacing it
+        changes.sort_by_key(|change| (change.range.start, change.range.end));
+
+        replacements_to_edits(&changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::*;
+
+    #[test]
+    fn patch_builder_test() {
+        let source = "kitten".as_bytes();
+
+        let mut builder = PatchBuilder::new(source.len());
+        builder
+            .insert(6, "s".as_bytes().to_vec())
+            .unwrap()
+            .replace(0..1, "s".as_bytes().to_vec())
+            .unwrap()
+            .insert(0, "a ".as_bytes().to_vec())
+            .unwrap()
+            .insert(0, "few ".as_bytes().to_vec())
+            .unwrap()
+            .delete(4..4)
+            .unwrap();
+
+        let edits = builder.build();
+        assert_eq!(
+            crate::apply_edits(source, &edits),
+            "a few sittens".as_bytes()
+        );
+
+        assert_eq!(
+            builder.delete(5..7).unwrap_err(),
+            PatchError::InvalidIndex(7)
+        );
+        assert_eq!(
+            builder.replace(0..3, vec![]).unwrap_err(),
+            PatchError::ConflictingEdits(0)
+        );
+    }
+}
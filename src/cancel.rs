@@ -0,0 +1,124 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// A way to stop a long computation early, either from another thread or once a deadline passes.
+/// Deadlines need a clock, so they are only available with the `std` feature.
+///
+/// The token is cheap to clone, and all its clones share the same flag, so one can be handed to
+/// the computation and another kept to call `cancel` with. Functions that accept a token check it
+/// regularly, e.g. once per row of the distance matrix, and return
+/// `LevenshteinError::Cancelled` as soon as it is cancelled.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{CancelToken, LevenshteinError};
+///
+/// let token = CancelToken::new();
+/// let s1 = "SATURDAY".as_bytes();
+/// let s2 = "SUNDAY".as_bytes();
+///
+/// let (distance, _) = levenshtein::distance_cancellable(s1, s2, &token).unwrap();
+/// assert_eq!(distance, 3);
+///
+/// token.cancel();
+/// assert!(matches!(
+///     levenshtein::distance_cancellable(s1, s2, &token),
+///     Err(LevenshteinError::Cancelled(_))
+/// ));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+}
+
+impl CancelToken {
+    /// Returns a token that is only cancelled by calling `cancel`.
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    /// Returns a token that is cancelled once the deadline passes, or by calling `cancel`.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - When to give up
+    #[cfg(feature = "std")]
+    pub fn with_deadline(deadline: Instant) -> Self {
+        CancelToken {
+            cancelled: Arc::default(),
+            deadline: Some(deadline),
+        }
+    }
+
+    /// Returns a token that is cancelled once the timeout has passed from now, or by calling
+    /// `cancel`.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to allow
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use levenshtein_diff as levenshtein;
+    /// use levenshtein::CancelToken;
+    ///
+    /// let token = CancelToken::with_timeout(Duration::from_secs(1));
+    ///
+    /// let (distance, _) =
+    ///     levenshtein::distance_cancellable("FLAW".as_bytes(), "LAWN".as_bytes(), &token).unwrap();
+    /// assert_eq!(distance, 2);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn with_timeout(timeout: Duration) -> Self {
+        CancelToken::with_deadline(Instant::now() + timeout)
+    }
+
+    /// Cancels the token and all its clones.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the token was cancelled or its deadline has passed.
+    pub fn is_cancelled(&self) -> bool {
+        #[cfg(feature = "std")]
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return true;
+        }
+
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cancel::*;
+
+    #[test]
+    fn cancel_token_test() {
+        let token = CancelToken::new();
+        let clone = token.clone();
+        assert!(!token.is_cancelled());
+
+        clone.cancel();
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn cancel_token_deadline_test() {
+        assert!(CancelToken::with_timeout(Duration::ZERO).is_cancelled());
+        assert!(!CancelToken::with_timeout(Duration::from_secs(3600)).is_cancelled());
+    }
+}
@@ -0,0 +1,234 @@
+use alloc::string::String;
+
+// The characters whose full case folding, from the Unicode Character Database's CaseFolding.txt,
+// isn't their lowercase, sorted by character. Cherokee is handled separately in fold_char, since
+// its letters fold to their uppercase.
+const FOLDINGS: &[(char, &str)] = &[
+    ('\u{b5}', "\u{3bc}"),
+    ('\u{df}', "\u{73}\u{73}"),
+    ('\u{149}', "\u{2bc}\u{6e}"),
+    ('\u{17f}', "\u{73}"),
+    ('\u{1f0}', "\u{6a}\u{30c}"),
+    ('\u{345}', "\u{3b9}"),
+    ('\u{390}', "\u{3b9}\u{308}\u{301}"),
+    ('\u{3b0}', "\u{3c5}\u{308}\u{301}"),
+    ('\u{3c2}', "\u{3c3}"),
+    ('\u{3d0}', "\u{3b2}"),
+    ('\u{3d1}', "\u{3b8}"),
+    ('\u{3d5}', "\u{3c6}"),
+    ('\u{3d6}', "\u{3c0}"),
+    ('\u{3f0}', "\u{3ba}"),
+    ('\u{3f1}', "\u{3c1}"),
+    ('\u{3f5}', "\u{3b5}"),
+    ('\u{587}', "\u{565}\u{582}"),
+    ('\u{1c80}', "\u{432}"),
+    ('\u{1c81}', "\u{434}"),
+    ('\u{1c82}', "\u{43e}"),
+    ('\u{1c83}', "\u{441}"),
+    ('\u{1c84}', "\u{442}"),
+    ('\u{1c85}', "\u{442}"),
+    ('\u{1c86}', "\u{44a}"),
+    ('\u{1c87}', "\u{463}"),
+    ('\u{1c88}', "\u{a64b}"),
+    ('\u{1e96}', "\u{68}\u{331}"),
+    ('\u{1e97}', "\u{74}\u{308}"),
+    ('\u{1e98}', "\u{77}\u{30a}"),
+    ('\u{1e99}', "\u{79}\u{30a}"),
+    ('\u{1e9a}', "\u{61}\u{2be}"),
+    ('\u{1e9b}', "\u{1e61}"),
+    ('\u{1e9e}', "\u{73}\u{73}"),
+    ('\u{1f50}', "\u{3c5}\u{313}"),
+    ('\u{1f52}', "\u{3c5}\u{313}\u{300}"),
+    ('\u{1f54}', "\u{3c5}\u{313}\u{301}"),
+    ('\u{1f56}', "\u{3c5}\u{313}\u{342}"),
+    ('\u{1f80}', "\u{1f00}\u{3b9}"),
+    ('\u{1f81}', "\u{1f01}\u{3b9}"),
+    ('\u{1f82}', "\u{1f02}\u{3b9}"),
+    ('\u{1f83}', "\u{1f03}\u{3b9}"),
+    ('\u{1f84}', "\u{1f04}\u{3b9}"),
+    ('\u{1f85}', "\u{1f05}\u{3b9}"),
+    ('\u{1f86}', "\u{1f06}\u{3b9}"),
+    ('\u{1f87}', "\u{1f07}\u{3b9}"),
+    ('\u{1f88}', "\u{1f00}\u{3b9}"),
+    ('\u{1f89}', "\u{1f01}\u{3b9}"),
+    ('\u{1f8a}', "\u{1f02}\u{3b9}"),
+    ('\u{1f8b}', "\u{1f03}\u{3b9}"),
+    ('\u{1f8c}', "\u{1f04}\u{3b9}"),
+    ('\u{1f8d}', "\u{1f05}\u{3b9}"),
+    ('\u{1f8e}', "\u{1f06}\u{3b9}"),
+    ('\u{1f8f}', "\u{1f07}\u{3b9}"),
+    ('\u{1f90}', "\u{1f20}\u{3b9}"),
+    ('\u{1f91}', "\u{1f21}\u{3b9}"),
+    ('\u{1f92}', "\u{1f22}\u{3b9}"),
+    ('\u{1f93}', "\u{1f23}\u{3b9}"),
+    ('\u{1f94}', "\u{1f24}\u{3b9}"),
+    ('\u{1f95}', "\u{1f25}\u{3b9}"),
+    ('\u{1f96}', "\u{1f26}\u{3b9}"),
+    ('\u{1f97}', "\u{1f27}\u{3b9}"),
+    ('\u{1f98}', "\u{1f20}\u{3b9}"),
+    ('\u{1f99}', "\u{1f21}\u{3b9}"),
+    ('\u{1f9a}', "\u{1f22}\u{3b9}"),
+    ('\u{1f9b}', "\u{1f23}\u{3b9}"),
+    ('\u{1f9c}', "\u{1f24}\u{3b9}"),
+    ('\u{1f9d}', "\u{1f25}\u{3b9}"),
+    ('\u{1f9e}', "\u{1f26}\u{3b9}"),
+    ('\u{1f9f}', "\u{1f27}\u{3b9}"),
+    ('\u{1fa0}', "\u{1f60}\u{3b9}"),
+    ('\u{1fa1}', "\u{1f61}\u{3b9}"),
+    ('\u{1fa2}', "\u{1f62}\u{3b9}"),
+    ('\u{1fa3}', "\u{1f63}\u{3b9}"),
+    ('\u{1fa4}', "\u{1f64}\u{3b9}"),
+    ('\u{1fa5}', "\u{1f65}\u{3b9}"),
+    ('\u{1fa6}', "\u{1f66}\u{3b9}"),
+    ('\u{1fa7}', "\u{1f67}\u{3b9}"),
+    ('\u{1fa8}', "\u{1f60}\u{3b9}"),
+    ('\u{1fa9}', "\u{1f61}\u{3b9}"),
+    ('\u{1faa}', "\u{1f62}\u{3b9}"),
+    ('\u{1fab}', "\u{1f63}\u{3b9}"),
+    ('\u{1fac}', "\u{1f64}\u{3b9}"),
+    ('\u{1fad}', "\u{1f65}\u{3b9}"),
+    ('\u{1fae}', "\u{1f66}\u{3b9}"),
+    ('\u{1faf}', "\u{1f67}\u{3b9}"),
+    ('\u{1fb2}', "\u{1f70}\u{3b9}"),
+    ('\u{1fb3}', "\u{3b1}\u{3b9}"),
+    ('\u{1fb4}', "\u{3ac}\u{3b9}"),
+    ('\u{1fb6}', "\u{3b1}\u{342}"),
+    ('\u{1fb7}', "\u{3b1}\u{342}\u{3b9}"),
+    ('\u{1fbc}', "\u{3b1}\u{3b9}"),
+    ('\u{1fbe}', "\u{3b9}"),
+    ('\u{1fc2}', "\u{1f74}\u{3b9}"),
+    ('\u{1fc3}', "\u{3b7}\u{3b9}"),
+    ('\u{1fc4}', "\u{3ae}\u{3b9}"),
+    ('\u{1fc6}', "\u{3b7}\u{342}"),
+    ('\u{1fc7}', "\u{3b7}\u{342}\u{3b9}"),
+    ('\u{1fcc}', "\u{3b7}\u{3b9}"),
+    ('\u{1fd2}', "\u{3b9}\u{308}\u{300}"),
+    ('\u{1fd3}', "\u{3b9}\u{308}\u{301}"),
+    ('\u{1fd6}', "\u{3b9}\u{342}"),
+    ('\u{1fd7}', "\u{3b9}\u{308}\u{342}"),
+    ('\u{1fe2}', "\u{3c5}\u{308}\u{300}"),
+    ('\u{1fe3}', "\u{3c5}\u{308}\u{301}"),
+    ('\u{1fe4}', "\u{3c1}\u{313}"),
+    ('\u{1fe6}', "\u{3c5}\u{342}"),
+    ('\u{1fe7}', "\u{3c5}\u{308}\u{342}"),
+    ('\u{1ff2}', "\u{1f7c}\u{3b9}"),
+    ('\u{1ff3}', "\u{3c9}\u{3b9}"),
+    ('\u{1ff4}', "\u{3ce}\u{3b9}"),
+    ('\u{1ff6}', "\u{3c9}\u{342}"),
+    ('\u{1ff7}', "\u{3c9}\u{342}\u{3b9}"),
+    ('\u{1ffc}', "\u{3c9}\u{3b9}"),
+    ('\u{fb00}', "\u{66}\u{66}"),
+    ('\u{fb01}', "\u{66}\u{69}"),
+    ('\u{fb02}', "\u{66}\u{6c}"),
+    ('\u{fb03}', "\u{66}\u{66}\u{69}"),
+    ('\u{fb04}', "\u{66}\u{66}\u{6c}"),
+    ('\u{fb05}', "\u{73}\u{74}"),
+    ('\u{fb06}', "\u{73}\u{74}"),
+    ('\u{fb13}', "\u{574}\u{576}"),
+    ('\u{fb14}', "\u{574}\u{565}"),
+    ('\u{fb15}', "\u{574}\u{56b}"),
+    ('\u{fb16}', "\u{57e}\u{576}"),
+    ('\u{fb17}', "\u{574}\u{56d}"),
+];
+
+// The full case folding of a character, as an array and the number of characters of it that are
+// used: no character folds to more than three
+pub(crate) fn fold_char(c: char) -> ([char; 3], usize) {
+    let mut folded = ['\0'; 3];
+
+    if c.is_ascii() {
+        folded[0] = c.to_ascii_lowercase();
+        return (folded, 1);
+    }
+
+    let code = c as u32;
+
+    let len = if let Ok(idx) = FOLDINGS.binary_search_by_key(&c, |&(from, _)| from) {
+        fill(&mut folded, FOLDINGS[idx].1.chars())
+    } else if (0xAB70..=0xABBF).contains(&code) {
+        // Cherokee small letters fold to their capitals, in the same order
+        folded[0] = char::from_u32(code - 0xAB70 + 0x13A0).expect("a Cherokee letter");
+        1
+    } else if (0x13F8..=0x13FD).contains(&code) {
+        folded[0] = char::from_u32(code - 8).expect("a Cherokee letter");
+        1
+    } else if (0x13A0..=0x13F5).contains(&code) {
+        folded[0] = c;
+        1
+    } else {
+        fill(&mut folded, c.to_lowercase())
+    };
+
+    (folded, len)
+}
+
+// Copies the characters to the front of folded, and returns how many there were
+fn fill(folded: &mut [char; 3], chars: impl Iterator<Item = char>) -> usize {
+    folded
+        .iter_mut()
+        .zip(chars)
+        .map(|(slot, to)| *slot = to)
+        .count()
+}
+
+/// Returns the full Unicode case folding of a string.
+///
+/// Case folding maps strings that only differ in case to the same string, which lowercasing
+/// doesn't always do: "Straße" and "STRASSE" both fold to "strasse", and the Greek final sigma
+/// "ς" folds to "σ" like "Σ" does. Unlike `str::to_lowercase`, the folding of each character
+/// doesn't depend on the characters around it.
+///
+/// # Arguments
+///
+/// * `text` - The string to fold
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// assert_eq!(levenshtein::fold_case("Straße"), "strasse");
+/// assert_eq!(levenshtein::fold_case("ΣΊΣΥΦΟΣ"), levenshtein::fold_case("σίσυφος"));
+/// ```
+pub fn fold_case(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| {
+            let (folded, len) = fold_char(c);
+            IntoIterator::into_iter(folded).take(len)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::case_fold::*;
+
+    #[test]
+    fn foldings_are_sorted_test() {
+        assert!(FOLDINGS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(FOLDINGS
+            .iter()
+            .all(|(_, to)| (1..=3).contains(&to.chars().count())));
+    }
+
+    #[test]
+    fn fold_case_test() {
+        assert_eq!(fold_case("Hello, World!"), "hello, world!");
+        assert_eq!(fold_case("MASSE"), fold_case("Maße"));
+        assert_eq!(fold_case("ﬁnd"), "find");
+        assert_eq!(fold_case("µ"), fold_case("Μ"));
+        assert_eq!(fold_case("ſ"), "s");
+        assert_eq!(fold_case("ΐ"), "\u{3b9}\u{308}\u{301}");
+
+        // Cherokee folds to its capitals
+        assert_eq!(fold_case("\u{ab70}\u{13f8}"), "\u{13a0}\u{13f0}");
+        assert_eq!(fold_case("\u{13a0}"), "\u{13a0}");
+
+        // İ, ς and σ
+        assert_eq!(fold_case("\u{130}"), "i\u{307}");
+        assert_eq!(fold_case("\u{3c2}"), fold_case("\u{3a3}"));
+
+        // NUL is a character like any other
+        assert_eq!(fold_case("A\0B"), "a\0b");
+    }
+}
@@ -0,0 +1,401 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+use crate::distance::diff_edits;
+use crate::edit::Edit;
+use crate::replace::{coalesce_edits, replacements_to_edits, Replace};
+use crate::util::strip_common_affix;
+
+// Regions whose distance matrix would have more cells than this are chunked again, with smaller
+// chunks, instead of being compared byte by byte
+const MAX_REFINE_CELLS: usize = 1 << 22;
+
+// Regions that are still too big once chunks are this small are replaced as a whole
+const MIN_AVG_SIZE: usize = 16;
+
+// A random number for every byte value, which the gear hash adds in as it rolls. They are
+// generated with splitmix64, so the chunk boundaries are the same in every build.
+const GEAR: [u64; 256] = gear_table();
+
+const fn gear_table() -> [u64; 256] {
+    let mut table = [0; 256];
+    let mut state: u64 = 0;
+    let mut i = 0;
+
+    while i < 256 {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+
+    table
+}
+
+// Returns a mask of the top `bits` bits, which only depend on the last 64 bytes the gear hash saw
+fn top_bits(bits: u32) -> u64 {
+    !0 << (64 - bits.clamp(1, 63))
+}
+
+// Returns the length of the chunk at the start of the bytes
+fn cut_point(bytes: &[u8], min_size: usize, avg_size: usize, max_size: usize) -> usize {
+    if bytes.len() <= min_size {
+        return bytes.len();
+    }
+
+    // Normalized chunking: it's harder to cut before the average size and easier after it, which
+    // keeps the sizes of the chunks close to the average
+    let bits = avg_size.trailing_zeros();
+    let (strict, loose) = (top_bits(bits + 2), top_bits(bits.saturating_sub(2)));
+
+    let end = std::cmp::min(bytes.len(), max_size);
+    let normal = std::cmp::min(avg_size, end);
+    let mut hash: u64 = 0;
+
+    for (i, &byte) in bytes.iter().enumerate().take(end).skip(min_size) {
+        hash = (hash << 1).wrapping_add(GEAR[byte as usize]);
+
+        let mask = if i < normal { strict } else { loose };
+        if hash & mask == 0 {
+            return i + 1;
+        }
+    }
+
+    end
+}
+
+/// Returns the chunks of the bytes, cut where their content says so, with the FastCDC algorithm.
+///
+/// A chunk ends wherever a hash rolled over the bytes meets a condition, so the boundaries only
+/// depend on the bytes around them: inserting or deleting bytes only changes the chunks around
+/// the change, and the chunks after it are cut at the same places as before. That makes the
+/// chunks of two versions of a file line up, which is what deduplication and sync tools rely on.
+///
+/// The chunks are at least a quarter and at most eight times the average size, except for the
+/// last one, which can be shorter. They are returned in order, and cover all the bytes.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to cut into chunks
+/// * `avg_size` - The average size of the chunks, rounded down to a power of two
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let bytes: Vec<u8> = (0..100_000u32)
+///     .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+///     .collect();
+/// let chunks = levenshtein::cdc_chunks(&bytes, 1024);
+///
+/// assert_eq!(chunks.first().unwrap().start, 0);
+/// assert_eq!(chunks.last().unwrap().end, bytes.len());
+/// assert!(chunks.iter().all(|chunk| chunk.len() <= 8 * 1024));
+/// ```
+pub fn cdc_chunks(bytes: &[u8], avg_size: usize) -> Vec<Range<usize>> {
+    let avg_size: usize = 1 << (usize::BITS - 1 - std::cmp::max(avg_size, 4).leading_zeros());
+    let (min_size, max_size) = (avg_size / 4, avg_size.saturating_mul(8));
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < bytes.len() {
+        let len = cut_point(&bytes[start..], min_size, avg_size, max_size);
+        chunks.push(start..start + len);
+        start += len;
+    }
+
+    chunks
+}
+
+// A chunk, compared by its hash first so that most comparisons of different chunks are cheap
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Chunk<'a> {
+    hash: u64,
+    bytes: &'a [u8],
+}
+
+impl Hash for Chunk<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+    }
+}
+
+impl<'a> Chunk<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+
+        Chunk {
+            hash: hasher.finish(),
+            bytes,
+        }
+    }
+}
+
+fn chunk_keys<'a>(bytes: &'a [u8], chunks: &[Range<usize>]) -> Vec<Chunk<'a>> {
+    chunks
+        .iter()
+        .map(|chunk| Chunk::new(&bytes[chunk.clone()]))
+        .collect()
+}
+
+// Returns the byte range of a range of chunks. An empty range of chunks is the position before
+// the chunk it starts at.
+fn byte_range(chunks: &[Range<usize>], range: &Range<usize>, len: usize) -> Range<usize> {
+    let start = chunks.get(range.start).map_or(len, |chunk| chunk.start);
+    let end = if range.is_empty() {
+        start
+    } else {
+        chunks[range.end - 1].end
+    };
+
+    start..end
+}
+
+// Returns the positions in the source and the target of the chunks that occur exactly once in
+// each, keeping as many of them as possible whose positions go up in both, like patience diff
+// does. The chunks in between them are the ones that may have changed.
+fn unique_anchors(source_keys: &[Chunk], target_keys: &[Chunk]) -> Vec<(usize, usize)> {
+    // How often each chunk occurs in the source and the target, and where it is in the target
+    let mut counts = HashMap::<&Chunk, (usize, usize, usize)>::new();
+
+    for key in source_keys {
+        counts.entry(key).or_insert((0, 0, 0)).0 += 1;
+    }
+
+    for (j, key) in target_keys.iter().enumerate() {
+        if let Some(count) = counts.get_mut(key) {
+            count.1 += 1;
+            count.2 = j;
+        }
+    }
+
+    let pairs: Vec<(usize, usize)> = source_keys
+        .iter()
+        .enumerate()
+        .filter_map(|(i, key)| match counts[key] {
+            (1, 1, j) => Some((i, j)),
+            _ => None,
+        })
+        .collect();
+
+    // The longest run of pairs with increasing target positions, found by patience sorting:
+    // `tails[l]` is the pair that ends the best run of length l + 1 so far
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![None; pairs.len()];
+
+    for (p, &(_, j)) in pairs.iter().enumerate() {
+        let len = tails.partition_point(|&tail| pairs[tail].1 < j);
+        previous[p] = len.checked_sub(1).map(|l| tails[l]);
+
+        if len == tails.len() {
+            tails.push(p);
+        } else {
+            tails[len] = p;
+        }
+    }
+
+    let mut anchors = Vec::with_capacity(tails.len());
+    let mut next = tails.last().copied();
+
+    while let Some(p) = next {
+        anchors.push(pairs[p]);
+        next = previous[p];
+    }
+
+    anchors.reverse();
+    anchors
+}
+
+// Adds the replacements that turn the source into the target to `out`, with the source starting
+// at `offset` in the whole source
+fn diff_region(
+    source: &[u8],
+    target: &[u8],
+    offset: usize,
+    avg_size: usize,
+    out: &mut Vec<Replace<u8>>,
+) {
+    if source.len().saturating_mul(target.len()) <= MAX_REFINE_CELLS {
+        let edits = diff_edits(source, target);
+
+        out.extend(coalesce_edits(&edits).into_iter().map(|r| Replace {
+            range: r.range.start + offset..r.range.end + offset,
+            with: r.with,
+        }));
+        return;
+    }
+
+    if avg_size < MIN_AVG_SIZE {
+        out.push(Replace {
+            range: offset..offset + source.len(),
+            with: target.to_vec(),
+        });
+        return;
+    }
+
+    let source_chunks = cdc_chunks(source, avg_size);
+    let target_chunks = cdc_chunks(target, avg_size);
+
+    let source_keys = chunk_keys(source, &source_chunks);
+    let target_keys = chunk_keys(target, &target_chunks);
+
+    // The runs of chunks between the anchors, and after the last one, are regions to compare in
+    // more detail once the chunks they share at either end are left out. This takes
+    // O(c log c) time for c chunks, however many of them changed.
+    let ends = (source_keys.len(), target_keys.len());
+    let mut from = (0, 0);
+
+    for (i, j) in unique_anchors(&source_keys, &target_keys)
+        .into_iter()
+        .chain(std::iter::once(ends))
+    {
+        let stripped = strip_common_affix(&source_keys[from.0..i], &target_keys[from.1..j]);
+        let (source_start, target_start) = (from.0 + stripped.prefix, from.1 + stripped.prefix);
+        from = (i + 1, j + 1);
+
+        if stripped.source.is_empty() && stripped.target.is_empty() {
+            continue;
+        }
+
+        let source_range = byte_range(
+            &source_chunks,
+            &(source_start..source_start + stripped.source.len()),
+            source.len(),
+        );
+        let target_range = byte_range(
+            &target_chunks,
+            &(target_start..target_start + stripped.target.len()),
+            target.len(),
+        );
+
+        diff_region(
+            &source[source_range.clone()],
+            &target[target_range],
+            offset + source_range.start,
+            avg_size / 4,
+            out,
+        );
+    }
+}
+
+/// Returns edits that transform the source into the target, found by comparing content-defined
+/// chunks first and only the bytes of the chunks that changed.
+///
+/// Both are cut into chunks with `cdc_chunks`, which are compared by their hashes. The chunks
+/// that occur exactly once in both are matched up, keeping as many of them as possible in order,
+/// like patience diff does, and every run of chunks between them that changed is compared again
+/// in the same way with smaller chunks, down to a size where comparing the bytes directly is
+/// cheap. The time and memory this takes grows with the number of chunks and the size of the
+/// changes rather than with the size of the inputs, so it works for large files that are mostly
+/// the same, at the cost of edits that are not always the fewest possible.
+///
+/// The edits are in the same form `generate_edits` returns them.
+///
+/// # Arguments
+///
+/// * `source` - The source bytes
+/// * `target` - The target bytes
+/// * `avg_size` - The average size of the chunks of the first pass. See `cdc_chunks`.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let source: Vec<u8> = (0..200_000u32)
+///     .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+///     .collect();
+///
+/// let mut target = source.clone();
+/// target.splice(150_000..150_010, "CHANGED".bytes());
+/// target.splice(1000..1000, "NEW".bytes());
+///
+/// let edits = levenshtein::chunked_edits(&source, &target, 4096);
+///
+/// assert!(edits.len() <= 13);
+/// assert_eq!(levenshtein::apply_edits(&source, &edits), target);
+/// ```
+pub fn chunked_edits(source: &[u8], target: &[u8], avg_size: usize) -> Vec<Edit<u8>> {
+    let mut replacements = Vec::new();
+    diff_region(source, target, 0, avg_size, &mut replacements);
+
+    replacements_to_edits(&replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cdc::*;
+    use crate::util::random_bytes;
+
+    #[test]
+    fn cdc_chunks_test() {
+        let bytes = random_bytes(200_000);
+        let chunks = cdc_chunks(&bytes, 1024);
+
+        let average = bytes.len() / chunks.len();
+        assert!(average > 512 && average < 2048);
+        assert!(chunks.windows(2).all(|pair| pair[0].end == pair[1].start));
+
+        // The chunks after an insertion are cut in the same places
+        let mut changed = bytes[..1000].to_vec();
+        changed.extend_from_slice(b"INSERTED");
+        changed.extend_from_slice(&bytes[1000..]);
+
+        let shifted: Vec<Range<usize>> = cdc_chunks(&changed, 1024)
+            .into_iter()
+            .skip(5)
+            .map(|chunk| chunk.start - 8..chunk.end - 8)
+            .collect();
+        assert_eq!(shifted, chunks[chunks.len() - shifted.len()..]);
+
+        assert!(cdc_chunks(&[], 1024).is_empty());
+
+        // Chunks can't be longer than the bytes, however large the average size is
+        assert_eq!(cdc_chunks(&bytes, usize::MAX), vec![0..bytes.len()]);
+    }
+
+    #[test]
+    fn chunked_edits_test() {
+        let source = random_bytes(300_000);
+
+        let mut target = source.clone();
+        target.splice(250_000..250_100, "CHANGED".bytes());
+        target.splice(100_000..100_000, random_bytes(5000));
+        target.drain(1000..1010);
+
+        let edits = chunked_edits(&source, &target, 8192);
+        assert_eq!(crate::apply_edits(&source, &edits), target);
+        assert!(edits.len() <= 10 + 5000 + 100);
+
+        // Completely different inputs fall back to replacing them
+        let other = random_bytes(300_001)[1..].to_vec();
+        let edits = chunked_edits(&source[..5000], &other[..5000], 8192);
+        assert_eq!(crate::apply_edits(&source[..5000], &edits), &other[..5000]);
+
+        assert!(chunked_edits(&[], &[], 64).is_empty());
+    }
+
+    #[test]
+    fn chunked_edits_of_many_chunks_test() {
+        // Tens of thousands of small chunks, with changes all over and a block moved to the end
+        let source = random_bytes(1_000_000);
+
+        let mut target = source.clone();
+        for start in (0..target.len()).step_by(50_000) {
+            target[start] = !target[start];
+        }
+        let moved: Vec<u8> = target.drain(300_000..310_000).collect();
+        target.extend(moved);
+
+        let edits = chunked_edits(&source, &target, 64);
+        assert_eq!(crate::apply_edits(&source, &edits), target);
+        assert!(edits.len() <= 20 + 2 * 10_000);
+    }
+}
@@ -0,0 +1,270 @@
+use alloc::vec::Vec;
+use core::cmp::max;
+
+use crate::replace::Replace;
+
+/// Makes a sequence of range replacements easier for humans to read, at the cost of possibly
+/// replacing more items than strictly necessary.
+///
+/// This works like the semantic cleanup of diff-match-patch, in three passes:
+///
+/// 1. Replacements separated by a run of unchanged items that's no longer than the changes on
+///    either side of it are merged, since such tiny equalities are usually coincidental.
+/// 2. Pure insertions and deletions are slid along the source as far as that keeps the target the
+///    same, and placed where they line up best with separators (e.g. spaces between words).
+/// 3. Replacements that now touch each other are merged.
+///
+/// The result still transforms the source into the same target.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `replacements` - The replacements, as returned by `coalesce_edits`
+/// * `is_separator` - Whether an item separates the semantic units (e.g. words) of the sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Replace;
+///
+/// let source = "The cat sat".as_bytes();
+///
+/// // Inserting "at c" after the first c is minimal, but inserting " cat" reads better
+/// let replacements = vec![Replace { range: 5..5, with: "at c".as_bytes().to_vec() }];
+///
+/// let cleaned = levenshtein::cleanup_semantic(source, &replacements, |c| *c == b' ');
+/// assert_eq!(cleaned, vec![Replace { range: 3..3, with: " cat".as_bytes().to_vec() }]);
+///
+/// let target = levenshtein::apply_replacements(source, &cleaned);
+/// assert_eq!(target, "The cat cat sat".as_bytes());
+/// ```
+pub fn cleanup_semantic<T, F>(
+    source: &[T],
+    replacements: &[Replace<T>],
+    is_separator: F,
+) -> Vec<Replace<T>>
+where
+    T: Clone + PartialEq,
+    F: Fn(&T) -> bool,
+{
+    let mut cleaned = merge_small_equalities(source, replacements);
+
+    for k in 0..cleaned.len() {
+        let lower = if k > 0 { cleaned[k - 1].range.end } else { 0 };
+        let upper = match cleaned.get(k + 1) {
+            Some(next) => next.range.start,
+            None => source.len(),
+        };
+
+        align_to_separators(source, &mut cleaned[k], lower, upper, &is_separator);
+    }
+
+    merge_touching(source, cleaned)
+}
+
+// The number of items a replacement changes, counting whichever side is larger
+fn size<T>(replacement: &Replace<T>) -> usize {
+    max(replacement.range.len(), replacement.with.len())
+}
+
+// Merges two replacements, along with the unchanged items between them
+fn merge<T: Clone>(source: &[T], first: &mut Replace<T>, second: Replace<T>) {
+    first
+        .with
+        .extend_from_slice(&source[first.range.end..second.range.start]);
+    first.with.extend(second.with);
+    first.range.end = second.range.end;
+}
+
+fn merge_small_equalities<T: Clone>(source: &[T], replacements: &[Replace<T>]) -> Vec<Replace<T>> {
+    let mut merged = Vec::<Replace<T>>::with_capacity(replacements.len());
+
+    for replacement in replacements {
+        merged.push(replacement.clone());
+
+        // Merging grows the replacement, which may in turn swallow the equality before it
+        while merged.len() >= 2 {
+            let last = &merged[merged.len() - 1];
+            let previous = &merged[merged.len() - 2];
+            let equality = last.range.start - previous.range.end;
+
+            if equality > size(previous) || equality > size(last) {
+                break;
+            }
+
+            let last = merged.pop().unwrap();
+            merge(source, merged.last_mut().unwrap(), last);
+        }
+    }
+
+    merged
+}
+
+fn merge_touching<T: Clone>(source: &[T], replacements: Vec<Replace<T>>) -> Vec<Replace<T>> {
+    let mut merged = Vec::<Replace<T>>::with_capacity(replacements.len());
+
+    for replacement in replacements {
+        match merged.last_mut() {
+            Some(last) if last.range.end == replacement.range.start => {
+                merge(source, last, replacement)
+            }
+            _ => merged.push(replacement),
+        }
+    }
+
+    merged
+}
+
+// Scores how well a run of changed items lines up with separators, given the unchanged items
+// right before and after it
+fn score<T, F: Fn(&T) -> bool>(
+    before: Option<&T>,
+    changed: &[T],
+    after: Option<&T>,
+    is_separator: &F,
+) -> usize {
+    let starts_unit = before.is_none_or(is_separator) || changed.first().is_some_and(is_separator);
+    let ends_unit = after.is_none_or(is_separator) || changed.last().is_some_and(is_separator);
+
+    starts_unit as usize + ends_unit as usize
+}
+
+// Slides a pure insertion or deletion within lower..upper to the place with the best score. On
+// ties, it stays put if it can, and goes as far left as possible otherwise.
+fn align_to_separators<T, F>(
+    source: &[T],
+    replacement: &mut Replace<T>,
+    lower: usize,
+    upper: usize,
+    is_separator: &F,
+) where
+    T: Clone + PartialEq,
+    F: Fn(&T) -> bool,
+{
+    let is_insertion = replacement.range.is_empty() && !replacement.with.is_empty();
+    let is_deletion = replacement.with.is_empty() && !replacement.range.is_empty();
+
+    if !is_insertion && !is_deletion {
+        return;
+    }
+
+    let changed = |r: &Replace<T>| -> Vec<T> {
+        if is_insertion {
+            r.with.clone()
+        } else {
+            source[r.range.clone()].to_vec()
+        }
+    };
+
+    let rate = |r: &Replace<T>| {
+        let before = r.range.start.checked_sub(1).map(|i| &source[i]);
+        score(before, &changed(r), source.get(r.range.end), is_separator)
+    };
+
+    // Slide all the way to the left first
+    let mut current = replacement.clone();
+
+    while current.range.start > lower {
+        let previous = &source[current.range.start - 1];
+
+        if is_insertion && current.with.last() == Some(previous) {
+            current.with.pop();
+            current.with.insert(0, previous.clone());
+        } else if !(is_deletion && source[current.range.end - 1] == *previous) {
+            break;
+        }
+
+        current.range.start -= 1;
+        current.range.end -= 1;
+    }
+
+    let mut best_score = rate(replacement);
+
+    // Then try every position to the right
+    loop {
+        let current_score = rate(&current);
+
+        if current_score > best_score {
+            best_score = current_score;
+            *replacement = current.clone();
+        }
+
+        if current.range.end >= upper {
+            break;
+        }
+
+        let next = &source[current.range.end];
+
+        if is_insertion && current.with.first() == Some(next) {
+            current.with.remove(0);
+            current.with.push(next.clone());
+        } else if !(is_deletion && source[current.range.start] == *next) {
+            break;
+        }
+
+        current.range.start += 1;
+        current.range.end += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cleanup::*;
+
+    #[test]
+    fn small_equalities_are_merged() {
+        let source = "SATURDAY".as_bytes();
+
+        // The U between the two replacements is shorter than both
+        let replacements = vec![
+            Replace {
+                range: 1..3,
+                with: vec![],
+            },
+            Replace {
+                range: 4..5,
+                with: vec![b'N'],
+            },
+        ];
+
+        let cleaned = cleanup_semantic(source, &replacements, |_| false);
+
+        assert_eq!(
+            cleaned,
+            vec![Replace {
+                range: 1..5,
+                with: "UN".as_bytes().to_vec(),
+            }]
+        );
+        assert_eq!(
+            crate::replace::apply_replacements(source, &cleaned),
+            "SUNDAY".as_bytes()
+        );
+    }
+
+    #[test]
+    fn deletions_are_aligned_to_separators() {
+        let source = "one two two three".as_bytes();
+
+        // Deleting "o tw" is minimal, deleting " two" reads better
+        let replacements = vec![Replace {
+            range: 6..10,
+            with: vec![],
+        }];
+
+        let cleaned = cleanup_semantic(source, &replacements, |c| *c == b' ');
+
+        assert_eq!(
+            cleaned,
+            vec![Replace {
+                range: 3..7,
+                with: vec![],
+            }]
+        );
+        assert_eq!(
+            crate::replace::apply_replacements(source, &cleaned),
+            "one two three".as_bytes()
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use icu_collator::Collator;
+
+use crate::distance::levenshtein_two_rows;
+use crate::edit::Edit;
+use crate::keyed::stripped_edit_ops;
+
+// A string that is equal to another when the collator compares them as equal
+struct Collated<'a> {
+    collator: &'a Collator,
+    text: &'a str,
+}
+
+impl PartialEq for Collated<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.collator.compare(self.text, other.text) == Ordering::Equal
+    }
+}
+
+fn collated<'a, S: AsRef<str>>(items: &'a [S], collator: &'a Collator) -> Vec<Collated<'a>> {
+    items
+        .iter()
+        .map(|item| Collated {
+            collator,
+            text: item.as_ref(),
+        })
+        .collect()
+}
+
+/// Returns the Levenshtein distance between two sequences of strings, taking two strings for
+/// equal when the collator compares them as equal.
+///
+/// Collation compares strings the way the people who use a locale expect: depending on the
+/// strength of the collator, "Straße" can be equal to "STRASSE", and "Müller" to "Muller",
+/// without transforming either first. Every pair of strings the distance matrix needs is compared
+/// by the collator, which is slower than comparing the strings themselves, but leading and
+/// trailing runs of equal strings are cheap. Comparing the characters of single strings this way
+/// wouldn't work, since collation can equate strings of different lengths, so split them into
+/// words or fields first.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `collator` - The collator, with the locale and strength to compare the strings at
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use icu_collator::{Collator, CollatorOptions, Strength};
+/// use icu_locid::locale;
+///
+/// let source = ["Straße", "Müller", "Berlin"];
+/// let target = ["STRASSE", "MÜLLER", "Berlin"];
+///
+/// // At secondary strength, case is ignored, but accents and ß aren't
+/// let mut options = CollatorOptions::new();
+/// options.strength = Some(Strength::Secondary);
+/// let collator = Collator::try_new(&locale!("de").into(), options).unwrap();
+/// assert_eq!(levenshtein::distance_collated(&source, &target, &collator), 1);
+///
+/// // At primary strength, only the base letters count, and ß is ss
+/// options.strength = Some(Strength::Primary);
+/// let collator = Collator::try_new(&locale!("de").into(), options).unwrap();
+/// assert_eq!(levenshtein::distance_collated(&source, &target, &collator), 0);
+/// ```
+pub fn distance_collated<S: AsRef<str>>(source: &[S], target: &[S], collator: &Collator) -> usize {
+    levenshtein_two_rows(&collated(source, collator), &collated(target, collator))
+}
+
+/// Generate a vector of edits that transform the source sequence of strings into the target
+/// sequence, taking two strings for equal when the collator compares them as equal. See
+/// `distance_collated`.
+///
+/// The edits hold the strings of the target as they are, so applying them keeps the strings of
+/// the source wherever the collator found them equal to those of the target.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `collator` - The collator, with the locale and strength to compare the strings at
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+/// use icu_collator::{Collator, CollatorOptions, Strength};
+/// use icu_locid::locale;
+///
+/// let mut options = CollatorOptions::new();
+/// options.strength = Some(Strength::Primary);
+/// let collator = Collator::try_new(&locale!("fr").into(), options).unwrap();
+///
+/// let source = ["Élodie", "Dupont", "Paris"];
+/// let target = ["elodie", "DUPONT", "Lyon"];
+///
+/// let edits = levenshtein::generate_edits_collated(&source, &target, &collator);
+/// assert_eq!(edits, vec![Edit::Substitute(3, "Lyon")]);
+/// ```
+pub fn generate_edits_collated<S: Clone + PartialEq + AsRef<str>>(
+    source: &[S],
+    target: &[S],
+    collator: &Collator,
+) -> Vec<Edit<S>> {
+    stripped_edit_ops(&collated(source, collator), &collated(target, collator))
+        .iter()
+        .map(|op| op.to_edit(target))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collation::*;
+    use icu_collator::{CollatorOptions, Strength};
+    use icu_locid::locale;
+
+    fn primary() -> CollatorOptions {
+        let mut options = CollatorOptions::new();
+        options.strength = Some(Strength::Primary);
+        options
+    }
+
+    #[test]
+    fn distance_collated_test() {
+        let source = ["Åsa", "Ärlig", "Lund"];
+        let target = ["asa", "arlig", "lund"];
+
+        // Å and Ä are letters of their own in Swedish, but A with accents elsewhere
+        let root = Collator::try_new(&Default::default(), primary()).unwrap();
+        let swedish = Collator::try_new(&locale!("sv").into(), primary()).unwrap();
+
+        assert_eq!(distance_collated(&source, &target, &root), 0);
+        assert_eq!(distance_collated(&source, &target, &swedish), 2);
+
+        let tertiary = Collator::try_new(&Default::default(), CollatorOptions::new()).unwrap();
+        assert_eq!(distance_collated(&source, &target, &tertiary), 3);
+        assert_eq!(distance_collated(&source, &[], &tertiary), 3);
+    }
+
+    #[test]
+    fn generate_edits_collated_test() {
+        let source = vec!["Åsa".to_string(), "Ärlig".to_string(), "Lund".to_string()];
+        let target = vec!["asa".to_string(), "ARLIG".to_string(), "Malmö".to_string()];
+
+        let swedish = Collator::try_new(&locale!("sv").into(), primary()).unwrap();
+        let edits = generate_edits_collated(&source, &target, &swedish);
+
+        assert_eq!(edits.len(), distance_collated(&source, &target, &swedish));
+        assert_eq!(
+            crate::apply_edits(&source, &edits),
+            vec!["asa", "ARLIG", "Malmö"]
+        );
+
+        let root = Collator::try_new(&Default::default(), primary()).unwrap();
+        let edits = generate_edits_collated(&source, &target, &root);
+        assert_eq!(
+            crate::apply_edits(&source, &edits),
+            vec!["Åsa", "Ärlig", "Malmö"]
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::edit::Edit;
+use crate::replace::{coalesce_edits, replacements_to_edits, Replace};
+
+// A piece of the sequence an edit script produces: either a range of items kept from its source,
+// or newly inserted items. The lengths of the sequences aren't known, so the last piece is always
+// a range ending at usize::MAX, standing in for "the rest of the source".
+enum Segment<T> {
+    Keep(Range<usize>),
+    Insert(Vec<T>),
+}
+
+impl<T> Segment<T> {
+    fn len(&self) -> usize {
+        match self {
+            Segment::Keep(range) => range.end - range.start,
+            Segment::Insert(items) => items.len(),
+        }
+    }
+}
+
+fn segments<T: Clone + PartialEq>(edits: &[Edit<T>]) -> Vec<Segment<T>> {
+    let mut segments = Vec::<Segment<T>>::new();
+    let mut kept_up_to = 0;
+
+    for replacement in coalesce_edits(edits) {
+        if replacement.range.start > kept_up_to {
+            segments.push(Segment::Keep(kept_up_to..replacement.range.start));
+        }
+
+        if !replacement.with.is_empty() {
+            segments.push(Segment::Insert(replacement.with));
+        }
+
+        kept_up_to = replacement.range.end;
+    }
+
+    segments.push(Segment::Keep(kept_up_to..usize::MAX));
+
+    segments
+}
+
+/// Composes two consecutive edit scripts into a single one.
+///
+/// Given the edits that transform `a` into `b` and those that transform `b` into `c`, returns
+/// edits that transform `a` directly into `c`. Neither `b` nor any of the sequences are needed:
+/// the second script is mapped through the first one. The result can be applied with
+/// `apply_edits`, but isn't necessarily minimal.
+///
+/// # Arguments
+///
+/// * `a_to_b` - The edits that transform the first sequence into the second
+/// * `b_to_c` - The edits that transform the second sequence into the third
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let a = "SATURDAY".as_bytes();
+/// let b = "SUNDAY".as_bytes();
+/// let c = "MONDAY".as_bytes();
+///
+/// let (_, matrix) = levenshtein::distance(a, b);
+/// let a_to_b = levenshtein::generate_edits(a, b, &matrix).unwrap();
+///
+/// let (_, matrix) = levenshtein::distance(b, c);
+/// let b_to_c = levenshtein::generate_edits(b, c, &matrix).unwrap();
+///
+/// let a_to_c = levenshtein::compose_edits(&a_to_b, &b_to_c);
+/// assert_eq!(levenshtein::apply_edits(a, &a_to_c), c);
+/// ```
+pub fn compose_edits<T: Clone + PartialEq>(a_to_b: &[Edit<T>], b_to_c: &[Edit<T>]) -> Vec<Edit<T>> {
+    let a_to_b = segments(a_to_b);
+    let b_to_c = segments(b_to_c);
+
+    // Express c as pieces of a and inserted items, by looking up every range c keeps from b in
+    // the pieces b is made of. Both lists of segments are in order, so a single pass suffices.
+    let mut a_to_c = Vec::<Segment<T>>::new();
+    let mut current = 0;
+    let mut current_start: usize = 0;
+
+    for segment in b_to_c {
+        let range = match segment {
+            Segment::Insert(items) => {
+                a_to_c.push(Segment::Insert(items));
+                continue;
+            }
+            Segment::Keep(range) => range,
+        };
+
+        while current < a_to_b.len() && range.start < range.end {
+            let piece = &a_to_b[current];
+            let piece_end = current_start.saturating_add(piece.len());
+
+            if piece_end <= range.start {
+                current += 1;
+                current_start = piece_end;
+                continue;
+            }
+
+            if current_start >= range.end {
+                break;
+            }
+
+            // The part of the piece that overlaps with the range, relative to the piece
+            let from = range.start.max(current_start) - current_start;
+            let to = range.end.min(piece_end) - current_start;
+
+            match piece {
+                Segment::Keep(kept) => {
+                    a_to_c.push(Segment::Keep(
+                        kept.start + from..kept.start.saturating_add(to),
+                    ));
+                }
+                Segment::Insert(items) => {
+                    a_to_c.push(Segment::Insert(items[from..to].to_vec()));
+                }
+            }
+
+            if piece_end > range.end {
+                break;
+            }
+
+            current += 1;
+            current_start = piece_end;
+        }
+    }
+
+    // Everything of a that isn't kept was removed, and everything inserted in between replaces it
+    let mut replacements = Vec::<Replace<T>>::new();
+    let mut kept_up_to = 0;
+    let mut inserted = Vec::<T>::new();
+
+    for segment in a_to_c {
+        match segment {
+            Segment::Insert(items) => inserted.extend(items),
+            Segment::Keep(range) if range.start == range.end => (),
+            Segment::Keep(range) => {
+                if range.start > kept_up_to || !inserted.is_empty() {
+                    replacements.push(Replace {
+                        range: kept_up_to..range.start,
+                        with: core::mem::take(&mut inserted),
+                    });
+                }
+
+                kept_up_to = range.end;
+            }
+        }
+    }
+
+    replacements_to_edits(&replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compose::*;
+    use crate::edit::apply_edits;
+
+    #[test]
+    fn compose_edits_test() {
+        let a = "FLAW".as_bytes();
+        let c = "FFLAWANN".as_bytes();
+
+        // FLAW -> LAWN
+        let a_to_b = vec![Edit::<u8>::Insert(4, b'N'), Edit::<u8>::Delete(1)];
+
+        // LAWN -> FFLAWANN
+        let b_to_c = vec![
+            Edit::<u8>::Insert(4, b'N'),
+            Edit::<u8>::Insert(3, b'A'),
+            Edit::<u8>::Insert(0, b'F'),
+            Edit::<u8>::Insert(0, b'F'),
+        ];
+
+        let a_to_c = compose_edits(&a_to_b, &b_to_c);
+
+        assert_eq!(apply_edits(a, &a_to_c), c);
+    }
+
+    #[test]
+    fn compose_with_empty_edits_test() {
+        let a_to_b = vec![Edit::<u8>::Substitute(1, b'M')];
+
+        assert!(compose_edits(&a_to_b, &[]) == a_to_b);
+        assert!(compose_edits(&[], &a_to_b) == a_to_b);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::distance::{levenshtein_tabulation_with_buffer, levenshtein_two_rows_with_buffer};
+use crate::edit::{generate_edits, Edit};
+use crate::util::{DistanceCell, DistanceMatrix};
+use alloc::vec::Vec;
+
+/// A session for computing many distances and edits, which keeps its working storage between
+/// calls instead of allocating it every time.
+///
+/// A `Differ` owns a distance matrix and the rows used when only the distance is needed, both of
+/// which grow to fit the longest sequences it has seen and are then reused. A long-running
+/// service can keep one per thread and compare sequences without allocating, except for the
+/// edits it asks for. The cells of the matrix are stored as `C`; see `distance_as`.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Differ;
+///
+/// let mut differ: Differ = Differ::new();
+///
+/// assert_eq!(differ.distance_only(b"SATURDAY", b"SUNDAY"), 3);
+///
+/// let (distance, matrix) = differ.distance(b"KITTEN", b"SITTING");
+/// assert_eq!(distance, 3);
+/// assert_eq!(matrix.dims(), (7, 8));
+///
+/// let edits = differ.edits(b"FLAW", b"LAWN");
+/// assert_eq!(levenshtein::apply_edits(b"FLAW", &edits), b"LAWN");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Differ<C = usize> {
+    matrix: DistanceMatrix<C>,
+    rows: Vec<usize>,
+}
+
+impl<C: DistanceCell + Default> Differ<C> {
+    /// Returns a session whose buffers are still empty.
+    pub fn new() -> Self {
+        Differ {
+            matrix: DistanceMatrix::default(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Returns the Levenshtein distance between source and target, along with the distance
+    /// matrix, which is kept until the next call that computes one. See `distance`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence
+    /// * `target` - The target sequence
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the longer sequence doesn't fit in `C`.
+    pub fn distance<T: PartialEq>(
+        &mut self,
+        source: &[T],
+        target: &[T],
+    ) -> (usize, &DistanceMatrix<C>) {
+        let distance = levenshtein_tabulation_with_buffer(source, target, &mut self.matrix);
+
+        (distance, &self.matrix)
+    }
+
+    /// Returns only the Levenshtein distance between source and target, which takes much less
+    /// memory than computing the matrix. See `distance_only`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence
+    /// * `target` - The target sequence
+    pub fn distance_only<T: PartialEq>(&mut self, source: &[T], target: &[T]) -> usize {
+        levenshtein_two_rows_with_buffer(source, target, &mut self.rows)
+    }
+
+    /// Returns the edits that transform source into target, in the same form `generate_edits`
+    /// returns them.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence
+    /// * `target` - The target sequence
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the longer sequence doesn't fit in `C`.
+    pub fn edits<T: Clone + PartialEq>(&mut self, source: &[T], target: &[T]) -> Vec<Edit<T>> {
+        levenshtein_tabulation_with_buffer(source, target, &mut self.matrix);
+
+        generate_edits(source, target, &self.matrix).expect("the matrix was computed for these")
+    }
+
+    /// Returns the distance matrix of the last call to `distance` or `edits`.
+    pub fn matrix(&self) -> &DistanceMatrix<C> {
+        &self.matrix
+    }
+}
+
+impl<C: DistanceCell + Default> Default for Differ<C> {
+    fn default() -> Self {
+        Differ::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::differ::*;
+
+    #[test]
+    fn differ_test() {
+        let mut differ = Differ::<u32>::new();
+
+        // Shorter sequences after longer ones get a matrix of their own size
+        for (source, target) in [("FFLAWANN", "LAWN"), ("LAWN", "FLAW"), ("", "")] {
+            let (source, target) = (source.as_bytes(), target.as_bytes());
+            let (expected, _) = crate::distance(source, target);
+
+            let (distance, matrix) = differ.distance(source, target);
+            assert_eq!(distance, expected);
+            assert_eq!(matrix.dims(), (source.len() + 1, target.len() + 1));
+
+            assert_eq!(differ.distance_only(source, target), expected);
+
+            let edits = differ.edits(source, target);
+            assert_eq!(crate::apply_edits(source, &edits), target);
+            assert_eq!(differ.matrix().distance() as usize, expected);
+        }
+    }
+}
@@ -1,5 +1,8 @@
-use std::cmp::{max, min};
+use alloc::vec::Vec;
+use core::cmp::{max, min};
 
+use crate::cancel::CancelToken;
+use crate::edit::{generate_edits, Edit, LevenshteinError};
 use crate::util::*;
 
 /// Returns the Levenshtein distance between source and target using Naive Recursion
@@ -22,7 +25,7 @@ use crate::util::*;
 /// let s1 = "SATURDAY";
 /// let s2 = "SUNDAY";
 /// let expected_leven = 3;
-
+///
 /// let leven_naive = levenshtein::levenshtein_naive(s1.as_bytes(), s2.as_bytes());
 /// assert_eq!(leven_naive, expected_leven);
 /// ```
@@ -64,38 +67,160 @@ pub fn levenshtein_naive<T: PartialEq>(source: &[T], target: &[T]) -> usize {
 /// let s1 = "SATURDAY";
 /// let s2 = "SUNDAY";
 /// let expected_leven = 3;
-
+///
 /// let (leven_naive, _) = levenshtein::levenshtein_tabulation(s1.as_bytes(), s2.as_bytes());
 /// assert_eq!(leven_naive, expected_leven);
 /// ```
 pub fn levenshtein_tabulation<T: PartialEq>(source: &[T], target: &[T]) -> (usize, DistanceMatrix) {
+    levenshtein_tabulation_as(source, target)
+}
+
+/// Returns the Levenshtein distance and the distance matrix between source and target using
+/// dynamic programming with tabulation, with the cells of the matrix stored as `C`.
+///
+/// A narrower cell type than the default `usize` makes the matrix proportionally smaller; with
+/// `u32` cells it takes half the memory. The matrix works with `generate_edits` and the other
+/// functions that take a `DistanceMatrix` just the same.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(n^2).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Panics
+///
+/// Panics if the length of the longer sequence doesn't fit in `C`.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::util::DistanceMatrix;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (leven, matrix): (usize, DistanceMatrix<u32>) =
+///     levenshtein::levenshtein_tabulation_as(s1.as_bytes(), s2.as_bytes());
+/// assert_eq!(leven, 3);
+///
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+/// assert_eq!(levenshtein::apply_edits(s1.as_bytes(), &edits), s2.as_bytes());
+/// ```
+pub fn levenshtein_tabulation_as<T: PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+) -> (usize, DistanceMatrix<C>) {
+    let mut distances = DistanceMatrix::filled(0, 0, C::from_usize(0).unwrap());
+    let distance = levenshtein_tabulation_with_buffer(source, target, &mut distances);
+
+    (distance, distances)
+}
+
+/// Returns the Levenshtein distance between source and target using dynamic programming with
+/// tabulation, and fills the given distance matrix with the distances instead of allocating a new
+/// one.
+///
+/// Whatever the matrix held before is overwritten, and its storage is reused, so calling this in a
+/// loop with the same matrix only allocates when the sequences get longer than they have been
+/// before.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(n^2).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `distances` - The matrix to fill
+///
+/// # Panics
+///
+/// Panics if the length of the longer sequence doesn't fit in `C`.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::util::DistanceMatrix;
+///
+/// let mut matrix: DistanceMatrix = DistanceMatrix::default();
+///
+/// for (s1, s2) in [("SATURDAY", "SUNDAY"), ("KITTEN", "SITTING")] {
+///     let (s1, s2) = (s1.as_bytes(), s2.as_bytes());
+///
+///     let leven = levenshtein::levenshtein_tabulation_with_buffer(s1, s2, &mut matrix);
+///     assert_eq!(leven, 3);
+///
+///     let edits = levenshtein::generate_edits(s1, s2, &matrix).unwrap();
+///     assert_eq!(levenshtein::apply_edits(s1, &edits), s2);
+/// }
+/// ```
+pub fn levenshtein_tabulation_with_buffer<T: PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+    distances: &mut DistanceMatrix<C>,
+) -> usize {
     let m = source.len();
     let n = target.len();
 
-    // table of distances
-    let mut distances = get_distance_table(m, n);
+    // No cell is larger than the length of the longer sequence, so if that fits, everything does
+    let cell = |value: usize| C::from_usize(value).unwrap();
+    assert!(
+        C::from_usize(max(m, n)).is_some(),
+        "the sequences are too long for the cell type"
+    );
+    let one = cell(1);
+
+    // table of distances, with the first row 0..n+1 and the first column 0..m+1
+    distances.refill(m + 1, n + 1, cell(0));
+
+    for j in 1..n + 1 {
+        distances.set(0, j, cell(j));
+    }
 
-    for i in 1..distances.len() {
-        for j in 1..distances[0].len() {
+    for i in 1..m + 1 {
+        let (above, row) = distances.row_pair_mut(i);
+        row[0] = cell(i);
+
+        for j in 1..n + 1 {
             if source[i - 1] == target[j - 1] {
                 // The item being looked at is the same, so the distance won't increase
-                distances[i][j] = distances[i - 1][j - 1];
+                row[j] = above[j - 1];
                 continue;
             }
 
-            let delete = distances[i - 1][j] + 1;
-            let insert = distances[i][j - 1] + 1;
-            let substitute = distances[i - 1][j - 1] + 1;
+            let delete = above[j];
+            let insert = row[j - 1];
+            let substitute = above[j - 1];
 
-            distances[i][j] = min(min(delete, insert), substitute);
+            row[j] = min(min(delete, insert), substitute) + one;
         }
     }
 
-    (distances[m][n], distances)
+    distances.distance().to_usize()
+}
+
+// Returns the edits that turn source into target, generated from their full distance matrix
+pub(crate) fn diff_edits<T: Clone + PartialEq>(source: &[T], target: &[T]) -> Vec<Edit<T>> {
+    let (_, matrix) = levenshtein_tabulation(source, target);
+
+    // The matrix was built for these very sequences, so it can't be invalid
+    generate_edits(source, target, &matrix).unwrap()
 }
 
+// The number of rows and columns of the tiles `par_levenshtein_tabulation` fills at a time
+#[cfg(feature = "rayon")]
+const TILE_SIZE: usize = 256;
+
 /// Returns the Levenshtein distance and the distance matrix between source and target using
-/// dynamic programming with memoization.
+/// dynamic programming with tabulation, filling the matrix on the rayon thread pool.
+///
+/// The matrix is split into square tiles, and the tiles are filled one anti-diagonal at a time:
+/// every tile only depends on the ones above it and to its left, so all the tiles of an
+/// anti-diagonal can be filled at once. The result is identical to `levenshtein_tabulation`'s,
+/// and worth the overhead once both sequences are a few thousand items long.
 ///
 /// This implementation has a time complexity of O(n^2) and a space complexity of O(n^2).
 ///
@@ -112,87 +237,1635 @@ pub fn levenshtein_tabulation<T: PartialEq>(source: &[T], target: &[T]) -> (usiz
 /// let s1 = "SATURDAY";
 /// let s2 = "SUNDAY";
 /// let expected_leven = 3;
-
-/// let (leven_naive, _) = levenshtein::levenshtein_memoization(s1.as_bytes(), s2.as_bytes());
-/// assert_eq!(leven_naive, expected_leven);
+///
+/// let (leven_par, _) = levenshtein::par_levenshtein_tabulation(s1.as_bytes(), s2.as_bytes());
+/// assert_eq!(leven_par, expected_leven);
 /// ```
-pub fn levenshtein_memoization<T: PartialEq>(
+#[cfg(feature = "rayon")]
+pub fn par_levenshtein_tabulation<T: PartialEq + Sync>(
     source: &[T],
     target: &[T],
 ) -> (usize, DistanceMatrix) {
-    fn levenshtein_memoization_helper<T: PartialEq>(
-        source: &[T],
-        target: &[T],
-        distances: &mut DistanceMatrix,
-    ) -> usize {
-        // check the cache first
-        if distances[source.len()][target.len()] < usize::MAX {
-            return distances[source.len()][target.len()];
+    use rayon::prelude::*;
+
+    let m = source.len();
+    let n = target.len();
+    let cols = n + 1;
+
+    let mut distances = get_distance_table(m, n);
+
+    if m == 0 || n == 0 {
+        return (distances.distance(), distances);
+    }
+
+    let tile_rows = m.div_ceil(TILE_SIZE);
+    let tile_cols = n.div_ceil(TILE_SIZE);
+
+    // The row above every row of tiles, as far as it has been filled: the first row of the table,
+    // and then the last row of every row of tiles
+    let mut above_tiles: Vec<Vec<usize>> = (0..tile_rows)
+        .map(|ti| {
+            let mut row = vec![usize::MAX; cols];
+            row[0] = ti * TILE_SIZE;
+            row
+        })
+        .collect();
+    above_tiles[0] = (0..cols).collect();
+
+    for diagonal in 0..tile_rows + tile_cols - 1 {
+        // Every row of tiles fills at most one tile of the anti-diagonal, and hands back the last
+        // row of it for the row of tiles below
+        let filled: Vec<(usize, usize, Vec<usize>)> = distances
+            .rows_from_mut(1)
+            .par_chunks_mut(TILE_SIZE * cols)
+            .enumerate()
+            .filter_map(|(ti, tile_row)| {
+                let tj = diagonal.checked_sub(ti).filter(|&tj| tj < tile_cols)?;
+
+                let height = tile_row.len() / cols;
+                let first_col = tj * TILE_SIZE + 1;
+                let last_col = min(first_col + TILE_SIZE, cols);
+
+                for r in 0..height {
+                    let i = ti * TILE_SIZE + r + 1;
+                    let (above, row) = if r == 0 {
+                        (&above_tiles[ti][..], &mut tile_row[..cols])
+                    } else {
+                        let (before, rest) = tile_row.split_at_mut(r * cols);
+                        (&before[(r - 1) * cols..], &mut rest[..cols])
+                    };
+
+                    for j in first_col..last_col {
+                        if source[i - 1] == target[j - 1] {
+                            row[j] = above[j - 1];
+                            continue;
+                        }
+
+                        row[j] = min(min(above[j], row[j - 1]), above[j - 1]) + 1;
+                    }
+                }
+
+                let last_row = &tile_row[(height - 1) * cols..];
+                Some((ti, first_col, last_row[first_col..last_col].to_vec()))
+            })
+            .collect();
+
+        for (ti, first_col, last_row) in filled {
+            if ti + 1 < tile_rows {
+                above_tiles[ti + 1][first_col..first_col + last_row.len()]
+                    .copy_from_slice(&last_row);
+            }
         }
+    }
+
+    (distances.distance(), distances)
+}
+
+/// Extends a distance matrix between source and target to items appended to the source since, and
+/// returns the new distance.
+///
+/// Only the rows for the new items are computed, so keeping a matrix up to date with a sequence
+/// that keeps growing costs as much as the items that were added, rather than the whole sequence
+/// every time. The matrix is left as `levenshtein_tabulation` would compute it for the whole
+/// source.
+///
+/// Returns an error, and leaves the matrix as it was, if it wasn't computed for a prefix of the
+/// source and the whole target, or if it's banded.
+///
+/// # Arguments
+///
+/// * `source` - The whole source sequence, including the items appended to it
+/// * `target` - The target sequence
+/// * `distances` - The distance matrix for the source as it was before, and the target
+///
+/// # Panics
+///
+/// Panics if the length of the longer sequence doesn't fit in `C`.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let mut log = "SAT".as_bytes().to_vec();
+/// let template = "SATURDAY".as_bytes();
+///
+/// let (_, mut matrix) = levenshtein::distance(&log, template);
+///
+/// log.extend_from_slice("URDAY".as_bytes());
+/// let distance = levenshtein::append_to_source(&log, template, &mut matrix).unwrap();
+///
+/// assert_eq!(distance, 0);
+/// assert_eq!(matrix, levenshtein::distance(&log, template).1);
+/// ```
+pub fn append_to_source<T: PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+    distances: &mut DistanceMatrix<C>,
+) -> Result<usize, LevenshteinError> {
+    let (rows, cols) = distances.dims();
+
+    if distances.is_banded() || rows > source.len() + 1 || cols != target.len() + 1 {
+        return Err(LevenshteinError::InvalidDistanceMatrixError);
+    }
+
+    let cell = |value: usize| C::from_usize(value).unwrap();
+    assert!(
+        C::from_usize(max(source.len(), target.len())).is_some(),
+        "the sequences are too long for the cell type"
+    );
+    let one = cell(1);
+
+    distances.add_rows(source.len() + 1 - rows, cell(0));
+
+    for i in rows..source.len() + 1 {
+        let (above, row) = distances.row_pair_mut(i);
+        row[0] = cell(i);
+
+        for j in 1..cols {
+            if source[i - 1] == target[j - 1] {
+                row[j] = above[j - 1];
+                continue;
+            }
 
-        // base case
-        if source.is_empty() || target.is_empty() {
-            return max(source.len(), target.len());
+            row[j] = min(min(above[j], row[j - 1]), above[j - 1]) + one;
         }
+    }
 
-        // couldn't find the value, time to recursively calculate it
+    Ok(distances.distance().to_usize())
+}
 
-        let k = if source.last() == target.last() { 0 } else { 1 };
+/// Extends a distance matrix between source and target to items appended to the target since, and
+/// returns the new distance.
+///
+/// Only the columns for the new items are computed, though the matrix is laid out row by row, so
+/// its cells are still moved to make room for them. See `append_to_source`, which is cheaper if
+/// the growing sequence can be made the source.
+///
+/// Returns an error, and leaves the matrix as it was, if it wasn't computed for the whole source
+/// and a prefix of the target, or if it's banded.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The whole target sequence, including the items appended to it
+/// * `distances` - The distance matrix for the source, and the target as it was before
+///
+/// # Panics
+///
+/// Panics if the length of the longer sequence doesn't fit in `C`.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let template = "SATURDAY".as_bytes();
+/// let mut log = "SUN".as_bytes().to_vec();
+///
+/// let (_, mut matrix) = levenshtein::distance(template, &log);
+///
+/// log.extend_from_slice("DAY".as_bytes());
+/// let distance = levenshtein::append_to_target(template, &log, &mut matrix).unwrap();
+///
+/// assert_eq!(distance, 3);
+/// assert_eq!(matrix, levenshtein::distance(template, &log).1);
+/// ```
+pub fn append_to_target<T: PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+    distances: &mut DistanceMatrix<C>,
+) -> Result<usize, LevenshteinError> {
+    let (rows, cols) = distances.dims();
 
-        let delete = levenshtein_memoization_helper(up_to_last(source), target, distances) + 1;
-        let insert = levenshtein_memoization_helper(source, up_to_last(target), distances) + 1;
-        let substitute =
-            levenshtein_memoization_helper(up_to_last(source), up_to_last(target), distances) + k;
+    if distances.is_banded() || rows != source.len() + 1 || cols > target.len() + 1 {
+        return Err(LevenshteinError::InvalidDistanceMatrixError);
+    }
 
-        let distance = min(min(delete, insert), substitute);
+    let cell = |value: usize| C::from_usize(value).unwrap();
+    assert!(
+        C::from_usize(max(source.len(), target.len())).is_some(),
+        "the sequences are too long for the cell type"
+    );
+    let one = cell(1);
 
-        // update the cache
-        distances[source.len()][target.len()] = distance;
+    distances.add_cols(target.len() + 1 - cols, cell(0));
 
-        distance
+    for j in cols..target.len() + 1 {
+        distances.set(0, j, cell(j));
     }
 
-    let mut distances = get_distance_table(source.len(), target.len());
+    for i in 1..rows {
+        let (above, row) = distances.row_pair_mut(i);
 
-    let distance = levenshtein_memoization_helper(source, target, &mut distances);
+        for j in cols..target.len() + 1 {
+            if source[i - 1] == target[j - 1] {
+                row[j] = above[j - 1];
+                continue;
+            }
 
-    (distance, distances)
+            row[j] = min(min(above[j], row[j - 1]), above[j - 1]) + one;
+        }
+    }
+
+    Ok(distances.distance().to_usize())
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::distance::*;
+/// The operation that produced a cell of the distance matrix, i.e. the step taken from one of its
+/// neighbors to reach it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Step {
+    /// The items are the same, coming from the diagonal neighbor
+    Match,
+    /// A target item is inserted, coming from the neighbor to the left
+    Insert,
+    /// A source item is deleted, coming from the neighbor above
+    Delete,
+    /// A source item is substituted with a target item, coming from the diagonal neighbor
+    Substitute,
+}
 
-    #[test]
-    fn levenshtein_naive_test() {
-        let s1 = String::from("LAWN");
-        let s2 = String::from("FFLAWANN");
-        let expected_leven = 4;
+/// A matrix of the same dimensions as a `DistanceMatrix`, recording the `Step` that produced each
+/// cell. It's stored the same way, as a single row-major allocation, and is indexed by
+/// `(row, column)`.
+pub type BackpointerMatrix = DistanceMatrix<Step>;
 
-        let leven_naive = levenshtein_naive(s1.as_bytes(), s2.as_bytes());
+/// Returns the Levenshtein distance, the distance matrix and the backpointer matrix between source
+/// and target using dynamic programming with tabulation.
+///
+/// The backpointers record the decision made for every cell, so edits can be generated from them
+/// with `generate_edits_from_backpointers` in O(m + n) time, without re-deriving each decision
+/// from the neighboring distances. Ties are broken the same way `generate_edits` breaks them.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(n^2).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Step;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (leven, _, backpointers) =
+///     levenshtein::levenshtein_tabulation_backpointers(s1.as_bytes(), s2.as_bytes());
+///
+/// assert_eq!(leven, 3);
+/// assert_eq!(backpointers[(8, 6)], Step::Match); // Both end with Y
+/// ```
+pub fn levenshtein_tabulation_backpointers<T: PartialEq>(
+    source: &[T],
+    target: &[T],
+) -> (usize, DistanceMatrix, BackpointerMatrix) {
+    let m = source.len();
+    let n = target.len();
 
-        assert_eq!(leven_naive, expected_leven);
+    let mut distances = get_distance_table(m, n);
+
+    // The first row can only be reached by inserting, and the first column by deleting
+    let mut backpointers = DistanceMatrix::filled(m + 1, n + 1, Step::Delete);
+    for j in 1..n + 1 {
+        backpointers.set(0, j, Step::Insert);
     }
+    backpointers.set(0, 0, Step::Match);
 
-    #[test]
-    fn levenshtein_memoization_test() {
-        let s1 = String::from("LAWN");
-        let s2 = String::from("FFLAWANN");
-        let expected_leven = 4;
+    for i in 1..m + 1 {
+        for j in 1..n + 1 {
+            let delete = distances[(i - 1, j)];
+            let insert = distances[(i, j - 1)];
+            let diagonal = distances[(i - 1, j - 1)];
 
-        let (leven_memo, _) = levenshtein_memoization(s1.as_bytes(), s2.as_bytes());
+            let min = min(min(delete, insert), diagonal);
 
-        assert_eq!(leven_memo, expected_leven);
+            // Keep matching items unless a neighbor is even closer, then prefer inserting,
+            // deleting and substituting, in that order
+            let (step, distance) = if source[i - 1] == target[j - 1] && min == diagonal {
+                (Step::Match, diagonal)
+            } else if min == insert {
+                (Step::Insert, insert + 1)
+            } else if min == delete {
+                (Step::Delete, delete + 1)
+            } else {
+                (Step::Substitute, diagonal + 1)
+            };
+
+            distances.set(i, j, distance);
+            backpointers.set(i, j, step);
+        }
     }
 
-    #[test]
-    fn levenshtein_tabulation_test() {
-        let s1 = String::from("LAWN");
-        let s2 = String::from("FFLAWANN");
-        let expected_leven = 4;
+    (distances.distance(), distances, backpointers)
+}
 
-        let (leven_tab, _) = levenshtein_tabulation(s1.as_bytes(), s2.as_bytes());
+/// Returns the Levenshtein distance and the distance matrix between source and target using
+/// dynamic programming with tabulation, unless the token is cancelled first.
+///
+/// The token is checked before every row of the matrix. If it is cancelled, or its deadline
+/// passes, `LevenshteinError::Cancelled` is returned with the number of rows that were filled.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(n^2).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `token` - The token to check
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{CancelToken, LevenshteinError};
+///
+/// let s1 = "SATURDAY".as_bytes();
+/// let s2 = "SUNDAY".as_bytes();
+///
+/// let token = CancelToken::new();
+/// token.cancel();
+///
+/// let result = levenshtein::levenshtein_tabulation_cancellable(s1, s2, &token);
+/// assert!(matches!(result, Err(LevenshteinError::Cancelled(0))));
+/// ```
+pub fn levenshtein_tabulation_cancellable<T: PartialEq>(
+    source: &[T],
+    target: &[T],
+    token: &CancelToken,
+) -> Result<(usize, DistanceMatrix), LevenshteinError> {
+    tabulate_checked(source, target, |rows| {
+        if token.is_cancelled() {
+            Err(LevenshteinError::Cancelled(rows))
+        } else {
+            Ok(())
+        }
+    })
+}
 
-        assert_eq!(leven_tab, expected_leven);
+/// Returns the Levenshtein distance and the distance matrix between source and target using
+/// dynamic programming with tabulation, reporting progress as the matrix is filled.
+///
+/// The callback is called with the number of rows of the matrix that are filled and the number
+/// there are in all, before every row and once more at the end, e.g. to drive a progress bar.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(n^2).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `progress` - The callback, called with the rows filled and the total
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY".as_bytes();
+/// let s2 = "SUNDAY".as_bytes();
+///
+/// let mut reports = Vec::new();
+/// let (leven, _) = levenshtein::levenshtein_tabulation_with_progress(s1, s2, |done, total| {
+///     reports.push((done, total))
+/// });
+///
+/// assert_eq!(leven, 3);
+/// assert_eq!(reports.first(), Some(&(0, 8)));
+/// assert_eq!(reports.last(), Some(&(8, 8)));
+/// ```
+pub fn levenshtein_tabulation_with_progress<T: PartialEq, F: FnMut(usize, usize)>(
+    source: &[T],
+    target: &[T],
+    mut progress: F,
+) -> (usize, DistanceMatrix) {
+    let total = source.len();
+
+    let result = tabulate_checked(source, target, |rows| {
+        progress(rows, total);
+        Ok(())
+    })
+    .expect("the check never fails");
+
+    progress(total, total);
+
+    result
+}
+
+// Fills the distance matrix like `levenshtein_tabulation`, calling check with the number of rows
+// filled before every row, and stopping with its error if it returns one
+fn tabulate_checked<T: PartialEq, F: FnMut(usize) -> Result<(), LevenshteinError>>(
+    source: &[T],
+    target: &[T],
+    mut check: F,
+) -> Result<(usize, DistanceMatrix), LevenshteinError> {
+    let m = source.len();
+    let n = target.len();
+
+    let mut distances = get_distance_table(m, n);
+
+    for i in 1..m + 1 {
+        check(i - 1)?;
+
+        let (above, row) = distances.row_pair_mut(i);
+
+        for j in 1..n + 1 {
+            if source[i - 1] == target[j - 1] {
+                row[j] = above[j - 1];
+                continue;
+            }
+
+            row[j] = min(min(above[j], row[j - 1]), above[j - 1]) + 1;
+        }
+    }
+
+    Ok((distances.distance(), distances))
+}
+
+/// Returns the Levenshtein distance and the distance matrix between source and target using
+/// dynamic programming with memoization.
+///
+/// The subproblems are worked out with an explicit stack rather than by recursion, so long
+/// sequences can't overflow the call stack.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(n^2).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+/// let expected_leven = 3;
+///
+/// let (leven_naive, _) = levenshtein::levenshtein_memoization(s1.as_bytes(), s2.as_bytes());
+/// assert_eq!(leven_naive, expected_leven);
+/// ```
+pub fn levenshtein_memoization<T: PartialEq>(
+    source: &[T],
+    target: &[T],
+) -> (usize, DistanceMatrix) {
+    let mut distances = get_distance_table(source.len(), target.len());
+
+    // The cells whose distances are being worked out, each waiting on the one after it. Instead of
+    // recursing into every subproblem, only the first one that isn't cached yet is pushed, so the
+    // stack never gets deeper than the path from the last cell to the first row or column, and it
+    // lives on the heap rather than the call stack.
+    let mut pending = vec![(source.len(), target.len())];
+
+    while let Some(&(i, j)) = pending.last() {
+        // check the cache first; the first row and column are always there
+        if distances[(i, j)] < usize::MAX {
+            pending.pop();
+            continue;
+        }
+
+        let subproblems = [(i - 1, j), (i, j - 1), (i - 1, j - 1)];
+
+        // couldn't find the value, so every subproblem has to be calculated first
+        if let Some(&subproblem) = subproblems
+            .iter()
+            .find(|&&(i, j)| distances[(i, j)] == usize::MAX)
+        {
+            pending.push(subproblem);
+            continue;
+        }
+
+        let k = if source[i - 1] == target[j - 1] { 0 } else { 1 };
+
+        let delete = distances[(i - 1, j)] + 1;
+        let insert = distances[(i, j - 1)] + 1;
+        let substitute = distances[(i - 1, j - 1)] + k;
+
+        // update the cache
+        distances.set(i, j, min(min(delete, insert), substitute));
+        pending.pop();
+    }
+
+    let distance = distances.distance();
+
+    (distance, distances)
+}
+
+/// Returns the Levenshtein distance between source and target using dynamic programming with
+/// tabulation, keeping only the two most recent rows of the table.
+///
+/// The shorter of the two sequences is used for the rows, so unlike `levenshtein_tabulation` the
+/// distance matrix is never materialized.
+///
+/// When the shorter sequence has fewer than 64 items, the row is kept on the stack and nothing is
+/// allocated, which is most of the cost of comparing short strings like identifiers.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(min(m, n)).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+/// let expected_leven = 3;
+///
+/// let leven_two_rows = levenshtein::levenshtein_two_rows(s1.as_bytes(), s2.as_bytes());
+/// assert_eq!(leven_two_rows, expected_leven);
+/// ```
+pub fn levenshtein_two_rows<T: PartialEq>(source: &[T], target: &[T]) -> usize {
+    if min(source.len(), target.len()) < SMALL_ROW {
+        return levenshtein_small(source, target);
+    }
+
+    levenshtein_two_rows_with_buffer(source, target, &mut Vec::new())
+}
+
+// The length of the shorter sequence below which levenshtein_small can be used
+const SMALL_ROW: usize = 64;
+
+// The two rows DP with a single row on the stack, for when the shorter sequence has fewer than
+// SMALL_ROW items. The cell above and to the left, which the row has already overwritten, is
+// carried in a variable instead.
+fn levenshtein_small<T: PartialEq>(source: &[T], target: &[T]) -> usize {
+    let (long, short) = if source.len() >= target.len() {
+        (source, target)
+    } else {
+        (target, source)
+    };
+
+    let mut row = [0usize; SMALL_ROW];
+    let row = &mut row[..short.len() + 1];
+
+    for (j, cell) in row.iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for (i, long_item) in long.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, short_item) in short.iter().enumerate() {
+            let above = row[j + 1];
+
+            row[j + 1] = if long_item == short_item {
+                diagonal
+            } else {
+                min(min(above, row[j]), diagonal) + 1
+            };
+
+            diagonal = above;
+        }
+    }
+
+    row[short.len()]
+}
+
+/// Returns the Levenshtein distance between source and target using two rows of storage taken
+/// from the given buffer, instead of allocating them.
+///
+/// Whatever the buffer held before is overwritten, and its storage is reused, so calling this in a
+/// loop with the same buffer only allocates when the sequences get longer than they have been
+/// before.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(n).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `buffer` - The storage for the rows
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let mut buffer = Vec::new();
+///
+/// for (s1, s2) in [("SATURDAY", "SUNDAY"), ("KITTEN", "SITTING")] {
+///     let (s1, s2) = (s1.as_bytes(), s2.as_bytes());
+///
+///     let leven = levenshtein::levenshtein_two_rows_with_buffer(s1, s2, &mut buffer);
+///     assert_eq!(leven, 3);
+/// }
+/// ```
+pub fn levenshtein_two_rows_with_buffer<T: PartialEq>(
+    source: &[T],
+    target: &[T],
+    buffer: &mut Vec<usize>,
+) -> usize {
+    // The distance is symmetric, so the rows can always span the shorter sequence
+    let (long, short) = if source.len() >= target.len() {
+        (source, target)
+    } else {
+        (target, source)
+    };
+
+    buffer.clear();
+    buffer.extend(0..short.len() + 1);
+    buffer.resize(2 * (short.len() + 1), 0);
+
+    let (mut prev, mut curr) = buffer.split_at_mut(short.len() + 1);
+
+    for i in 1..long.len() + 1 {
+        curr[0] = i;
+
+        for j in 1..short.len() + 1 {
+            if long[i - 1] == short[j - 1] {
+                curr[j] = prev[j - 1];
+                continue;
+            }
+
+            let delete = prev[j] + 1;
+            let insert = curr[j - 1] + 1;
+            let substitute = prev[j - 1] + 1;
+
+            curr[j] = min(min(delete, insert), substitute);
+        }
+
+        core::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[short.len()]
+}
+
+/// Returns the Levenshtein distance between source and target using two rows of storage, unless
+/// the token is cancelled first. See `levenshtein_two_rows`.
+///
+/// The token is checked before every row. If it is cancelled, or its deadline passes,
+/// `LevenshteinError::Cancelled` is returned with the number of rows that were filled.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(min(m, n)).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `token` - The token to check
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::CancelToken;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let token = CancelToken::new();
+///
+/// let leven = levenshtein::levenshtein_two_rows_cancellable(s1.as_bytes(), s2.as_bytes(), &token);
+/// assert_eq!(leven.unwrap(), 3);
+/// ```
+pub fn levenshtein_two_rows_cancellable<T: PartialEq>(
+    source: &[T],
+    target: &[T],
+    token: &CancelToken,
+) -> Result<usize, LevenshteinError> {
+    // The distance is symmetric, so the rows can always span the shorter sequence
+    let (long, short) = if source.len() >= target.len() {
+        (source, target)
+    } else {
+        (target, source)
+    };
+
+    let mut prev: Vec<usize> = (0..short.len() + 1).collect();
+    let mut curr = vec![0; short.len() + 1];
+
+    for i in 1..long.len() + 1 {
+        if token.is_cancelled() {
+            return Err(LevenshteinError::Cancelled(i - 1));
+        }
+
+        curr[0] = i;
+
+        for j in 1..short.len() + 1 {
+            if long[i - 1] == short[j - 1] {
+                curr[j] = prev[j - 1];
+                continue;
+            }
+
+            curr[j] = min(min(prev[j], curr[j - 1]), prev[j - 1]) + 1;
+        }
+
+        core::mem::swap(&mut prev, &mut curr);
+    }
+
+    Ok(prev[short.len()])
+}
+
+/// The maximum length of the shorter sequence accepted by `levenshtein_const`.
+pub const CONST_MAX_LEN: usize = 256;
+
+/// Returns the Levenshtein distance between two byte strings, and can be evaluated at compile
+/// time.
+///
+/// Heap allocation isn't available in a `const fn`, so a single row of the table is kept in a
+/// fixed-size array on the stack. The shorter of the two sequences must therefore be at most
+/// `CONST_MAX_LEN` bytes long; evaluation panics (or fails to compile, in a const context)
+/// otherwise.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(1).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// // Checked at compile time
+/// const _: () = assert!(levenshtein::levenshtein_const(b"commit", b"comit") == 1);
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+/// let expected_leven = 3;
+///
+/// let leven_const = levenshtein::levenshtein_const(s1.as_bytes(), s2.as_bytes());
+/// assert_eq!(leven_const, expected_leven);
+/// ```
+pub const fn levenshtein_const(source: &[u8], target: &[u8]) -> usize {
+    // The distance is symmetric, so the row can always span the shorter sequence
+    let (long, short) = if source.len() >= target.len() {
+        (source, target)
+    } else {
+        (target, source)
+    };
+
+    assert!(
+        short.len() <= CONST_MAX_LEN,
+        "levenshtein_const: sequences are too long"
+    );
+
+    let mut row = [0; CONST_MAX_LEN + 1];
+
+    let mut j = 0;
+    while j <= short.len() {
+        row[j] = j;
+        j += 1;
+    }
+
+    let mut i = 1;
+    while i <= long.len() {
+        // row[j - 1] of the previous row, which is overwritten before it's needed
+        let mut diagonal = row[0];
+        row[0] = i;
+
+        let mut j = 1;
+        while j <= short.len() {
+            let above = row[j];
+
+            row[j] = if long[i - 1] == short[j - 1] {
+                diagonal
+            } else {
+                let delete = above + 1;
+                let insert = row[j - 1] + 1;
+                let substitute = diagonal + 1;
+
+                let min = if delete < insert { delete } else { insert };
+                if min < substitute {
+                    min
+                } else {
+                    substitute
+                }
+            };
+
+            diagonal = above;
+            j += 1;
+        }
+
+        i += 1;
+    }
+
+    row[short.len()]
+}
+
+// The number of cells of an anti-diagonal `levenshtein_simd` computes at once
+#[cfg(feature = "simd")]
+pub(crate) const SIMD_LANES: usize = 16;
+
+/// Returns the Levenshtein distance between two byte strings, computing many cells of the table
+/// at once with SIMD instructions.
+///
+/// The table is filled one anti-diagonal at a time rather than row by row. The cells of an
+/// anti-diagonal only depend on the two anti-diagonals before it, never on each other, so they
+/// are computed `SIMD_LANES` at a time with `std::simd`; the target is reversed up front so that
+/// the items compared for consecutive cells are adjacent in both sequences. This requires a
+/// nightly compiler and the `simd` feature, and is much faster when built for a CPU with wide
+/// vectors, e.g. with `-C target-cpu=native`.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(n).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+/// let expected_leven = 3;
+///
+/// let leven_simd = levenshtein::levenshtein_simd(s1.as_bytes(), s2.as_bytes());
+/// assert_eq!(leven_simd, expected_leven);
+/// ```
+#[cfg(feature = "simd")]
+pub fn levenshtein_simd(source: &[u8], target: &[u8]) -> usize {
+    use core::simd::cmp::{SimdOrd, SimdPartialEq};
+    use core::simd::{Select, Simd};
+
+    type Cells = Simd<u32, SIMD_LANES>;
+    type Items = Simd<u8, SIMD_LANES>;
+
+    // The anti-diagonals are indexed by the row of their cells, so they are as long as the source
+    // plus one; keeping the source the shorter of the two keeps them short
+    let (source, target) = if source.len() <= target.len() {
+        (source, target)
+    } else {
+        (target, source)
+    };
+
+    let m = source.len();
+    let n = target.len();
+
+    assert!(
+        n <= u32::MAX as usize,
+        "the sequences are too long for levenshtein_simd"
+    );
+
+    // target[j - 1] for the cell in row i of anti-diagonal d is reversed[n - d + i]
+    let reversed: Vec<u8> = target.iter().rev().copied().collect();
+
+    // The last two anti-diagonals, and the one being filled
+    let mut before_last = vec![0u32; m + 1];
+    let mut last = vec![0u32; m + 1];
+    let mut current = vec![0u32; m + 1];
+
+    let one = Cells::splat(1);
+
+    for d in 1..m + n + 1 {
+        let first_row = d.saturating_sub(n);
+        let last_row = min(d, m);
+
+        // The cells in the first row and the first column
+        if first_row == 0 {
+            current[0] = d as u32;
+        }
+        if last_row == d {
+            current[d] = d as u32;
+        }
+
+        // The cells in between, where i >= 1 and j >= 1
+        let mut i = max(first_row, 1);
+        let end = if last_row == d { d } else { last_row + 1 };
+
+        while i + SIMD_LANES <= end {
+            let offset = n + i - d;
+
+            let delete = Cells::from_slice(&last[i - 1..i - 1 + SIMD_LANES]);
+            let insert = Cells::from_slice(&last[i..i + SIMD_LANES]);
+            let diagonal = Cells::from_slice(&before_last[i - 1..i - 1 + SIMD_LANES]);
+
+            let same = Items::from_slice(&source[i - 1..i - 1 + SIMD_LANES])
+                .simd_eq(Items::from_slice(&reversed[offset..offset + SIMD_LANES]));
+            let substitute = same.cast::<i32>().select(diagonal, diagonal + one);
+
+            (delete.simd_min(insert) + one)
+                .simd_min(substitute)
+                .copy_to_slice(&mut current[i..i + SIMD_LANES]);
+
+            i += SIMD_LANES;
+        }
+
+        // The cells left over after the last full vector
+        for i in i..end {
+            let substitute = if source[i - 1] == reversed[n + i - d] {
+                before_last[i - 1]
+            } else {
+                before_last[i - 1] + 1
+            };
+
+            current[i] = min(min(last[i - 1], last[i]) + 1, substitute);
+        }
+
+        core::mem::swap(&mut before_last, &mut last);
+        core::mem::swap(&mut last, &mut current);
+    }
+
+    last[m] as usize
+}
+
+/// Returns true if the Levenshtein distance between source and target is at most `k`.
+///
+/// This is considerably cheaper than computing the full distance: the length difference is
+/// checked first, and only a diagonal band of width 2k+1 is evaluated using two rows of storage.
+/// No distance matrix is ever allocated, and the computation stops as soon as every cell in a row
+/// of the band exceeds `k`.
+///
+/// This implementation has a time complexity of O(k * min(m, n)) and a space complexity of O(n).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `k` - The maximum allowed distance
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// assert!(levenshtein::is_within_distance(s1.as_bytes(), s2.as_bytes(), 3));
+/// assert!(!levenshtein::is_within_distance(s1.as_bytes(), s2.as_bytes(), 2));
+/// ```
+pub fn is_within_distance<T: PartialEq>(source: &[T], target: &[T], k: usize) -> bool {
+    // The distance is at most the length of the longer sequence
+    if max(source.len(), target.len()) <= k {
+        return true;
+    }
+
+    bounded_distance(source, target, k).is_some()
+}
+
+// Returns the Levenshtein distance between source and target if it is at most k, and None
+// otherwise. Only the diagonal band of width 2k+1 is evaluated, using two rows of storage.
+pub(crate) fn bounded_distance<T: PartialEq>(
+    source: &[T],
+    target: &[T],
+    k: usize,
+) -> Option<usize> {
+    let stripped = strip_common_affix(source, target);
+    let (source, target) = (stripped.source, stripped.target);
+
+    let m = source.len();
+    let n = target.len();
+
+    // The distance is at least the difference in lengths
+    if max(m, n) - min(m, n) > k {
+        return None;
+    }
+
+    // The distance is at most the length of the longer sequence, so a larger k changes nothing
+    let k = k.min(max(m, n));
+
+    // Any value above k is as good as any other, so everything outside the band is capped here
+    let cap = k + 1;
+
+    let mut prev: Vec<usize> = (0..n + 1).map(|j| min(j, cap)).collect();
+    let mut curr = vec![cap; n + 1];
+
+    for i in 1..m + 1 {
+        // The band for row i covers the columns i - k..=i + k
+        let lo = max(i.saturating_sub(k), 1);
+        let hi = min(i + k, n);
+
+        curr[lo - 1] = if lo == 1 { min(i, cap) } else { cap };
+
+        let mut row_min = curr[lo - 1];
+
+        for j in lo..hi + 1 {
+            curr[j] = if source[i - 1] == target[j - 1] {
+                prev[j - 1]
+            } else {
+                let delete = prev[j] + 1;
+                let insert = curr[j - 1] + 1;
+                let substitute = prev[j - 1] + 1;
+
+                min(min(min(delete, insert), substitute), cap)
+            };
+
+            row_min = min(row_min, curr[j]);
+        }
+
+        // The next row reads one column past the end of this band, which must be out of reach
+        if hi < n {
+            curr[hi + 1] = cap;
+        }
+
+        if row_min > k {
+            return None;
+        }
+
+        core::mem::swap(&mut prev, &mut curr);
+    }
+
+    if prev[n] <= k {
+        Some(prev[n])
+    } else {
+        None
+    }
+}
+
+/// Returns the Levenshtein distance and a banded distance matrix between source and target if the
+/// distance is at most `k`, and None otherwise.
+///
+/// Only the cells at most `k` columns away from the diagonal are computed and stored, since an
+/// alignment of cost at most `k` never leaves them; every other cell reads as `k + 1`. The matrix
+/// takes O(k * m) memory instead of O(m * n), and `generate_edits` traces back through it within
+/// the band, producing the same edits as with the full matrix. Cells inside the band whose
+/// distance exceeds `k` are capped at `k + 1` as well, so `validate_distance_matrix` rejects a
+/// banded matrix even though the edits generated from it are correct.
+///
+/// This implementation has a time complexity of O(k * m) and a space complexity of O(k * m).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `k` - The maximum allowed distance
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY".as_bytes();
+/// let s2 = "SUNDAY".as_bytes();
+///
+/// let (leven, matrix) = levenshtein::levenshtein_banded(s1, s2, 3).unwrap();
+/// assert_eq!(leven, 3);
+///
+/// let edits = levenshtein::generate_edits(s1, s2, &matrix).unwrap();
+/// assert_eq!(levenshtein::apply_edits(s1, &edits), s2);
+///
+/// assert!(levenshtein::levenshtein_banded(s1, s2, 2).is_none());
+/// ```
+pub fn levenshtein_banded<T: PartialEq>(
+    source: &[T],
+    target: &[T],
+    k: usize,
+) -> Option<(usize, DistanceMatrix)> {
+    if length_lower_bound(source, target) > k {
+        return None;
+    }
+
+    let m = source.len();
+    let n = target.len();
+
+    // No distance is larger than the longer sequence, so a wider band holds nothing more
+    let k = k.min(max(m, n));
+
+    // Any value above k is as good as any other, so everything outside the band is capped here
+    let cap = k + 1;
+
+    let mut distances = DistanceMatrix::banded(m + 1, n + 1, k, cap);
+
+    for i in 0..m + 1 {
+        // The band for row i covers the columns i - k..=i + k
+        for j in i.saturating_sub(k)..min(i + k, n) + 1 {
+            let distance = if i == 0 || j == 0 {
+                i + j
+            } else if source[i - 1] == target[j - 1] {
+                distances[(i - 1, j - 1)]
+            } else {
+                let delete = distances[(i - 1, j)] + 1;
+                let insert = distances[(i, j - 1)] + 1;
+                let substitute = distances[(i - 1, j - 1)] + 1;
+
+                min(min(delete, insert), substitute)
+            };
+
+            distances.set(i, j, min(distance, cap));
+        }
+    }
+
+    let distance = distances.distance();
+
+    if distance <= k {
+        Some((distance, distances))
+    } else {
+        None
+    }
+}
+
+// The furthest row reached on every diagonal k = j - i with e edits, for e = 0, 1, ... up to the
+// distance. Front e covers the diagonals -e..=e, at index k + e, and is None on the diagonals
+// that are outside the matrix.
+pub(crate) type DiagonalFronts = Vec<Vec<Option<usize>>>;
+
+// Returns the row of front e - 1 at diagonal k, if there is one
+fn front_row(front: &[Option<usize>], k: isize) -> Option<usize> {
+    let index = k + front.len() as isize / 2;
+
+    if index < 0 {
+        None
+    } else {
+        front.get(index as usize).copied().flatten()
+    }
+}
+
+// Returns how the furthest row on diagonal k is reached from the previous front, before sliding
+// along the matching items: the row, and the edit taken, or None if the previous front already
+// got as far without another edit
+pub(crate) fn diagonal_predecessor(
+    previous: &[Option<usize>],
+    k: isize,
+    (m, n): (usize, usize),
+) -> Option<(usize, Option<Step>)> {
+    // The column of row i on diagonal k
+    let column = |k: isize, i: usize| (i as isize + k) as usize;
+
+    let candidates = [
+        front_row(previous, k).map(|i| (i, None)),
+        front_row(previous, k)
+            .filter(|&i| i < m && column(k, i) < n)
+            .map(|i| (i + 1, Some(Step::Substitute))),
+        front_row(previous, k - 1)
+            .filter(|&i| column(k - 1, i) < n)
+            .map(|i| (i, Some(Step::Insert))),
+        front_row(previous, k + 1)
+            .filter(|&i| i < m)
+            .map(|i| (i + 1, Some(Step::Delete))),
+    ];
+
+    // The first of the furthest, so that an edit is only taken if it gets further
+    let mut best: Option<(usize, Option<Step>)> = None;
+
+    for candidate in candidates.iter().flatten() {
+        if best.is_none_or(|best| candidate.0 > best.0) {
+            best = Some(*candidate);
+        }
+    }
+
+    best
+}
+
+// Returns the fronts of every number of edits, up to the one that reaches the bottom right corner
+pub(crate) fn diagonal_fronts<T: PartialEq>(source: &[T], target: &[T]) -> DiagonalFronts {
+    let (m, n) = (source.len(), target.len());
+    let goal = n as isize - m as isize;
+
+    // Follows diagonal k from row i for as long as the items match
+    let slide = |k: isize, mut i: usize| {
+        while i < m && ((i as isize + k) as usize) < n {
+            if source[i] != target[(i as isize + k) as usize] {
+                break;
+            }
+
+            i += 1;
+        }
+
+        i
+    };
+
+    let mut fronts = vec![vec![Some(slide(0, 0))]];
+
+    while front_row(fronts.last().unwrap(), goal) != Some(m) {
+        let e = fronts.len() as isize;
+        let previous = fronts.last().unwrap();
+
+        let front = (-e..=e)
+            .map(|k| {
+                if k < -(m as isize) || k > n as isize {
+                    return None;
+                }
+
+                diagonal_predecessor(previous, k, (m, n)).map(|(i, _)| slide(k, i))
+            })
+            .collect();
+
+        fronts.push(front);
+    }
+
+    fronts
+}
+
+/// Returns the Levenshtein distance between source and target by greedy extension along the
+/// diagonals of the distance matrix, computing only the cells that matter.
+///
+/// For every number of edits e = 0, 1, ..., this finds how far along each diagonal of the matrix
+/// e edits can get, sliding for free over runs of matching items, and stops as soon as the
+/// bottom right corner is reached (the Landau-Vishkin algorithm). Long unchanged stretches cost
+/// one comparison per item, so two megabyte inputs that differ in a few places take little more
+/// than a pass over them, where the full matrix would have a trillion cells. See
+/// `generate_edits_greedy` for the edits.
+///
+/// This implementation has a time complexity of O((m + n) * d) and a space complexity of
+/// O(d^2), where d is the distance, so it's only worth it for similar sequences.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY".as_bytes();
+/// let s2 = "SUNDAY".as_bytes();
+///
+/// assert_eq!(levenshtein::levenshtein_greedy(s1, s2), 3);
+///
+/// let long = vec![b'A'; 1_000_000];
+/// let mut changed = long.clone();
+/// changed[500_000] = b'B';
+/// assert_eq!(levenshtein::levenshtein_greedy(&long, &changed), 1);
+/// ```
+pub fn levenshtein_greedy<T: PartialEq>(source: &[T], target: &[T]) -> usize {
+    diagonal_fronts(source, target).len() - 1
+}
+
+/// Returns the number of distinct optimal alignments between source and target, i.e. the number
+/// of different minimal edit scripts that transform the source into the target.
+///
+/// Every path through the distance matrix that only takes optimal steps counts as an alignment.
+/// The count grows exponentially with the length of the sequences, so it saturates at `u64::MAX`.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(n^2).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// // Either A can be deleted
+/// assert_eq!(levenshtein::count_optimal_alignments("AA".as_bytes(), "A".as_bytes()), 2);
+///
+/// // There's only one way to turn SATURDAY into SUNDAY in 3 edits
+/// assert_eq!(levenshtein::count_optimal_alignments("SATURDAY".as_bytes(), "SUNDAY".as_bytes()), 1);
+/// ```
+pub fn count_optimal_alignments<T: PartialEq>(source: &[T], target: &[T]) -> u64 {
+    let (_, distances) = levenshtein_tabulation(source, target);
+
+    let m = source.len();
+    let n = target.len();
+
+    // The number of optimal paths from the top left corner to each cell
+    let mut counts = vec![vec![0u64; n + 1]; m + 1];
+    counts[0][0] = 1;
+
+    for i in 0..m + 1 {
+        for j in 0..n + 1 {
+            let current = distances[(i, j)];
+            let mut count = counts[i][j];
+
+            if i > 0 && distances[(i - 1, j)] + 1 == current {
+                count = count.saturating_add(counts[i - 1][j]);
+            }
+
+            if j > 0 && distances[(i, j - 1)] + 1 == current {
+                count = count.saturating_add(counts[i][j - 1]);
+            }
+
+            if i > 0 && j > 0 {
+                let cost = if source[i - 1] == target[j - 1] { 0 } else { 1 };
+
+                if distances[(i - 1, j - 1)] + cost == current {
+                    count = count.saturating_add(counts[i - 1][j - 1]);
+                }
+            }
+
+            counts[i][j] = count;
+        }
+    }
+
+    counts[m][n]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::distance::*;
+
+    #[test]
+    fn levenshtein_small_test() {
+        let s1 = "The quick brown fox jumps over the lazy dog, then naps".repeat(3);
+        let s2 = "A quick brown dog jumps over the lazy fox, and then naps".repeat(3);
+
+        // Around the largest shorter sequence that fits on the stack
+        for &len in &[0, 1, 10, 62, 63, 64, 65] {
+            let (s1, s2) = (&s1.as_bytes()[..len], s2.as_bytes());
+            let expected = levenshtein_tabulation(s1, s2).0;
+
+            assert_eq!(levenshtein_two_rows(s1, s2), expected);
+            assert_eq!(levenshtein_two_rows(s2, s1), expected);
+        }
+    }
+
+    #[test]
+    fn levenshtein_naive_test() {
+        let s1 = String::from("LAWN");
+        let s2 = String::from("FFLAWANN");
+        let expected_leven = 4;
+
+        let leven_naive = levenshtein_naive(s1.as_bytes(), s2.as_bytes());
+
+        assert_eq!(leven_naive, expected_leven);
+    }
+
+    #[test]
+    fn levenshtein_memoization_test() {
+        let s1 = String::from("LAWN");
+        let s2 = String::from("FFLAWANN");
+        let expected_leven = 4;
+
+        let (leven_memo, _) = levenshtein_memoization(s1.as_bytes(), s2.as_bytes());
+
+        assert_eq!(leven_memo, expected_leven);
+    }
+
+    #[test]
+    fn levenshtein_memoization_deep_test() {
+        // Far deeper than a recursive implementation could go on the test thread's stack
+        let s1 = vec![0u8; 200_000];
+        let s2 = vec![1u8];
+
+        let (leven_memo, _) = levenshtein_memoization(&s1, &s2);
+
+        assert_eq!(leven_memo, 200_000);
+    }
+
+    #[test]
+    fn levenshtein_tabulation_test() {
+        let s1 = String::from("LAWN");
+        let s2 = String::from("FFLAWANN");
+        let expected_leven = 4;
+
+        let (leven_tab, _) = levenshtein_tabulation(s1.as_bytes(), s2.as_bytes());
+
+        assert_eq!(leven_tab, expected_leven);
+    }
+
+    #[test]
+    fn levenshtein_tabulation_as_test() {
+        let s1 = String::from("LAWN");
+        let s2 = String::from("FFLAWANN");
+
+        let (_, wide) = levenshtein_tabulation(s1.as_bytes(), s2.as_bytes());
+        let (leven_tab, narrow): (_, DistanceMatrix<u8>) =
+            levenshtein_tabulation_as(s1.as_bytes(), s2.as_bytes());
+
+        assert_eq!(leven_tab, 4);
+        assert!(wide
+            .rows()
+            .flatten()
+            .zip(narrow.rows().flatten())
+            .all(|(w, n)| w == n as usize));
+    }
+
+    #[test]
+    fn levenshtein_with_buffer_test() {
+        let mut matrix = DistanceMatrix::default();
+        let mut buffer = Vec::new();
+
+        // Reusing the buffers for shorter sequences leaves nothing behind from longer ones
+        for (s1, s2) in [("FFLAWANN", "LAWN"), ("LAWN", "FLAW"), ("", "")] {
+            let (leven, full) = levenshtein_tabulation(s1.as_bytes(), s2.as_bytes());
+
+            assert_eq!(
+                levenshtein_tabulation_with_buffer(s1.as_bytes(), s2.as_bytes(), &mut matrix),
+                leven
+            );
+            assert_eq!(matrix, full);
+            assert_eq!(
+                levenshtein_two_rows_with_buffer(s1.as_bytes(), s2.as_bytes(), &mut buffer),
+                leven
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn levenshtein_tabulation_as_overflow_test() {
+        let s1 = vec![0; 256];
+
+        let _: (_, DistanceMatrix<u8>) = levenshtein_tabulation_as(&s1, &[1]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_levenshtein_tabulation_test() {
+        // Long enough for several tiles in both directions, with some of them partial
+        let s1: Vec<u8> = (0..700).map(|i| (i * 7 + i / 3) as u8 % 5).collect();
+        let s2: Vec<u8> = (0..600).map(|i| (i * 3 + i / 5) as u8 % 5).collect();
+
+        for (s1, s2) in [(&s1[..], &s2[..]), (&s2[..100], &s1[..]), (&[], &s2[..])] {
+            assert_eq!(
+                par_levenshtein_tabulation(s1, s2),
+                levenshtein_tabulation(s1, s2)
+            );
+        }
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn levenshtein_simd_test() {
+        let s1: Vec<u8> = (0..100).map(|i| (i * 7 + i / 3) as u8 % 5).collect();
+        let s2: Vec<u8> = (0..70).map(|i| (i * 3 + i / 5) as u8 % 5).collect();
+
+        // Lengths on either side of the number of lanes, in both orders
+        for len_1 in [0, 1, 15, 16, 17, 40, 100] {
+            for len_2 in [0, 3, 16, 33, 70] {
+                let (s1, s2) = (&s1[..len_1], &s2[..len_2]);
+
+                assert_eq!(levenshtein_simd(s1, s2), levenshtein_two_rows(s1, s2));
+                assert_eq!(levenshtein_simd(s2, s1), levenshtein_two_rows(s1, s2));
+            }
+        }
+    }
+
+    #[test]
+    fn append_test() {
+        let s1 = "LAWN".as_bytes();
+        let s2 = "FFLAWANN".as_bytes();
+
+        // One item at a time, starting from nothing
+        let (_, mut source_grown) = levenshtein_tabulation(&[], s2);
+        let (_, mut target_grown) = levenshtein_tabulation(s1, &[]);
+
+        for len in 1..s1.len() + 1 {
+            append_to_source(&s1[..len], s2, &mut source_grown).unwrap();
+        }
+        for len in 1..s2.len() + 1 {
+            append_to_target(s1, &s2[..len], &mut target_grown).unwrap();
+        }
+
+        let (leven, full) = levenshtein_tabulation(s1, s2);
+        assert_eq!(source_grown, full);
+        assert_eq!(target_grown, full);
+
+        // Nothing appended
+        assert_eq!(append_to_source(s1, s2, &mut source_grown).unwrap(), leven);
+
+        // Matrices for other sequences are rejected
+        assert!(append_to_source(&s1[..2], s2, &mut source_grown).is_err());
+        assert!(append_to_target(s1, &s2[1..], &mut target_grown).is_err());
+
+        let (_, mut banded) = levenshtein_banded(s1, s2, 4).unwrap();
+        assert!(append_to_source(s1, s2, &mut banded).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn cancellable_test() {
+        let s1 = "LAWN".as_bytes();
+        let s2 = "FFLAWANN".as_bytes();
+
+        let token = CancelToken::new();
+        assert_eq!(
+            levenshtein_tabulation_cancellable(s1, s2, &token).unwrap(),
+            levenshtein_tabulation(s1, s2)
+        );
+        assert_eq!(levenshtein_two_rows_cancellable(s1, s2, &token).unwrap(), 4);
+
+        // Cancelled between two rows by a deadline that has already passed
+        let token = CancelToken::with_deadline(std::time::Instant::now());
+        assert!(matches!(
+            levenshtein_tabulation_cancellable(s1, s2, &token),
+            Err(LevenshteinError::Cancelled(0))
+        ));
+        assert!(matches!(
+            levenshtein_two_rows_cancellable(s1, s2, &token),
+            Err(LevenshteinError::Cancelled(0))
+        ));
+
+        // Nothing to cancel without rows to fill
+        assert_eq!(
+            levenshtein_tabulation_cancellable(&[], s2, &token)
+                .unwrap()
+                .0,
+            8
+        );
+    }
+
+    #[test]
+    fn levenshtein_tabulation_with_progress_test() {
+        let s1 = "LAWN".as_bytes();
+        let s2 = "FFLAWANN".as_bytes();
+
+        let mut reports = Vec::new();
+        let result =
+            levenshtein_tabulation_with_progress(s1, s2, |done, total| reports.push((done, total)));
+
+        assert_eq!(result, levenshtein_tabulation(s1, s2));
+        assert_eq!(reports, vec![(0, 4), (1, 4), (2, 4), (3, 4), (4, 4)]);
+    }
+
+    #[test]
+    fn levenshtein_two_rows_test() {
+        let s1 = String::from("LAWN");
+        let s2 = String::from("FFLAWANN");
+        let expected_leven = 4;
+
+        let leven_two_rows = levenshtein_two_rows(s1.as_bytes(), s2.as_bytes());
+
+        assert_eq!(leven_two_rows, expected_leven);
+    }
+
+    #[test]
+    fn levenshtein_const_test() {
+        const LEVEN_CONST: usize = levenshtein_const(b"LAWN", b"FFLAWANN");
+        let expected_leven = 4;
+
+        assert_eq!(LEVEN_CONST, expected_leven);
+        assert_eq!(levenshtein_const(b"", b"LAWN"), 4);
+    }
+
+    #[test]
+    fn is_within_distance_test() {
+        let s1 = String::from("LAWN");
+        let s2 = String::from("FFLAWANN");
+
+        assert!(is_within_distance(s1.as_bytes(), s2.as_bytes(), 4));
+        assert!(is_within_distance(s1.as_bytes(), s2.as_bytes(), 10));
+        assert!(!is_within_distance(s1.as_bytes(), s2.as_bytes(), 3));
+        assert!(!is_within_distance(s1.as_bytes(), s2.as_bytes(), 0));
+        assert!(is_within_distance(s1.as_bytes(), s2.as_bytes(), usize::MAX));
+
+        assert_eq!(bounded_distance(s1.as_bytes(), s2.as_bytes(), 4), Some(4));
+        assert_eq!(bounded_distance(s1.as_bytes(), s2.as_bytes(), 3), None);
+        assert_eq!(
+            bounded_distance("kitten".as_bytes(), "sitting".as_bytes(), usize::MAX),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn levenshtein_banded_test() {
+        let s1 = String::from("LAWN");
+        let s2 = String::from("FFLAWANN");
+
+        let (leven_banded, banded) = levenshtein_banded(s1.as_bytes(), s2.as_bytes(), 5).unwrap();
+        let (_, full) = levenshtein_tabulation(s1.as_bytes(), s2.as_bytes());
+
+        assert_eq!(leven_banded, 4);
+        assert_eq!(banded.dims(), full.dims());
+
+        // Cells within the bound are exact, and the rest are capped at one more than it
+        assert!(banded
+            .rows()
+            .flatten()
+            .zip(full.rows().flatten())
+            .all(|(b, f)| b == min(f, 6)));
+
+        assert!(levenshtein_banded(s1.as_bytes(), s2.as_bytes(), 3).is_none());
+
+        // A band wider than the sequences is as good as the full matrix
+        let (leven_banded, banded) =
+            levenshtein_banded(s1.as_bytes(), s2.as_bytes(), usize::MAX).unwrap();
+        assert_eq!(leven_banded, 4);
+        assert_eq!(banded, full);
+    }
+
+    #[test]
+    fn levenshtein_greedy_test() {
+        let s1 = String::from("LAWN");
+        let s2 = String::from("FFLAWANN");
+
+        assert_eq!(levenshtein_greedy(s1.as_bytes(), s2.as_bytes()), 4);
+        assert_eq!(levenshtein_greedy(s2.as_bytes(), s1.as_bytes()), 4);
+        assert_eq!(levenshtein_greedy(s1.as_bytes(), "".as_bytes()), 4);
+        assert_eq!(levenshtein_greedy::<u8>(&[], &[]), 0);
+
+        // One front per edit, each two diagonals wider than the last
+        let fronts = diagonal_fronts(s1.as_bytes(), s2.as_bytes());
+        assert_eq!(fronts.len(), 5);
+        assert_eq!(fronts[4].len(), 9);
+    }
+
+    #[test]
+    fn count_optimal_alignments_test() {
+        assert_eq!(count_optimal_alignments::<u8>(&[], &[]), 1);
+        assert_eq!(count_optimal_alignments("".as_bytes(), "ABC".as_bytes()), 1);
+
+        // Substitute both items, move A to the end, or move B to the front
+        assert_eq!(
+            count_optimal_alignments("AB".as_bytes(), "BA".as_bytes()),
+            3
+        );
+
+        // The count saturates instead of overflowing
+        let source = vec![0; 200];
+        let target = vec![1; 100];
+        assert_eq!(count_optimal_alignments(&source, &target), u64::MAX);
     }
 }
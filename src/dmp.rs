@@ -0,0 +1,347 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use crate::distance::diff_edits;
+use crate::edit::Edit;
+use crate::hunk::{group_into_hunks, HunkPart};
+use crate::replace::{replacements_to_edits, Replace};
+use crate::unified::hunk_range;
+
+// The number of unchanged characters diff-match-patch keeps around each change
+const PATCH_MARGIN: usize = 4;
+
+/// Represents an error found while parsing a diff-match-patch patch. Line numbers start at 1.
+#[derive(Debug, PartialEq)]
+pub enum PatchTextError {
+    // The line isn't a valid patch header, or the patch starts before the end of the previous one
+    InvalidHeader(usize),
+    // The line doesn't start with a space, '-' or '+', or comes before the first header
+    InvalidLine(usize),
+    // The line has an invalid percent-encoded character
+    InvalidEncoding(usize),
+}
+
+impl fmt::Display for PatchTextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchTextError::InvalidHeader(line) => {
+                write!(f, "Invalid patch header on line {}", line)
+            }
+            PatchTextError::InvalidLine(line) => write!(f, "Invalid patch line {}", line),
+            PatchTextError::InvalidEncoding(line) => {
+                write!(f, "Invalid percent-encoding on line {}", line)
+            }
+        }
+    }
+}
+
+impl Error for PatchTextError {}
+
+// Appends a line of the patch body, percent-encoded like `encodeURI` in JavaScript except for
+// spaces, which is how diff-match-patch writes them
+fn write_line(out: &mut String, sign: char, text: &[char]) {
+    if text.is_empty() {
+        return;
+    }
+
+    out.push(sign);
+
+    for &c in text {
+        if c.is_ascii_alphanumeric() || " !#$&'()*+,-./:;=?@_~".contains(c) {
+            out.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                out.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+
+    out.push('\n');
+}
+
+// Decodes a percent-encoded line of the patch body
+fn read_line(text: &str) -> Option<Vec<char>> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut input = text.as_bytes();
+
+    while let Some((&byte, rest)) = input.split_first() {
+        if byte != b'%' {
+            bytes.push(byte);
+            input = rest;
+            continue;
+        }
+
+        let hex = core::str::from_utf8(rest.get(..2)?).ok()?;
+        bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        input = &rest[2..];
+    }
+
+    Some(String::from_utf8(bytes).ok()?.chars().collect())
+}
+
+// Parses one side of a patch header, such as "-3,2", into its start and length, which follow
+// the conventions of `hunk_range`
+fn read_range(text: &str) -> Option<(usize, usize)> {
+    let parse = |number: &str| number.parse::<usize>().ok();
+
+    match text.split_once(',') {
+        None => Some((parse(text)?.checked_sub(1)?, 1)),
+        Some((start, "0")) => Some((parse(start)?, 0)),
+        Some((start, len)) => Some((parse(start)?.checked_sub(1)?, parse(len)?)),
+    }
+}
+
+/// Returns the patch that transforms the source string into the target string, in the patch text
+/// format of Google's diff-match-patch library. See `dmp_patch_from_edits`.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let patch = levenshtein::dmp_patch("The cat sat.", "The cats sat!");
+/// assert_eq!(patch, "@@ -4,9 +4,10 @@\n  cat\n+s\n  sat\n-.\n+!\n");
+/// ```
+pub fn dmp_patch(source: &str, target: &str) -> String {
+    let source: Vec<char> = source.chars().collect();
+    let target: Vec<char> = target.chars().collect();
+
+    let edits = diff_edits(&source, &target);
+
+    dmp_patch_from_edits(&source, &edits)
+}
+
+/// Returns a patch that applies the edits to the source, in the patch text format of Google's
+/// diff-match-patch library, so it can be applied with `patch_fromText` and `patch_apply` in any
+/// of its ports.
+///
+/// Like diff-match-patch, each patch keeps 4 unchanged characters of context around its changes,
+/// and the positions in the header of a patch account for the patches before it. Positions and
+/// lengths count characters, as the Python port does; the JavaScript and Java ports count UTF-16
+/// code units, so they only agree on text without characters outside the Basic Multilingual
+/// Plane.
+///
+/// # Arguments
+///
+/// * `source` - The characters of the source
+/// * `edits` - The edits, as generated by `generate_edits`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1: Vec<char> = "one\ntwo".chars().collect();
+/// let s2: Vec<char> = "one\n2".chars().collect();
+///
+/// let (_, matrix) = levenshtein::distance(&s1, &s2);
+/// let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+///
+/// let patch = levenshtein::dmp_patch_from_edits(&s1, &edits);
+/// assert_eq!(patch, "@@ -1,7 +1,5 @@\n one%0A\n-two\n+2\n");
+/// ```
+pub fn dmp_patch_from_edits(source: &[char], edits: &[Edit<char>]) -> String {
+    let mut out = String::new();
+
+    for hunk in group_into_hunks(source, edits, PATCH_MARGIN) {
+        // Positions are in the text with the previous patches applied, which is where the target
+        // and the source line up
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(&(hunk.target.start..hunk.target.start + hunk.source.len())),
+            hunk_range(&hunk.target)
+        ));
+
+        for part in &hunk.parts {
+            match part {
+                HunkPart::Context(chars) => write_line(&mut out, ' ', chars),
+                HunkPart::Change { removed, added } => {
+                    write_line(&mut out, '-', removed);
+                    write_line(&mut out, '+', added);
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// Parses a patch in the patch text format of Google's diff-match-patch library, and returns the
+/// edits it makes, as if they had been generated by `generate_edits`.
+///
+/// The unchanged context in the patch is only used to find the positions of the changes; it isn't
+/// compared to any source.
+///
+/// # Arguments
+///
+/// * `text` - The patch text
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let edits = levenshtein::parse_dmp_patch("@@ -4,9 +4,10 @@\n  cat\n+s\n  sat\n-.\n+!\n").unwrap();
+///
+/// let source: Vec<char> = "The cat sat.".chars().collect();
+/// let target: String = levenshtein::apply_edits(&source, &edits).into_iter().collect();
+///
+/// assert_eq!(target, "The cats sat!");
+/// ```
+pub fn parse_dmp_patch(text: &str) -> Result<Vec<Edit<char>>, PatchTextError> {
+    let mut replacements: Vec<Replace<char>> = Vec::new();
+
+    // How much longer the previous patches made the text, and where the last one ended in the
+    // source
+    let mut growth: isize = 0;
+    let mut source_end = 0;
+
+    // Where the patch being read is in the source, if there is one
+    let mut pos: Option<usize> = None;
+
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+
+        if let Some(header) = line.strip_prefix("@@ -") {
+            let ranges = header
+                .strip_suffix(" @@")
+                .and_then(|ranges| ranges.split_once(" +"))
+                .and_then(|(source, target)| Some((read_range(source)?, read_range(target)?)));
+
+            let ((start, source_len), (_, target_len)) =
+                ranges.ok_or(PatchTextError::InvalidHeader(line_number))?;
+
+            let start = start as isize - growth;
+
+            if start < source_end as isize {
+                return Err(PatchTextError::InvalidHeader(line_number));
+            }
+
+            growth += target_len as isize - source_len as isize;
+            pos = Some(start as usize);
+            continue;
+        }
+
+        // diff-match-patch ignores blank lines
+        if line.is_empty() {
+            continue;
+        }
+
+        let at = pos
+            .as_mut()
+            .ok_or(PatchTextError::InvalidLine(line_number))?;
+        let sign = line.as_bytes()[0];
+
+        if !b" -+".contains(&sign) {
+            return Err(PatchTextError::InvalidLine(line_number));
+        }
+
+        let chars = read_line(&line[1..]).ok_or(PatchTextError::InvalidEncoding(line_number))?;
+
+        // Deletions and insertions next to each other make up a single replacement
+        let last = replacements
+            .last_mut()
+            .filter(|replacement| replacement.range.end == *at);
+
+        match (sign, last) {
+            (b' ', _) => *at += chars.len(),
+            (b'-', Some(replacement)) => {
+                replacement.range.end += chars.len();
+                *at += chars.len();
+            }
+            (b'-', None) => {
+                replacements.push(Replace {
+                    range: *at..*at + chars.len(),
+                    with: Vec::new(),
+                });
+                *at += chars.len();
+            }
+            (_, Some(replacement)) => replacement.with.extend(chars),
+            (_, None) => replacements.push(Replace {
+                range: *at..*at,
+                with: chars,
+            }),
+        }
+
+        source_end = *at;
+    }
+
+    Ok(replacements_to_edits(&replacements))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dmp::*;
+
+    #[test]
+    fn encoding_test() {
+        let text: Vec<char> = "a b%\n\u{e9}[+]".chars().collect();
+
+        let mut out = String::new();
+        write_line(&mut out, '+', &text);
+
+        assert_eq!(out, "+a b%25%0A%C3%A9%5B+%5D\n");
+        assert_eq!(read_line(&out[1..out.len() - 1]), Some(text));
+
+        assert_eq!(read_line("%C3"), None);
+        assert_eq!(read_line("%G0"), None);
+        assert_eq!(read_line("%4"), None);
+    }
+
+    #[test]
+    fn dmp_patch_round_trip_test() {
+        // Changes far enough apart to be in separate patches, at both ends of the text
+        let source = "The quick brown fox jumps over the lazy dog";
+        let target = "A quick brown fox jumps over the lazy dogs";
+
+        let patch = dmp_patch(source, target);
+        assert_eq!(
+            patch,
+            "@@ -1,7 +1,5 @@\n-The\n+A\n  qui\n@@ -38,4 +38,5 @@\n  dog\n+s\n"
+        );
+
+        let source: Vec<char> = source.chars().collect();
+        let edits = parse_dmp_patch(&patch).unwrap();
+
+        assert_eq!(
+            crate::apply_edits(&source, &edits),
+            target.chars().collect::<Vec<char>>()
+        );
+
+        assert!(dmp_patch("same", "same").is_empty());
+    }
+
+    #[test]
+    fn invalid_patch_test() {
+        assert_eq!(
+            parse_dmp_patch("@@ -1,2 +1,2\n"),
+            Err(PatchTextError::InvalidHeader(1))
+        );
+        assert_eq!(
+            parse_dmp_patch(" ab\n"),
+            Err(PatchTextError::InvalidLine(1))
+        );
+        assert_eq!(
+            parse_dmp_patch("@@ -1,2 +1,2 @@\n ab\n*c\n"),
+            Err(PatchTextError::InvalidLine(3))
+        );
+        assert_eq!(
+            parse_dmp_patch("@@ -1 +1 @@\n-%ZZ\n"),
+            Err(PatchTextError::InvalidEncoding(2))
+        );
+
+        // The second patch starts inside the first one
+        assert_eq!(
+            parse_dmp_patch("@@ -1,3 +1,3 @@\n-abc\n+xyz\n@@ -2 +2 @@\n-b\n"),
+            Err(PatchTextError::InvalidHeader(4))
+        );
+    }
+}
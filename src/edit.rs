@@ -1,8 +1,16 @@
-use std::cmp::min;
-use std::error::Error;
-use std::fmt;
+use alloc::borrow::Cow;
+use alloc::collections::BTreeSet;
+use alloc::string::{FromUtf8Error, String, ToString};
+use alloc::vec::Vec;
+use core::cmp::min;
+use core::error::Error;
+use core::fmt;
 
-use crate::util::DistanceMatrix;
+use crate::distance::{diagonal_fronts, diagonal_predecessor, BackpointerMatrix, Step};
+use crate::edit_op::EditOp;
+use crate::patch::PatchError;
+use crate::replace::coalesce_edits;
+use crate::util::{CommonAffix, DistanceCell, DistanceMatrix};
 
 /// Represents an error specific to working with the Levenshtein distance, or the generated
 /// distance matrix
@@ -10,94 +18,1376 @@ use crate::util::DistanceMatrix;
 pub enum LevenshteinError {
     // The supplied distance matrix is invalid
     InvalidDistanceMatrixError,
+    // The cell at (row, column) of the supplied distance matrix is inconsistent with its neighbors
+    InvalidDistanceMatrixCell(usize, usize),
+    // The computation was cancelled after filling this many rows of the distance matrix
+    Cancelled(usize),
 }
 
 impl fmt::Display for LevenshteinError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let error = match self {
-            LevenshteinError::InvalidDistanceMatrixError => "Invalid matrix error",
+        match self {
+            LevenshteinError::InvalidDistanceMatrixError => write!(f, "Invalid matrix error"),
+            LevenshteinError::InvalidDistanceMatrixCell(row, col) => {
+                write!(f, "Invalid matrix cell at ({}, {})", row, col)
+            }
+            LevenshteinError::Cancelled(rows) => write!(f, "Cancelled after {} rows", rows),
+        }
+    }
+}
+
+impl Error for LevenshteinError {}
+
+/// Represents an Edit applied on a source sequence.
+///
+/// The index is a `usize` by default. Edits of sequences shorter than 4 billion items can store it
+/// as a `u32` instead, which halves the size of an edit of bytes, see `generate_edits_as`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Edit<T: PartialEq, I = usize> {
+    Delete(I),        // Delete item at index
+    Insert(I, T),     // Insert item T at index
+    Substitute(I, T), // Substitute item at index with T
+}
+
+impl<T: PartialEq, I: DistanceCell> Edit<T, I> {
+    /// Returns the index of the edit.
+    pub fn index(&self) -> usize {
+        match self {
+            Edit::Delete(idx) | Edit::Insert(idx, _) | Edit::Substitute(idx, _) => idx.to_usize(),
+        }
+    }
+
+    /// Returns the same edit with its index stored as a `J`, or `None` if the index doesn't fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use levenshtein_diff as levenshtein;
+    /// use levenshtein::Edit;
+    ///
+    /// let edit: Edit<u8> = Edit::Substitute(300, b'N');
+    ///
+    /// assert_eq!(edit.clone().with_index::<u32>(), Some(Edit::Substitute(300u32, b'N')));
+    /// assert_eq!(edit.with_index::<u8>(), None);
+    /// ```
+    pub fn with_index<J: DistanceCell>(self) -> Option<Edit<T, J>> {
+        Some(match self {
+            Edit::Delete(idx) => Edit::Delete(J::from_usize(idx.to_usize())?),
+            Edit::Insert(idx, val) => Edit::Insert(J::from_usize(idx.to_usize())?, val),
+            Edit::Substitute(idx, val) => Edit::Substitute(J::from_usize(idx.to_usize())?, val),
+        })
+    }
+}
+
+impl<T: PartialEq + fmt::Display> fmt::Display for Edit<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Edit::Delete(idx) => write!(f, "delete at {}", idx),
+            Edit::Insert(0, val) => write!(f, "insert '{}' at the start", val),
+            Edit::Insert(idx, val) => write!(f, "insert '{}' after {}", val, idx),
+            Edit::Substitute(idx, val) => write!(f, "substitute '{}' at {}", val, idx),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Edit<&T> {
+    /// Returns the edit with its item cloned, as returned by `generate_edits`.
+    pub fn into_owned(self) -> Edit<T> {
+        match self {
+            Edit::Delete(idx) => Edit::Delete(idx),
+            Edit::Insert(idx, val) => Edit::Insert(idx, val.clone()),
+            Edit::Substitute(idx, val) => Edit::Substitute(idx, val.clone()),
+        }
+    }
+}
+
+/// Returns a human readable description of a sequence of edits, one edit per line.
+///
+/// Unlike the `Display` implementation of `Edit`, this also names the source items that are
+/// deleted or substituted.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `edits` - The edits, as generated by `generate_edits`
+///
+/// # Panics
+///
+/// Panics if a deletion or substitution refers to an index outside the source, i.e. if the edits
+/// weren't generated for this source.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1: Vec<char> = "SATURDAY".chars().collect();
+/// let s2: Vec<char> = "SUNDAY".chars().collect();
+///
+/// let (_, matrix) = levenshtein::distance(&s1, &s2);
+/// let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+///
+/// assert_eq!(
+///     levenshtein::render_edits(&s1, &edits),
+///     "substitute 'R' with 'N' at 5\ndelete 'T' at 3\ndelete 'A' at 2"
+/// );
+/// ```
+pub fn render_edits<T: PartialEq + fmt::Display>(source: &[T], edits: &[Edit<T>]) -> String {
+    let lines: Vec<String> = edits
+        .iter()
+        .map(|edit| match edit {
+            Edit::Delete(idx) => format!("delete '{}' at {}", source[idx - 1], idx),
+            Edit::Substitute(idx, val) => {
+                format!("substitute '{}' with '{}' at {}", source[idx - 1], val, idx)
+            }
+            Edit::Insert(..) => edit.to_string(),
+        })
+        .collect();
+
+    lines.join("\n")
+}
+
+/// How the indices of `Edit` values are interpreted.
+///
+/// With `OneBased` indexing (the default, used by `generate_edits` and `apply_edits`), `Delete(i)`
+/// and `Substitute(i, _)` refer to the i-th item, counting from 1. With `ZeroBased` indexing they
+/// refer to the item at index i, like slice indices do. Either way, `Insert(i, _)` inserts in
+/// front of the item at (0-based) index i, just like `Vec::insert`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EditIndexing {
+    #[default]
+    OneBased,
+    ZeroBased,
+}
+
+impl EditIndexing {
+    // The index of the first item
+    fn first(self) -> usize {
+        match self {
+            EditIndexing::OneBased => 1,
+            EditIndexing::ZeroBased => 0,
+        }
+    }
+}
+
+/// Decides which edit the traceback picks when several of them lead to an optimal edit script.
+///
+/// Matching items are always kept when that's optimal. The policy only breaks ties between
+/// inserting, deleting and substituting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TiebreakPolicy {
+    /// Prefer insertions, then deletions, then substitutions. This is what `generate_edits` does.
+    #[default]
+    PreferInsertion,
+    /// Prefer deletions, then insertions, then substitutions.
+    PreferDeletion,
+    /// Prefer substitutions, then insertions, then deletions.
+    PreferSubstitution,
+    /// Prefer the same kind of edit as the previous one, so that runs of edits stay together.
+    /// Otherwise, this is the same as `PreferInsertion`.
+    PreferContiguity,
+}
+
+impl TiebreakPolicy {
+    // The order in which to try the steps, given the step that was taken last
+    fn order(self, last: Option<Step>) -> [Step; 3] {
+        match (self, last) {
+            (TiebreakPolicy::PreferDeletion, _) => [Step::Delete, Step::Insert, Step::Substitute],
+            (TiebreakPolicy::PreferSubstitution, _) => {
+                [Step::Substitute, Step::Insert, Step::Delete]
+            }
+            (TiebreakPolicy::PreferContiguity, Some(Step::Delete)) => {
+                [Step::Delete, Step::Insert, Step::Substitute]
+            }
+            (TiebreakPolicy::PreferContiguity, Some(Step::Substitute)) => {
+                [Step::Substitute, Step::Insert, Step::Delete]
+            }
+            _ => [Step::Insert, Step::Delete, Step::Substitute],
+        }
+    }
+}
+
+/// Applies a sequence of edits on the source sequence, and returns a vector representing the
+/// target sequence.
+///
+/// Edits that delete or substitute an item that another edit already deleted or substituted are
+/// ignored, see `apply_edits_indexed`.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `edits` - A reference to a vector of edits of the same type as elements of source
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "FLOWER";
+/// let expected_s2 = "FOLLOWER";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), expected_s2.as_bytes());
+///
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), expected_s2.as_bytes(), &matrix).unwrap();
+///
+/// let target = levenshtein::apply_edits(s1.as_bytes(), &edits);
+///
+/// let s2 = match std::str::from_utf8(&target) {
+///     Ok(v) => v,
+///     Err(_) => panic!("Not a valid UTF-8 sequence!"),
+/// };
+///
+/// assert_eq!(s2, expected_s2);
+/// ```
+pub fn apply_edits<T: Clone + PartialEq>(source: &[T], edits: &[Edit<T>]) -> Vec<T> {
+    apply_edits_indexed(source, edits, EditIndexing::OneBased)
+}
+
+/// Applies a sequence of edits on a vector in place, turning it from the source sequence into the
+/// target sequence.
+///
+/// Unlike `apply_edits`, this doesn't copy the items that stay the same. Each run of adjacent
+/// edits is spliced into the vector, starting from the end, so only the items after a run are
+/// moved. As with `apply_edits`, the last of several edits that delete or substitute the same item
+/// is the one that applies.
+///
+/// Returns `PatchError::InvalidIndex` if an edit refers to an index outside the vector, in which
+/// case the vector is left as it was.
+///
+/// # Arguments
+///
+/// * `target` - The source sequence, which is turned into the target sequence
+/// * `edits` - The edits, as generated by `generate_edits`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let mut sequence = "SATURDAY".as_bytes().to_vec();
+/// let target = "SUNDAY".as_bytes();
+///
+/// let (_, matrix) = levenshtein::distance(&sequence, target);
+/// let edits = levenshtein::generate_edits(&sequence, target, &matrix).unwrap();
+///
+/// levenshtein::apply_edits_in_place(&mut sequence, &edits).unwrap();
+/// assert_eq!(sequence, target);
+/// ```
+pub fn apply_edits_in_place<T: Clone + PartialEq>(
+    target: &mut Vec<T>,
+    edits: &[Edit<T>],
+) -> Result<(), PatchError> {
+    let mut changed = BTreeSet::new();
+    let mut kept = Vec::with_capacity(edits.len());
+
+    // Only the last edit of each item is kept, so the edits are checked from the end
+    for edit in edits.iter().rev() {
+        match *edit {
+            Edit::Insert(idx, _) if idx > target.len() => {
+                return Err(PatchError::InvalidIndex(idx))
+            }
+            Edit::Insert(..) => {}
+            Edit::Delete(idx) | Edit::Substitute(idx, _) => {
+                if idx == 0 || idx > target.len() {
+                    return Err(PatchError::InvalidIndex(idx));
+                }
+
+                if !changed.insert(idx) {
+                    continue;
+                }
+            }
+        }
+
+        kept.push(edit.clone());
+    }
+
+    kept.reverse();
+
+    for replacement in coalesce_edits(&kept).into_iter().rev() {
+        target.splice(replacement.range, replacement.with);
+    }
+
+    Ok(())
+}
+
+/// Applies a sequence of edits on the characters of a string, and returns the target string.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `edits` - The edits, as generated by `generate_edits` on the characters of the strings
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1: Vec<char> = "Grüße".chars().collect();
+/// let s2: Vec<char> = "Grüezi".chars().collect();
+///
+/// let (_, matrix) = levenshtein::distance(&s1, &s2);
+/// let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+///
+/// assert_eq!(levenshtein::apply_edits_str("Grüße", &edits), "Grüezi");
+/// ```
+pub fn apply_edits_str(source: &str, edits: &[Edit<char>]) -> String {
+    let source: Vec<char> = source.chars().collect();
+
+    apply_edits(&source, edits).into_iter().collect()
+}
+
+/// Applies a sequence of edits on the bytes of a string, and returns the target string.
+///
+/// Returns an error if the target isn't valid UTF-8, which can happen if the edits split up the
+/// bytes of a character.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `edits` - The edits, as generated by `generate_edits` on the bytes of the strings
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+///
+/// assert_eq!(levenshtein::apply_edits_utf8(s1, &edits).unwrap(), s2);
+///
+/// // Removes the first byte of 'é', but not the second
+/// assert!(levenshtein::apply_edits_utf8("é", &[Edit::Delete(1)]).is_err());
+/// ```
+pub fn apply_edits_utf8(source: &str, edits: &[Edit<u8>]) -> Result<String, FromUtf8Error> {
+    String::from_utf8(apply_edits(source.as_bytes(), edits))
+}
+
+/// Applies a sequence of edits on the source sequence like `apply_edits`, but borrows the source
+/// instead of copying it if the edits leave it unchanged: if there are none, or if they only
+/// substitute items with equal ones.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `edits` - The edits, as generated by `generate_edits`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+/// use std::borrow::Cow;
+///
+/// let source = "SUNDAY".as_bytes();
+///
+/// assert!(matches!(levenshtein::apply_edits_cow(source, &[]), Cow::Borrowed(_)));
+///
+/// let target = levenshtein::apply_edits_cow(source, &[Edit::Substitute(1, b'M')]);
+/// assert_eq!(target, Cow::<[u8]>::Owned("MUNDAY".as_bytes().to_vec()));
+/// ```
+pub fn apply_edits_cow<'a, T: Clone + PartialEq>(
+    source: &'a [T],
+    edits: &[Edit<T>],
+) -> Cow<'a, [T]> {
+    let is_identity = edits.iter().all(|edit| match edit {
+        Edit::Substitute(idx, val) => idx.checked_sub(1).and_then(|i| source.get(i)) == Some(val),
+        _ => false,
+    });
+
+    if is_identity {
+        Cow::Borrowed(source)
+    } else {
+        Cow::Owned(apply_edits(source, edits))
+    }
+}
+
+/// Applies a sequence of edits on the source sequence like `apply_edits`, but checks the edits
+/// first instead of panicking or producing garbage if they don't fit the source.
+///
+/// Returns an error if an edit refers to an index outside the source, or if two edits delete or
+/// substitute the same item.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `edits` - The edits, as generated by `generate_edits`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{Edit, PatchError};
+///
+/// let source = "SUNDAY".as_bytes();
+///
+/// let edits = vec![Edit::Substitute(3, b'M'), Edit::Delete(2)];
+/// assert_eq!(levenshtein::try_apply_edits(source, &edits).unwrap(), "SMDAY".as_bytes());
+///
+/// let edits = vec![Edit::Delete(7)];
+/// assert_eq!(levenshtein::try_apply_edits(source, &edits), Err(PatchError::InvalidIndex(7)));
+///
+/// let edits = vec![Edit::Substitute(3, b'M'), Edit::Delete(3)];
+/// assert_eq!(levenshtein::try_apply_edits(source, &edits), Err(PatchError::ConflictingEdits(3)));
+/// ```
+pub fn try_apply_edits<T: Clone + PartialEq>(
+    source: &[T],
+    edits: &[Edit<T>],
+) -> Result<Vec<T>, PatchError> {
+    let mut changed = vec![false; source.len()];
+
+    for edit in edits {
+        match *edit {
+            Edit::Insert(idx, _) if idx > source.len() => {
+                return Err(PatchError::InvalidIndex(idx))
+            }
+            Edit::Insert(..) => {}
+            Edit::Delete(idx) | Edit::Substitute(idx, _) => {
+                if idx == 0 || idx > source.len() {
+                    return Err(PatchError::InvalidIndex(idx));
+                }
+
+                if core::mem::replace(&mut changed[idx - 1], true) {
+                    return Err(PatchError::ConflictingEdits(idx));
+                }
+            }
+        }
+    }
+
+    Ok(apply_edits(source, edits))
+}
+
+// Returns the edits in the order they apply to the source, where `first` is the index of the first
+// source item
+pub(crate) fn in_source_order<T: PartialEq, I: DistanceCell>(
+    edits: &[Edit<T, I>],
+    first: usize,
+) -> Vec<&Edit<T, I>> {
+    sorted_by_slot(edits, |edit| match edit {
+        Edit::Insert(idx, _) => 2 * idx.to_usize(),
+        Edit::Delete(idx) | Edit::Substitute(idx, _) => 2 * (idx.to_usize() - first) + 1,
+    })
+}
+
+// Returns the edits sorted by their slots. Every gap between source items and every source item
+// gets a slot: gap g is slot 2g and item i (0-indexed) is slot 2i + 1, so applying edits in order
+// of their slots is a single forward pass over the source.
+pub(crate) fn sorted_by_slot<E, F: Fn(&E) -> usize>(edits: &[E], slot: F) -> Vec<&E> {
+    // Edits are generated in descending order of indices, so reversing them usually sorts them.
+    // Inserts at the same index end up in the reverse order of the edits.
+    let mut ordered: Vec<&E> = edits.iter().rev().collect();
+    if ordered.windows(2).any(|pair| slot(pair[0]) > slot(pair[1])) {
+        ordered.sort_by_key(|edit| slot(edit));
+    }
+
+    ordered
+}
+
+// Builds the target in a single pass over the source, from edits in source order whose indices
+// are all 0-indexed. `inserted` is the number of inserts, to size the target up front.
+pub(crate) fn apply_in_order<T: Clone + PartialEq, I: Iterator<Item = Edit<T>>>(
+    source: &[T],
+    ordered: I,
+    inserted: usize,
+) -> Vec<T> {
+    let mut target = Vec::with_capacity(source.len() + inserted);
+
+    // The first source item that hasn't been copied or edited yet
+    let mut next = 0;
+
+    for edit in ordered {
+        match edit {
+            Edit::Insert(idx, val) => {
+                if idx > next {
+                    target.extend_from_slice(&source[next..idx]);
+                    next = idx;
+                }
+                target.push(val);
+            }
+            Edit::Delete(item) | Edit::Substitute(item, _) => {
+                // Later edits of an item that was already edited are ignored
+                if item < next {
+                    continue;
+                }
+
+                target.extend_from_slice(&source[next..item]);
+                next = item + 1;
+
+                if let Edit::Substitute(_, val) = edit {
+                    target.push(val);
+                }
+            }
+        }
+    }
+
+    target.extend_from_slice(&source[next..]);
+
+    target
+}
+
+/// Applies a sequence of edits on the source sequence, interpreting their indices according to
+/// `indexing`, and returns a vector representing the target sequence.
+///
+/// The target is built in a single pass over the source, which takes O(n + k) time for a source
+/// of length n and k edits in the order `generate_edits` returns them. Edits in any other order
+/// are sorted first.
+///
+/// Each source item can only be deleted or substituted once. If several edits delete or
+/// substitute the same item, the one that comes last in `edits` is applied and the others are
+/// ignored. Use `try_apply_edits` to get an error for such edits instead.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `edits` - A reference to a vector of edits of the same type as elements of source
+/// * `indexing` - How the indices of the edits are interpreted
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{Edit, EditIndexing};
+///
+/// // Substitute the item at index 0, and delete the one at index 2
+/// let edits = vec![Edit::Delete(2), Edit::Substitute(0, b'M')];
+///
+/// let target = levenshtein::apply_edits_indexed("SUNDAY".as_bytes(), &edits, EditIndexing::ZeroBased);
+/// assert_eq!(target, "MUDAY".as_bytes());
+/// ```
+pub fn apply_edits_indexed<T: Clone + PartialEq>(
+    source: &[T],
+    edits: &[Edit<T>],
+    indexing: EditIndexing,
+) -> Vec<T> {
+    apply_with_index_type(source, edits, indexing)
+}
+
+/// Applies a sequence of edits like `apply_edits`, to edits whose indices are stored in another
+/// type than `usize`, like those from `generate_edits_as`.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `edits` - The edits, as generated by `generate_edits_as`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+///
+/// let edits: Vec<Edit<u8, u32>> = vec![Edit::Delete(3), Edit::Substitute(1, b'M')];
+///
+/// let target = levenshtein::apply_edits_as("SUNDAY".as_bytes(), &edits);
+/// assert_eq!(target, "MUDAY".as_bytes());
+/// ```
+pub fn apply_edits_as<T: Clone + PartialEq, I: DistanceCell>(
+    source: &[T],
+    edits: &[Edit<T, I>],
+) -> Vec<T> {
+    apply_with_index_type(source, edits, EditIndexing::OneBased)
+}
+
+fn apply_with_index_type<T: Clone + PartialEq, I: DistanceCell>(
+    source: &[T],
+    edits: &[Edit<T, I>],
+    indexing: EditIndexing,
+) -> Vec<T> {
+    let first = indexing.first();
+
+    let ordered = in_source_order(edits, first)
+        .into_iter()
+        .map(|edit| match edit {
+            Edit::Insert(idx, val) => Edit::Insert(idx.to_usize(), val.clone()),
+            Edit::Delete(idx) => Edit::Delete(idx.to_usize() - first),
+            Edit::Substitute(idx, val) => Edit::Substitute(idx.to_usize() - first, val.clone()),
+        });
+
+    let inserted = edits
+        .iter()
+        .filter(|edit| matches!(edit, Edit::Insert(..)))
+        .count();
+
+    apply_in_order(source, ordered, inserted)
+}
+
+/// Generate a vector of edits that, when applied to the source sequence, transform it into the
+/// target sequence.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `distances` - A reference to the `DistanceMatrix` for converting source to target
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+///
+/// // This can be used with the `apply_edits` function to transform source to target
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+/// ```
+pub fn generate_edits<T: Clone + PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+    distances: &DistanceMatrix<C>,
+) -> Result<Vec<Edit<T>>, LevenshteinError> {
+    generate_edits_indexed(source, target, distances, EditIndexing::OneBased)
+}
+
+/// Generate a vector of edits like `generate_edits`, but with their indices stored as `I`.
+///
+/// With `u32` indices an edit of bytes takes 8 bytes instead of 16, which adds up for long
+/// sequences that differ a lot. Apply the edits with `apply_edits_as`, or turn them back into
+/// edits with `usize` indices with `Edit::with_index`.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `distances` - A reference to the `DistanceMatrix` for converting source to target
+///
+/// # Panics
+///
+/// Panics if the length of the longer sequence doesn't fit in `I`.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+///
+/// let s1 = "SATURDAY".as_bytes();
+/// let s2 = "SUNDAY".as_bytes();
+///
+/// let (_, matrix) = levenshtein::distance_as::<_, u32>(s1, s2);
+/// let edits = levenshtein::generate_edits_as::<_, u32, _>(s1, s2, &matrix).unwrap();
+///
+/// assert_eq!(edits[0], Edit::Substitute(5u32, b'N'));
+/// assert_eq!(levenshtein::apply_edits_as(s1, &edits), s2);
+/// ```
+pub fn generate_edits_as<T: Clone + PartialEq, I: DistanceCell, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+    distances: &DistanceMatrix<C>,
+) -> Result<Vec<Edit<T, I>>, LevenshteinError> {
+    let edits = EditsIter::new(
+        source,
+        target,
+        distances,
+        EditIndexing::OneBased,
+        TiebreakPolicy::default(),
+    )?;
+
+    assert!(
+        I::from_usize(core::cmp::max(source.len(), target.len())).is_some(),
+        "the sequences are too long for the index type"
+    );
+
+    // No index is larger than the length of the longer sequence
+    edits
+        .map(|edit| edit.map(|edit| edit.with_index().unwrap()))
+        .collect()
+}
+
+/// Generate a vector of edits like `generate_edits`, but with the new items borrowed from the
+/// target instead of cloned. Use `Edit::into_owned` to turn an edit into one that owns its item.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `distances` - A reference to the `DistanceMatrix` for converting source to target
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+///
+/// let s1 = vec![String::from("one"), String::from("two")];
+/// let s2 = vec![String::from("one"), String::from("2")];
+///
+/// let (_, matrix) = levenshtein::distance(&s1, &s2);
+/// let edits = levenshtein::generate_edits_ref(&s1, &s2, &matrix).unwrap();
+///
+/// assert_eq!(edits, vec![Edit::Substitute(2, &s2[1])]);
+/// assert_eq!(edits[0].clone().into_owned(), Edit::Substitute(2, String::from("2")));
+/// ```
+pub fn generate_edits_ref<'a, T: PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &'a [T],
+    distances: &DistanceMatrix<C>,
+) -> Result<Vec<Edit<&'a T>>, LevenshteinError> {
+    let mut iter = EditsIter::new(
+        source,
+        target,
+        distances,
+        EditIndexing::OneBased,
+        TiebreakPolicy::default(),
+    )?;
+
+    core::iter::from_fn(|| iter.next_op())
+        .map(|result| {
+            result.map(|op| match op {
+                EditOp::Delete(idx) => Edit::Delete(idx),
+                EditOp::Insert(idx, target_idx) => Edit::Insert(idx, &target[target_idx]),
+                EditOp::Substitute(idx, target_idx) => Edit::Substitute(idx, &target[target_idx]),
+            })
+        })
+        .collect()
+}
+
+/// Generate a vector of edits that, when applied to the source sequence with
+/// `apply_edits_indexed`, transform it into the target sequence. The indices of the edits follow
+/// `indexing`.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `distances` - A reference to the `DistanceMatrix` for converting source to target
+/// * `indexing` - How the indices of the edits are interpreted
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{Edit, EditIndexing};
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+///
+/// let edits = levenshtein::generate_edits_indexed(
+///     s1.as_bytes(),
+///     s2.as_bytes(),
+///     &matrix,
+///     EditIndexing::ZeroBased,
+/// )
+/// .unwrap();
+///
+/// // R, at index 4, becomes N. A and T, at indices 1 and 2, are deleted.
+/// assert!(edits == vec![Edit::Substitute(4, b'N'), Edit::Delete(2), Edit::Delete(1)]);
+///
+/// let target = levenshtein::apply_edits_indexed(s1.as_bytes(), &edits, EditIndexing::ZeroBased);
+/// assert_eq!(target, s2.as_bytes());
+/// ```
+pub fn generate_edits_indexed<T: Clone + PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+    distances: &DistanceMatrix<C>,
+    indexing: EditIndexing,
+) -> Result<Vec<Edit<T>>, LevenshteinError> {
+    EditsIter::new(
+        source,
+        target,
+        distances,
+        indexing,
+        TiebreakPolicy::default(),
+    )?
+    .collect()
+}
+
+/// Generate a vector of edits that transform the source sequence into the target sequence, using
+/// `policy` to choose between equally good edits.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `distances` - A reference to the `DistanceMatrix` for converting source to target
+/// * `policy` - How to break ties between equally good edits
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{Edit, TiebreakPolicy};
+///
+/// let s1 = "AB";
+/// let s2 = "BC";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+///
+/// let edits = levenshtein::generate_edits_with_policy(
+///     s1.as_bytes(),
+///     s2.as_bytes(),
+///     &matrix,
+///     TiebreakPolicy::PreferSubstitution,
+/// )
+/// .unwrap();
+///
+/// assert!(edits == vec![Edit::Substitute(2, b'C'), Edit::Substitute(1, b'B')]);
+/// ```
+pub fn generate_edits_with_policy<T: Clone + PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+    distances: &DistanceMatrix<C>,
+    policy: TiebreakPolicy,
+) -> Result<Vec<Edit<T>>, LevenshteinError> {
+    EditsIter::new(source, target, distances, EditIndexing::OneBased, policy)?.collect()
+}
+
+/// Returns an iterator over the edits that transform the source sequence into the target
+/// sequence, in the same order as `generate_edits` returns them.
+///
+/// The traceback through the distance matrix is performed lazily, one edit at a time, so it's
+/// cheap to only look at the first few edits. Each item is an error if the traceback runs into an
+/// inconsistent cell of the matrix, after which the iterator stops.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `distances` - A reference to the `DistanceMatrix` for converting source to target
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+///
+/// // The edits closest to the end of the source come first
+/// let mut edits = levenshtein::edits_iter(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+/// assert!(matches!(edits.next(), Some(Ok(Edit::Substitute(5, b'N')))));
+/// ```
+pub fn edits_iter<'a, T: Clone + PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &'a [T],
+    distances: &'a DistanceMatrix<C>,
+) -> Result<EditsIter<'a, T, C>, LevenshteinError> {
+    EditsIter::new(
+        source,
+        target,
+        distances,
+        EditIndexing::OneBased,
+        TiebreakPolicy::default(),
+    )
+}
+
+/// Generate a vector of edits that transform the source sequence into the target sequence,
+/// reporting progress along the way. See `generate_edits`.
+///
+/// The traceback walks from the last cell of the matrix to the first, one row or column or both
+/// at a time, so the callback is called with the number of rows and columns crossed so far and
+/// the number there are in all, after every edit and once more at the end.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `distances` - A reference to the `DistanceMatrix` for converting source to target
+/// * `progress` - The callback, called with the rows and columns crossed and the total
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY".as_bytes();
+/// let s2 = "SUNDAY".as_bytes();
+///
+/// let (_, matrix) = levenshtein::distance(s1, s2);
+///
+/// let mut reports = Vec::new();
+/// let edits = levenshtein::generate_edits_with_progress(s1, s2, &matrix, |done, total| {
+///     reports.push((done, total))
+/// })
+/// .unwrap();
+///
+/// assert_eq!(edits.len(), 3);
+/// assert_eq!(reports.len(), 4);
+/// assert_eq!(reports.last(), Some(&(14, 14)));
+/// ```
+pub fn generate_edits_with_progress<
+    T: Clone + PartialEq,
+    C: DistanceCell,
+    F: FnMut(usize, usize),
+>(
+    source: &[T],
+    target: &[T],
+    distances: &DistanceMatrix<C>,
+    mut progress: F,
+) -> Result<Vec<Edit<T>>, LevenshteinError> {
+    let total = source.len() + target.len();
+    let mut iter = edits_iter(source, target, distances)?;
+    let mut edits = Vec::new();
+
+    while let Some(edit) = iter.next() {
+        edits.push(edit?);
+        progress(total - iter.source_idx - iter.target_idx, total);
+    }
+
+    progress(total, total);
+
+    Ok(edits)
+}
+
+/// A lazy traceback through a distance matrix, as returned by `edits_iter`.
+pub struct EditsIter<'a, T, C = usize> {
+    target: &'a [T],
+    distances: &'a DistanceMatrix<C>,
+    // How much the recorded source indices are shifted down from the 1-indexed matrix rows
+    shift: usize,
+    policy: TiebreakPolicy,
+    // The step that produced the previous edit
+    last: Option<Step>,
+    source_idx: usize,
+    target_idx: usize,
+    failed: bool,
+}
+
+impl<'a, T: PartialEq, C: DistanceCell> EditsIter<'a, T, C> {
+    pub(crate) fn new(
+        source: &[T],
+        target: &'a [T],
+        distances: &'a DistanceMatrix<C>,
+        indexing: EditIndexing,
+        policy: TiebreakPolicy,
+    ) -> Result<Self, LevenshteinError> {
+        if (source.len() + 1, target.len() + 1) != distances.dims() {
+            return Err(LevenshteinError::InvalidDistanceMatrixError);
+        }
+
+        Ok(EditsIter {
+            target,
+            distances,
+            shift: 1 - indexing.first(),
+            policy,
+            last: None,
+            source_idx: source.len(),
+            target_idx: target.len(),
+            failed: false,
+        })
+    }
+
+    // Returns the next step of the traceback, with the index of the new item in the target
+    // instead of the item itself
+    pub(crate) fn next_op(&mut self) -> Option<Result<EditOp, LevenshteinError>> {
+        let distances = self.distances;
+
+        // When both source and target indices are 0, we have succesfully computed all the edits
+        // required to transform the source into the target
+        while !self.failed && (self.source_idx != 0 || self.target_idx != 0) {
+            let source_idx = self.source_idx;
+            let target_idx = self.target_idx;
+
+            let current_item = distances[(source_idx, target_idx)].to_usize();
+
+            // These represent the options we have: substitute, insert and delete
+            let substitute = if source_idx > 0 && target_idx > 0 {
+                distances[(source_idx - 1, target_idx - 1)].to_usize()
+            } else {
+                usize::MAX
+            };
+
+            let delete = if source_idx > 0 {
+                distances[(source_idx - 1, target_idx)].to_usize()
+            } else {
+                usize::MAX
+            };
+
+            let insert = if target_idx > 0 {
+                distances[(source_idx, target_idx - 1)].to_usize()
+            } else {
+                usize::MAX
+            };
+
+            let min = min(min(insert, delete), substitute);
+
+            if min == current_item {
+                self.source_idx -= 1;
+                self.target_idx -= 1;
+                continue;
+            }
+
+            // Any neighbor one less than the current item is an optimal choice
+            let step = if min == current_item.wrapping_sub(1) {
+                self.policy
+                    .order(self.last)
+                    .iter()
+                    .copied()
+                    .find(|step| match step {
+                        Step::Insert => insert == min,
+                        Step::Delete => delete == min,
+                        _ => substitute == min,
+                    })
+            } else {
+                None
+            };
+
+            let op = match step {
+                Some(Step::Insert) => {
+                    // The matrix is 1-indexed, but the slices obviously aren't
+                    // Hence target_idx - 1 is the index of the inserted item
+                    self.target_idx -= 1;
+                    EditOp::Insert(source_idx, target_idx - 1)
+                }
+                Some(Step::Delete) => {
+                    self.source_idx -= 1;
+                    EditOp::Delete(source_idx - self.shift)
+                }
+                Some(_) => {
+                    self.source_idx -= 1;
+                    self.target_idx -= 1;
+                    EditOp::Substitute(source_idx - self.shift, target_idx - 1)
+                }
+                None => {
+                    self.failed = true;
+                    return Some(Err(LevenshteinError::InvalidDistanceMatrixError));
+                }
+            };
+
+            self.last = step;
+
+            return Some(Ok(op));
+        }
+
+        None
+    }
+}
+
+impl<'a, T: Clone + PartialEq, C: DistanceCell> Iterator for EditsIter<'a, T, C> {
+    type Item = Result<Edit<T>, LevenshteinError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let target = self.target;
+
+        self.next_op()
+            .map(|result| result.map(|op| op.to_edit(target)))
+    }
+}
+
+/// Generate a vector of edits that transform the source sequence into the target sequence by
+/// following the backpointers returned by `levenshtein_tabulation_backpointers`.
+///
+/// The edits are the same as those `generate_edits` returns, but every decision is simply read
+/// off the backpointer matrix, so the traceback takes O(m + n) time.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `backpointers` - A reference to the `BackpointerMatrix` for converting source to target
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, _, backpointers) =
+///     levenshtein::levenshtein_tabulation_backpointers(s1.as_bytes(), s2.as_bytes());
+///
+/// let edits =
+///     levenshtein::generate_edits_from_backpointers(s1.as_bytes(), s2.as_bytes(), &backpointers)
+///         .unwrap();
+///
+/// let target = levenshtein::apply_edits(s1.as_bytes(), &edits);
+/// assert_eq!(target, s2.as_bytes());
+/// ```
+pub fn generate_edits_from_backpointers<T: Clone + PartialEq>(
+    source: &[T],
+    target: &[T],
+    backpointers: &BackpointerMatrix,
+) -> Result<Vec<Edit<T>>, LevenshteinError> {
+    let mut source_idx = source.len();
+    let mut target_idx = target.len();
+
+    if backpointers.dims() != (source_idx + 1, target_idx + 1) {
+        return Err(LevenshteinError::InvalidDistanceMatrixError);
+    }
+
+    let mut edits = Vec::<Edit<T>>::new();
+
+    while source_idx != 0 || target_idx != 0 {
+        let step = backpointers[(source_idx, target_idx)];
+
+        // A step that would leave the matrix can't be followed
+        let leaves_matrix = match step {
+            Step::Insert => target_idx == 0,
+            Step::Delete => source_idx == 0,
+            Step::Match | Step::Substitute => source_idx == 0 || target_idx == 0,
         };
 
-        write!(f, "{}", error)
+        if leaves_matrix {
+            return Err(LevenshteinError::InvalidDistanceMatrixCell(
+                source_idx, target_idx,
+            ));
+        }
+
+        match step {
+            Step::Match => {}
+            Step::Insert => edits.push(Edit::Insert(source_idx, target[target_idx - 1].clone())),
+            Step::Delete => edits.push(Edit::Delete(source_idx)),
+            Step::Substitute => {
+                edits.push(Edit::Substitute(source_idx, target[target_idx - 1].clone()))
+            }
+        }
+
+        if step != Step::Insert {
+            source_idx -= 1;
+        }
+
+        if step != Step::Delete {
+            target_idx -= 1;
+        }
     }
+
+    Ok(edits)
 }
 
-impl Error for LevenshteinError {}
+/// Checks that a distance matrix really is the Levenshtein distance matrix for converting source
+/// to target.
+///
+/// Besides the dimensions, every cell is checked against its neighbors: the first row and column
+/// must count up from 0, and every other cell must be the cheapest of deleting, inserting, or
+/// substituting (or keeping) an item. Returns the first offending cell, in row-major order.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `distances` - A reference to the `DistanceMatrix` for converting source to target
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+///
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::util::DistanceMatrix;
+/// use levenshtein::LevenshteinError;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// assert!(levenshtein::validate_distance_matrix(s1.as_bytes(), s2.as_bytes(), &matrix).is_ok());
+///
+/// let mut rows: Vec<Vec<usize>> = matrix.into();
+/// rows[3][2] = 0;
+/// let matrix = DistanceMatrix::try_from(rows).unwrap();
+///
+/// assert!(matches!(
+///     levenshtein::validate_distance_matrix(s1.as_bytes(), s2.as_bytes(), &matrix),
+///     Err(LevenshteinError::InvalidDistanceMatrixCell(3, 2))
+/// ));
+/// ```
+pub fn validate_distance_matrix<T: PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+    distances: &DistanceMatrix<C>,
+) -> Result<(), LevenshteinError> {
+    if (source.len() + 1, target.len() + 1) != distances.dims() {
+        return Err(LevenshteinError::InvalidDistanceMatrixError);
+    }
+
+    for (i, row) in distances.rows().enumerate() {
+        for (j, cell) in row.enumerate() {
+            let cell = cell.to_usize();
+            let expected = match (i, j) {
+                (0, _) => j,
+                (_, 0) => i,
+                _ => {
+                    let cost = if source[i - 1] == target[j - 1] { 0 } else { 1 };
+
+                    // Saturate, so that huge values in the neighbors are reported rather than
+                    // overflowing
+                    min(
+                        min(
+                            distances[(i - 1, j)].to_usize().saturating_add(1),
+                            distances[(i, j - 1)].to_usize().saturating_add(1),
+                        ),
+                        distances[(i - 1, j - 1)].to_usize().saturating_add(cost),
+                    )
+                }
+            };
+
+            if cell != expected {
+                return Err(LevenshteinError::InvalidDistanceMatrixCell(i, j));
+            }
+        }
+    }
 
-/// Represents an Edit applied on a source sequence.
-#[derive(Clone, PartialEq)]
-pub enum Edit<T: PartialEq> {
-    Delete(usize),        // Delete item at index
-    Insert(usize, T),     // Insert item T at index
-    Substitute(usize, T), // Substitute item at index with T
+    Ok(())
 }
 
-/// Applies a sequence of edits on the source sequence, and returns a vector representing the
-/// target sequence.
+/// Generate a vector of edits that transform the source sequence into the target sequence, after
+/// validating the distance matrix with `validate_distance_matrix`.
+///
+/// `generate_edits` only checks the dimensions of the matrix, so a matrix with the wrong contents
+/// can produce nonsensical edits. This function is slower, since it has to look at every cell,
+/// but pinpoints the offending cell instead.
 ///
 /// # Arguments
 ///
 /// * `source` - The source sequence
-/// * `edits` - A reference to a vector of edits of the same type as elements of source
+/// * `target` - The target sequence
+/// * `distances` - A reference to the `DistanceMatrix` for converting source to target
 ///
 /// # Examples
 ///
 /// ```
 /// use levenshtein_diff as levenshtein;
 ///
-/// let s1 = "FLOWER";
-/// let expected_s2 = "FOLLOWER";
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
 ///
-/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), expected_s2.as_bytes());
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
 ///
-/// let edits = levenshtein::generate_edits(s1.as_bytes(), expected_s2.as_bytes(), &matrix).unwrap();
+/// let edits = levenshtein::generate_edits_validated(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+/// assert_eq!(edits.len(), 3);
+/// ```
+pub fn generate_edits_validated<T: Clone + PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+    distances: &DistanceMatrix<C>,
+) -> Result<Vec<Edit<T>>, LevenshteinError> {
+    validate_distance_matrix(source, target, distances)?;
+    generate_edits(source, target, distances)
+}
+
+/// Generate a vector of edits that transform the source sequence into the target sequence, from
+/// the distance matrix of what's left of them once their common prefix and suffix are stripped.
 ///
-/// let target = levenshtein::apply_edits(s1.as_bytes(), &edits);
+/// The edits are generated for the stripped sequences and then shifted by the length of the
+/// prefix, so their indices refer to the original source. There are as few of them as
+/// `generate_edits` produces with the full matrix, though where there are several optimal
+/// alignments they may not be the same ones.
 ///
-/// let s2 = match std::str::from_utf8(&target) {
-///     Ok(v) => v,
-///     Err(_) => panic!("Not a valid UTF-8 sequence!"),
-/// };
+/// # Arguments
+///
+/// * `stripped` - The stripped sequences, as returned by `strip_common_affix`
+/// * `distances` - A reference to the `DistanceMatrix` for converting the stripped source to the
+///   stripped target
+///
+/// # Examples
 ///
-/// assert_eq!(s2, expected_s2);
 /// ```
-pub fn apply_edits<T: Clone + PartialEq>(source: &[T], edits: &[Edit<T>]) -> Vec<T> {
-    // Convert each item of source into Some(item)
-    let mut target_constructor: Vec<Option<T>> =
-        source.iter().map(|item| Some(item.clone())).collect();
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::util::strip_common_affix;
+///
+/// let s1 = "SATURDAY".as_bytes();
+/// let s2 = "SUNDAY".as_bytes();
+///
+/// let stripped = strip_common_affix(s1, s2);
+/// let (_, matrix) = levenshtein::distance(stripped.source, stripped.target);
+/// assert_eq!(matrix.dims(), (5, 3));
+///
+/// let edits = levenshtein::generate_edits_stripped(&stripped, &matrix).unwrap();
+/// assert_eq!(levenshtein::apply_edits(s1, &edits), s2);
+/// ```
+pub fn generate_edits_stripped<T: Clone + PartialEq, C: DistanceCell>(
+    stripped: &CommonAffix<'_, T>,
+    distances: &DistanceMatrix<C>,
+) -> Result<Vec<Edit<T>>, LevenshteinError> {
+    let prefix = stripped.prefix;
+    let edits = generate_edits(stripped.source, stripped.target, distances)?;
 
-    let mut inserts = Vec::<Edit<T>>::with_capacity(source.len());
+    Ok(edits
+        .into_iter()
+        .map(|edit| match edit {
+            Edit::Delete(idx) => Edit::Delete(idx + prefix),
+            Edit::Insert(idx, val) => Edit::Insert(idx + prefix, val),
+            Edit::Substitute(idx, val) => Edit::Substitute(idx + prefix, val),
+        })
+        .collect())
+}
 
-    // We iterate in the reverse order because we want to populate the inserts vector in the
-    // reverse order of indices. This ensures that we don't need any operational transforms on the
-    // inserts.
-    for edit in edits.iter().rev() {
-        match edit {
-            Edit::Substitute(idx, val) => target_constructor[idx - 1] = Some(val.clone()),
-            Edit::Delete(idx) => target_constructor[idx - 1] = None,
-            Edit::Insert(idx, val) => inserts.push(Edit::Insert(*idx, val.clone())),
-        }
-    }
+/// Generate a vector of edits that transform the source sequence into the target sequence,
+/// without a distance matrix, for sequences that are very similar.
+///
+/// The edits are traced back through the diagonals of `levenshtein_greedy`, so only the cells
+/// where the sequences differ are ever computed: this takes O((m + n) * d) time and O(d^2)
+/// memory, where d is the distance, instead of the O(m * n) of a matrix. The edits are as few as
+/// `generate_edits` produces and in the same order, but where several alignments are optimal, a
+/// different one may be chosen.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let source = vec![b'A'; 1_000_000];
+/// let mut target = source.clone();
+/// target[10] = b'B';
+/// target.insert(900_000, b'C');
+///
+/// let edits = levenshtein::generate_edits_greedy(&source, &target);
+/// assert_eq!(edits.len(), 2);
+/// assert_eq!(levenshtein::apply_edits(&source, &edits), target);
+/// ```
+pub fn generate_edits_greedy<T: Clone + PartialEq>(source: &[T], target: &[T]) -> Vec<Edit<T>> {
+    let (m, n) = (source.len(), target.len());
+    let fronts = diagonal_fronts(source, target);
 
-    for i in &inserts {
-        if let Edit::Insert(idx, val) = i {
-            target_constructor.insert(*idx, Some(val.clone()));
-        }
-    }
+    let mut edits = Vec::<Edit<T>>::new();
+    let mut k = n as isize - m as isize;
+
+    // Each front is reached from the one before, so walk them back to the start, keeping only
+    // the steps that were edits
+    for e in (1..fronts.len()).rev() {
+        let (i, step) = diagonal_predecessor(&fronts[e - 1], k, (m, n))
+            .expect("every diagonal on the path was reached");
 
-    let mut target = Vec::<T>::new();
-    for i in &target_constructor {
-        match i {
-            Some(val) => target.push(val.clone()),
-            None => (),
+        match step {
+            None => {}
+            Some(Step::Substitute) => edits.push(Edit::Substitute(
+                i,
+                target[(i as isize + k) as usize - 1].clone(),
+            )),
+            Some(Step::Insert) => {
+                edits.push(Edit::Insert(
+                    i,
+                    target[(i as isize + k) as usize - 1].clone(),
+                ));
+                k -= 1;
+            }
+            Some(Step::Delete) => {
+                edits.push(Edit::Delete(i));
+                k += 1;
+            }
+            Some(Step::Match) => unreachable!("matches are never edits"),
         }
     }
 
-    target
+    edits
 }
 
-/// Generate a vector of edits that, when applied to the source sequence, transform it into the
-/// target sequence.
+/// Generate a vector of edits that transform the source sequence into the target sequence, in
+/// ascending order of indices.
+///
+/// Unlike `generate_edits`, the index of every edit refers to the sequence as it is after all
+/// the edits before it have been applied, so the edits can be applied one at a time, left to
+/// right, with `apply_edits_forward`.
 ///
 /// # Arguments
 ///
@@ -109,89 +1399,132 @@ pub fn apply_edits<T: Clone + PartialEq>(source: &[T], edits: &[Edit<T>]) -> Vec
 ///
 /// ```
 /// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
 ///
 /// let s1 = "SATURDAY";
 /// let s2 = "SUNDAY";
 ///
 /// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
 ///
-/// // This can be used with the `apply_edits` function to transform source to target
-/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+/// let edits = levenshtein::generate_edits_forward(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+///
+/// // A is deleted, after which T is the second item and gets deleted too. R is now the third.
+/// assert!(edits == vec![Edit::Delete(2), Edit::Delete(2), Edit::Substitute(3, b'N')]);
+///
+/// let target = levenshtein::apply_edits_forward(s1.as_bytes(), &edits);
+/// assert_eq!(target, s2.as_bytes());
 /// ```
-pub fn generate_edits<T: Clone + PartialEq>(
+pub fn generate_edits_forward<T: Clone + PartialEq, C: DistanceCell>(
     source: &[T],
     target: &[T],
-    distances: &DistanceMatrix,
+    distances: &DistanceMatrix<C>,
 ) -> Result<Vec<Edit<T>>, LevenshteinError> {
-    let mut source_idx = source.len();
-    let mut target_idx = target.len();
+    let edits = generate_edits(source, target, distances)?;
 
-    if source_idx + 1 != distances.len() || target_idx + 1 != distances[0].len() {
-        return Err(LevenshteinError::InvalidDistanceMatrixError);
-    }
+    let mut forward = Vec::<Edit<T>>::with_capacity(edits.len());
 
-    let mut edits = Vec::<Edit<T>>::new();
+    // How much longer the sequence has become because of the edits so far
+    let mut inserted = 0;
+    let mut removed = 0;
 
-    // When both source and target indices are 0, we have succesfully computed all the edits
-    // required to transform the source into the target
-    while source_idx != 0 || target_idx != 0 {
-        let current_item = distances[source_idx][target_idx];
+    for replacement in coalesce_edits(&edits) {
+        let start = replacement.range.start + inserted - removed;
+        let substituted = min(replacement.range.len(), replacement.with.len());
 
-        // These represent the options we have: substitute, insert and delete
-        let substitute = if source_idx > 0 && target_idx > 0 {
-            distances[source_idx - 1][target_idx - 1]
-        } else {
-            usize::MAX
-        };
+        for (i, val) in replacement.with.iter().enumerate() {
+            if i < substituted {
+                forward.push(Edit::Substitute(start + i + 1, val.clone()));
+            } else {
+                forward.push(Edit::Insert(start + i, val.clone()));
+            }
+        }
 
-        let delete = if source_idx > 0 {
-            distances[source_idx - 1][target_idx]
-        } else {
-            usize::MAX
-        };
+        // The remaining items to delete all end up at the same index, one after another
+        for _ in substituted..replacement.range.len() {
+            forward.push(Edit::Delete(start + substituted + 1));
+        }
 
-        let insert = if target_idx > 0 {
-            distances[source_idx][target_idx - 1]
-        } else {
-            usize::MAX
+        inserted += replacement.with.len();
+        removed += replacement.range.len();
+    }
+
+    Ok(forward)
+}
+
+/// Applies a sequence of edits generated by `generate_edits_forward` on the source sequence, one
+/// after another, and returns a vector representing the target sequence.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `edits` - A reference to a vector of edits in ascending order
+///
+/// The target is built in a single pass over the source when the edits are in ascending order.
+/// Edits that go back to an earlier index are still applied one after another, but each of them
+/// takes time linear in the length of the target.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+///
+/// let edits = vec![Edit::Delete(2), Edit::Delete(2), Edit::Substitute(3, b'N')];
+///
+/// let target = levenshtein::apply_edits_forward("SATURDAY".as_bytes(), &edits);
+/// assert_eq!(target, "SUNDAY".as_bytes());
+/// ```
+pub fn apply_edits_forward<T: Clone + PartialEq>(source: &[T], edits: &[Edit<T>]) -> Vec<T> {
+    let mut target = Vec::with_capacity(source.len());
+    // The items of the current sequence are the target so far, followed by the source from here
+    let mut source_idx = 0;
+
+    for edit in edits {
+        let idx = match edit {
+            Edit::Substitute(idx, _) | Edit::Delete(idx) => idx - 1,
+            Edit::Insert(idx, _) => *idx,
         };
 
-        let min = min(min(insert, delete), substitute);
-
-        if min == current_item {
-            source_idx = source_idx - 1;
-            target_idx = target_idx - 1;
-        } else if min == current_item - 1 {
-            if min == insert {
-                // The edits are expected to be 1-indexed, but the slices obviously aren't
-                // Hence we do target_idx - 1 to access the right value
-                edits.push(Edit::Insert(source_idx, target[target_idx - 1].clone()));
-                target_idx = target_idx - 1;
-            } else if min == delete {
-                edits.push(Edit::Delete(source_idx));
-                source_idx = source_idx - 1;
-            } else if min == substitute {
-                edits.push(Edit::Substitute(source_idx, target[target_idx - 1].clone()));
-                source_idx = source_idx - 1;
-                target_idx = target_idx - 1;
-            } else {
-                return Err(LevenshteinError::InvalidDistanceMatrixError);
-            };
+        // Edits in ascending order only ever touch the end of the target, so the items in front
+        // of them are copied over once
+        if idx >= target.len() {
+            let end = source_idx + idx - target.len();
+            target.extend_from_slice(&source[source_idx..end]);
+            source_idx = end;
+
+            match edit {
+                Edit::Substitute(_, val) => {
+                    target.push(val.clone());
+                    source_idx += 1;
+                }
+                Edit::Delete(_) => source_idx += 1,
+                Edit::Insert(_, val) => target.push(val.clone()),
+            }
         } else {
-            return Err(LevenshteinError::InvalidDistanceMatrixError);
-        };
+            match edit {
+                Edit::Substitute(_, val) => target[idx] = val.clone(),
+                Edit::Delete(_) => {
+                    target.remove(idx);
+                }
+                Edit::Insert(_, val) => target.insert(idx, val.clone()),
+            }
+        }
     }
 
-    Ok(edits)
+    target.extend_from_slice(&source[source_idx..]);
+
+    target
 }
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryFrom;
+
     use crate::edit::*;
 
     // Copied verbatim from
     // https://stackoverflow.com/questions/29504514/whats-the-best-way-to-compare-2-vectors-or-strings-element-by-element
-    fn do_vecs_match<T: PartialEq>(a: &Vec<T>, b: &Vec<T>) -> bool {
+    fn do_vecs_match<T: PartialEq>(a: &[T], b: &[T]) -> bool {
         let matching = a.iter().zip(b.iter()).filter(|&(a, b)| a == b).count();
         matching == a.len() && matching == b.len()
     }
@@ -203,7 +1536,7 @@ mod tests {
 
         // This is the distance matrix for the strings
         // SATURDAY and SUNDAY
-        let distances = vec![
+        let distances = DistanceMatrix::<usize>::try_from(vec![
             vec![0, 1, 2, 3, 4, 5, 6],
             vec![1, 0, 1, 2, 3, 4, 5],
             vec![2, 1, 1, 2, 3, 3, 4],
@@ -213,7 +1546,8 @@ mod tests {
             vec![6, 5, 4, 4, 3, 4, 5],
             vec![7, 6, 5, 5, 4, 3, 4],
             vec![8, 7, 6, 6, 5, 4, 3],
-        ];
+        ])
+        .unwrap();
 
         let expected_edits = vec![
             Edit::<u8>::Substitute(5, 78),
@@ -223,7 +1557,7 @@ mod tests {
 
         let edits = generate_edits(s1.as_bytes(), s2.as_bytes(), &distances).unwrap();
 
-        assert_eq!(do_vecs_match(&edits, &expected_edits), true);
+        assert!(do_vecs_match(&edits, &expected_edits));
     }
 
     #[test]
@@ -232,13 +1566,13 @@ mod tests {
         let expected_s2 = "SUNDAY";
 
         // Edits that convert SATURDAY to SUNDAY
-        let mut edits = vec![
+        let edits = vec![
             Edit::<u8>::Substitute(5, 78),
             Edit::<u8>::Delete(3),
             Edit::<u8>::Delete(2),
         ];
 
-        let s2_bytes_vec = apply_edits(s1.as_bytes(), &mut edits);
+        let s2_bytes_vec = apply_edits(s1.as_bytes(), &edits);
 
         let s2 = match std::str::from_utf8(&s2_bytes_vec) {
             Ok(v) => v,
@@ -247,4 +1581,423 @@ mod tests {
 
         assert_eq!(s2, expected_s2);
     }
+
+    #[test]
+    fn interleaved_inserts_are_applied_correctly() {
+        let s1 = "AB";
+        let expected_s2 = "XAYBZ";
+
+        let (_, matrix) = crate::distance(s1.as_bytes(), expected_s2.as_bytes());
+        let edits = generate_edits(s1.as_bytes(), expected_s2.as_bytes(), &matrix).unwrap();
+
+        let s2_bytes_vec = apply_edits(s1.as_bytes(), &edits);
+
+        assert_eq!(s2_bytes_vec, expected_s2.as_bytes());
+    }
+
+    #[test]
+    fn forward_edits_are_ascending() {
+        let s1 = "AB";
+        let expected_s2 = "XAYBZ";
+
+        let (_, matrix) = crate::distance(s1.as_bytes(), expected_s2.as_bytes());
+        let edits = generate_edits_forward(s1.as_bytes(), expected_s2.as_bytes(), &matrix).unwrap();
+
+        let expected_edits = vec![
+            Edit::<u8>::Insert(0, b'X'),
+            Edit::<u8>::Insert(2, b'Y'),
+            Edit::<u8>::Insert(4, b'Z'),
+        ];
+
+        assert!(do_vecs_match(&edits, &expected_edits));
+        assert_eq!(
+            apply_edits_forward(s1.as_bytes(), &edits),
+            expected_s2.as_bytes()
+        );
+    }
+
+    #[test]
+    fn forward_edits_going_back_are_applied_in_order() {
+        // Builds XAYBZ from AB, then goes back to turn it into QAYB
+        let edits = vec![
+            Edit::<u8>::Insert(0, b'X'),
+            Edit::<u8>::Insert(2, b'Y'),
+            Edit::<u8>::Insert(4, b'Z'),
+            Edit::<u8>::Substitute(1, b'Q'),
+            Edit::<u8>::Delete(5),
+        ];
+
+        assert_eq!(
+            apply_edits_forward("AB".as_bytes(), &edits),
+            "QAYB".as_bytes()
+        );
+
+        let edits = vec![Edit::<u8>::Substitute(2, b'Q'), Edit::<u8>::Insert(0, b'X')];
+        assert_eq!(
+            apply_edits_forward("AB".as_bytes(), &edits),
+            "XAQ".as_bytes()
+        );
+    }
+
+    #[test]
+    fn zero_based_edits_are_shifted() {
+        let s1 = "SATURDAY";
+        let expected_s2 = "SUNDAY";
+
+        let (_, matrix) = crate::distance(s1.as_bytes(), expected_s2.as_bytes());
+        let one_based = generate_edits(s1.as_bytes(), expected_s2.as_bytes(), &matrix).unwrap();
+        let zero_based = generate_edits_indexed(
+            s1.as_bytes(),
+            expected_s2.as_bytes(),
+            &matrix,
+            EditIndexing::ZeroBased,
+        )
+        .unwrap();
+
+        let expected_edits = vec![
+            Edit::<u8>::Substitute(4, b'N'),
+            Edit::<u8>::Delete(2),
+            Edit::<u8>::Delete(1),
+        ];
+
+        assert!(do_vecs_match(&zero_based, &expected_edits));
+        assert_eq!(
+            apply_edits_indexed(s1.as_bytes(), &zero_based, EditIndexing::ZeroBased),
+            apply_edits(s1.as_bytes(), &one_based)
+        );
+    }
+
+    #[test]
+    fn edits_iter_matches_generate_edits() {
+        let s1 = "SATURDAY";
+        let s2 = "SUNDAY";
+
+        let (_, matrix) = crate::distance(s1.as_bytes(), s2.as_bytes());
+        let edits = generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+
+        let lazy_edits: Result<Vec<_>, _> = edits_iter(s1.as_bytes(), s2.as_bytes(), &matrix)
+            .unwrap()
+            .collect();
+
+        assert!(do_vecs_match(&lazy_edits.unwrap(), &edits));
+    }
+
+    #[test]
+    fn edits_iter_stops_at_invalid_cell() {
+        let s1 = "AB";
+        let s2 = "AB";
+
+        let (_, mut matrix) = crate::distance(s1.as_bytes(), s2.as_bytes());
+        matrix.set(2, 2, 5);
+
+        let mut edits = edits_iter(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+
+        assert!(matches!(
+            edits.next(),
+            Some(Err(LevenshteinError::InvalidDistanceMatrixError))
+        ));
+        assert!(edits.next().is_none());
+    }
+
+    #[test]
+    fn invalid_matrix_cell_is_reported() {
+        let s1 = "SATURDAY";
+        let s2 = "SUNDAY";
+
+        let (_, mut matrix) = crate::distance(s1.as_bytes(), s2.as_bytes());
+        assert!(validate_distance_matrix(s1.as_bytes(), s2.as_bytes(), &matrix).is_ok());
+
+        // The first row must count up from 0
+        matrix.set(0, 3, 2);
+        assert!(matches!(
+            generate_edits_validated(s1.as_bytes(), s2.as_bytes(), &matrix),
+            Err(LevenshteinError::InvalidDistanceMatrixCell(0, 3))
+        ));
+
+        // A matrix of the wrong dimensions is rejected outright
+        assert!(matches!(
+            validate_distance_matrix(s2.as_bytes(), s1.as_bytes(), &matrix),
+            Err(LevenshteinError::InvalidDistanceMatrixError)
+        ));
+
+        // And a ragged one can't be made at all
+        let mut rows: Vec<Vec<usize>> = matrix.into();
+        rows[4].pop();
+        assert!(matches!(
+            DistanceMatrix::try_from(rows),
+            Err(LevenshteinError::InvalidDistanceMatrixError)
+        ));
+    }
+
+    #[test]
+    fn narrow_edits_match_generate_edits() {
+        let s1 = "kitten sitting on the mitten".as_bytes();
+        let s2 = "sitting kitten in the mittens".as_bytes();
+
+        let (_, matrix) = crate::distance(s1, s2);
+        let edits = generate_edits(s1, s2, &matrix).unwrap();
+        let narrow = generate_edits_as::<_, u32, _>(s1, s2, &matrix).unwrap();
+
+        assert_eq!(core::mem::size_of::<Edit<u8, u32>>(), 8);
+        assert_eq!(apply_edits_as(s1, &narrow), s2);
+
+        let widened: Vec<Edit<u8>> = narrow
+            .into_iter()
+            .map(|edit| edit.with_index().unwrap())
+            .collect();
+        assert!(do_vecs_match(&widened, &edits));
+    }
+
+    #[test]
+    #[should_panic]
+    fn narrow_edits_of_long_sequences_test() {
+        let s1 = vec![b'A'; 300];
+
+        let (_, matrix) = crate::distance(&s1, b"B");
+        let _ = generate_edits_as::<_, u8, _>(&s1, b"B", &matrix);
+    }
+
+    #[test]
+    fn backpointer_edits_match_generate_edits() {
+        let s1 = "SATURDAY";
+        let s2 = "SUNDAY";
+
+        let (_, matrix, backpointers) =
+            crate::levenshtein_tabulation_backpointers(s1.as_bytes(), s2.as_bytes());
+
+        let edits = generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+        let backpointer_edits =
+            generate_edits_from_backpointers(s1.as_bytes(), s2.as_bytes(), &backpointers).unwrap();
+
+        assert!(do_vecs_match(&backpointer_edits, &edits));
+
+        // The matrix is for the sequences the other way round
+        assert!(matches!(
+            generate_edits_from_backpointers(s2.as_bytes(), s1.as_bytes(), &backpointers),
+            Err(LevenshteinError::InvalidDistanceMatrixError)
+        ));
+    }
+
+    #[test]
+    fn tiebreak_policies_give_valid_edits() {
+        let s1 = "ABC";
+        let expected_s2 = "CAB";
+
+        let (distance, matrix) = crate::distance(s1.as_bytes(), expected_s2.as_bytes());
+
+        let policies = [
+            TiebreakPolicy::PreferInsertion,
+            TiebreakPolicy::PreferDeletion,
+            TiebreakPolicy::PreferSubstitution,
+            TiebreakPolicy::PreferContiguity,
+        ];
+
+        for policy in policies {
+            let edits =
+                generate_edits_with_policy(s1.as_bytes(), expected_s2.as_bytes(), &matrix, policy)
+                    .unwrap();
+
+            assert_eq!(edits.len(), distance);
+            assert_eq!(apply_edits(s1.as_bytes(), &edits), expected_s2.as_bytes());
+        }
+
+        // C is deleted from the end before being inserted at the front
+        let deletion_first = generate_edits_with_policy(
+            s1.as_bytes(),
+            expected_s2.as_bytes(),
+            &matrix,
+            TiebreakPolicy::PreferDeletion,
+        )
+        .unwrap();
+
+        assert!(matches!(deletion_first[0], Edit::Delete(3)));
+    }
+
+    #[test]
+    fn edits_are_displayed() {
+        let edits = [
+            Edit::Insert(2, 'Z'),
+            Edit::Insert(0, 'X'),
+            Edit::Substitute(2, 'Y'),
+            Edit::Delete(1),
+        ];
+
+        let displayed: Vec<String> = edits.iter().map(|edit| edit.to_string()).collect();
+
+        assert_eq!(
+            displayed,
+            vec![
+                "insert 'Z' after 2",
+                "insert 'X' at the start",
+                "substitute 'Y' at 2",
+                "delete at 1",
+            ]
+        );
+        assert_eq!(format!("{:?}", edits[0]), "Insert(2, 'Z')");
+
+        let source: Vec<char> = "AB".chars().collect();
+        assert_eq!(
+            render_edits(&source, &edits[2..]),
+            "substitute 'B' with 'Y' at 2\ndelete 'A' at 1"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn render_edits_out_of_range_test() {
+        let source: Vec<char> = "AB".chars().collect();
+
+        render_edits(&source, &[Edit::Substitute(3, 'Y')]);
+    }
+
+    #[test]
+    fn conflicting_edits_are_ignored() {
+        let source = "SUNDAY".as_bytes();
+
+        // The last edit of the third item wins, whatever the order of the edits is
+        let edits = vec![Edit::Substitute(3, b'M'), Edit::Delete(3)];
+        assert_eq!(apply_edits(source, &edits), "SUDAY".as_bytes());
+
+        let edits = vec![
+            Edit::Delete(3),
+            Edit::Insert(6, b'S'),
+            Edit::Substitute(3, b'M'),
+        ];
+        assert_eq!(apply_edits(source, &edits), "SUMDAYS".as_bytes());
+
+        assert_eq!(
+            try_apply_edits(source, &edits),
+            Err(PatchError::ConflictingEdits(3))
+        );
+    }
+
+    #[test]
+    fn try_apply_edits_test() {
+        let source = "SATURDAY".as_bytes();
+
+        let (_, matrix) = crate::distance(source, "SUNDAY".as_bytes());
+        let edits = generate_edits(source, "SUNDAY".as_bytes(), &matrix).unwrap();
+        assert_eq!(
+            try_apply_edits(source, &edits).unwrap(),
+            "SUNDAY".as_bytes()
+        );
+
+        // Inserting after the last item is fine, but deleting the 0th item isn't
+        assert!(try_apply_edits(source, &[Edit::Insert(8, b'S')]).is_ok());
+        assert_eq!(
+            try_apply_edits(source, &[Edit::Insert(9, b'S')]),
+            Err(PatchError::InvalidIndex(9))
+        );
+        assert_eq!(
+            try_apply_edits(source, &[Edit::Delete(0)]),
+            Err(PatchError::InvalidIndex(0))
+        );
+
+        // Even an exact duplicate is rejected, since it can't have come from `generate_edits`
+        assert_eq!(
+            try_apply_edits(source, &[Edit::Delete(4), Edit::Delete(4)]),
+            Err(PatchError::ConflictingEdits(4))
+        );
+    }
+
+    #[test]
+    fn apply_edits_in_place_test() {
+        let source = "kitten sitting on the mitten".as_bytes();
+        let target = "sitting kitten in the mittens".as_bytes();
+
+        let (_, matrix) = crate::distance(source, target);
+        let edits = generate_edits(source, target, &matrix).unwrap();
+
+        let mut sequence = source.to_vec();
+        apply_edits_in_place(&mut sequence, &edits).unwrap();
+        assert_eq!(sequence, target);
+
+        let mut sequence = source.to_vec();
+        apply_edits_in_place(&mut sequence, &[]).unwrap();
+        assert_eq!(sequence, source);
+
+        // Edits outside the source leave it as it was
+        let mut sequence = source.to_vec();
+        for edits in [
+            vec![Edit::Delete(1), Edit::Delete(0)],
+            vec![Edit::Delete(1), Edit::Substitute(29, b'X')],
+            vec![Edit::Delete(1), Edit::Insert(29, b'X')],
+        ] {
+            assert!(apply_edits_in_place(&mut sequence, &edits).is_err());
+            assert_eq!(sequence, source);
+        }
+    }
+
+    #[test]
+    fn apply_edits_in_place_conflict_test() {
+        let source = "abcdef".as_bytes();
+        let edits = vec![Edit::Delete(2), Edit::Substitute(2, b'X')];
+
+        // The last edit of the item wins, as with apply_edits
+        let mut sequence = source.to_vec();
+        apply_edits_in_place(&mut sequence, &edits).unwrap();
+        assert_eq!(sequence, "aXcdef".as_bytes());
+        assert_eq!(sequence, apply_edits(source, &edits));
+    }
+
+    #[test]
+    fn apply_edits_order_test() {
+        let source = "AB".as_bytes();
+
+        // Inserts at the same index end up in the reverse order of the edits
+        let edits = vec![
+            Edit::Insert(2, b'Z'),
+            Edit::Insert(2, b'Y'),
+            Edit::Delete(1),
+        ];
+        assert_eq!(apply_edits(source, &edits), "BYZ".as_bytes());
+
+        // Edits in ascending order are sorted before they are applied
+        let edits = vec![
+            Edit::Insert(0, b'X'),
+            Edit::Substitute(1, b'C'),
+            Edit::Insert(2, b'Y'),
+        ];
+        assert_eq!(apply_edits(source, &edits), "XCBY".as_bytes());
+    }
+
+    #[test]
+    fn apply_edits_cow_test() {
+        let source = "SUNDAY".as_bytes();
+
+        // Substituting an item with itself changes nothing
+        let edits = vec![Edit::Substitute(2, b'U'), Edit::Substitute(1, b'S')];
+        assert!(matches!(apply_edits_cow(source, &edits), Cow::Borrowed(_)));
+
+        let edits = vec![Edit::Insert(6, b'S')];
+        assert_eq!(&*apply_edits_cow(source, &edits), "SUNDAYS".as_bytes());
+    }
+
+    #[test]
+    fn generate_edits_greedy_test() {
+        let s1 = "SATURDAY".as_bytes();
+        let s2 = "SUNDAY".as_bytes();
+
+        let edits = generate_edits_greedy(s1, s2);
+        assert_eq!(edits.len(), 3);
+        assert_eq!(apply_edits(s1, &edits), s2);
+
+        // Inserts at the same index, and edits at both ends
+        for (s1, s2) in [("", "ABC"), ("ABC", ""), ("XAY", "ZABCW")] {
+            let edits = generate_edits_greedy(s1.as_bytes(), s2.as_bytes());
+            assert_eq!(apply_edits(s1.as_bytes(), &edits), s2.as_bytes());
+        }
+    }
+
+    #[test]
+    fn generate_edits_ref_test() {
+        let s1 = "SATURDAY".as_bytes();
+        let s2 = "SUNDAY".as_bytes();
+
+        let (_, matrix) = crate::distance(s1, s2);
+        let borrowed = generate_edits_ref(s1, s2, &matrix).unwrap();
+
+        let owned: Vec<Edit<u8>> = borrowed.into_iter().map(Edit::into_owned).collect();
+        assert_eq!(owned, generate_edits(s1, s2, &matrix).unwrap());
+    }
 }
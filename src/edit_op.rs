@@ -0,0 +1,185 @@
+use crate::edit::{
+    apply_in_order, sorted_by_slot, Edit, EditIndexing, EditsIter, LevenshteinError, TiebreakPolicy,
+};
+use crate::util::{DistanceCell, DistanceMatrix};
+use alloc::vec::Vec;
+
+/// Represents an edit like `Edit`, but refers to the new item by its index in the target instead
+/// of holding a copy of it.
+///
+/// Source indices follow the same convention as `Edit`: 1-indexed for `Delete` and `Substitute`,
+/// and the index to insert at for `Insert`. Target indices are 0-indexed, like slice indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EditOp {
+    Delete(usize),            // Delete item at index
+    Insert(usize, usize),     // Insert the item at target index at index
+    Substitute(usize, usize), // Substitute item at index with the item at target index
+}
+
+impl EditOp {
+    /// Returns the equivalent `Edit`, with the new item copied from the target.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target sequence the op refers to
+    pub fn to_edit<T: Clone + PartialEq>(&self, target: &[T]) -> Edit<T> {
+        match *self {
+            EditOp::Delete(idx) => Edit::Delete(idx),
+            EditOp::Insert(idx, target_idx) => Edit::Insert(idx, target[target_idx].clone()),
+            EditOp::Substitute(idx, target_idx) => {
+                Edit::Substitute(idx, target[target_idx].clone())
+            }
+        }
+    }
+}
+
+/// Generate a vector of edit ops that transform the source sequence into the target sequence.
+///
+/// The ops are the same as the edits `generate_edits` returns, in the same order, but nothing is
+/// copied out of the target, so the items don't even need to implement `Clone`.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `distances` - A reference to the `DistanceMatrix` for converting source to target
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::EditOp;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// let ops = levenshtein::generate_edit_ops(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+///
+/// // Substitute the 5th item with the item at index 2 of the target, then delete 2 items
+/// assert_eq!(ops, vec![EditOp::Substitute(5, 2), EditOp::Delete(3), EditOp::Delete(2)]);
+/// ```
+pub fn generate_edit_ops<T: PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+    distances: &DistanceMatrix<C>,
+) -> Result<Vec<EditOp>, LevenshteinError> {
+    let mut iter = EditsIter::new(
+        source,
+        target,
+        distances,
+        EditIndexing::OneBased,
+        TiebreakPolicy::default(),
+    )?;
+
+    core::iter::from_fn(|| iter.next_op()).collect()
+}
+
+/// Applies a sequence of edit ops on the source sequence, taking the new items from the target,
+/// and returns a vector representing the target sequence.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence the ops refer to
+/// * `ops` - The edit ops, as generated by `generate_edit_ops`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// let ops = levenshtein::generate_edit_ops(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+///
+/// let target = levenshtein::apply_edit_ops(s1.as_bytes(), s2.as_bytes(), &ops);
+/// assert_eq!(target, s2.as_bytes());
+/// ```
+pub fn apply_edit_ops<T: Clone + PartialEq>(source: &[T], target: &[T], ops: &[EditOp]) -> Vec<T> {
+    apply_edit_ops_with(source, ops, |target_idx| target[target_idx].clone())
+}
+
+/// Applies a sequence of edit ops on the source sequence, asking `value` for the new item at each
+/// target index, and returns a vector representing the target sequence.
+///
+/// This is useful when the target isn't kept around as a slice, e.g. when its items are loaded
+/// on demand.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `ops` - The edit ops, as generated by `generate_edit_ops`
+/// * `value` - Returns the item at an index of the target
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::EditOp;
+///
+/// let source = vec![0, 1, 2];
+/// let ops = vec![EditOp::Insert(3, 3), EditOp::Substitute(1, 0)];
+///
+/// let target = levenshtein::apply_edit_ops_with(&source, &ops, |target_idx| target_idx * 10);
+/// assert_eq!(target, vec![0, 1, 2, 30]);
+/// ```
+pub fn apply_edit_ops_with<T: Clone + PartialEq, F: FnMut(usize) -> T>(
+    source: &[T],
+    ops: &[EditOp],
+    mut value: F,
+) -> Vec<T> {
+    let ordered = sorted_by_slot(ops, |op| match *op {
+        EditOp::Insert(idx, _) => 2 * idx,
+        EditOp::Delete(idx) | EditOp::Substitute(idx, _) => 2 * (idx - 1) + 1,
+    });
+
+    let inserted = ops
+        .iter()
+        .filter(|op| matches!(op, EditOp::Insert(..)))
+        .count();
+
+    let edits = ordered.into_iter().map(|op| match *op {
+        EditOp::Insert(idx, target_idx) => Edit::Insert(idx, value(target_idx)),
+        EditOp::Delete(idx) => Edit::Delete(idx - 1),
+        EditOp::Substitute(idx, target_idx) => Edit::Substitute(idx - 1, value(target_idx)),
+    });
+
+    apply_in_order(source, edits, inserted)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edit_op::*;
+
+    #[test]
+    fn edit_ops_match_edits() {
+        let source = "kitten sitting on the mitten".as_bytes();
+        let target = "sitting kitten in the mittens".as_bytes();
+
+        let (_, matrix) = crate::distance(source, target);
+        let edits = crate::generate_edits(source, target, &matrix).unwrap();
+        let ops = generate_edit_ops(source, target, &matrix).unwrap();
+
+        let converted: Vec<Edit<u8>> = ops.iter().map(|op| op.to_edit(target)).collect();
+        assert_eq!(converted, edits);
+        assert_eq!(apply_edit_ops(source, target, &ops), target);
+    }
+
+    #[test]
+    fn items_need_not_be_clone() {
+        #[derive(PartialEq)]
+        struct Opaque(u8);
+
+        let source = [Opaque(1), Opaque(2)];
+        let target = [Opaque(2), Opaque(3)];
+
+        let (_, matrix) = crate::distance(&source, &target);
+        let ops = generate_edit_ops(&source, &target, &matrix).unwrap();
+
+        assert_eq!(ops, vec![EditOp::Insert(2, 1), EditOp::Delete(1)]);
+    }
+}
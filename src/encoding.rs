@@ -0,0 +1,425 @@
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::error::Error;
+use core::fmt;
+
+use crate::edit::Edit;
+
+/// An item that can be written to, and read back from, the compact binary encoding of edits.
+///
+/// The encoding of every item must take at least one byte, which is what lets the decoder reject
+/// runs of items that are longer than its input.
+pub trait BinaryItem: Sized {
+    /// Appends the encoding of the item to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Reads an item from the front of `input`, and advances `input` past it.
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError>;
+}
+
+impl BinaryItem for u8 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        let (&byte, rest) = input.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+        *input = rest;
+        Ok(byte)
+    }
+}
+
+macro_rules! impl_binary_item {
+    ($($t:ty),*) => {
+        $(
+            impl BinaryItem for $t {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    write_varint(out, *self as u64);
+                }
+
+                fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+                    <$t>::try_from(read_varint(input)?).map_err(|_| DecodeError::Overflow)
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_item!(u16, u32, u64, usize);
+
+impl BinaryItem for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        write_varint(out, *self as u64);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        u32::try_from(read_varint(input)?)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(DecodeError::InvalidItem)
+    }
+}
+
+/// Represents an error found while decoding edits.
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    // The input ends in the middle of an edit
+    UnexpectedEnd,
+    // A number doesn't fit in its type
+    Overflow,
+    // An edit has an unknown tag
+    InvalidTag(u8),
+    // An item isn't a valid value of its type
+    InvalidItem,
+    // A run of edits reaches below the first index
+    InvalidIndex,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "Unexpected end of input"),
+            DecodeError::Overflow => write!(f, "Number out of range"),
+            DecodeError::InvalidTag(tag) => write!(f, "Invalid edit tag {}", tag),
+            DecodeError::InvalidItem => write!(f, "Invalid item"),
+            DecodeError::InvalidIndex => write!(f, "Invalid edit index"),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+// Writes an unsigned LEB128 varint: 7 bits per byte, least significant first, with the high bit
+// set on every byte but the last
+pub(crate) fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    out.push(value as u8);
+}
+
+pub(crate) fn read_varint(input: &mut &[u8]) -> Result<u64, DecodeError> {
+    let mut value = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let byte = u8::decode(input)?;
+        let bits = (byte & 0x7f) as u64;
+
+        if bits << shift >> shift != bits {
+            return Err(DecodeError::Overflow);
+        }
+
+        value |= bits << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(DecodeError::Overflow)
+}
+
+const INSERT: u8 = 0;
+const DELETE: u8 = 1;
+const SUBSTITUTE: u8 = 2;
+
+// The tag and index of an edit
+fn tag_and_index<T: PartialEq>(edit: &Edit<T>) -> (u8, usize) {
+    match edit {
+        Edit::Insert(idx, _) => (INSERT, *idx),
+        Edit::Delete(idx) => (DELETE, *idx),
+        Edit::Substitute(idx, _) => (SUBSTITUTE, *idx),
+    }
+}
+
+// Whether `next` continues a run that `previous` is part of. Runs follow the order in which
+// `generate_edits` lists edits: inserts at the same index, and deletions or substitutions of
+// consecutive items from last to first.
+fn continues_run<T: PartialEq>(previous: &Edit<T>, next: &Edit<T>) -> bool {
+    let (previous_tag, previous_idx) = tag_and_index(previous);
+    let (next_tag, next_idx) = tag_and_index(next);
+
+    match previous_tag {
+        _ if previous_tag != next_tag => false,
+        INSERT => next_idx == previous_idx,
+        _ => next_idx + 1 == previous_idx,
+    }
+}
+
+/// Encodes a sequence of edits into a compact binary representation.
+///
+/// Edits are grouped into runs of the same operation on adjacent items, in the order
+/// `generate_edits` produces them. Each run is written as a varint holding its length and
+/// operation, followed by the zigzag varint difference between its first index and the last index
+/// of the run before it, followed by the inserted or substituted items. Any sequence of edits can
+/// be encoded, and decodes back to exactly the same edits.
+///
+/// # Arguments
+///
+/// * `edits` - The edits to encode
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+///
+/// // One substitution, and a run of two deletions
+/// let encoded = levenshtein::encode_edits(&edits);
+/// assert_eq!(encoded.len(), 5);
+///
+/// let decoded = levenshtein::decode_edits::<u8>(&encoded).unwrap();
+/// assert_eq!(decoded, edits);
+/// ```
+pub fn encode_edits<T: PartialEq + BinaryItem>(edits: &[Edit<T>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut last_idx = 0;
+    let mut start = 0;
+
+    while start < edits.len() {
+        let mut end = start + 1;
+
+        while end < edits.len() && continues_run(&edits[end - 1], &edits[end]) {
+            end += 1;
+        }
+
+        let (tag, idx) = tag_and_index(&edits[start]);
+        let delta = idx as i64 - last_idx as i64;
+
+        write_varint(&mut out, ((end - start) as u64) << 2 | tag as u64);
+        write_varint(&mut out, ((delta << 1) ^ (delta >> 63)) as u64);
+
+        for edit in &edits[start..end] {
+            match edit {
+                Edit::Insert(_, val) | Edit::Substitute(_, val) => val.encode(&mut out),
+                Edit::Delete(_) => {}
+            }
+        }
+
+        last_idx = tag_and_index(&edits[end - 1]).1;
+        start = end;
+    }
+
+    out
+}
+
+/// Decodes a sequence of edits encoded with `encode_edits`.
+///
+/// Returns an error if the input is truncated or malformed. Runs of inserts and substitutions
+/// can't be longer than the input, but a run of deletions takes a few bytes whatever its length,
+/// and is only bounded by its index. Use `decode_edits_within` for input that can't be trusted.
+///
+/// # Arguments
+///
+/// * `input` - The encoded edits
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{DecodeError, Edit};
+///
+/// let edits = vec![Edit::Insert(3, 'Y'), Edit::Insert(3, 'X')];
+/// let encoded = levenshtein::encode_edits(&edits);
+///
+/// assert_eq!(levenshtein::decode_edits::<char>(&encoded).unwrap(), edits);
+/// assert_eq!(
+///     levenshtein::decode_edits::<char>(&encoded[..3]),
+///     Err(DecodeError::UnexpectedEnd)
+/// );
+/// ```
+pub fn decode_edits<T: PartialEq + BinaryItem>(input: &[u8]) -> Result<Vec<Edit<T>>, DecodeError> {
+    decode_edits_up_to(input, usize::MAX)
+}
+
+/// Decodes a sequence of edits encoded with `encode_edits`, as generated for a source of the
+/// given length.
+///
+/// Returns an error if the input is truncated or malformed, or as soon as an edit refers to an
+/// index outside the source, so the number of edits decoded is bounded by the length of the
+/// input and the length of the source.
+///
+/// # Arguments
+///
+/// * `input` - The encoded edits
+/// * `source_len` - The length of the source the edits apply to
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{DecodeError, Edit};
+///
+/// let edits = vec![Edit::Delete(8), Edit::Delete(7), Edit::Insert(2, b'X')];
+/// let encoded = levenshtein::encode_edits(&edits);
+///
+/// assert_eq!(levenshtein::decode_edits_within::<u8>(&encoded, 8).unwrap(), edits);
+/// assert_eq!(
+///     levenshtein::decode_edits_within::<u8>(&encoded, 7),
+///     Err(DecodeError::InvalidIndex)
+/// );
+/// ```
+pub fn decode_edits_within<T: PartialEq + BinaryItem>(
+    input: &[u8],
+    source_len: usize,
+) -> Result<Vec<Edit<T>>, DecodeError> {
+    decode_edits_up_to(input, source_len)
+}
+
+// Decodes edits whose indices are at most max_idx
+fn decode_edits_up_to<T: PartialEq + BinaryItem>(
+    input: &[u8],
+    max_idx: usize,
+) -> Result<Vec<Edit<T>>, DecodeError> {
+    let mut input = input;
+    let mut edits = Vec::new();
+    let mut last_idx = 0usize;
+
+    while !input.is_empty() {
+        let header = read_varint(&mut input)?;
+        let tag = (header & 3) as u8;
+        let len = usize::try_from(header >> 2).map_err(|_| DecodeError::Overflow)?;
+
+        if tag > SUBSTITUTE {
+            return Err(DecodeError::InvalidTag(tag));
+        }
+
+        let zigzag = read_varint(&mut input)?;
+        let delta = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+
+        let idx = if delta < 0 {
+            last_idx.checked_sub(delta.unsigned_abs() as usize)
+        } else {
+            last_idx.checked_add(delta as usize)
+        }
+        .filter(|&idx| idx <= max_idx)
+        .ok_or(DecodeError::InvalidIndex)?;
+
+        // Every item takes at least a byte
+        if tag != DELETE && len > input.len() {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+
+        // Deletions and substitutions are 1-indexed, and go down from idx
+        if tag != INSERT && len > idx {
+            return Err(DecodeError::InvalidIndex);
+        }
+
+        for i in 0..len {
+            let edit = match tag {
+                INSERT => Edit::Insert(idx, T::decode(&mut input)?),
+                DELETE => Edit::Delete(idx - i),
+                _ => Edit::Substitute(idx - i, T::decode(&mut input)?),
+            };
+
+            edits.push(edit);
+        }
+
+        if len > 0 {
+            last_idx = if tag == INSERT { idx } else { idx + 1 - len };
+        }
+    }
+
+    Ok(edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encoding::*;
+
+    #[test]
+    fn varint_test() {
+        for &value in &[0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut out = Vec::new();
+            write_varint(&mut out, value);
+
+            let mut input = &out[..];
+            assert_eq!(read_varint(&mut input), Ok(value));
+            assert!(input.is_empty());
+        }
+
+        // Two bytes are enough for anything below 2^14
+        let mut out = Vec::new();
+        write_varint(&mut out, 300);
+        assert_eq!(out, vec![0xac, 0x02]);
+
+        // Too many continuation bytes
+        let mut input = &[0xff; 11][..];
+        assert_eq!(read_varint(&mut input), Err(DecodeError::Overflow));
+    }
+
+    #[test]
+    fn encode_edits_test() {
+        // Runs of inserts at the same index, and deletions from last to first, followed by edits
+        // going in the other direction
+        let edits = vec![
+            Edit::<u16>::Insert(10, 1000),
+            Edit::<u16>::Insert(10, 2000),
+            Edit::<u16>::Delete(9),
+            Edit::<u16>::Delete(8),
+            Edit::<u16>::Delete(7),
+            Edit::<u16>::Substitute(1, 3),
+            Edit::<u16>::Substitute(20, 4),
+        ];
+
+        let encoded = encode_edits(&edits);
+        assert_eq!(decode_edits::<u16>(&encoded).unwrap(), edits);
+
+        // Deletions can't reach below the first item
+        let mut invalid = Vec::new();
+        write_varint(&mut invalid, 2 << 2 | DELETE as u64);
+        write_varint(&mut invalid, 2);
+        assert_eq!(
+            decode_edits::<u16>(&invalid),
+            Err(DecodeError::InvalidIndex)
+        );
+
+        assert_eq!(
+            decode_edits::<u16>(&[3, 0]),
+            Err(DecodeError::InvalidTag(3))
+        );
+    }
+
+    #[test]
+    fn decode_untrusted_edits_test() {
+        // A run of a billion inserts, with a single item
+        let mut invalid = Vec::new();
+        write_varint(&mut invalid, 1 << 32 | INSERT as u64);
+        write_varint(&mut invalid, 0);
+        invalid.push(b'X');
+        assert_eq!(
+            decode_edits::<u8>(&invalid),
+            Err(DecodeError::UnexpectedEnd)
+        );
+
+        // A run of deletions longer than its index
+        let mut invalid = Vec::new();
+        write_varint(&mut invalid, 1 << 40 | DELETE as u64);
+        write_varint(&mut invalid, 1 << 20);
+        assert_eq!(decode_edits::<u8>(&invalid), Err(DecodeError::InvalidIndex));
+
+        // A long run of deletions that fits, but not in a source of 10 items
+        let mut deletions = Vec::new();
+        write_varint(&mut deletions, 1 << 40 | DELETE as u64);
+        write_varint(&mut deletions, 1 << 41);
+        assert_eq!(
+            decode_edits_within::<u8>(&deletions, 10),
+            Err(DecodeError::InvalidIndex)
+        );
+
+        let edits = vec![Edit::Insert(10, b'X'), Edit::Delete(10), Edit::Delete(9)];
+        let encoded = encode_edits(&edits);
+        assert_eq!(decode_edits_within::<u8>(&encoded, 10), Ok(edits));
+        assert_eq!(
+            decode_edits_within::<u8>(&encoded, 9),
+            Err(DecodeError::InvalidIndex)
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::distance::diff_edits;
+use crate::edit::Edit;
+use crate::encoding::{read_varint, write_varint, DecodeError};
+use crate::replace::coalesce_edits;
+
+// The longest copy and insert a single instruction can hold
+const MAX_COPY_LEN: usize = 0xff_ffff;
+const MAX_INSERT_LEN: usize = 0x7f;
+
+// The first offset a copy instruction can't hold, as it only has room for 4 bytes of it
+const MAX_COPY_OFFSET: u64 = 1 << 32;
+
+// Writes a copy instruction: a command byte whose low 7 bits say which bytes of the offset and the
+// length follow, followed by the non-zero bytes of both, least significant first
+fn write_copy(out: &mut Vec<u8>, offset: usize, len: usize) {
+    assert!(
+        (offset as u64) < MAX_COPY_OFFSET,
+        "git deltas can't copy from offset {}, past the first 4 GiB of the source",
+        offset
+    );
+
+    let command_at = out.len();
+    let mut command = 0x80;
+
+    for i in 0..4 {
+        let byte = (offset >> (8 * i)) as u8;
+        if byte != 0 {
+            command |= 1 << i;
+            out.push(byte);
+        }
+    }
+
+    for i in 0..3 {
+        let byte = (len >> (8 * i)) as u8;
+        if byte != 0 {
+            command |= 1 << (4 + i);
+            out.push(byte);
+        }
+    }
+
+    out.insert(command_at, command);
+}
+
+fn write_copies(out: &mut Vec<u8>, mut offset: usize, mut len: usize) {
+    while len > 0 {
+        let chunk = core::cmp::min(len, MAX_COPY_LEN);
+        write_copy(out, offset, chunk);
+        offset += chunk;
+        len -= chunk;
+    }
+}
+
+fn write_inserts(out: &mut Vec<u8>, bytes: &[u8]) {
+    for chunk in bytes.chunks(MAX_INSERT_LEN) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+/// Returns a delta that transforms the source into the target, in the format git uses for deltified
+/// objects in packfiles.
+///
+/// # Arguments
+///
+/// * `source` - The source bytes
+/// * `target` - The target bytes
+///
+/// # Panics
+///
+/// Panics if an unchanged run of the source starts past its first 4 GiB, since git's copy
+/// instructions can't refer to it.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let source = "SATURDAY".as_bytes();
+/// let target = "SUNDAY".as_bytes();
+///
+/// let delta = levenshtein::git_delta(source, target);
+/// assert_eq!(levenshtein::apply_git_delta(source, &delta).unwrap(), target);
+/// ```
+pub fn git_delta(source: &[u8], target: &[u8]) -> Vec<u8> {
+    let edits = diff_edits(source, target);
+
+    git_delta_from_edits(source, &edits)
+}
+
+/// Returns a delta in git's format that applies the edits to the source. See `git_delta`.
+///
+/// The delta starts with the lengths of the source and target, followed by instructions that copy
+/// the unchanged runs of the source and insert everything else.
+///
+/// # Arguments
+///
+/// * `source` - The source bytes
+/// * `edits` - The edits, as generated by `generate_edits`
+///
+/// # Panics
+///
+/// Panics if an unchanged run of the source starts past its first 4 GiB, since git's copy
+/// instructions can't refer to it.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+///
+/// let delta = levenshtein::git_delta_from_edits(s1.as_bytes(), &edits);
+///
+/// // Both lengths, copy S, copy U, insert N, copy DAY
+/// assert_eq!(delta, vec![8, 6, 0x90, 1, 0x91, 3, 1, 1, b'N', 0x91, 5, 3]);
+/// ```
+pub fn git_delta_from_edits(source: &[u8], edits: &[Edit<u8>]) -> Vec<u8> {
+    let replacements = coalesce_edits(edits);
+
+    let target_len = replacements
+        .iter()
+        .fold(source.len(), |len, r| len - r.range.len() + r.with.len());
+
+    let mut out = Vec::new();
+    write_varint(&mut out, source.len() as u64);
+    write_varint(&mut out, target_len as u64);
+
+    let mut copied_up_to = 0;
+
+    for replacement in replacements {
+        write_copies(
+            &mut out,
+            copied_up_to,
+            replacement.range.start - copied_up_to,
+        );
+        write_inserts(&mut out, &replacement.with);
+        copied_up_to = replacement.range.end;
+    }
+
+    write_copies(&mut out, copied_up_to, source.len() - copied_up_to);
+
+    out
+}
+
+/// Applies a delta in git's format to the source, and returns the target.
+///
+/// Returns an error if the delta is malformed, or was made for a source of a different length.
+/// The target is never allowed to grow past the length the delta declares for it.
+///
+/// # Arguments
+///
+/// * `source` - The source bytes
+/// * `delta` - The delta
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::DecodeError;
+///
+/// let delta = levenshtein::git_delta("SATURDAY".as_bytes(), "SUNDAY".as_bytes());
+///
+/// assert_eq!(
+///     levenshtein::apply_git_delta("SUNDAY".as_bytes(), &delta),
+///     Err(DecodeError::InvalidIndex)
+/// );
+/// ```
+pub fn apply_git_delta(source: &[u8], delta: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let mut input = delta;
+
+    let read_len =
+        |input: &mut &[u8]| usize::try_from(read_varint(input)?).map_err(|_| DecodeError::Overflow);
+
+    if read_len(&mut input)? != source.len() {
+        return Err(DecodeError::InvalidIndex);
+    }
+
+    let target_len = read_len(&mut input)?;
+
+    // The declared length can't be trusted to allocate up front
+    let mut target = Vec::with_capacity(core::cmp::min(
+        target_len,
+        source.len().saturating_add(delta.len()),
+    ));
+
+    while let Some((&command, rest)) = input.split_first() {
+        input = rest;
+
+        if command == 0 {
+            return Err(DecodeError::InvalidTag(0));
+        }
+
+        if command & 0x80 == 0 {
+            let len = command as usize;
+
+            if input.len() < len {
+                return Err(DecodeError::UnexpectedEnd);
+            }
+
+            if target_len - target.len() < len {
+                return Err(DecodeError::Overflow);
+            }
+
+            target.extend_from_slice(&input[..len]);
+            input = &input[len..];
+            continue;
+        }
+
+        // The bytes of the offset and length that are present, least significant first
+        let mut offset = 0;
+        let mut len = 0;
+
+        for i in 0..7 {
+            if command & (1 << i) == 0 {
+                continue;
+            }
+
+            let (&byte, rest) = input.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+            input = rest;
+
+            if i < 4 {
+                offset |= (byte as usize) << (8 * i);
+            } else {
+                len |= (byte as usize) << (8 * (i - 4));
+            }
+        }
+
+        // A length of zero stands for 0x10000
+        if len == 0 {
+            len = 0x10000;
+        }
+
+        let end = offset.checked_add(len).ok_or(DecodeError::Overflow)?;
+        let copied = source.get(offset..end).ok_or(DecodeError::InvalidIndex)?;
+
+        if target_len - target.len() < len {
+            return Err(DecodeError::Overflow);
+        }
+
+        target.extend_from_slice(copied);
+    }
+
+    if target.len() != target_len {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git_delta::*;
+
+    #[test]
+    fn write_copy_test() {
+        // Only the non-zero bytes of the offset and length are written
+        let mut out = Vec::new();
+        write_copy(&mut out, 0x0100, 0x10);
+        assert_eq!(out, vec![0x92, 0x01, 0x10]);
+
+        let mut out = Vec::new();
+        write_copy(&mut out, 0, 0x010203);
+        assert_eq!(out, vec![0xf0, 0x03, 0x02, 0x01]);
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    #[should_panic]
+    fn write_copy_past_4_gib_test() {
+        write_copy(&mut Vec::new(), 1 << 32, 0x10);
+    }
+
+    #[test]
+    fn target_longer_than_declared_test() {
+        let source = "SATURDAY".as_bytes();
+
+        // Declares a target of 10 bytes, then copies the source twice
+        let delta = vec![8, 10, 0x90, 8, 0x90, 8];
+        assert_eq!(apply_git_delta(source, &delta), Err(DecodeError::Overflow));
+
+        // Declares a huge target, which isn't allocated up front
+        let mut delta = vec![8];
+        write_varint(&mut delta, u32::MAX as u64);
+        delta.extend([0x90, 8]);
+        assert_eq!(
+            apply_git_delta(source, &delta),
+            Err(DecodeError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn long_runs_are_split() {
+        let source = vec![b'A'; 300];
+        let mut target = source.clone();
+        target.extend(vec![b'B'; 300]);
+
+        let delta = git_delta(&source, &target);
+
+        assert_eq!(apply_git_delta(&source, &delta).unwrap(), target);
+        assert_eq!(
+            apply_git_delta(&source, &delta[..delta.len() - 1]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+    }
+}
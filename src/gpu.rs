@@ -0,0 +1,516 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::sync::mpsc;
+
+use wgpu::util::DeviceExt;
+
+// One invocation per pair of sequences. Each keeps the row of its pair in its own stretch of
+// `rows`, with the shorter sequence along the row, and fills the table one row at a time, like
+// `levenshtein_two_rows` does with a single row and the diagonal cell on the side.
+const SHADER: &str = r#"
+struct Params {
+    pairs: u32,
+    row_len: u32,
+}
+
+// Where the longer and the shorter sequence of a pair are in `items`
+struct Pair {
+    long_start: u32,
+    long_len: u32,
+    short_start: u32,
+    short_len: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> items: array<u32>;
+@group(0) @binding(2) var<storage, read> pairs: array<Pair>;
+@group(0) @binding(3) var<storage, read_write> rows: array<u32>;
+@group(0) @binding(4) var<storage, read_write> distances: array<u32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let p = id.x;
+    if (p >= params.pairs) {
+        return;
+    }
+
+    let pair = pairs[p];
+    let row = p * params.row_len;
+
+    for (var j = 0u; j <= pair.short_len; j++) {
+        rows[row + j] = j;
+    }
+
+    for (var i = 1u; i <= pair.long_len; i++) {
+        let item = items[pair.long_start + i - 1u];
+        var diagonal = rows[row];
+        rows[row] = i;
+
+        for (var j = 1u; j <= pair.short_len; j++) {
+            let above = rows[row + j];
+            let same = item == items[pair.short_start + j - 1u];
+            let substitute = diagonal + select(1u, 0u, same);
+
+            rows[row + j] = min(min(above, rows[row + j - 1u]) + 1u, substitute);
+            diagonal = above;
+        }
+    }
+
+    distances[p] = rows[row + pair.short_len];
+}
+"#;
+
+// The number of invocations in a workgroup of the shader
+const WORKGROUP_SIZE: usize = 64;
+
+// The most workgroups a single dispatch may have along one dimension
+const MAX_WORKGROUPS: usize = 65_535;
+
+/// Represents an error found while setting up the GPU or computing distances on it.
+#[derive(Debug)]
+pub enum GpuError {
+    // No adapter could be found, e.g. because there's no GPU or no driver for it
+    NoAdapter,
+    // The adapter couldn't provide a device
+    RequestDevice(wgpu::RequestDeviceError),
+    // The sequences, or the row of a single pair, don't fit in a storage buffer of the device
+    TooLarge,
+    // The distances couldn't be read back from the device
+    ReadBack(wgpu::BufferAsyncError),
+}
+
+impl fmt::Display for GpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuError::NoAdapter => write!(f, "No GPU adapter is available"),
+            GpuError::RequestDevice(error) => write!(f, "Couldn't get a GPU device: {}", error),
+            GpuError::TooLarge => write!(f, "The sequences don't fit in a GPU buffer"),
+            GpuError::ReadBack(error) => write!(f, "Couldn't read the distances: {}", error),
+        }
+    }
+}
+
+impl Error for GpuError {}
+
+/// Computes batches of Levenshtein distances on the GPU with a wgpu compute shader.
+///
+/// Every pair of sequences is handled by its own GPU thread, which fills the distance table of
+/// the pair with a single row, so thousands of pairs are compared at the same time. This pays off
+/// for large batches of short to medium sequences, like the all-pairs distances of a corpus for
+/// deduplication. Setting up the device and the shader is expensive, so a `GpuDistances` should
+/// be created once and used for every batch. This requires the `gpu` feature.
+///
+/// Items are compared by their value as a `u32`, which covers bytes, chars and integer tokens.
+/// All the sequences of a call are uploaded at once, and the pairs are sent in as many
+/// dispatches as the storage buffers of the device need.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::GpuDistances;
+///
+/// // There may not be a GPU, e.g. on a build server
+/// let gpu = match GpuDistances::new() {
+///     Ok(gpu) => gpu,
+///     Err(_) => return,
+/// };
+///
+/// let query = "SATURDAY".as_bytes();
+/// let words = ["SUNDAY".as_bytes(), "SATURN".as_bytes(), "MONDAY".as_bytes()];
+///
+/// let distances = gpu.distances_one_to_many(query, &words, Some(3)).unwrap();
+/// assert_eq!(distances, vec![Some(3), Some(3), None]);
+/// ```
+pub struct GpuDistances {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    // The size in bytes of the largest storage buffer a dispatch can bind
+    max_buffer: u64,
+}
+
+impl GpuDistances {
+    /// Returns a `GpuDistances` on the most powerful GPU available, blocking until the device
+    /// is ready.
+    ///
+    /// Returns an error if there is no GPU, or it can't provide a device.
+    pub fn new() -> Result<Self, GpuError> {
+        pollster::block_on(Self::new_async())
+    }
+
+    async fn new_async() -> Result<Self, GpuError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                ..Default::default()
+            })
+            .await
+            .ok_or(GpuError::NoAdapter)?;
+
+        // Ask for the largest buffers the adapter supports, since they decide how many pairs fit
+        // in one dispatch
+        let limits = adapter.limits();
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("levenshtein"),
+                    required_features: wgpu::Features::empty(),
+                    required_limits: limits.clone(),
+                    memory_hints: wgpu::MemoryHints::Performance,
+                },
+                None,
+            )
+            .await
+            .map_err(GpuError::RequestDevice)?;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("levenshtein"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("levenshtein"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let max_buffer = std::cmp::min(
+            limits.max_storage_buffer_binding_size as u64,
+            limits.max_buffer_size,
+        );
+
+        Ok(GpuDistances {
+            device,
+            queue,
+            pipeline,
+            max_buffer,
+        })
+    }
+
+    /// Returns the Levenshtein distances between source and each of the targets.
+    ///
+    /// If a cutoff is supplied, targets whose distance exceeds it are reported as `None`, as with
+    /// `distances_one_to_many`. Every distance is computed in full either way.
+    ///
+    /// Returns an error if the sequences don't fit in the buffers of the device, or the
+    /// distances can't be read back.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence
+    /// * `targets` - The target sequences
+    /// * `cutoff` - An optional maximum distance
+    pub fn distances_one_to_many<T: Copy + Into<u32>>(
+        &self,
+        source: &[T],
+        targets: &[&[T]],
+        cutoff: Option<usize>,
+    ) -> Result<Vec<Option<usize>>, GpuError> {
+        let sequences: Vec<&[T]> = std::iter::once(source)
+            .chain(targets.iter().copied())
+            .collect();
+
+        let mut distances = vec![None; targets.len()];
+        self.run(
+            &sequences,
+            (1..sequences.len()).map(|t| (0, t)),
+            |_, t, d| {
+                distances[t - 1] = match cutoff {
+                    Some(k) if d > k => None,
+                    _ => Some(d),
+                };
+            },
+        )?;
+
+        Ok(distances)
+    }
+
+    /// Returns the matrix of Levenshtein distances between every pair of items.
+    ///
+    /// This is identical to `pairwise_distances`, but compares the pairs on the GPU. Each pair
+    /// is only compared once, and the diagonal is always 0.
+    ///
+    /// Returns an error if the sequences don't fit in the buffers of the device, or the
+    /// distances can't be read back.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The sequences to compare
+    pub fn pairwise_distances<T: Copy + Into<u32>>(
+        &self,
+        items: &[&[T]],
+    ) -> Result<Vec<Vec<usize>>, GpuError> {
+        let n = items.len();
+        let pairs = (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j)));
+
+        let mut distances = vec![vec![0; n]; n];
+        self.run(items, pairs, |i, j, d| {
+            distances[i][j] = d;
+            distances[j][i] = d;
+        })?;
+
+        Ok(distances)
+    }
+
+    // Computes the distance of every pair of sequences, in as many dispatches as the buffers of
+    // the device need, and hands each one to `emit` along with the indices of the pair
+    fn run<T, I, F>(&self, sequences: &[&[T]], pairs: I, mut emit: F) -> Result<(), GpuError>
+    where
+        T: Copy + Into<u32>,
+        I: Iterator<Item = (usize, usize)>,
+        F: FnMut(usize, usize, usize),
+    {
+        // Every sequence, one after the other, and where each of them starts. The buffer can't
+        // be empty, so there's always an item at the end that no sequence covers.
+        let mut items: Vec<u32> = Vec::new();
+        let mut starts = Vec::with_capacity(sequences.len());
+        for sequence in sequences {
+            starts.push(items.len());
+            items.extend(sequence.iter().map(|&item| item.into()));
+        }
+        items.push(0);
+
+        if items.len() as u64 * 4 > self.max_buffer {
+            return Err(GpuError::TooLarge);
+        }
+
+        let items = self.storage_buffer(&items, wgpu::BufferUsages::empty());
+        let max_pairs = MAX_WORKGROUPS * WORKGROUP_SIZE;
+
+        let mut pairs = pairs.peekable();
+        while pairs.peek().is_some() {
+            // Take pairs while their rows still fit in one buffer
+            let mut batch: Vec<(usize, usize)> = Vec::new();
+            let mut shapes: Vec<u32> = Vec::new();
+            let mut row_len = 1;
+
+            while let Some(&(a, b)) = pairs.peek() {
+                let (long, short) = if sequences[a].len() >= sequences[b].len() {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+
+                let len = std::cmp::max(row_len, sequences[short].len() + 1);
+                if (batch.len() + 1) as u64 * len as u64 * 4 > self.max_buffer {
+                    if batch.is_empty() {
+                        return Err(GpuError::TooLarge);
+                    }
+                    break;
+                }
+
+                row_len = len;
+                batch.push((a, b));
+                shapes.extend(
+                    [long, short]
+                        .iter()
+                        .flat_map(|&s| [starts[s] as u32, sequences[s].len() as u32]),
+                );
+                pairs.next();
+
+                if batch.len() == max_pairs {
+                    break;
+                }
+            }
+
+            let distances = self.dispatch(&items, &shapes, batch.len(), row_len)?;
+            for (&(a, b), distance) in batch.iter().zip(distances) {
+                emit(a, b, distance as usize);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Runs the shader over one batch of pairs and returns their distances
+    fn dispatch(
+        &self,
+        items: &wgpu::Buffer,
+        shapes: &[u32],
+        pairs: usize,
+        row_len: usize,
+    ) -> Result<Vec<u32>, GpuError> {
+        let params = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &to_bytes(&[pairs as u32, row_len as u32, 0, 0]),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+        let shapes = self.storage_buffer(shapes, wgpu::BufferUsages::empty());
+        let rows = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (pairs * row_len * 4) as u64,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        let size = (pairs * 4) as u64;
+        let distances = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                binding(0, &params),
+                binding(1, items),
+                binding(2, &shapes),
+                binding(3, &rows),
+                binding(4, &distances),
+            ],
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(pairs.div_ceil(WORKGROUP_SIZE) as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&distances, 0, &readback, 0, size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let (sender, receiver) = mpsc::channel();
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+
+        receiver
+            .recv()
+            .expect("the buffer is mapped once the device is polled")
+            .map_err(GpuError::ReadBack)?;
+
+        let distances = slice
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|bytes| u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        readback.unmap();
+
+        Ok(distances)
+    }
+
+    // Returns a storage buffer holding the values
+    fn storage_buffer(&self, values: &[u32], usage: wgpu::BufferUsages) -> wgpu::Buffer {
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &to_bytes(values),
+                usage: wgpu::BufferUsages::STORAGE | usage,
+            })
+    }
+}
+
+// The bytes of the values, in the byte order of the host, which is the one the GPU uses
+fn to_bytes(values: &[u32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_ne_bytes())
+        .collect()
+}
+
+fn binding(index: u32, buffer: &wgpu::Buffer) -> wgpu::BindGroupEntry<'_> {
+    wgpu::BindGroupEntry {
+        binding: index,
+        resource: buffer.as_entire_binding(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::{distances_one_to_many, pairwise_distances};
+    use crate::gpu::*;
+
+    // There's nothing to test on machines without a GPU
+    fn gpu() -> Option<GpuDistances> {
+        GpuDistances::new().ok()
+    }
+
+    #[test]
+    fn gpu_distances_one_to_many_test() {
+        let gpu = match gpu() {
+            Some(gpu) => gpu,
+            None => return,
+        };
+
+        let s1 = "LAWN".as_bytes();
+        let long = "LAWN".repeat(30);
+        let targets = [
+            "FFLAWANN".as_bytes(),
+            "FLAW".as_bytes(),
+            "".as_bytes(),
+            long.as_bytes(),
+        ];
+
+        assert_eq!(
+            gpu.distances_one_to_many(s1, &targets, None).unwrap(),
+            distances_one_to_many(s1, &targets, None)
+        );
+        assert_eq!(
+            gpu.distances_one_to_many(s1, &targets, Some(2)).unwrap(),
+            vec![None, Some(2), None, None]
+        );
+        assert!(gpu.distances_one_to_many(s1, &[], None).unwrap().is_empty());
+
+        let chars: Vec<char> = "SATURDAY".chars().collect();
+        let sunday: Vec<char> = "SUNDAY".chars().collect();
+        assert_eq!(
+            gpu.distances_one_to_many(&chars, &[&sunday], None).unwrap(),
+            vec![Some(3)]
+        );
+    }
+
+    #[test]
+    fn gpu_pairwise_distances_test() {
+        let mut gpu = match gpu() {
+            Some(gpu) => gpu,
+            None => return,
+        };
+
+        let words = [
+            "LAWN", "FFLAWANN", "FLAW", "", "SATURDAY", "SUNDAY", "KITTEN", "SITTING",
+        ];
+        let items: Vec<&[u8]> = words.iter().map(|w| w.as_bytes()).collect();
+
+        assert_eq!(
+            gpu.pairwise_distances(&items).unwrap(),
+            pairwise_distances(&items)
+        );
+        assert!(gpu.pairwise_distances::<u8>(&[]).unwrap().is_empty());
+
+        // Small buffers split the pairs over many dispatches
+        gpu.max_buffer = 256;
+        assert_eq!(
+            gpu.pairwise_distances(&items).unwrap(),
+            pairwise_distances(&items)
+        );
+
+        let long = [0u8; 40];
+        assert!(matches!(
+            gpu.pairwise_distances(&[&long[..], &long[..]]),
+            Err(GpuError::TooLarge)
+        ));
+    }
+}
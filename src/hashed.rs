@@ -0,0 +1,225 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::edit::{generate_edits_stripped, Edit};
+use crate::edit_op::EditOp;
+use crate::keyed::stripped_edit_ops;
+use crate::pattern::Pattern;
+use crate::util::strip_common_affix;
+
+/// Returns the 64-bit hash of every item of a sequence.
+///
+/// Equal items always have the same hash, and different items almost never do, so the hashes can
+/// stand in for items that are expensive to compare, like large structs: comparing two hashes
+/// costs a single instruction. The hashes are the same on every run of the same build.
+///
+/// # Arguments
+///
+/// * `items` - The sequence to hash
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let hashes = levenshtein::hash_items(&["one", "two", "one"]);
+///
+/// assert_eq!(hashes[0], hashes[2]);
+/// assert_ne!(hashes[0], hashes[1]);
+/// ```
+pub fn hash_items<T: Hash>(items: &[T]) -> Vec<u64> {
+    items
+        .iter()
+        .map(|item| {
+            let mut hasher = DefaultHasher::new();
+            item.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+/// Returns the Levenshtein distance between source and target, comparing the hashes of their
+/// items instead of the items. See `hash_items`.
+///
+/// Unlike `distance_interned`, this doesn't need the items to implement `Eq`, and doesn't keep a
+/// table of them, but two different items with the same hash are taken for equal: with 64-bit
+/// hashes this is vanishingly unlikely, but it can make the distance smaller than it is.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = ["fn", "main", "(", ")", "{", "}"];
+/// let s2 = ["fn", "run", "(", ")", "{", "}"];
+///
+/// assert_eq!(levenshtein::distance_hashed(&s1, &s2), 1);
+/// ```
+pub fn distance_hashed<T: Hash>(source: &[T], target: &[T]) -> usize {
+    let source = hash_items(source);
+    let target = hash_items(target);
+
+    Pattern::new(&source).distance_to(&target)
+}
+
+// Returns true if every item the ops leave in place is equal to the target item it ends up as
+fn kept_items_match<T: PartialEq>(source: &[T], target: &[T], ops: &[EditOp]) -> bool {
+    // The next source and target items, 0-indexed
+    let mut i = 0;
+    let mut j = 0;
+
+    // Checks the kept items up to the given source index, and moves past them
+    let keep_until = |end: usize, i: &mut usize, j: &mut usize| {
+        let kept = end - *i;
+        let matched = source[*i..end] == target[*j..*j + kept];
+        *i = end;
+        *j += kept;
+        matched
+    };
+
+    // The ops are in descending order
+    for op in ops.iter().rev() {
+        let matched = match *op {
+            EditOp::Delete(idx) => {
+                let matched = keep_until(idx - 1, &mut i, &mut j);
+                i += 1;
+                matched
+            }
+            EditOp::Substitute(idx, _) => {
+                let matched = keep_until(idx - 1, &mut i, &mut j);
+                i += 1;
+                j += 1;
+                matched
+            }
+            EditOp::Insert(idx, _) => {
+                let matched = keep_until(idx, &mut i, &mut j);
+                j += 1;
+                matched
+            }
+        };
+
+        if !matched {
+            return false;
+        }
+    }
+
+    keep_until(source.len(), &mut i, &mut j)
+}
+
+/// Generate a vector of edits that transform the source sequence into the target sequence,
+/// comparing the hashes of their items instead of the items. See `hash_items`.
+///
+/// With `verify`, the items that the edits leave in place are compared with the target items
+/// they end up as, which only takes O(m + n) comparisons. If two of them turn out to be different
+/// items with the same hash, the edits are computed again from the items themselves, so they are
+/// always correct and as few as `generate_edits` finds. Without it, a hash collision can make the
+/// edits wrong, though with 64-bit hashes that is vanishingly unlikely.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `verify` - Whether to check the items left in place against the target
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// #[derive(Clone, Debug, Hash, PartialEq)]
+/// struct Token {
+///     kind: u8,
+///     text: String,
+/// }
+///
+/// let token = |kind, text: &str| Token { kind, text: text.to_string() };
+/// let source = vec![token(0, "let"), token(1, "x"), token(2, "=")];
+/// let target = vec![token(0, "let"), token(1, "y"), token(2, "=")];
+///
+/// let edits = levenshtein::generate_edits_hashed(&source, &target, true);
+/// assert_eq!(edits, vec![levenshtein::Edit::Substitute(2, token(1, "y"))]);
+/// ```
+pub fn generate_edits_hashed<T: Clone + Hash + PartialEq>(
+    source: &[T],
+    target: &[T],
+    verify: bool,
+) -> Vec<Edit<T>> {
+    let source_hashes = hash_items(source);
+    let target_hashes = hash_items(target);
+
+    let ops = stripped_edit_ops(&source_hashes, &target_hashes);
+
+    if verify && !kept_items_match(source, target, &ops) {
+        // A collision, so the hashes can't be trusted
+        let stripped = strip_common_affix(source, target);
+        let (_, matrix) = crate::distance(stripped.source, stripped.target);
+
+        return generate_edits_stripped(&stripped, &matrix)
+            .expect("the matrix was computed for these sequences");
+    }
+
+    ops.iter().map(|op| op.to_edit(target)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edit_op::generate_edit_ops;
+    use crate::hashed::*;
+
+    // An item whose hash ignores half of it, so different items collide
+    #[derive(Clone, Debug, PartialEq)]
+    struct Colliding(u8, u8);
+
+    impl Hash for Colliding {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.0.hash(state);
+        }
+    }
+
+    #[test]
+    fn generate_edits_hashed_test() {
+        let source = "kitten".as_bytes();
+        let target = "sitting".as_bytes();
+
+        let edits = generate_edits_hashed(source, target, false);
+        assert_eq!(edits.len(), 3);
+        assert_eq!(crate::apply_edits(source, &edits), target);
+        assert_eq!(generate_edits_hashed(source, target, true), edits);
+
+        assert_eq!(distance_hashed(source, target), 3);
+        assert!(generate_edits_hashed(source, source, true).is_empty());
+    }
+
+    #[test]
+    fn generate_edits_hashed_collision_test() {
+        let source = vec![Colliding(1, 0), Colliding(2, 0), Colliding(3, 0)];
+        let target = vec![Colliding(1, 0), Colliding(2, 1), Colliding(4, 0)];
+
+        // The second items collide, so the hashes miss that they differ
+        assert_eq!(distance_hashed(&source, &target), 1);
+
+        let unverified = generate_edits_hashed(&source, &target, false);
+        assert_ne!(crate::apply_edits(&source, &unverified), target);
+
+        let verified = generate_edits_hashed(&source, &target, true);
+        assert_eq!(verified.len(), 2);
+        assert_eq!(crate::apply_edits(&source, &verified), target);
+    }
+
+    #[test]
+    fn kept_items_match_test() {
+        let source = "SATURDAY".as_bytes();
+        let target = "SUNDAY".as_bytes();
+
+        let (_, matrix) = crate::distance(source, target);
+        let ops = generate_edit_ops(source, target, &matrix).unwrap();
+
+        assert!(kept_items_match(source, target, &ops));
+        assert!(!kept_items_match(source, "SUNDAE".as_bytes(), &ops));
+    }
+}
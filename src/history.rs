@@ -0,0 +1,271 @@
+use crate::edit::{generate_edits, try_apply_edits, Edit};
+use crate::patch::PatchError;
+use crate::replace::{coalesce_edits, replacements_to_edits, Replace};
+use crate::script::EditScript;
+use alloc::vec::Vec;
+
+/// Returns the edits that undo a sequence of edits, i.e. that transform the target back into the
+/// source.
+///
+/// The items the edits delete or overwrite aren't part of the edits, so the source is needed to
+/// put them back. The inverted edits are in the same descending order as `generate_edits`
+/// produces, so they can be applied to the target with `apply_edits`.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence the edits apply to
+/// * `edits` - The edits, as generated by `generate_edits`
+///
+/// # Panics
+///
+/// Panics if an edit refers to an index outside the source.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY".as_bytes();
+/// let s2 = "SUNDAY".as_bytes();
+///
+/// let (_, matrix) = levenshtein::distance(s1, s2);
+/// let edits = levenshtein::generate_edits(s1, s2, &matrix).unwrap();
+///
+/// let undo = levenshtein::invert_edits(s1, &edits);
+/// assert_eq!(levenshtein::apply_edits(s2, &undo), s1);
+/// ```
+pub fn invert_edits<T: Clone + PartialEq>(source: &[T], edits: &[Edit<T>]) -> Vec<Edit<T>> {
+    // The number of items the replacements so far have added to and removed from the source
+    let mut added = 0;
+    let mut removed = 0;
+
+    let inverted: Vec<Replace<T>> = coalesce_edits(edits)
+        .into_iter()
+        .map(|replacement| {
+            // Where the replacement ended up in the target
+            let start = replacement.range.start + added - removed;
+
+            added += replacement.with.len();
+            removed += replacement.range.len();
+
+            Replace {
+                range: start..start + replacement.with.len(),
+                with: source[replacement.range].to_vec(),
+            }
+        })
+        .collect();
+
+    replacements_to_edits(&inverted)
+}
+
+// A change recorded in the history, along with the script that reverts it
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Change<T: PartialEq> {
+    forward: EditScript<T>,
+    backward: EditScript<T>,
+}
+
+/// A sequence that keeps track of the changes made to it, so they can be undone and redone.
+///
+/// Every change is recorded as an edit script rather than a copy of the sequence, so a long
+/// history of small changes to a large document stays small. Making a new change after undoing
+/// discards the changes that could have been redone, like in a text editor.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::History;
+///
+/// let mut history = History::new("SATURDAY".as_bytes().to_vec());
+///
+/// history.update("SUNDAY".as_bytes().to_vec());
+/// history.update("MONDAY".as_bytes().to_vec());
+///
+/// assert!(history.undo());
+/// assert_eq!(history.current(), "SUNDAY".as_bytes());
+///
+/// assert!(history.redo());
+/// assert_eq!(history.current(), "MONDAY".as_bytes());
+///
+/// // The chain of scripts leads from the original sequence to the current one
+/// let mut sequence = history.original();
+/// for script in history.scripts() {
+///     sequence = script.apply(&sequence);
+/// }
+/// assert_eq!(sequence, "MONDAY".as_bytes());
+/// ```
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct History<T: PartialEq> {
+    current: Vec<T>,
+    // The changes that led to the current sequence, oldest first
+    done: Vec<Change<T>>,
+    // The changes that were undone, most recently undone last
+    undone: Vec<Change<T>>,
+}
+
+impl<T: Clone + PartialEq> History<T> {
+    /// Returns a history with no changes, starting at the given sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial` - The sequence before any changes
+    pub fn new(initial: Vec<T>) -> Self {
+        History {
+            current: initial,
+            done: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// Returns the sequence with all the changes that haven't been undone applied.
+    pub fn current(&self) -> &[T] {
+        &self.current
+    }
+
+    /// Applies a sequence of edits to the current sequence and records them as a change.
+    ///
+    /// Returns an error, and leaves the history as it was, if the edits don't fit the current
+    /// sequence. See `try_apply_edits`.
+    ///
+    /// # Arguments
+    ///
+    /// * `edits` - The edits, as generated by `generate_edits`
+    pub fn apply(&mut self, edits: Vec<Edit<T>>) -> Result<(), PatchError> {
+        let target = try_apply_edits(&self.current, &edits)?;
+        let backward = invert_edits(&self.current, &edits);
+
+        self.current = target;
+        self.done.push(Change {
+            forward: EditScript::from(edits),
+            backward: EditScript::from(backward),
+        });
+        self.undone.clear();
+
+        Ok(())
+    }
+
+    /// Replaces the current sequence with a new one, and records the edits between them as a
+    /// change.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The new sequence
+    pub fn update(&mut self, target: Vec<T>) {
+        let (_, matrix) = crate::distance(&self.current, &target);
+        let edits = generate_edits(&self.current, &target, &matrix)
+            .expect("the matrix was computed for these sequences");
+        let backward = invert_edits(&self.current, &edits);
+
+        self.current = target;
+        self.done.push(Change {
+            forward: EditScript::from(edits),
+            backward: EditScript::from(backward),
+        });
+        self.undone.clear();
+    }
+
+    /// Reverts the most recent change that hasn't been undone yet. Returns false if there is
+    /// nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.done.pop() {
+            Some(change) => {
+                self.current = change.backward.apply(&self.current);
+                self.undone.push(change);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reapplies the most recently undone change. Returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.undone.pop() {
+            Some(change) => {
+                self.current = change.forward.apply(&self.current);
+                self.done.push(change);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if there is a change to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    /// Returns true if there is a change to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Returns the edit scripts of the changes that led to the current sequence, oldest first.
+    /// Applying them in order to `original` produces `current`.
+    pub fn scripts(&self) -> impl Iterator<Item = &EditScript<T>> {
+        self.done.iter().map(|change| &change.forward)
+    }
+
+    /// Returns the sequence as it was before any of the changes that led to the current sequence,
+    /// by undoing all of them.
+    pub fn original(&self) -> Vec<T> {
+        self.done
+            .iter()
+            .rev()
+            .fold(self.current.clone(), |sequence, change| {
+                change.backward.apply(&sequence)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::history::*;
+
+    #[test]
+    fn invert_edits_test() {
+        let source = "kitten sitting on the mitten".as_bytes();
+        let target = "sitting kitten in the mittens".as_bytes();
+
+        let (_, matrix) = crate::distance(source, target);
+        let edits = generate_edits(source, target, &matrix).unwrap();
+
+        let undo = invert_edits(source, &edits);
+        assert_eq!(crate::apply_edits(target, &undo), source);
+        assert_eq!(
+            crate::apply_edits(source, &invert_edits(target, &undo)),
+            target
+        );
+    }
+
+    #[test]
+    fn history_test() {
+        let mut history = History::new(vec![1, 2, 3]);
+        assert!(!history.undo());
+
+        history.update(vec![1, 3, 4]);
+        history
+            .apply(vec![Edit::Insert(0, 0), Edit::Delete(2)])
+            .unwrap();
+        assert_eq!(history.current(), &[0, 1, 4]);
+
+        // Edits that don't fit the current sequence aren't recorded
+        assert!(history.apply(vec![Edit::Delete(4)]).is_err());
+        assert_eq!(history.scripts().count(), 2);
+
+        assert!(history.undo());
+        assert!(history.undo());
+        assert_eq!(history.current(), &[1, 2, 3]);
+        assert!(!history.can_undo());
+
+        assert!(history.redo());
+        assert_eq!(history.current(), &[1, 3, 4]);
+
+        // A new change discards the one that could have been redone
+        history.update(vec![5]);
+        assert!(!history.can_redo());
+        assert_eq!(history.original(), vec![1, 2, 3]);
+    }
+}
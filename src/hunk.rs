@@ -0,0 +1,455 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::edit::Edit;
+use crate::replace::{coalesce_edits, Replace};
+
+/// A part of a hunk: either a run of unchanged items, or a change.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HunkPart<T> {
+    /// Items that are the same in the source and the target.
+    Context(Vec<T>),
+    /// Items of the source replaced with new items. Either side may be empty, but not both.
+    Change { removed: Vec<T>, added: Vec<T> },
+}
+
+/// A run of nearby changes, along with the unchanged items around and between them.
+///
+/// Ranges are 0-indexed and half-open, like slice ranges. `source` is the part of the source the
+/// hunk covers, and `target` is the part of the target it turns into. Since a hunk holds the items
+/// it removes as well as the ones it adds, it can be applied to a source that has drifted from the
+/// one it was made for (see `apply_hunks_fuzzy`).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hunk<T> {
+    pub source: Range<usize>,
+    pub target: Range<usize>,
+    pub parts: Vec<HunkPart<T>>,
+}
+
+impl<T: Clone> Hunk<T> {
+    /// Returns the changes in the hunk, in order, as replacements of ranges of the whole source.
+    pub fn changes(&self) -> Vec<Replace<T>> {
+        let mut pos = self.source.start;
+        let mut changes = Vec::new();
+
+        for part in &self.parts {
+            match part {
+                HunkPart::Context(items) => pos += items.len(),
+                HunkPart::Change { removed, added } => {
+                    changes.push(Replace {
+                        range: pos..pos + removed.len(),
+                        with: added.clone(),
+                    });
+                    pos += removed.len();
+                }
+            }
+        }
+
+        changes
+    }
+
+    // Returns the items the hunk covers in the source if `is_source`, and in the target otherwise
+    fn side(&self, is_source: bool) -> Vec<T> {
+        let mut items = Vec::new();
+
+        for part in &self.parts {
+            match part {
+                HunkPart::Context(context) => items.extend_from_slice(context),
+                HunkPart::Change { removed, .. } if is_source => items.extend_from_slice(removed),
+                HunkPart::Change { added, .. } => items.extend_from_slice(added),
+            }
+        }
+
+        items
+    }
+
+    // Returns the number of unchanged items the hunk starts and ends with
+    fn context_lens(&self) -> (usize, usize) {
+        let len = |part: Option<&HunkPart<T>>| match part {
+            Some(HunkPart::Context(items)) => items.len(),
+            _ => 0,
+        };
+
+        (len(self.parts.first()), len(self.parts.last()))
+    }
+}
+
+/// Groups an edit script into hunks of nearby changes, each with up to `context_len` unchanged
+/// items around it, as shown by `diff`.
+///
+/// Changes separated by at most `2 * context_len` unchanged items end up in the same hunk, since
+/// their context would otherwise overlap. A hunk starts and ends with context, unless it's at the
+/// start or the end of the source.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `edits` - The edits, as generated by `generate_edits`
+/// * `context_len` - The number of unchanged items to keep around each change
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::HunkPart;
+///
+/// let source = "SATURDAY".as_bytes();
+/// let target = "SUNDAY".as_bytes();
+///
+/// let (_, matrix) = levenshtein::distance(source, target);
+/// let edits = levenshtein::generate_edits(source, target, &matrix).unwrap();
+///
+/// let hunks = levenshtein::group_into_hunks(source, &edits, 1);
+///
+/// assert_eq!(hunks.len(), 1);
+/// assert_eq!(hunks[0].source, 0..6);
+/// assert_eq!(hunks[0].target, 0..4);
+/// assert_eq!(
+///     hunks[0].parts,
+///     vec![
+///         HunkPart::Context("S".as_bytes().to_vec()),
+///         HunkPart::Change { removed: "AT".as_bytes().to_vec(), added: vec![] },
+///         HunkPart::Context("U".as_bytes().to_vec()),
+///         HunkPart::Change { removed: vec![b'R'], added: vec![b'N'] },
+///         HunkPart::Context("D".as_bytes().to_vec()),
+///     ]
+/// );
+/// ```
+pub fn group_into_hunks<T: Clone + PartialEq>(
+    source: &[T],
+    edits: &[Edit<T>],
+    context_len: usize,
+) -> Vec<Hunk<T>> {
+    let mut hunks: Vec<Hunk<T>> = Vec::new();
+
+    // How much longer the target is than the source, up to the current change
+    let mut growth: isize = 0;
+
+    let context = |range: Range<usize>| HunkPart::Context(source[range].to_vec());
+
+    let mut changes = coalesce_edits(edits).into_iter().peekable();
+
+    while let Some(change) = changes.next() {
+        let target_start = (change.range.start as isize + growth) as usize;
+        growth += change.with.len() as isize - change.range.len() as isize;
+
+        // Continue the current hunk, unless this is the first change of a new one
+        let hunk = match hunks.last_mut() {
+            Some(hunk) if hunk.source.end == change.range.start => hunk,
+            _ => {
+                let before = core::cmp::min(context_len, change.range.start);
+                let start = change.range.start - before;
+
+                hunks.push(Hunk {
+                    source: start..change.range.start,
+                    target: target_start - before..target_start,
+                    parts: Vec::new(),
+                });
+
+                let hunk = hunks.last_mut().unwrap();
+
+                if before > 0 {
+                    hunk.parts.push(context(start..change.range.start));
+                }
+
+                hunk
+            }
+        };
+
+        let end = change.range.end;
+        hunk.source.end = end;
+        hunk.target.end = target_start + change.with.len();
+        hunk.parts.push(HunkPart::Change {
+            removed: source[change.range].to_vec(),
+            added: change.with,
+        });
+
+        // Take the unchanged items up to the next change if it's close enough to join this hunk,
+        // or else up to `context_len` of them to end the hunk
+        let after = match changes.peek() {
+            Some(next) if next.range.start - end <= context_len.saturating_mul(2) => {
+                next.range.start - end
+            }
+            _ => core::cmp::min(context_len, source.len() - end),
+        };
+
+        if after > 0 {
+            hunk.source.end += after;
+            hunk.target.end += after;
+            hunk.parts.push(context(end..end + after));
+        }
+    }
+
+    hunks
+}
+
+/// Where `apply_hunks_fuzzy` applied a hunk.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HunkPlacement {
+    /// How far the hunk was from its position in the source it was made for.
+    pub offset: isize,
+    /// How many unchanged items were ignored at each end of the hunk to find a match.
+    pub fuzz: usize,
+}
+
+/// The outcome of `apply_hunks_fuzzy`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuzzyPatchResult<T> {
+    /// The source with every hunk that could be placed applied to it.
+    pub target: Vec<T>,
+    /// Where each hunk was applied, or `None` if it was rejected.
+    pub placements: Vec<Option<HunkPlacement>>,
+}
+
+impl<T> FuzzyPatchResult<T> {
+    /// Returns the indices of the rejected hunks.
+    pub fn rejected(&self) -> Vec<usize> {
+        self.placements
+            .iter()
+            .enumerate()
+            .filter(|(_, placement)| placement.is_none())
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+// Returns the position closest to `expected` where the needle occurs in the source, searching at
+// most `max_offset` positions away from it, and not before `from`. Later positions win ties.
+fn find_near<T: PartialEq>(
+    source: &[T],
+    needle: &[T],
+    expected: isize,
+    from: usize,
+    max_offset: usize,
+) -> Option<usize> {
+    let last = source.len().checked_sub(needle.len())?;
+    let (first, last) = (from as isize, last as isize);
+
+    // Offsets that reach past both ends of the source can't find anything, so a huge
+    // `max_offset` is the same as searching the whole source
+    let reach = expected
+        .saturating_sub(first)
+        .unsigned_abs()
+        .max(last.saturating_sub(expected).unsigned_abs());
+    let max_offset = core::cmp::min(max_offset, core::cmp::min(reach, isize::MAX as usize));
+
+    (0..=max_offset as isize)
+        .flat_map(|offset| [expected.checked_add(offset), expected.checked_sub(offset)])
+        .flatten()
+        .filter(|&at| at >= first && at <= last)
+        .find(|&at| source[at as usize..at as usize + needle.len()] == *needle)
+        .map(|at| at as usize)
+}
+
+/// Applies hunks to a source that may have drifted from the one they were made for, like
+/// `patch -F`.
+///
+/// Each hunk is looked for at its position in the original source, shifted by however far the
+/// previous hunk was found from its own position, and then up to `max_offset` items away from
+/// there. If the items it covers aren't found, up to `max_fuzz` unchanged items are ignored at
+/// each end of the hunk, one at a time, and it's looked for again. Hunks that can't be found at
+/// all are rejected, leaving that part of the source unchanged. Hunks are applied in order, and
+/// never overlap.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `hunks` - The hunks, as returned by `group_into_hunks`
+/// * `max_offset` - The furthest a hunk may be applied from where it's expected
+/// * `max_fuzz` - The most unchanged items that may be ignored at each end of a hunk
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::HunkPlacement;
+///
+/// let source = "the quick brown fox".as_bytes();
+/// let target = "the quick red fox".as_bytes();
+///
+/// let (_, matrix) = levenshtein::distance(source, target);
+/// let edits = levenshtein::generate_edits(source, target, &matrix).unwrap();
+/// let hunks = levenshtein::group_into_hunks(source, &edits, 3);
+///
+/// // Some words were added before the change since the hunks were made
+/// let drifted = "so the very quick brown fox".as_bytes();
+///
+/// let result = levenshtein::apply_hunks_fuzzy(drifted, &hunks, 10, 0);
+///
+/// assert_eq!(result.target, "so the very quick red fox".as_bytes());
+/// assert_eq!(result.placements, vec![Some(HunkPlacement { offset: 8, fuzz: 0 })]);
+/// ```
+pub fn apply_hunks_fuzzy<T: Clone + PartialEq>(
+    source: &[T],
+    hunks: &[Hunk<T>],
+    max_offset: usize,
+    max_fuzz: usize,
+) -> FuzzyPatchResult<T> {
+    let mut target = Vec::with_capacity(source.len());
+    let mut placements = Vec::with_capacity(hunks.len());
+
+    let mut copied_up_to = 0;
+    let mut drift: isize = 0;
+
+    for hunk in hunks {
+        let old = hunk.side(true);
+        let new = hunk.side(false);
+        let (leading, trailing) = hunk.context_lens();
+
+        // Ignoring more items than either end has changes nothing, so a huge `max_fuzz` is
+        // the same as ignoring all of the context
+        let max_fuzz = core::cmp::min(max_fuzz, core::cmp::max(leading, trailing));
+
+        let found = (0..=max_fuzz).find_map(|fuzz| {
+            let front = core::cmp::min(fuzz, leading);
+            let back = core::cmp::min(fuzz, trailing);
+
+            // Where the hunk would be, without the ignored items
+            let position = (hunk.source.start + front) as isize;
+            let needle = &old[front..old.len() - back];
+
+            find_near(source, needle, position + drift, copied_up_to, max_offset)
+                .map(|at| (at, position, needle.len(), fuzz, front, back))
+        });
+
+        let (at, position, len, fuzz, front, back) = match found {
+            Some(found) => found,
+            None => {
+                placements.push(None);
+                continue;
+            }
+        };
+
+        target.extend_from_slice(&source[copied_up_to..at]);
+        target.extend_from_slice(&new[front..new.len() - back]);
+        copied_up_to = at + len;
+
+        drift = at as isize - position;
+        placements.push(Some(HunkPlacement {
+            offset: drift,
+            fuzz,
+        }));
+    }
+
+    target.extend_from_slice(&source[copied_up_to..]);
+
+    FuzzyPatchResult { target, placements }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hunk::*;
+
+    fn hunks(source: &str, target: &str, context_len: usize) -> Vec<Hunk<u8>> {
+        let (_, matrix) = crate::distance(source.as_bytes(), target.as_bytes());
+        let edits = crate::generate_edits(source.as_bytes(), target.as_bytes(), &matrix).unwrap();
+
+        group_into_hunks(source.as_bytes(), &edits, context_len)
+    }
+
+    #[test]
+    fn group_into_hunks_test() {
+        // Changes 7 items apart only share a hunk with at least 4 items of context
+        let split = hunks("abcdefgh", "Abcdefgh!", 1);
+
+        assert_eq!(split.len(), 2);
+        assert_eq!(
+            (split[0].source.clone(), split[0].target.clone()),
+            (0..2, 0..2)
+        );
+        assert_eq!(
+            (split[1].source.clone(), split[1].target.clone()),
+            (7..8, 7..9)
+        );
+        assert_eq!(split[1].changes().len(), 1);
+
+        let joined = hunks("abcdefgh", "Abcdefgh!", 4);
+
+        assert_eq!(joined.len(), 1);
+        assert_eq!(
+            (joined[0].source.clone(), joined[0].target.clone()),
+            (0..8, 0..9)
+        );
+        assert_eq!(joined[0].parts.len(), 3);
+        assert_eq!(joined[0].parts[1], HunkPart::Context(b"bcdefgh".to_vec()));
+
+        // The whole sequence as context
+        assert_eq!(hunks("abcdefgh", "Abcdefgh!", usize::MAX), joined);
+    }
+
+    #[test]
+    fn no_context_test() {
+        let hunks = hunks("abc", "aXc", 0);
+
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(
+            hunks[0].parts,
+            vec![HunkPart::Change {
+                removed: vec![b'b'],
+                added: vec![b'X']
+            }]
+        );
+
+        assert_eq!(
+            hunks[0].changes(),
+            vec![Replace {
+                range: 1..2,
+                with: vec![b'X']
+            }]
+        );
+
+        assert!(group_into_hunks::<u8>(&[], &[], 3).is_empty());
+    }
+
+    #[test]
+    fn apply_hunks_fuzzy_test() {
+        let hunks = hunks("abcdefghijklmnop", "abcdeFghijklmnoP", 2);
+        assert_eq!(hunks.len(), 2);
+
+        // Unchanged, and with an item added before each hunk
+        let result = apply_hunks_fuzzy(b"abcdefghijklmnop", &hunks, 0, 0);
+        assert_eq!(result.target, b"abcdeFghijklmnoP");
+
+        let result = apply_hunks_fuzzy(b"XabcdefghijklYmnop", &hunks, 1, 0);
+        assert_eq!(result.target, b"XabcdeFghijklYmnoP");
+        assert_eq!(
+            result.placements,
+            vec![
+                Some(HunkPlacement { offset: 1, fuzz: 0 }),
+                Some(HunkPlacement { offset: 2, fuzz: 0 })
+            ]
+        );
+
+        // The context of the first hunk changed, so it only applies with fuzz
+        let result = apply_hunks_fuzzy(b"abcZefghijklmnop", &hunks, 0, 0);
+        assert_eq!(result.target, b"abcZefghijklmnoP");
+        assert_eq!(result.rejected(), vec![0]);
+
+        let result = apply_hunks_fuzzy(b"abcZefghijklmnop", &hunks, 0, 1);
+        assert_eq!(result.target, b"abcZeFghijklmnoP");
+        assert_eq!(
+            result.placements[0],
+            Some(HunkPlacement { offset: 0, fuzz: 1 })
+        );
+
+        // The changed item itself is different, so no amount of fuzz helps
+        let result = apply_hunks_fuzzy(b"abcdeZghijklmnop", &hunks, 5, 2);
+        assert_eq!(result.rejected(), vec![0]);
+
+        // An offset as large as it gets searches the whole source
+        let result = apply_hunks_fuzzy(b"0123456789abcdefghijklmnop", &hunks, usize::MAX, 0);
+        assert_eq!(result.target, b"0123456789abcdeFghijklmnoP");
+        assert_eq!(
+            result.placements[0],
+            Some(HunkPlacement {
+                offset: 10,
+                fuzz: 0
+            })
+        );
+
+        let result = apply_hunks_fuzzy(b"abc", &hunks, usize::MAX, usize::MAX);
+        assert_eq!(result.rejected(), vec![0, 1]);
+    }
+}
@@ -0,0 +1,173 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::edit::Edit;
+use crate::replace::{coalesce_edits, Replace};
+
+/// Maps indices in a source sequence to the corresponding indices in the target sequence an edit
+/// script produces.
+///
+/// Indices are 0-indexed, like slice indices. Items that the script deletes or substitutes have no
+/// counterpart in the target, while all other items keep their identity and are simply shifted by
+/// the edits in front of them.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::IndexMap;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+///
+/// let map = IndexMap::new(&edits);
+///
+/// assert_eq!(map.map_index(0), Some(0)); // S stays put
+/// assert_eq!(map.map_index(1), None); // A is deleted
+/// assert_eq!(map.map_index(3), Some(1)); // U moves to the front
+/// assert_eq!(map.map_index(4), None); // R is substituted with N
+/// assert_eq!(map.map_index(7), Some(5)); // Y is the last item in both
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexMap {
+    // The source range of each replacement, and the target range of the items replacing it
+    changes: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl IndexMap {
+    /// Builds the index map of an edit script.
+    ///
+    /// # Arguments
+    ///
+    /// * `edits` - The edits, as generated by `generate_edits`
+    pub fn new<T: Clone + PartialEq>(edits: &[Edit<T>]) -> Self {
+        IndexMap::from_replacements(&coalesce_edits(edits))
+    }
+
+    /// Builds the index map of a sequence of range replacements.
+    ///
+    /// # Arguments
+    ///
+    /// * `replacements` - The replacements, sorted and non-overlapping
+    pub fn from_replacements<T>(replacements: &[Replace<T>]) -> Self {
+        let mut changes = Vec::with_capacity(replacements.len());
+
+        // Where the previous replacement ended in the source and in the target. Unchanged items
+        // in between are shifted by the difference, tracked this way to avoid signed arithmetic.
+        let mut source_end = 0;
+        let mut target_end = 0;
+
+        for replacement in replacements {
+            let target_start = replacement.range.start - source_end + target_end;
+
+            source_end = replacement.range.end;
+            target_end = target_start + replacement.with.len();
+
+            changes.push((replacement.range.clone(), target_start..target_end));
+        }
+
+        IndexMap { changes }
+    }
+
+    // Shifts a source index that lies after the first `k` changes
+    fn shift(&self, k: usize, idx: usize) -> usize {
+        match k {
+            0 => idx,
+            _ => {
+                let (source, target) = &self.changes[k - 1];
+                idx - source.end + target.end
+            }
+        }
+    }
+
+    /// Returns the index of the source item at `idx` in the target, or `None` if it was deleted
+    /// or substituted.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - An index in the source
+    pub fn map_index(&self, idx: usize) -> Option<usize> {
+        // Insertions right in front of the item count as coming before it
+        let k = self
+            .changes
+            .partition_point(|(source, _)| source.end <= idx);
+
+        match self.changes.get(k) {
+            Some((source, _)) if source.start <= idx => None,
+            _ => Some(self.shift(k, idx)),
+        }
+    }
+
+    /// Returns the position in the target that corresponds to a position between source items,
+    /// such as a cursor. Position `pos` is right in front of the item at index `pos`.
+    ///
+    /// Unlike items, positions always have a counterpart. Positions inside a replaced range map
+    /// to the start of its replacement, and items inserted right at a position end up after it.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - A position in the source, from 0 up to and including its length
+    pub fn map_position(&self, pos: usize) -> usize {
+        let k = self.changes.partition_point(|(source, _)| {
+            source.end < pos || source.start < pos && source.end == pos
+        });
+
+        match self.changes.get(k) {
+            Some((source, target)) if source.start < pos => target.start,
+            _ => self.shift(k, pos),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::index_map::*;
+
+    #[test]
+    fn map_index_test() {
+        // ABCD -> XAYD: insert X at the start and replace BC with Y
+        let replacements = vec![
+            Replace {
+                range: 0..0,
+                with: vec![b'X'],
+            },
+            Replace {
+                range: 1..3,
+                with: vec![b'Y'],
+            },
+        ];
+
+        let map = IndexMap::from_replacements(&replacements);
+
+        assert_eq!(map.map_index(0), Some(1));
+        assert_eq!(map.map_index(1), None);
+        assert_eq!(map.map_index(2), None);
+        assert_eq!(map.map_index(3), Some(3));
+    }
+
+    #[test]
+    fn map_position_test() {
+        // ABCD -> XAYD: insert X at the start and replace BC with Y
+        let replacements = vec![
+            Replace {
+                range: 0..0,
+                with: vec![b'X'],
+            },
+            Replace {
+                range: 1..3,
+                with: vec![b'Y'],
+            },
+        ];
+
+        let map = IndexMap::from_replacements(&replacements);
+
+        assert_eq!(map.map_position(0), 0);
+        assert_eq!(map.map_position(1), 2);
+        assert_eq!(map.map_position(2), 2);
+        assert_eq!(map.map_position(3), 3);
+        assert_eq!(map.map_position(4), 4);
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::edit::{generate_edits_stripped, Edit};
+use crate::pattern::Pattern;
+use crate::util::strip_common_affix;
+
+/// Maps items to small integer tokens, so that sequences of items that are expensive to compare,
+/// like lines, can be diffed as sequences of tokens instead.
+///
+/// Equal items get the same token and different items different ones, so two sequences of tokens
+/// have the same distance and edits as the sequences they were interned from. Each item is hashed
+/// once when it's interned, whereas the distance matrix compares every item of the source with
+/// every item of the target: for lines, those comparisons can cost more than the rest of the diff.
+/// Tokens are numbered from 0 in the order their items were first seen.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Interner;
+///
+/// let source = vec!["one".to_string(), "two".to_string(), "one".to_string()];
+/// let target = vec!["two".to_string(), "three".to_string()];
+///
+/// let mut interner = Interner::new();
+/// assert_eq!(interner.intern(&source), vec![0, 1, 0]);
+/// assert_eq!(interner.intern(&target), vec![1, 2]);
+///
+/// assert_eq!(interner.len(), 3);
+/// assert_eq!(interner.get(2), Some(&"three".to_string()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Interner<'a, T> {
+    tokens: HashMap<&'a T, usize>,
+    // The item of every token
+    items: Vec<&'a T>,
+}
+
+impl<'a, T: Eq + Hash> Interner<'a, T> {
+    /// Returns an interner with no tokens.
+    pub fn new() -> Self {
+        Interner {
+            tokens: HashMap::new(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Returns the tokens of a sequence, giving new tokens to the items that haven't been seen
+    /// before.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The sequence to intern
+    pub fn intern(&mut self, items: &'a [T]) -> Vec<usize> {
+        items
+            .iter()
+            .map(|item| {
+                let next = self.items.len();
+                let token = *self.tokens.entry(item).or_insert(next);
+
+                if token == next {
+                    self.items.push(item);
+                }
+
+                token
+            })
+            .collect()
+    }
+
+    /// Returns the token of an item, if it has been interned.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item to look for
+    pub fn token(&self, item: &T) -> Option<usize> {
+        self.tokens.get(item).copied()
+    }
+
+    /// Returns the item of a token, if there is one.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token, as returned by `intern`
+    pub fn get(&self, token: usize) -> Option<&'a T> {
+        self.items.get(token).copied()
+    }
+
+    /// Returns the number of distinct items interned.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if no items have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl<'a, T: Eq + Hash> Default for Interner<'a, T> {
+    fn default() -> Self {
+        Interner::new()
+    }
+}
+
+/// Returns the Levenshtein distance between source and target, comparing their tokens instead of
+/// their items. See `Interner`.
+///
+/// The tokens of the source are also preprocessed into a `Pattern`, so this takes
+/// O(⌈m/64⌉ * n) word operations once the items are interned.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let source: Vec<String> = "fn main() {\n    println!(\"hello\");\n}"
+///     .lines()
+///     .map(String::from)
+///     .collect();
+/// let target: Vec<String> = "fn main() {\n    println!(\"hello, world\");\n}"
+///     .lines()
+///     .map(String::from)
+///     .collect();
+///
+/// assert_eq!(levenshtein::distance_interned(&source, &target), 1);
+/// ```
+pub fn distance_interned<T: Eq + Hash>(source: &[T], target: &[T]) -> usize {
+    let mut interner = Interner::new();
+    let source = interner.intern(source);
+    let target = interner.intern(target);
+
+    Pattern::new(&source).distance_to(&target)
+}
+
+/// Generate a vector of edits that transform the source sequence into the target sequence,
+/// comparing their tokens instead of their items. See `Interner`.
+///
+/// The edits are the ones `generate_edits` produces for the sequences with their common prefix
+/// and suffix stripped, as `generate_edits_stripped` does.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let source: Vec<String> = ["one", "two", "three"].iter().map(|s| s.to_string()).collect();
+/// let target: Vec<String> = ["one", "2", "three"].iter().map(|s| s.to_string()).collect();
+///
+/// let edits = levenshtein::generate_edits_interned(&source, &target);
+/// assert_eq!(edits, vec![levenshtein::Edit::Substitute(2, "2".to_string())]);
+/// ```
+pub fn generate_edits_interned<T: Clone + Eq + Hash>(source: &[T], target: &[T]) -> Vec<Edit<T>> {
+    let mut interner = Interner::new();
+    let source = interner.intern(source);
+    let target = interner.intern(target);
+
+    let stripped = strip_common_affix(&source, &target);
+    let (_, matrix) = crate::distance(stripped.source, stripped.target);
+
+    let edits = generate_edits_stripped(&stripped, &matrix)
+        .expect("the matrix was computed for these sequences");
+
+    // Every token in the edits came from the target, so it has an item
+    let item = |token: usize| interner.get(token).expect("an interned token").clone();
+
+    edits
+        .into_iter()
+        .map(|edit| match edit {
+            Edit::Delete(idx) => Edit::Delete(idx),
+            Edit::Insert(idx, token) => Edit::Insert(idx, item(token)),
+            Edit::Substitute(idx, token) => Edit::Substitute(idx, item(token)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::intern::*;
+
+    #[test]
+    fn interner_test() {
+        let source = ["a", "b", "a", "c"];
+        let target = ["c", "d"];
+
+        let mut interner = Interner::new();
+        assert!(interner.is_empty());
+
+        assert_eq!(interner.intern(&source), vec![0, 1, 0, 2]);
+        assert_eq!(interner.intern(&target), vec![2, 3]);
+        assert!(interner.intern(&[]).is_empty());
+
+        assert_eq!(interner.len(), 4);
+        assert_eq!(interner.token(&"d"), Some(3));
+        assert_eq!(interner.token(&"e"), None);
+        assert_eq!(interner.get(1), Some(&"b"));
+        assert_eq!(interner.get(4), None);
+    }
+
+    #[test]
+    fn generate_edits_interned_test() {
+        let source: Vec<String> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let target: Vec<String> = ["x", "a", "c", "d", "y", "e"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let edits = generate_edits_interned(&source, &target);
+        assert_eq!(edits.len(), distance_interned(&source, &target));
+        assert_eq!(edits.len(), 3);
+        assert_eq!(crate::apply_edits(&source, &edits), target);
+
+        assert!(generate_edits_interned(&source, &source).is_empty());
+        assert_eq!(distance_interned(&source, &[]), 5);
+    }
+}
@@ -0,0 +1,151 @@
+use serde_json::{json, Value};
+
+use crate::distance::diff_edits;
+use crate::edit::Edit;
+use crate::replace::coalesce_edits;
+
+// Returns the JSON Pointer to the element at the index of the array at `path`
+fn element(path: &str, index: usize) -> Value {
+    Value::String(format!("{}/{}", path, index))
+}
+
+/// Returns a JSON Patch (RFC 6902) that transforms the source array into the target array.
+///
+/// # Arguments
+///
+/// * `source` - The elements of the source array
+/// * `target` - The elements of the target array
+/// * `path` - The JSON Pointer to the array in the document the patch applies to, or `""` if the
+///   array is the whole document
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use serde_json::json;
+///
+/// let source = vec![json!(1), json!(2), json!(3)];
+/// let target = vec![json!(1), json!(4), json!(3), json!(5)];
+///
+/// assert_eq!(
+///     levenshtein::json_patch(&source, &target, "/items"),
+///     json!([
+///         { "op": "add", "path": "/items/3", "value": 5 },
+///         { "op": "replace", "path": "/items/1", "value": 4 },
+///     ])
+/// );
+/// ```
+pub fn json_patch(source: &[Value], target: &[Value], path: &str) -> Value {
+    let edits = diff_edits(source, target);
+
+    json_patch_from_edits(&edits, path)
+}
+
+/// Returns a JSON Patch (RFC 6902) that applies the edits to an array. See `json_patch`.
+///
+/// JSON Patch operations are applied one after the other, and each one shifts the indices of the
+/// elements after it. The operations are therefore ordered from the end of the array to its start,
+/// so that every index refers to the source array.
+///
+/// # Arguments
+///
+/// * `edits` - The edits, as generated by `generate_edits`
+/// * `path` - The JSON Pointer to the array in the document the patch applies to, or `""` if the
+///   array is the whole document
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+/// use serde_json::json;
+///
+/// let edits = vec![Edit::Insert(1, json!("b")), Edit::Delete(1)];
+///
+/// assert_eq!(
+///     levenshtein::json_patch_from_edits(&edits, ""),
+///     json!([
+///         { "op": "replace", "path": "/0", "value": "b" },
+///     ])
+/// );
+/// ```
+pub fn json_patch_from_edits(edits: &[Edit<Value>], path: &str) -> Value {
+    let mut operations = Vec::new();
+
+    for replacement in coalesce_edits(edits).into_iter().rev() {
+        let start = replacement.range.start;
+        let replaced = std::cmp::min(replacement.range.len(), replacement.with.len());
+
+        for (i, value) in replacement.with.iter().enumerate() {
+            let op = if i < replaced { "replace" } else { "add" };
+            operations.push(json!({ "op": op, "path": element(path, start + i), "value": value }));
+        }
+
+        // Every removal shifts the rest of the range down to the same index
+        for _ in replaced..replacement.range.len() {
+            operations.push(json!({ "op": "remove", "path": element(path, start + replaced) }));
+        }
+    }
+
+    Value::Array(operations)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_patch::*;
+
+    // Applies the subset of JSON Patch that `json_patch` produces to an array at the root
+    fn apply(source: &[Value], patch: &Value) -> Vec<Value> {
+        let mut target = source.to_vec();
+
+        for operation in patch.as_array().unwrap() {
+            let path = operation["path"].as_str().unwrap();
+            let index: usize = path.strip_prefix('/').unwrap().parse().unwrap();
+            let value = operation["value"].clone();
+
+            match operation["op"].as_str().unwrap() {
+                "add" => target.insert(index, value),
+                "remove" => {
+                    target.remove(index);
+                }
+                "replace" => target[index] = value,
+                op => panic!("unexpected operation {}", op),
+            }
+        }
+
+        target
+    }
+
+    #[test]
+    fn json_patch_test() {
+        let arrays = [
+            json!([1, 2, 3, 4, 5]),
+            json!([]),
+            json!([0, 1, 3, "four", 5, 6, 7]),
+            json!([{ "id": 1 }, [2], null]),
+        ];
+
+        for source in &arrays {
+            for target in &arrays {
+                let source = source.as_array().unwrap();
+                let target = target.as_array().unwrap();
+
+                assert_eq!(apply(source, &json_patch(source, target, "")), *target);
+            }
+        }
+    }
+
+    #[test]
+    fn longer_removals_test() {
+        let source = vec![json!(1), json!(2), json!(3)];
+
+        assert_eq!(
+            json_patch(&source, &[], "/a~1b"),
+            json!([
+                { "op": "remove", "path": "/a~1b/0" },
+                { "op": "remove", "path": "/a~1b/0" },
+                { "op": "remove", "path": "/a~1b/0" },
+            ])
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use alloc::vec::Vec;
+
+use crate::distance::levenshtein_two_rows;
+use crate::edit::Edit;
+use crate::edit_op::{generate_edit_ops, EditOp};
+use crate::util::strip_common_affix;
+
+// The ops generate_edit_ops finds between two sequences once their common prefix and suffix are
+// stripped, with their indices into the whole sequences
+pub(crate) fn stripped_edit_ops<T: PartialEq>(source: &[T], target: &[T]) -> Vec<EditOp> {
+    let stripped = strip_common_affix(source, target);
+    let (_, matrix) = crate::distance(stripped.source, stripped.target);
+    let prefix = stripped.prefix;
+
+    generate_edit_ops(stripped.source, stripped.target, &matrix)
+        .expect("the matrix was computed for these sequences")
+        .into_iter()
+        .map(|op| match op {
+            EditOp::Delete(idx) => EditOp::Delete(idx + prefix),
+            EditOp::Insert(idx, t) => EditOp::Insert(idx + prefix, t + prefix),
+            EditOp::Substitute(idx, t) => EditOp::Substitute(idx + prefix, t + prefix),
+        })
+        .collect()
+}
+
+/// Returns the Levenshtein distance between source and target, comparing a key computed from each
+/// of their items instead of the items.
+///
+/// Two items are taken for equal when their keys are, so this compares sequences up to whatever
+/// the key leaves out: the case of strings, the whitespace in lines, or the fields of a struct
+/// that don't matter. The key is computed once per item, in order, the source first.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `key` - Computes the key an item is compared by
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = ["Apple", "banana", "Cherry"];
+/// let s2 = ["apple", "BANANA", "date"];
+///
+/// assert_eq!(levenshtein::distance_by_key(&s1, &s2, |s| s.to_lowercase()), 1);
+/// ```
+pub fn distance_by_key<T, K: PartialEq, F: FnMut(&T) -> K>(
+    source: &[T],
+    target: &[T],
+    mut key: F,
+) -> usize {
+    let source: Vec<K> = source.iter().map(&mut key).collect();
+    let target: Vec<K> = target.iter().map(&mut key).collect();
+
+    levenshtein_two_rows(&source, &target)
+}
+
+/// Generate a vector of edit ops that transform the source sequence into the target sequence,
+/// comparing a key computed from each of their items instead of the items. See
+/// `distance_by_key`.
+///
+/// The ops are the ones `generate_edit_ops` finds for the keys once their common prefix and suffix
+/// are stripped. They refer to the new items by their index in the target, so they can be turned
+/// into edits of the original items with `EditOp::to_edit`.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `key` - Computes the key an item is compared by
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::EditOp;
+///
+/// let s1 = [(1, "one"), (2, "two"), (3, "three")];
+/// let s2 = [(1, "uno"), (3, "tres")];
+///
+/// // Compared by their numbers only, the second item was deleted
+/// let ops = levenshtein::generate_edit_ops_by_key(&s1, &s2, |item| item.0);
+/// assert_eq!(ops, vec![EditOp::Delete(2)]);
+/// ```
+pub fn generate_edit_ops_by_key<T, K: PartialEq, F: FnMut(&T) -> K>(
+    source: &[T],
+    target: &[T],
+    mut key: F,
+) -> Vec<EditOp> {
+    let source: Vec<K> = source.iter().map(&mut key).collect();
+    let target: Vec<K> = target.iter().map(&mut key).collect();
+
+    stripped_edit_ops(&source, &target)
+}
+
+/// Generate a vector of edits that transform the source sequence into the target sequence,
+/// comparing a key computed from each of their items instead of the items. See
+/// `distance_by_key`.
+///
+/// The new items in the edits are the original items of the target, not their keys, so applying
+/// the edits to the source gives a sequence with the same keys as the target, with the target's
+/// items wherever an edit was needed and the source's everywhere else.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `key` - Computes the key an item is compared by
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+///
+/// let s1 = ["Apple", "banana", "Cherry"];
+/// let s2 = ["apple", "BANANA", "date"];
+///
+/// let edits = levenshtein::generate_edits_by_key(&s1, &s2, |s| s.to_lowercase());
+/// assert_eq!(edits, vec![Edit::Substitute(3, "date")]);
+/// assert_eq!(levenshtein::apply_edits(&s1, &edits), vec!["Apple", "banana", "date"]);
+/// ```
+pub fn generate_edits_by_key<T: Clone + PartialEq, K: PartialEq, F: FnMut(&T) -> K>(
+    source: &[T],
+    target: &[T],
+    key: F,
+) -> Vec<Edit<T>> {
+    generate_edit_ops_by_key(source, target, key)
+        .iter()
+        .map(|op| op.to_edit(target))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keyed::*;
+
+    #[test]
+    fn generate_edits_by_key_test() {
+        let source = "kitten".as_bytes();
+        let target = "SITTING".as_bytes();
+        let key = |b: &u8| b.to_ascii_lowercase();
+
+        assert_eq!(distance_by_key(source, target, key), 3);
+
+        let edits = generate_edits_by_key(source, target, key);
+        assert_eq!(edits.len(), 3);
+        assert_eq!(crate::apply_edits(source, &edits), "SittInG".as_bytes());
+
+        assert!(generate_edits_by_key(source, "KITTEN".as_bytes(), key).is_empty());
+        assert_eq!(distance_by_key(source, &[], key), 6);
+    }
+
+    #[test]
+    fn stripped_edit_ops_test() {
+        let source = "xxSATURDAYxx".as_bytes();
+        let target = "xxSUNDAYxx".as_bytes();
+
+        let ops = stripped_edit_ops(source, target);
+        assert_eq!(
+            ops,
+            vec![
+                EditOp::Substitute(7, 4),
+                EditOp::Delete(5),
+                EditOp::Delete(4)
+            ]
+        );
+        assert_eq!(crate::apply_edit_ops(source, target, &ops), target);
+    }
+}
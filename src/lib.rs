@@ -1,10 +1,150 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
+#[macro_use]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod alignment;
+pub mod automaton;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod bk_tree;
+pub mod bsdiff;
+pub mod builder;
+pub mod cancel;
+pub mod case_fold;
+#[cfg(feature = "std")]
+pub mod cdc;
+pub mod cleanup;
+#[cfg(feature = "collation")]
+pub mod collation;
+pub mod compose;
+pub mod differ;
 pub mod distance;
+pub mod dmp;
 pub mod edit;
+pub mod edit_op;
+pub mod encoding;
+pub mod git_delta;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "std")]
+pub mod hashed;
+pub mod history;
+pub mod hunk;
+pub mod index_map;
+#[cfg(feature = "std")]
+pub mod intern;
+#[cfg(feature = "json")]
+pub mod json_patch;
+pub mod keyed;
+pub mod lines;
+pub mod merge;
+#[cfg(feature = "std")]
+pub mod minhash;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod moves;
+#[cfg(feature = "std")]
+pub mod online;
+pub mod patch;
+#[cfg(feature = "std")]
+pub mod pattern;
+#[cfg(feature = "regex")]
+pub mod regex_normalizer;
+pub mod replace;
+#[cfg(feature = "std")]
+pub mod rsync;
+pub mod script;
+#[cfg(feature = "std")]
+pub mod stream;
+pub mod strings;
+#[cfg(feature = "std")]
+pub mod symspell;
+pub mod transform;
+pub mod trie;
+#[cfg(feature = "std")]
+pub mod trigram;
+pub mod unified;
 pub mod util;
+pub mod vcdiff;
+pub mod weighted;
+pub mod whitespace;
+pub mod words;
+
+use alloc::vec::Vec;
 
+#[cfg(feature = "std")]
+pub use alignment::*;
+pub use automaton::*;
+#[cfg(feature = "std")]
+pub use batch::*;
+#[cfg(feature = "std")]
+pub use bk_tree::*;
+pub use bsdiff::*;
+pub use builder::*;
+pub use cancel::*;
+pub use case_fold::*;
+#[cfg(feature = "std")]
+pub use cdc::*;
+pub use cleanup::*;
+#[cfg(feature = "collation")]
+pub use collation::*;
+pub use compose::*;
+pub use differ::*;
 pub use distance::*;
+pub use dmp::*;
 pub use edit::*;
-use util::DistanceMatrix;
+pub use edit_op::*;
+pub use encoding::*;
+pub use git_delta::*;
+#[cfg(feature = "gpu")]
+pub use gpu::*;
+#[cfg(feature = "std")]
+pub use hashed::*;
+pub use history::*;
+pub use hunk::*;
+pub use index_map::*;
+#[cfg(feature = "std")]
+pub use intern::*;
+#[cfg(feature = "json")]
+pub use json_patch::*;
+pub use keyed::*;
+pub use lines::*;
+pub use merge::*;
+#[cfg(feature = "std")]
+pub use minhash::*;
+#[cfg(feature = "mmap")]
+pub use mmap::*;
+pub use moves::*;
+#[cfg(feature = "std")]
+pub use online::*;
+pub use patch::*;
+#[cfg(feature = "std")]
+pub use pattern::*;
+#[cfg(feature = "regex")]
+pub use regex_normalizer::*;
+pub use replace::*;
+#[cfg(feature = "std")]
+pub use rsync::*;
+pub use script::*;
+#[cfg(feature = "std")]
+pub use stream::*;
+pub use strings::*;
+#[cfg(feature = "std")]
+pub use symspell::*;
+pub use transform::*;
+pub use trie::*;
+#[cfg(feature = "std")]
+pub use trigram::*;
+pub use unified::*;
+use util::{DistanceCell, DistanceMatrix};
+pub use vcdiff::*;
+pub use weighted::*;
+pub use whitespace::*;
+pub use words::*;
 
 /// Computes and returns the Levenshtein distance between the source and target sequences.
 ///
@@ -34,6 +174,238 @@ pub fn distance<T: PartialEq>(source: &[T], target: &[T]) -> (usize, DistanceMat
     levenshtein_tabulation(source, target)
 }
 
+/// Computes and returns the Levenshtein distance between the source and target sequences, along
+/// with a distance matrix whose cells are stored as `C`.
+///
+/// The matrix takes (m+1) x (n+1) cells, which dominates the memory used for long sequences. With
+/// `u32` cells it takes half as much as with the `usize` cells `distance` uses, and with `u16`
+/// cells a quarter, as long as both sequences are shorter than what `C` can count to.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Panics
+///
+/// Panics if the length of the longer sequence doesn't fit in `C`.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "FLAW";
+/// let s2 = "LAWN";
+///
+/// let (distance, matrix) = levenshtein::distance_as::<_, u16>(s1.as_bytes(), s2.as_bytes());
+/// assert_eq!(distance, 2);
+/// assert_eq!(matrix.distance(), 2u16);
+/// ```
+pub fn distance_as<T: PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+) -> (usize, DistanceMatrix<C>) {
+    levenshtein_tabulation_as(source, target)
+}
+
+/// Computes and returns the Levenshtein distance between the source and target sequences, and
+/// fills the given distance matrix with the distances instead of allocating a new one.
+///
+/// The matrix's storage is reused, so computing many distances in a loop with the same matrix
+/// only allocates when the sequences get longer than they have been before. Use
+/// `distance_only_with_buffer` if the matrix isn't needed.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `matrix` - The distance matrix to fill, e.g. the one from the previous call
+///
+/// # Panics
+///
+/// Panics if the length of the longer sequence doesn't fit in `C`.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::util::DistanceMatrix;
+///
+/// let mut matrix: DistanceMatrix = DistanceMatrix::default();
+///
+/// let distance = levenshtein::distance_with_buffer(b"FLAW", b"LAWN", &mut matrix);
+/// assert_eq!(distance, 2);
+///
+/// let distance = levenshtein::distance_with_buffer(b"SUNDAY", b"MONDAY", &mut matrix);
+/// assert_eq!(distance, 2);
+/// assert_eq!(matrix.dims(), (7, 7));
+/// ```
+pub fn distance_with_buffer<T: PartialEq, C: DistanceCell>(
+    source: &[T],
+    target: &[T],
+    matrix: &mut DistanceMatrix<C>,
+) -> usize {
+    levenshtein_tabulation_with_buffer(source, target, matrix)
+}
+
+/// Computes and returns the Levenshtein distance between the source and target sequences, along
+/// with the distance matrix, unless the token is cancelled first.
+///
+/// Returns `LevenshteinError::Cancelled` with the number of rows of the matrix that were filled
+/// if the token is cancelled or its deadline passes before the end. Generating edits from the
+/// matrix afterwards takes time proportional to the lengths of the sequences, so it doesn't need
+/// a token of its own.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `token` - The token to check once per row of the matrix
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::CancelToken;
+///
+/// let token = CancelToken::new();
+///
+/// let (distance, _) =
+///     levenshtein::distance_cancellable("FLAW".as_bytes(), "LAWN".as_bytes(), &token).unwrap();
+/// assert_eq!(distance, 2);
+/// ```
+pub fn distance_cancellable<T: PartialEq>(
+    source: &[T],
+    target: &[T],
+    token: &CancelToken,
+) -> Result<(usize, DistanceMatrix), LevenshteinError> {
+    levenshtein_tabulation_cancellable(source, target, token)
+}
+
+/// Computes and returns the Levenshtein distance between the source and target sequences, along
+/// with the distance matrix, reporting progress as the matrix is filled.
+///
+/// The callback is called with the number of rows of the matrix that are filled and the number
+/// there are in all, before every row and once more at the end. Use `generate_edits_with_progress`
+/// to keep reporting progress while generating edits.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `progress` - The callback, called with the rows filled and the total
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let mut percent = 0;
+/// let (distance, _) =
+///     levenshtein::distance_with_progress("FLAW".as_bytes(), "LAWN".as_bytes(), |done, total| {
+///         percent = 100 * done / total
+///     });
+///
+/// assert_eq!(distance, 2);
+/// assert_eq!(percent, 100);
+/// ```
+pub fn distance_with_progress<T: PartialEq, F: FnMut(usize, usize)>(
+    source: &[T],
+    target: &[T],
+    progress: F,
+) -> (usize, DistanceMatrix) {
+    levenshtein_tabulation_with_progress(source, target, progress)
+}
+
+/// Computes and returns only the Levenshtein distance between the source and target sequences.
+///
+/// Prefer this over `distance` when the distance matrix isn't needed: only O(min(m, n)) memory is
+/// used instead of a full (m+1) x (n+1) matrix.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "FLAW";
+/// let s2 = "LAWN";
+///
+/// let distance = levenshtein::distance_only(s1.as_bytes(), s2.as_bytes());
+/// assert_eq!(distance, 2);
+/// ```
+pub fn distance_only<T: PartialEq>(source: &[T], target: &[T]) -> usize {
+    levenshtein_two_rows(source, target)
+}
+
+/// Computes and returns only the Levenshtein distance between the source and target sequences,
+/// using the given buffer for its working storage instead of allocating it.
+///
+/// The buffer's storage is reused, so computing many distances in a loop with the same buffer
+/// only allocates when the sequences get longer than they have been before.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `buffer` - The working storage, e.g. the one from the previous call
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let mut buffer = Vec::new();
+///
+/// for candidate in ["LAWN", "FLOWN", "FLAW"] {
+///     let candidate = candidate.as_bytes();
+///
+///     let distance = levenshtein::distance_only_with_buffer(b"FLAW", candidate, &mut buffer);
+///     assert!(distance <= 2);
+/// }
+/// ```
+pub fn distance_only_with_buffer<T: PartialEq>(
+    source: &[T],
+    target: &[T],
+    buffer: &mut Vec<usize>,
+) -> usize {
+    levenshtein_two_rows_with_buffer(source, target, buffer)
+}
+
+/// Computes and returns only the Levenshtein distance between the source and target sequences,
+/// unless the token is cancelled first. See `distance_only` and `distance_cancellable`.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `token` - The token to check once per row
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::CancelToken;
+///
+/// let token = CancelToken::new();
+///
+/// let distance =
+///     levenshtein::distance_only_cancellable("FLAW".as_bytes(), "LAWN".as_bytes(), &token);
+/// assert_eq!(distance.unwrap(), 2);
+/// ```
+pub fn distance_only_cancellable<T: PartialEq>(
+    source: &[T],
+    target: &[T],
+    token: &CancelToken,
+) -> Result<usize, LevenshteinError> {
+    levenshtein_two_rows_cancellable(source, target, token)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
@@ -48,4 +420,28 @@ mod tests {
 
         assert_eq!(expected_dist, dist);
     }
+
+    #[test]
+    fn distance_as_test() {
+        let s1 = "FLOWER";
+        let s2 = "FOLLOWER";
+
+        let (dist, matrix) = distance_as::<_, u32>(s1.as_bytes(), s2.as_bytes());
+        let edits = generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+
+        assert_eq!(dist, 2);
+        assert_eq!(apply_edits(s1.as_bytes(), &edits), s2.as_bytes());
+    }
+
+    #[test]
+    fn distance_only_test() {
+        let s1 = "FLOWER";
+        let s2 = "FOLLOWER";
+
+        let expected_dist = 2;
+
+        let dist = distance_only(s1.as_bytes(), s2.as_bytes());
+
+        assert_eq!(expected_dist, dist);
+    }
 }
@@ -0,0 +1,554 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::distance::levenshtein_two_rows;
+use crate::edit::Edit;
+use crate::edit_op::EditOp;
+use crate::keyed::stripped_edit_ops;
+use crate::whitespace::Whitespace;
+use crate::words::{diff_words, without_whitespace, TextChange, Tokenizer};
+
+/// A line of a text, along with where it is in the text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Line<'a> {
+    /// The line number, counting from 1
+    pub number: usize,
+    /// The byte offset of the start of the line in the text
+    pub offset: usize,
+    /// The line, without its terminator
+    pub text: &'a str,
+    /// The line terminator: "\n", "\r\n", or "" for a last line that doesn't have one
+    pub ending: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Returns the range of bytes of the text the line covers, including its terminator.
+    pub fn bytes(&self) -> Range<usize> {
+        self.offset..self.offset + self.text.len() + self.ending.len()
+    }
+}
+
+/// How the terminators of lines are compared.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineEndings {
+    /// Lines with different terminators are different, as they are for `diff`.
+    #[default]
+    Exact,
+    /// "\r\n" is the same as "\n", but a last line without a terminator is still different from
+    /// the same line with one.
+    Normalize,
+    /// Terminators are ignored, so only the text of the lines is compared.
+    Ignore,
+}
+
+/// How lines are compared. The default compares them exactly.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{LineEndings, LineOptions, Whitespace};
+///
+/// let options = LineOptions {
+///     endings: LineEndings::Normalize,
+///     whitespace: Whitespace::Collapse,
+/// };
+///
+/// let source = "if x {\n    y();\n}\n";
+/// let target = "if  x {  \r\n\ty();\r\n}\r\n";
+///
+/// assert_eq!(levenshtein::distance_lines(source, target, LineOptions::default()), 3);
+/// assert_eq!(levenshtein::distance_lines(source, target, options), 0);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineOptions {
+    /// How the terminators of lines are compared
+    pub endings: LineEndings,
+    /// How whitespace in the text of lines is compared
+    pub whitespace: Whitespace,
+}
+
+impl LineOptions {
+    // The terminator of a line as it is compared
+    pub(crate) fn ending<'a>(&self, line: &Line<'a>) -> &'a str {
+        match self.endings {
+            LineEndings::Exact => line.ending,
+            LineEndings::Normalize if !line.ending.is_empty() => "\n",
+            LineEndings::Normalize | LineEndings::Ignore => "",
+        }
+    }
+
+    // The part of a line that is compared
+    fn key<'a>(&self, line: &Line<'a>) -> (Cow<'a, str>, &'a str) {
+        (self.whitespace.normalize(line.text), self.ending(line))
+    }
+}
+
+/// Splits a text into lines, keeping their numbers, byte offsets and terminators.
+///
+/// Lines end with "\n" or "\r\n", as they do for `str::lines`. A text that ends with a terminator
+/// doesn't have an empty line after it, and an empty text has no lines.
+///
+/// # Arguments
+///
+/// * `text` - The text to split
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Line;
+///
+/// let lines = levenshtein::split_lines("one\r\ntwo");
+///
+/// assert_eq!(
+///     lines,
+///     vec![
+///         Line { number: 1, offset: 0, text: "one", ending: "\r\n" },
+///         Line { number: 2, offset: 5, text: "two", ending: "" },
+///     ]
+/// );
+/// ```
+pub fn split_lines(text: &str) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+
+    for (idx, line) in text.split_inclusive('\n').enumerate() {
+        let content = line
+            .strip_suffix("\r\n")
+            .or_else(|| line.strip_suffix('\n'))
+            .unwrap_or(line);
+
+        lines.push(Line {
+            number: idx + 1,
+            offset,
+            text: content,
+            ending: &line[content.len()..],
+        });
+
+        offset += line.len();
+    }
+
+    lines
+}
+
+/// A change to a single line, along with the lines it involves in the source and the target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum LineEdit<'a> {
+    /// A line of the source is deleted.
+    Delete { source: Line<'a> },
+    /// A line of the target is inserted after the first `at` lines of the source, which is at
+    /// byte `offset` of the source.
+    Insert {
+        at: usize,
+        offset: usize,
+        target: Line<'a>,
+    },
+    /// A line of the source is replaced with a line of the target.
+    Substitute { source: Line<'a>, target: Line<'a> },
+}
+
+impl<'a> LineEdit<'a> {
+    /// Returns the equivalent `Edit` of the lines of the source, with the text of the new line
+    /// without its terminator, as `unified_diff` and the other line formats expect.
+    pub fn to_edit(&self) -> Edit<&'a str> {
+        match *self {
+            LineEdit::Delete { source } => Edit::Delete(source.number),
+            LineEdit::Insert { at, target, .. } => Edit::Insert(at, target.text),
+            LineEdit::Substitute { source, target } => Edit::Substitute(source.number, target.text),
+        }
+    }
+}
+
+/// Returns the Levenshtein distance between two texts, counting lines. See `split_lines`.
+///
+/// # Arguments
+///
+/// * `source` - The source text
+/// * `target` - The target text
+/// * `options` - How the lines are compared
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{LineEndings, LineOptions};
+///
+/// let source = "one\ntwo\nthree\n";
+/// let target = "one\r\n2\r\nthree\r\n";
+///
+/// let normalized = LineOptions { endings: LineEndings::Normalize, ..Default::default() };
+///
+/// assert_eq!(levenshtein::distance_lines(source, target, LineOptions::default()), 3);
+/// assert_eq!(levenshtein::distance_lines(source, target, normalized), 1);
+/// ```
+pub fn distance_lines(source: &str, target: &str, options: LineOptions) -> usize {
+    let keys = |text| -> Vec<(Cow<str>, &str)> {
+        split_lines(text)
+            .iter()
+            .map(|line| options.key(line))
+            .collect()
+    };
+
+    levenshtein_two_rows(&keys(source), &keys(target))
+}
+
+/// Generate the line edits that transform the source text into the target text. See
+/// `split_lines`.
+///
+/// The edits are the ones `generate_edits` finds for the lines once their common prefix and
+/// suffix are stripped, in the same order, but each of them carries the lines it involves, with
+/// their line numbers and byte offsets in the source and the target.
+///
+/// # Arguments
+///
+/// * `source` - The source text
+/// * `target` - The target text
+/// * `options` - How the lines are compared
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{Line, LineEdit, LineEndings, LineOptions};
+///
+/// let source = "fn main() {\n    run();\n}\n";
+/// let target = "fn main() {\r\n    init();\r\n    run();\r\n}\r\n";
+///
+/// let options = LineOptions { endings: LineEndings::Normalize, ..Default::default() };
+/// let edits = levenshtein::diff_lines(source, target, options);
+/// assert_eq!(
+///     edits,
+///     vec![LineEdit::Insert {
+///         at: 1,
+///         offset: 12,
+///         target: Line { number: 2, offset: 13, text: "    init();", ending: "\r\n" },
+///     }]
+/// );
+/// ```
+pub fn diff_lines<'a>(source: &'a str, target: &'a str, options: LineOptions) -> Vec<LineEdit<'a>> {
+    line_edits(source, target, |line| options.key(line))
+}
+
+// The line edits between two texts, comparing the keys of their lines
+pub(crate) fn line_edits<'a, K, F>(source: &'a str, target: &'a str, key: F) -> Vec<LineEdit<'a>>
+where
+    K: PartialEq,
+    F: Fn(&Line<'a>) -> K,
+{
+    let source_lines = split_lines(source);
+    let target_lines = split_lines(target);
+
+    let source_keys: Vec<K> = source_lines.iter().map(&key).collect();
+    let target_keys: Vec<K> = target_lines.iter().map(&key).collect();
+
+    stripped_edit_ops(&source_keys, &target_keys)
+        .into_iter()
+        .map(|op| match op {
+            EditOp::Delete(idx) => LineEdit::Delete {
+                source: source_lines[idx - 1],
+            },
+            EditOp::Insert(idx, target_idx) => LineEdit::Insert {
+                at: idx,
+                offset: source_lines
+                    .get(idx)
+                    .map_or(source.len(), |line| line.offset),
+                target: target_lines[target_idx],
+            },
+            EditOp::Substitute(idx, target_idx) => LineEdit::Substitute {
+                source: source_lines[idx - 1],
+                target: target_lines[target_idx],
+            },
+        })
+        .collect()
+}
+
+/// A line edit, along with the changes within the line for a substitution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RefinedLineEdit<'a> {
+    pub edit: LineEdit<'a>,
+    /// The changes that turn the text of the source line into the text of the target line, as
+    /// byte ranges of the texts of the lines, without their terminators. Empty unless `edit` is a
+    /// substitution.
+    pub changes: Vec<TextChange>,
+}
+
+/// Generate the line edits that transform the source text into the target text, and the changes
+/// within every line that is substituted. See `diff_lines` and `diff_words`.
+///
+/// The line diff pairs up lines that were changed rather than added or removed, and the tokens of
+/// each pair are diffed to show what changed inside it, like `git diff --word-diff` does. With
+/// `WordTokenizer` this highlights the changed words, and with `CharTokenizer` the changed
+/// characters. Unless `options` compares whitespace exactly, tokens that are only whitespace are
+/// left out, as `SkipWhitespace` does.
+///
+/// # Arguments
+///
+/// * `source` - The source text
+/// * `target` - The target text
+/// * `options` - How the lines are compared
+/// * `tokenizer` - Splits the substituted lines into tokens
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{LineEdit, LineOptions, TextChange, WordTokenizer};
+///
+/// let source = "let x = 1;\nprint(x);\n";
+/// let target = "let y = 1;\nprint(x);\nexit();\n";
+///
+/// let options = LineOptions::default();
+/// let edits = levenshtein::diff_lines_refined(source, target, options, &WordTokenizer);
+/// assert_eq!(edits.len(), 2);
+///
+/// // The new last line is inserted whole
+/// assert!(matches!(edits[0].edit, LineEdit::Insert { .. }));
+/// assert!(edits[0].changes.is_empty());
+///
+/// // Only the x changed in the first line
+/// assert!(matches!(edits[1].edit, LineEdit::Substitute { .. }));
+/// assert_eq!(edits[1].changes, vec![TextChange { source: 4..5, target: 4..5 }]);
+/// ```
+pub fn diff_lines_refined<'a, K: Tokenizer + ?Sized>(
+    source: &'a str,
+    target: &'a str,
+    options: LineOptions,
+    tokenizer: &K,
+) -> Vec<RefinedLineEdit<'a>> {
+    let skipping = |text: &str| without_whitespace(text, tokenizer.tokenize(text));
+
+    diff_lines(source, target, options)
+        .into_iter()
+        .map(|edit| {
+            let changes = match edit {
+                LineEdit::Substitute { source, target }
+                    if options.whitespace == Whitespace::Exact =>
+                {
+                    diff_words(source.text, target.text, tokenizer)
+                }
+                LineEdit::Substitute { source, target } => {
+                    diff_words(source.text, target.text, &skipping)
+                }
+                _ => Vec::new(),
+            };
+
+            RefinedLineEdit { edit, changes }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lines::*;
+
+    fn with_endings(endings: LineEndings) -> LineOptions {
+        LineOptions {
+            endings,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn split_lines_test() {
+        assert!(split_lines("").is_empty());
+
+        let lines = split_lines("a\n\r\nb\r\n\n");
+        let texts: Vec<&str> = lines.iter().map(|line| line.text).collect();
+        let endings: Vec<&str> = lines.iter().map(|line| line.ending).collect();
+
+        assert_eq!(texts, vec!["a", "", "b", ""]);
+        assert_eq!(endings, vec!["\n", "\r\n", "\r\n", "\n"]);
+        assert_eq!(lines[2].bytes(), 4..7);
+        assert_eq!(lines[3].number, 4);
+
+        // A lone carriage return isn't a terminator
+        assert_eq!(split_lines("a\rb").len(), 1);
+    }
+
+    #[test]
+    fn line_endings_test() {
+        let source = "a\nb\nc";
+        let target = "a\r\nb\r\nc\n";
+
+        assert_eq!(
+            distance_lines(source, target, with_endings(LineEndings::Exact)),
+            3
+        );
+        assert_eq!(
+            distance_lines(source, target, with_endings(LineEndings::Normalize)),
+            1
+        );
+        assert_eq!(
+            distance_lines(source, target, with_endings(LineEndings::Ignore)),
+            0
+        );
+
+        let edits = diff_lines(source, target, with_endings(LineEndings::Normalize));
+        assert_eq!(
+            edits,
+            vec![LineEdit::Substitute {
+                source: Line {
+                    number: 3,
+                    offset: 4,
+                    text: "c",
+                    ending: ""
+                },
+                target: Line {
+                    number: 3,
+                    offset: 6,
+                    text: "c",
+                    ending: "\n"
+                },
+            }]
+        );
+        assert!(diff_lines(source, target, with_endings(LineEndings::Ignore)).is_empty());
+    }
+
+    #[test]
+    fn diff_lines_test() {
+        let source = "one\ntwo\nthree\nfour\n";
+        let target = "zero\none\n2\nthree\n";
+
+        let edits = diff_lines(source, target, with_endings(LineEndings::Exact));
+        assert_eq!(
+            edits.len(),
+            distance_lines(source, target, with_endings(LineEndings::Exact))
+        );
+
+        let source_lines: Vec<&str> = source.lines().collect();
+        let target_lines: Vec<&str> = target.lines().collect();
+        let edits: Vec<Edit<&str>> = edits.iter().map(LineEdit::to_edit).collect();
+
+        assert_eq!(crate::apply_edits(&source_lines, &edits), target_lines);
+
+        // Insertions at the end of the source are at its length
+        let edits = diff_lines("a\n", "a\nb\n", LineOptions::default());
+        assert!(matches!(
+            edits[..],
+            [LineEdit::Insert {
+                at: 1,
+                offset: 2,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn diff_lines_refined_test() {
+        use crate::words::{apply_text_changes, CharTokenizer, WordTokenizer};
+
+        let source = "The cat sat.\nOn the mat.\nThe end.\n";
+        let target = "The dog sat.\nOn the mat.\nThe end!\nBye.\n";
+
+        let edits = diff_lines_refined(source, target, LineOptions::default(), &WordTokenizer);
+        assert_eq!(edits.len(), 3);
+
+        for refined in &edits {
+            match refined.edit {
+                LineEdit::Substitute { source, target } => {
+                    assert!(!refined.changes.is_empty());
+                    assert_eq!(
+                        apply_text_changes(source.text, target.text, &refined.changes),
+                        target.text
+                    );
+                }
+                _ => assert!(refined.changes.is_empty()),
+            }
+        }
+
+        // The last substitution is the first line, where a whole word changed
+        assert_eq!(
+            edits[2].changes,
+            vec![TextChange {
+                source: 4..7,
+                target: 4..7
+            }]
+        );
+
+        // By characters, only the changed punctuation of the third line is
+        let edits = diff_lines_refined(source, target, LineOptions::default(), &CharTokenizer);
+        assert_eq!(
+            edits[1].changes,
+            vec![TextChange {
+                source: 7..8,
+                target: 7..8
+            }]
+        );
+
+        // A line whose text is the same but whose terminator isn't has no changes
+        let edits = diff_lines_refined("a\n", "a\r\n", LineOptions::default(), &WordTokenizer);
+        assert_eq!(edits.len(), 1);
+        assert!(edits[0].changes.is_empty());
+    }
+
+    #[test]
+    fn whitespace_test() {
+        let source = "fn f() {\n    x = 1;\n}\n";
+        let target = "fn f()  {\n\tx = 2; \n}\n";
+
+        let options = |whitespace| LineOptions {
+            whitespace,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            distance_lines(source, target, options(Whitespace::Exact)),
+            2
+        );
+        assert_eq!(
+            distance_lines(source, target, options(Whitespace::IgnoreTrailing)),
+            2
+        );
+        assert_eq!(
+            distance_lines(source, target, options(Whitespace::Collapse)),
+            1
+        );
+        assert_eq!(
+            distance_lines(source, target, options(Whitespace::IgnoreAll)),
+            1
+        );
+        assert_eq!(
+            distance_lines("a b\n", "ab\n", options(Whitespace::Collapse)),
+            1
+        );
+        assert_eq!(
+            distance_lines("a b\n", "ab\n", options(Whitespace::IgnoreAll)),
+            0
+        );
+
+        // The edits hold the target lines as they are
+        let edits = diff_lines(source, target, options(Whitespace::Collapse));
+        assert!(matches!(
+            edits[..],
+            [LineEdit::Substitute {
+                target: Line {
+                    text: "\tx = 2; ",
+                    ..
+                },
+                ..
+            }]
+        ));
+
+        // Changes within the line leave out the whitespace too
+        let refined = diff_lines_refined(
+            source,
+            target,
+            options(Whitespace::Collapse),
+            &crate::words::WordTokenizer,
+        );
+        assert_eq!(
+            refined[0].changes,
+            vec![TextChange {
+                source: 8..9,
+                target: 5..6
+            }]
+        );
+    }
+}
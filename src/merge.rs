@@ -0,0 +1,360 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::{max, min};
+use core::ops::Range;
+
+use crate::edit::generate_edits;
+use crate::replace::{coalesce_edits, Replace};
+use crate::unified::write_lines;
+
+/// A part of the result of a three-way merge.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MergeRegion<T> {
+    /// Items that were merged cleanly: unchanged, changed on one side only, or changed the same
+    /// way on both sides.
+    Resolved(Vec<T>),
+    /// A part of the base that both sides changed differently, with what each side turned it into.
+    Conflict {
+        base: Vec<T>,
+        ours: Vec<T>,
+        theirs: Vec<T>,
+    },
+}
+
+// Returns the changes that transform the base into a side, as replacements of ranges of the base
+fn changes<T: Clone + PartialEq>(base: &[T], side: &[T]) -> Vec<Replace<T>> {
+    let (_, matrix) = crate::distance(base, side);
+    let edits = generate_edits(base, side, &matrix).expect("the matrix was computed for these");
+
+    coalesce_edits(&edits)
+}
+
+// Returns what the changes turn a range of the base into. The changes must lie within the range.
+fn replaced<T: Clone>(base: &[T], range: Range<usize>, changes: &[Replace<T>]) -> Vec<T> {
+    let mut items = Vec::new();
+    let mut copied_up_to = range.start;
+
+    for change in changes {
+        items.extend_from_slice(&base[copied_up_to..change.range.start]);
+        items.extend_from_slice(&change.with);
+        copied_up_to = change.range.end;
+    }
+
+    items.extend_from_slice(&base[copied_up_to..range.end]);
+
+    items
+}
+
+/// Merges two sequences that were both derived from a common base, and returns the merged
+/// sequence as a list of regions that either merged cleanly or conflict.
+///
+/// Both sides are diffed against the base. Changes that only one side made are taken from that
+/// side, and changes that both sides made identically are taken once. Changes from both sides
+/// that overlap or touch, i.e. aren't separated by at least one unchanged item of the base, are a
+/// conflict, since there is no telling which should win or in what order they go.
+///
+/// # Arguments
+///
+/// * `base` - The sequence both sides were derived from
+/// * `ours` - One side
+/// * `theirs` - The other side
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::MergeRegion;
+///
+/// let base = vec!["one", "two", "three", "four"];
+/// let ours = vec!["one", "2", "three", "four"];
+/// let theirs = vec!["one", "deux", "three", "4"];
+///
+/// let regions = levenshtein::merge_regions(&base, &ours, &theirs);
+///
+/// assert_eq!(
+///     regions,
+///     vec![
+///         MergeRegion::Resolved(vec!["one"]),
+///         MergeRegion::Conflict { base: vec!["two"], ours: vec!["2"], theirs: vec!["deux"] },
+///         MergeRegion::Resolved(vec!["three", "4"]),
+///     ]
+/// );
+/// ```
+pub fn merge_regions<T: Clone + PartialEq>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+) -> Vec<MergeRegion<T>> {
+    let ours = changes(base, ours);
+    let theirs = changes(base, theirs);
+
+    let mut regions = Vec::new();
+    let mut resolved = Vec::new();
+    let mut copied_up_to = 0;
+
+    let mut i = 0;
+    let mut j = 0;
+
+    while i < ours.len() || j < theirs.len() {
+        let start = min(
+            ours.get(i).map_or(usize::MAX, |change| change.range.start),
+            theirs
+                .get(j)
+                .map_or(usize::MAX, |change| change.range.start),
+        );
+        let mut end = start;
+
+        // Gather the changes of both sides that overlap or touch the ones gathered so far
+        let (first_ours, first_theirs) = (i, j);
+
+        loop {
+            if i < ours.len() && ours[i].range.start <= end {
+                end = max(end, ours[i].range.end);
+                i += 1;
+            } else if j < theirs.len() && theirs[j].range.start <= end {
+                end = max(end, theirs[j].range.end);
+                j += 1;
+            } else {
+                break;
+            }
+        }
+
+        resolved.extend_from_slice(&base[copied_up_to..start]);
+        copied_up_to = end;
+
+        let ours_items = replaced(base, start..end, &ours[first_ours..i]);
+        let theirs_items = replaced(base, start..end, &theirs[first_theirs..j]);
+
+        if first_theirs == j || ours_items == theirs_items {
+            resolved.extend(ours_items);
+        } else if first_ours == i {
+            resolved.extend(theirs_items);
+        } else {
+            if !resolved.is_empty() {
+                regions.push(MergeRegion::Resolved(core::mem::take(&mut resolved)));
+            }
+
+            regions.push(MergeRegion::Conflict {
+                base: base[start..end].to_vec(),
+                ours: ours_items,
+                theirs: theirs_items,
+            });
+        }
+    }
+
+    resolved.extend_from_slice(&base[copied_up_to..]);
+
+    if !resolved.is_empty() {
+        regions.push(MergeRegion::Resolved(resolved));
+    }
+
+    regions
+}
+
+/// Merges two sequences that were both derived from a common base. See `merge_regions`.
+///
+/// Returns the merged sequence if there are no conflicts, and all the regions of the merge
+/// otherwise, so the conflicts can be resolved or shown with `conflict_markers`.
+///
+/// # Arguments
+///
+/// * `base` - The sequence both sides were derived from
+/// * `ours` - One side
+/// * `theirs` - The other side
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let base = "SATURDAY".as_bytes();
+/// let ours = "SUNDAY".as_bytes();
+/// let theirs = "SATURDAYS".as_bytes();
+///
+/// let merged = levenshtein::merge(base, ours, theirs).unwrap();
+/// assert_eq!(merged, "SUNDAYS".as_bytes());
+///
+/// // Both sides change the R
+/// let theirs = "SATUKDAY".as_bytes();
+/// assert!(levenshtein::merge(base, ours, theirs).is_err());
+/// ```
+pub fn merge<T: Clone + PartialEq>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+) -> Result<Vec<T>, Vec<MergeRegion<T>>> {
+    let regions = merge_regions(base, ours, theirs);
+
+    if regions
+        .iter()
+        .any(|region| matches!(region, MergeRegion::Conflict { .. }))
+    {
+        return Err(regions);
+    }
+
+    Ok(regions
+        .into_iter()
+        .flat_map(|region| match region {
+            MergeRegion::Resolved(items) => items,
+            MergeRegion::Conflict { .. } => unreachable!(),
+        })
+        .collect())
+}
+
+/// Returns the merged text of a three-way merge of lines, with every conflict written between
+/// conflict markers as `git merge` does.
+///
+/// The lines must not include their line terminators, since every line is written followed by a
+/// newline.
+///
+/// # Arguments
+///
+/// * `regions` - The regions of the merge, as returned by `merge_regions`
+/// * `ours_label` - The name of our side, for the `<<<<<<<` marker
+/// * `theirs_label` - The name of their side, for the `>>>>>>>` marker
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let base = vec!["one", "two", "three"];
+/// let ours = vec!["one", "2", "three"];
+/// let theirs = vec!["one", "deux", "three"];
+///
+/// let regions = levenshtein::merge_regions(&base, &ours, &theirs);
+/// let text = levenshtein::conflict_markers(&regions, "ours", "theirs");
+///
+/// assert_eq!(text, "one\n<<<<<<< ours\n2\n=======\ndeux\n>>>>>>> theirs\nthree\n");
+/// ```
+pub fn conflict_markers<S: AsRef<str>>(
+    regions: &[MergeRegion<S>],
+    ours_label: &str,
+    theirs_label: &str,
+) -> String {
+    write_merge(regions, ours_label, None, theirs_label)
+}
+
+/// Returns the merged text of a three-way merge of lines, with every conflict written between
+/// conflict markers along with the base lines both sides changed, as `diff3 -m` and
+/// `git merge-file --diff3` do.
+///
+/// The lines must not include their line terminators, since every line is written followed by a
+/// newline.
+///
+/// # Arguments
+///
+/// * `regions` - The regions of the merge, as returned by `merge_regions`
+/// * `ours_label` - The name of our side, for the `<<<<<<<` marker
+/// * `base_label` - The name of the base, for the `|||||||` marker
+/// * `theirs_label` - The name of their side, for the `>>>>>>>` marker
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let base = vec!["one", "two", "three"];
+/// let ours = vec!["one", "2", "three"];
+/// let theirs = vec!["one", "deux", "three"];
+///
+/// let regions = levenshtein::merge_regions(&base, &ours, &theirs);
+/// let text = levenshtein::diff3_conflict_markers(&regions, "ours", "base", "theirs");
+///
+/// assert_eq!(
+///     text,
+///     "one\n<<<<<<< ours\n2\n||||||| base\ntwo\n=======\ndeux\n>>>>>>> theirs\nthree\n"
+/// );
+/// ```
+pub fn diff3_conflict_markers<S: AsRef<str>>(
+    regions: &[MergeRegion<S>],
+    ours_label: &str,
+    base_label: &str,
+    theirs_label: &str,
+) -> String {
+    write_merge(regions, ours_label, Some(base_label), theirs_label)
+}
+
+// Writes the merged lines with conflict markers, including the base lines of conflicts if there
+// is a base label
+fn write_merge<S: AsRef<str>>(
+    regions: &[MergeRegion<S>],
+    ours_label: &str,
+    base_label: Option<&str>,
+    theirs_label: &str,
+) -> String {
+    let mut out = String::new();
+
+    for region in regions {
+        match region {
+            MergeRegion::Resolved(lines) => write_lines(&mut out, "", lines),
+            MergeRegion::Conflict { base, ours, theirs } => {
+                out.push_str(&format!("<<<<<<< {}\n", ours_label));
+                write_lines(&mut out, "", ours);
+
+                if let Some(base_label) = base_label {
+                    out.push_str(&format!("||||||| {}\n", base_label));
+                    write_lines(&mut out, "", base);
+                }
+
+                out.push_str("=======\n");
+                write_lines(&mut out, "", theirs);
+                out.push_str(&format!(">>>>>>> {}\n", theirs_label));
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::merge::*;
+
+    #[test]
+    fn merge_test() {
+        let base = "the quick brown fox".as_bytes();
+
+        // Changes at both ends merge cleanly
+        let ours = "a quick brown fox".as_bytes();
+        let theirs = "the quick brown cat".as_bytes();
+        assert_eq!(
+            merge(base, ours, theirs).unwrap(),
+            "a quick brown cat".as_bytes()
+        );
+
+        // The same change on both sides is taken once
+        assert_eq!(merge(base, ours, ours).unwrap(), ours);
+
+        // Inserts at the same place conflict
+        let ours = "the quick brown fox!".as_bytes();
+        let theirs = "the quick brown fox?".as_bytes();
+        assert_eq!(
+            merge(base, ours, theirs).unwrap_err(),
+            vec![
+                MergeRegion::Resolved(base.to_vec()),
+                MergeRegion::Conflict {
+                    base: vec![],
+                    ours: vec![b'!'],
+                    theirs: vec![b'?'],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff3_conflict_markers_test() {
+        let base = vec!["a", "b", "c", "d", "e"];
+        let ours = vec!["a", "B", "c", "d", "E"];
+        let theirs = vec!["a", "X", "c", "d", "e", "f"];
+
+        // The same as `diff3 -m`: changing the last line and appending after it conflict too
+        let regions = merge_regions(&base, &ours, &theirs);
+        assert_eq!(
+            diff3_conflict_markers(&regions, "ours", "base", "theirs"),
+            "a\n<<<<<<< ours\nB\n||||||| base\nb\n=======\nX\n>>>>>>> theirs\nc\nd\n\
+             <<<<<<< ours\nE\n||||||| base\ne\n=======\ne\nf\n>>>>>>> theirs\n"
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use crate::distance::bounded_distance;
+
+fn hash_of<H: Hash + ?Sized>(value: &H) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+// The finalizer of splitmix64, which turns a hash and a seed into what is in effect the hash of a
+// different function of the family
+fn mix(hash: u64, seed: u64) -> u64 {
+    let mut z = hash ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Returns the MinHash signature of the n-grams of a sequence.
+///
+/// Each of the `num_hashes` values is the smallest hash of any n-gram of the sequence, under a
+/// different hash function. The fraction of values two signatures have in common estimates the
+/// Jaccard similarity of the sets of n-grams of their sequences, so similar sequences have
+/// similar signatures, whatever their lengths. A sequence shorter than `n` is a single n-gram.
+///
+/// # Arguments
+///
+/// * `items` - The sequence
+/// * `n` - The length of the n-grams
+/// * `num_hashes` - The length of the signature
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let a = levenshtein::minhash_signature("the quick brown fox".as_bytes(), 3, 64);
+/// let b = levenshtein::minhash_signature("the quick brown fax".as_bytes(), 3, 64);
+/// let c = levenshtein::minhash_signature("lorem ipsum dolor".as_bytes(), 3, 64);
+///
+/// let common = |x: &[u64], y: &[u64]| x.iter().zip(y).filter(|(p, q)| p == q).count();
+/// assert!(common(&a, &b) > common(&a, &c));
+/// ```
+pub fn minhash_signature<T: Hash>(items: &[T], n: usize, num_hashes: usize) -> Vec<u64> {
+    let n = std::cmp::max(n, 1);
+    let grams: HashSet<u64> = if items.len() < n {
+        std::iter::once(hash_of(items)).collect()
+    } else {
+        items.windows(n).map(hash_of).collect()
+    };
+
+    (0..num_hashes as u64)
+        .map(|seed| {
+            grams
+                .iter()
+                .map(|&gram| mix(gram, seed))
+                .min()
+                .unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+/// Returns the pairs of signatures that are likely to be similar, with locality-sensitive
+/// hashing.
+///
+/// Every signature is cut into `bands` bands of the same length, and two signatures are a
+/// candidate pair if they are the same in at least one band. With r values per band, signatures
+/// that agree on a fraction s of their values end up a candidate pair with probability
+/// 1 - (1 - s^r)^bands, which rises steeply around (1 / bands)^(1 / r): more bands find less
+/// similar pairs, and longer bands fewer false candidates. Only the signatures that share a
+/// bucket are paired, so this takes time proportional to the number of signatures and
+/// candidates, instead of the number of pairs.
+///
+/// The pairs are returned as `(i, j)` with `i < j`, in ascending order.
+///
+/// # Arguments
+///
+/// * `signatures` - The signatures, all of the same length, as returned by `minhash_signature`
+/// * `bands` - The number of bands
+///
+/// # Panics
+///
+/// Panics if the signatures can't be cut into `bands` bands of the same length.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let documents = ["the quick brown fox", "lorem ipsum dolor", "the quick brown fax"];
+/// let signatures: Vec<Vec<u64>> = documents
+///     .iter()
+///     .map(|document| levenshtein::minhash_signature(document.as_bytes(), 3, 64))
+///     .collect();
+///
+/// assert_eq!(levenshtein::lsh_candidate_pairs(&signatures, 32), vec![(0, 2)]);
+/// ```
+pub fn lsh_candidate_pairs(signatures: &[Vec<u64>], bands: usize) -> Vec<(usize, usize)> {
+    let len = signatures.first().map_or(0, |signature| signature.len());
+    assert!(
+        bands > 0 && len % bands == 0 && signatures.iter().all(|s| s.len() == len),
+        "the signatures must all be cut into {} bands of the same length",
+        bands
+    );
+
+    let rows = len / bands;
+    let mut pairs = HashSet::new();
+
+    for band in 0..bands {
+        let mut buckets = HashMap::<u64, Vec<usize>>::new();
+
+        for (i, signature) in signatures.iter().enumerate() {
+            let key = hash_of(&signature[band * rows..(band + 1) * rows]);
+            buckets.entry(key).or_default().push(i);
+        }
+
+        for bucket in buckets.values() {
+            for (a, &i) in bucket.iter().enumerate() {
+                pairs.extend(bucket[a + 1..].iter().map(|&j| (i, j)));
+            }
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = pairs.into_iter().collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Returns the pairs of sequences within distance `k` of each other that MinHash and LSH find,
+/// with their distances.
+///
+/// The sequences are given signatures of `bands * rows` values with `minhash_signature`, and the
+/// candidate pairs from `lsh_candidate_pairs` have their exact distance checked. This finds
+/// near duplicates in a large corpus without comparing every pair, but it's probabilistic: a
+/// pair within distance k can be missed if the edits change too many of its n-grams, so `k`
+/// should be small compared to the lengths of the sequences, and more bands of fewer rows find
+/// more of them.
+///
+/// The pairs are returned as `(i, j, distance)` with `i < j`, in ascending order.
+///
+/// # Arguments
+///
+/// * `items` - The sequences
+/// * `n` - The length of the n-grams
+/// * `bands` - The number of bands
+/// * `rows` - The number of values in a band
+/// * `k` - The maximum distance
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let documents = [
+///     "the quick brown fox".as_bytes(),
+///     "lorem ipsum dolor".as_bytes(),
+///     "the quick brown fax".as_bytes(),
+///     "the quack brown fax".as_bytes(),
+/// ];
+///
+/// let pairs = levenshtein::near_duplicates(&documents, 3, 32, 2, 1);
+/// assert_eq!(pairs, vec![(0, 2, 1), (2, 3, 1)]);
+/// ```
+pub fn near_duplicates<T: Hash + PartialEq>(
+    items: &[&[T]],
+    n: usize,
+    bands: usize,
+    rows: usize,
+    k: usize,
+) -> Vec<(usize, usize, usize)> {
+    let signatures: Vec<Vec<u64>> = items
+        .iter()
+        .map(|item| minhash_signature(item, n, bands * rows))
+        .collect();
+
+    lsh_candidate_pairs(&signatures, bands)
+        .into_iter()
+        .filter_map(|(i, j)| bounded_distance(items[i], items[j], k).map(|d| (i, j, d)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::minhash::*;
+
+    #[test]
+    fn minhash_signature_test() {
+        // Only the set of n-grams matters
+        assert_eq!(
+            minhash_signature("abab".as_bytes(), 2, 16),
+            minhash_signature("bab".as_bytes(), 2, 16)
+        );
+
+        // Short sequences are a single n-gram
+        assert_ne!(
+            minhash_signature("ab".as_bytes(), 3, 16),
+            minhash_signature("ba".as_bytes(), 3, 16)
+        );
+
+        assert_eq!(minhash_signature::<u8>(&[], 3, 4).len(), 4);
+    }
+
+    #[test]
+    fn lsh_candidate_pairs_test() {
+        let signatures = vec![
+            vec![1, 2, 3, 4],
+            vec![1, 2, 5, 6],
+            vec![7, 8, 3, 4],
+            vec![0; 4],
+        ];
+
+        assert_eq!(lsh_candidate_pairs(&signatures, 2), vec![(0, 1), (0, 2)]);
+        assert_eq!(lsh_candidate_pairs(&signatures, 1), vec![]);
+        assert!(lsh_candidate_pairs(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn near_duplicates_test() {
+        let lines = [
+            "fn main() { println!(\"hello\"); }",
+            "fn main() { println!(\"hallo\"); }",
+            "struct Point { x: i32, y: i32 }",
+            "fn main() { println!(\"hello\") }",
+        ];
+        let items: Vec<&[u8]> = lines.iter().map(|line| line.as_bytes()).collect();
+
+        // The two changed lines are 2 apart, and the struct is nothing like the others
+        assert_eq!(
+            near_duplicates(&items, 4, 20, 2, 2),
+            vec![(0, 1, 1), (0, 3, 1), (1, 3, 2)]
+        );
+    }
+}
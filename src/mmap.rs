@@ -0,0 +1,193 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::cdc::chunked_edits;
+use crate::edit::{generate_edits_greedy, Edit};
+
+// Maps the whole file into memory, read-only.
+//
+// Safety: the caller must make sure the file isn't modified or truncated while the map is alive
+unsafe fn map_file(path: &Path) -> io::Result<Mmap> {
+    let file = File::open(path)?;
+
+    Mmap::map(&file)
+}
+
+/// Returns edits that transform the source file into the target file, compared byte by byte.
+///
+/// Both files are memory-mapped rather than read, so only the pages the comparison touches are
+/// loaded, and the operating system can drop them again under memory pressure. The edits are
+/// found with `generate_edits_greedy`, which never builds a distance matrix: it takes
+/// O((m + n) * d) time and O(d^2) memory, where d is the number of differing bytes. That suits
+/// large files with a few thousand changed bytes, like two snapshots of a database that only
+/// differ in a few records, but not files that differ a lot, which need `diff_files_chunked`.
+///
+/// Returns an error if either file can't be opened or mapped.
+///
+/// # Arguments
+///
+/// * `source` - The path of the source file
+/// * `target` - The path of the target file
+///
+/// # Safety
+///
+/// The files must not be modified, or truncated, by this or another process until the function
+/// returns. Bytes that change under the map make the edits meaningless, and reading the map of a
+/// file that was truncated is undefined behavior, which usually crashes the process with a bus
+/// error.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let dir = std::env::temp_dir();
+/// let source = dir.join(format!("levenshtein_doc_{}_source", std::process::id()));
+/// let target = dir.join(format!("levenshtein_doc_{}_target", std::process::id()));
+/// std::fs::write(&source, "SATURDAY").unwrap();
+/// std::fs::write(&target, "SUNDAY").unwrap();
+///
+/// // Nothing else touches the files while they're compared
+/// let edits = unsafe { levenshtein::diff_files(&source, &target) }.unwrap();
+///
+/// assert_eq!(edits.len(), 3);
+/// assert_eq!(levenshtein::apply_edits(b"SATURDAY", &edits), b"SUNDAY");
+///
+/// std::fs::remove_file(&source).unwrap();
+/// std::fs::remove_file(&target).unwrap();
+/// ```
+pub unsafe fn diff_files<P: AsRef<Path>, Q: AsRef<Path>>(
+    source: P,
+    target: Q,
+) -> io::Result<Vec<Edit<u8>>> {
+    let source = map_file(source.as_ref())?;
+    let target = map_file(target.as_ref())?;
+
+    Ok(generate_edits_greedy(&source, &target))
+}
+
+/// Returns edits that transform the source file into the target file, found by comparing
+/// content-defined chunks first and only the bytes of the chunks that changed.
+///
+/// This is `chunked_edits` over memory-mapped files, so neither file is read into memory as a
+/// whole. Unlike `diff_files`, the bytes are only compared within the runs of chunks that
+/// changed, which keeps it fast and its memory small when the files differ a lot, at the cost of
+/// edits that are not always the fewest possible.
+///
+/// Returns an error if either file can't be opened or mapped.
+///
+/// # Arguments
+///
+/// * `source` - The path of the source file
+/// * `target` - The path of the target file
+/// * `avg_size` - The average size of the chunks of the first pass. See `cdc_chunks`.
+///
+/// # Safety
+///
+/// The files must not be modified, or truncated, until the function returns, as for
+/// `diff_files`.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let source: Vec<u8> = (0..200_000u32)
+///     .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+///     .collect();
+///
+/// let mut target = source.clone();
+/// target.splice(150_000..150_010, "CHANGED".bytes());
+///
+/// let dir = std::env::temp_dir();
+/// let source_path = dir.join(format!("levenshtein_doc_{}_large", std::process::id()));
+/// let target_path = dir.join(format!("levenshtein_doc_{}_changed", std::process::id()));
+/// std::fs::write(&source_path, &source).unwrap();
+/// std::fs::write(&target_path, &target).unwrap();
+///
+/// // Nothing else touches the files while they're compared
+/// let edits =
+///     unsafe { levenshtein::diff_files_chunked(&source_path, &target_path, 4096) }.unwrap();
+///
+/// assert_eq!(levenshtein::apply_edits(&source, &edits), target);
+///
+/// std::fs::remove_file(&source_path).unwrap();
+/// std::fs::remove_file(&target_path).unwrap();
+/// ```
+pub unsafe fn diff_files_chunked<P: AsRef<Path>, Q: AsRef<Path>>(
+    source: P,
+    target: Q,
+    avg_size: usize,
+) -> io::Result<Vec<Edit<u8>>> {
+    let source = map_file(source.as_ref())?;
+    let target = map_file(target.as_ref())?;
+
+    Ok(chunked_edits(&source, &target, avg_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mmap::*;
+    use std::path::PathBuf;
+
+    // A file in the temporary directory that is removed again when the test is done with it
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &[u8]) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "levenshtein_mmap_{}_{}",
+                std::process::id(),
+                name
+            ));
+            std::fs::write(&path, contents).unwrap();
+
+            TempFile(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn diff_files_test() {
+        let source: Vec<u8> = (0..50_000u32).map(|i| (i * 7 % 251) as u8).collect();
+
+        let mut target = source.clone();
+        target.splice(20_000..20_005, "CHANGED".bytes());
+        target.drain(20_100..20_110);
+
+        let source_file = TempFile::new("diff_source", &source);
+        let target_file = TempFile::new("diff_target", &target);
+
+        let edits = unsafe { diff_files(&source_file.0, &target_file.0) }.unwrap();
+        assert!(edits.len() <= 17);
+        assert_eq!(crate::apply_edits(&source, &edits), target);
+
+        let edits = unsafe { diff_files_chunked(&source_file.0, &target_file.0, 1024) }.unwrap();
+        assert_eq!(crate::apply_edits(&source, &edits), target);
+
+        // An empty map is fine on either side
+        let empty = TempFile::new("diff_empty", b"");
+        let short = TempFile::new("diff_short", b"abc");
+        let edits = unsafe { diff_files(&empty.0, &short.0) }.unwrap();
+        assert_eq!(crate::apply_edits(b"", &edits), b"abc");
+        assert!(unsafe { diff_files_chunked(&empty.0, &empty.0, 1024) }
+            .unwrap()
+            .is_empty());
+
+        let missing = std::env::temp_dir().join("levenshtein_mmap_missing");
+        assert_eq!(
+            unsafe { diff_files(&missing, &target_file.0) }
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+}
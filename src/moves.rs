@@ -0,0 +1,237 @@
+use crate::replace::Replace;
+use alloc::vec::Vec;
+
+/// An edit on a block of the source sequence.
+///
+/// All indices are 0-indexed and refer to the original source sequence, like those of `Replace`.
+/// `to` is a gap between source items: a block moved or copied to `to` ends up right before the
+/// source item that was at index `to`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlockEdit<T> {
+    /// Replace a range of the source with new items.
+    Replace(Replace<T>),
+    /// Remove `source[from..from + len]` and reinsert it at `to`.
+    Move { from: usize, to: usize, len: usize },
+    /// Insert a copy of `source[from..from + len]` at `to`.
+    Copy { from: usize, to: usize, len: usize },
+}
+
+impl<T> BlockEdit<T> {
+    // The gap at which the edit inserts items, used to keep block edits ordered
+    fn insertion_point(&self) -> usize {
+        match self {
+            BlockEdit::Replace(replace) => replace.range.start,
+            BlockEdit::Move { to, .. } | BlockEdit::Copy { to, .. } => *to,
+        }
+    }
+}
+
+/// Recognizes blocks that were deleted and reinserted elsewhere, and represents them as moves.
+///
+/// A pure deletion whose items are exactly those of a pure insertion elsewhere becomes a single
+/// `BlockEdit::Move`. If `copies` is true, pure insertions of a block that already exists in the
+/// source also become a `BlockEdit::Copy`. Only blocks of at least `min_len` items are considered,
+/// since moving or copying a tiny block isn't any cheaper than inserting it. All other
+/// replacements are kept as they are.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `replacements` - The replacements, as returned by `coalesce_edits`
+/// * `min_len` - The minimum length of a moved or copied block
+/// * `copies` - Whether to detect copies as well as moves
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::BlockEdit;
+///
+/// let s1 = "XYZSATURDAY";
+/// let s2 = "SATURDAYXYZ";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+/// let replacements = levenshtein::coalesce_edits(&edits);
+///
+/// let block_edits = levenshtein::detect_moves(s1.as_bytes(), &replacements, 3, false);
+/// assert_eq!(block_edits, vec![BlockEdit::Move { from: 0, to: 11, len: 3 }]);
+///
+/// let target = levenshtein::apply_block_edits(s1.as_bytes(), &block_edits);
+/// assert_eq!(target, s2.as_bytes());
+/// ```
+pub fn detect_moves<T: Clone + PartialEq>(
+    source: &[T],
+    replacements: &[Replace<T>],
+    min_len: usize,
+    copies: bool,
+) -> Vec<BlockEdit<T>> {
+    let is_deletion = |r: &Replace<T>| r.with.is_empty() && r.range.len() >= min_len;
+    let is_insertion = |r: &Replace<T>| r.range.is_empty() && r.with.len() >= min_len;
+
+    // Each deletion can only be paired up with a single insertion
+    let mut moved = vec![false; replacements.len()];
+    let mut block_edits = Vec::<Option<BlockEdit<T>>>::with_capacity(replacements.len());
+
+    for insertion in replacements {
+        if !is_insertion(insertion) {
+            block_edits.push(None);
+            continue;
+        }
+
+        let deletion = replacements.iter().enumerate().position(|(i, r)| {
+            !moved[i] && is_deletion(r) && source[r.range.clone()] == insertion.with[..]
+        });
+
+        if let Some(i) = deletion {
+            moved[i] = true;
+            block_edits.push(Some(BlockEdit::Move {
+                from: replacements[i].range.start,
+                to: insertion.range.start,
+                len: insertion.with.len(),
+            }));
+            continue;
+        }
+
+        let len = insertion.with.len();
+        let copy = if copies && len > 0 {
+            source.windows(len).position(|w| w == &insertion.with[..])
+        } else {
+            None
+        };
+
+        block_edits.push(copy.map(|from| BlockEdit::Copy {
+            from,
+            to: insertion.range.start,
+            len,
+        }));
+    }
+
+    let mut block_edits: Vec<BlockEdit<T>> = replacements
+        .iter()
+        .zip(block_edits)
+        .enumerate()
+        .filter(|(i, _)| !moved[*i])
+        .map(|(_, (replacement, block_edit))| {
+            block_edit.unwrap_or_else(|| BlockEdit::Replace(replacement.clone()))
+        })
+        .collect();
+
+    block_edits.sort_by_key(|block_edit| block_edit.insertion_point());
+
+    block_edits
+}
+
+/// Applies a sequence of block edits to the source sequence, and returns a vector representing
+/// the target sequence.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `block_edits` - The block edits, as returned by `detect_moves`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::BlockEdit;
+///
+/// let block_edits = vec![BlockEdit::<u8>::Copy { from: 0, to: 3, len: 3 }];
+///
+/// let target = levenshtein::apply_block_edits("ABC".as_bytes(), &block_edits);
+/// assert_eq!(target, "ABCABC".as_bytes());
+/// ```
+pub fn apply_block_edits<T: Clone>(source: &[T], block_edits: &[BlockEdit<T>]) -> Vec<T> {
+    let mut removed = vec![false; source.len()];
+    let mut inserts = vec![Vec::<T>::new(); source.len() + 1];
+
+    for block_edit in block_edits {
+        let (range, to, with) = match block_edit {
+            BlockEdit::Replace(replace) => (
+                replace.range.clone(),
+                replace.range.start,
+                &replace.with[..],
+            ),
+            BlockEdit::Move { from, to, len } => {
+                (*from..from + len, *to, &source[*from..from + len])
+            }
+            BlockEdit::Copy { from, to, len } => (0..0, *to, &source[*from..from + len]),
+        };
+
+        for item in &mut removed[range] {
+            *item = true;
+        }
+
+        inserts[to].extend_from_slice(with);
+    }
+
+    let mut target = Vec::<T>::with_capacity(source.len());
+
+    for (i, item) in source.iter().enumerate() {
+        target.append(&mut inserts[i]);
+
+        if !removed[i] {
+            target.push(item.clone());
+        }
+    }
+
+    target.append(&mut inserts[source.len()]);
+
+    target
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::moves::*;
+
+    #[test]
+    fn detect_moves_test() {
+        let source = "ABCDEFGH".as_bytes();
+
+        // Move CD to the end and insert a copy of FG at the start
+        let replacements = vec![
+            Replace {
+                range: 0..0,
+                with: "FG".as_bytes().to_vec(),
+            },
+            Replace {
+                range: 2..4,
+                with: vec![],
+            },
+            Replace {
+                range: 8..8,
+                with: "CD".as_bytes().to_vec(),
+            },
+        ];
+
+        let block_edits = detect_moves(source, &replacements, 2, true);
+
+        assert_eq!(
+            block_edits,
+            vec![
+                BlockEdit::Copy {
+                    from: 5,
+                    to: 0,
+                    len: 2
+                },
+                BlockEdit::Move {
+                    from: 2,
+                    to: 8,
+                    len: 2
+                },
+            ]
+        );
+        assert_eq!(
+            apply_block_edits(source, &block_edits),
+            "FGABEFGHCD".as_bytes()
+        );
+
+        // Without copy detection, or with a larger minimum length, the replacements are kept
+        assert_eq!(
+            detect_moves(source, &replacements, 2, false)[0],
+            BlockEdit::Replace(replacements[0].clone())
+        );
+        assert_eq!(detect_moves(source, &replacements, 3, true).len(), 3);
+    }
+}
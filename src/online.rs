@@ -0,0 +1,210 @@
+use std::cmp::min;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+use crate::pattern::Pattern;
+
+/// Tracks how closely a stream matches a pattern, one item at a time.
+///
+/// After every item, `distance` is the smallest Levenshtein distance between the pattern and any
+/// stretch of the stream that ends with that item, so a small distance means the pattern (or
+/// something close to it) just went by. Only one column of the table is kept, so the stream is
+/// never buffered: every item takes O(m) time and the tracker O(m) memory, where m is the length
+/// of the pattern.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::OnlineDistance;
+///
+/// let mut tracker = OnlineDistance::new("ERROR".as_bytes().to_vec());
+///
+/// let distances: Vec<usize> = "an EROR here"
+///     .bytes()
+///     .map(|item| tracker.push(&item))
+///     .collect();
+///
+/// // The closest the stream came was right after the misspelled EROR
+/// assert_eq!(distances.iter().min(), Some(&1));
+/// assert_eq!(distances[6], 1);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct OnlineDistance<T> {
+    pattern: Vec<T>,
+    // The distance between every prefix of the pattern and the closest stretch of the stream
+    // ending with the last item
+    column: Vec<usize>,
+}
+
+impl<T: PartialEq> OnlineDistance<T> {
+    /// Returns a tracker that hasn't seen any of the stream yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The pattern to look for
+    pub fn new(pattern: Vec<T>) -> Self {
+        let column = (0..pattern.len() + 1).collect();
+
+        OnlineDistance { pattern, column }
+    }
+
+    /// Returns the pattern being looked for.
+    pub fn pattern(&self) -> &[T] {
+        &self.pattern
+    }
+
+    /// Consumes the next item of the stream, and returns the new distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The next item of the stream
+    pub fn push(&mut self, item: &T) -> usize {
+        // A match can start anywhere in the stream, so the empty prefix is always free
+        let mut diagonal = 0;
+
+        for i in 1..self.pattern.len() + 1 {
+            let above = self.column[i];
+
+            let substitute = if self.pattern[i - 1] == *item {
+                diagonal
+            } else {
+                diagonal + 1
+            };
+
+            self.column[i] = min(min(above + 1, self.column[i - 1] + 1), substitute);
+            diagonal = above;
+        }
+
+        self.distance()
+    }
+
+    /// Returns the smallest distance between the pattern and a stretch of the stream ending with
+    /// the last item, or the length of the pattern if nothing has been pushed yet.
+    pub fn distance(&self) -> usize {
+        self.column[self.pattern.len()]
+    }
+
+    /// Forgets the stream seen so far, as if nothing had been pushed.
+    pub fn reset(&mut self) {
+        for (i, cell) in self.column.iter_mut().enumerate() {
+            *cell = i;
+        }
+    }
+}
+
+/// Tracks the distance between a pattern and the last items of a stream, in a window of fixed
+/// size.
+///
+/// Unlike `OnlineDistance`, which lets a match start anywhere, this compares the pattern with
+/// exactly the last `size` items (or all of them, until there are that many), e.g. to check
+/// every packet-sized stretch of traffic against a known signature. The window is kept in a ring
+/// buffer, and the work that only depends on the pattern is done once by a `Pattern`, so a
+/// pattern of m items takes O(⌈m/64⌉ * W) time per item with Myers' bit-parallel algorithm,
+/// where W is the size of the window.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::WindowMatcher;
+///
+/// let mut matcher = WindowMatcher::new("SIGNATURE".as_bytes(), 9);
+///
+/// let mut closest = usize::MAX;
+/// for item in "..data.SIGNATUER.data..".bytes() {
+///     closest = closest.min(matcher.push(item));
+/// }
+///
+/// // The two transposed letters
+/// assert_eq!(closest, 2);
+/// ```
+pub struct WindowMatcher<'a, T> {
+    pattern: Pattern<'a, T>,
+    window: VecDeque<T>,
+    size: usize,
+    distance: usize,
+}
+
+impl<'a, T: Eq + Hash> WindowMatcher<'a, T> {
+    /// Returns a matcher with an empty window.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The pattern to compare the window with
+    /// * `size` - The number of items in a full window
+    pub fn new(pattern: &'a [T], size: usize) -> Self {
+        WindowMatcher {
+            pattern: Pattern::new(pattern),
+            window: VecDeque::with_capacity(size + 1),
+            size,
+            distance: pattern.len(),
+        }
+    }
+
+    /// Adds the next item of the stream to the window, dropping the oldest one if the window was
+    /// full, and returns the new distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The next item of the stream
+    pub fn push(&mut self, item: T) -> usize {
+        self.window.push_back(item);
+
+        if self.window.len() > self.size {
+            self.window.pop_front();
+        }
+
+        self.distance = self.pattern.distance_to(self.window.make_contiguous());
+        self.distance
+    }
+
+    /// Returns the distance between the pattern and the items in the window.
+    pub fn distance(&self) -> usize {
+        self.distance
+    }
+
+    /// Returns the items in the window, oldest first.
+    pub fn window(&self) -> impl Iterator<Item = &T> {
+        self.window.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::online::*;
+
+    #[test]
+    fn online_distance_test() {
+        let mut tracker = OnlineDistance::new("LAWN".as_bytes().to_vec());
+        assert_eq!(tracker.distance(), 4);
+
+        let distances: Vec<usize> = "FFLAWANN".bytes().map(|item| tracker.push(&item)).collect();
+
+        // The closest stretch ending at each item, e.g. LAWA, LAWAN and LAWANN for the last three
+        assert_eq!(distances, vec![4, 4, 3, 2, 1, 1, 1, 2]);
+
+        tracker.reset();
+        assert_eq!(tracker.distance(), 4);
+
+        // An empty pattern matches the empty stretch anywhere
+        let mut tracker = OnlineDistance::new(Vec::new());
+        assert_eq!(tracker.push(&b'A'), 0);
+    }
+
+    #[test]
+    fn window_matcher_test() {
+        let mut matcher = WindowMatcher::new("LAWN".as_bytes(), 3);
+        assert_eq!(matcher.distance(), 4);
+
+        let distances: Vec<usize> = "FFLAWANN".bytes().map(|item| matcher.push(item)).collect();
+
+        // FLA is 3 away from LAWN, and LAW only 1
+        assert_eq!(distances, vec![4, 4, 4, 3, 1, 2, 2, 2]);
+        assert!(matcher.window().eq("ANN".as_bytes()));
+
+        // An empty window never matches
+        let mut matcher = WindowMatcher::new("LAWN".as_bytes(), 0);
+        assert_eq!(matcher.push(b'L'), 4);
+    }
+}
@@ -0,0 +1,415 @@
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::error::Error;
+use core::fmt;
+
+use crate::distance::diff_edits;
+use crate::edit::{apply_edits, try_apply_edits, Edit};
+use crate::encoding::{
+    decode_edits_within, encode_edits, read_varint, write_varint, BinaryItem, DecodeError,
+};
+
+// Identifies serialized patches
+const MAGIC: &[u8] = b"LDP";
+
+/// The version of the patch format written by `Patch::to_bytes`.
+pub const PATCH_FORMAT_VERSION: u8 = 1;
+
+// The flag set on patches whose edits are compressed with deflate
+const DEFLATED: u8 = 1;
+
+/// Represents an error found while reading or applying a patch.
+#[derive(Debug, PartialEq)]
+pub enum PatchError {
+    // The input doesn't start with a patch header
+    InvalidHeader,
+    // The patch was written in a format version this crate doesn't know
+    UnsupportedVersion(u8),
+    // The edits in the patch are compressed, and the `compression` feature is disabled
+    UnsupportedCompression,
+    // The edits in the patch couldn't be decoded
+    Decode(DecodeError),
+    // The patch was made for a different source
+    SourceMismatch,
+    // Applying the patch didn't produce the target it was made for
+    TargetMismatch,
+    // An edit refers to an index outside the source
+    InvalidIndex(usize),
+    // Two edits delete or substitute the item at this index
+    ConflictingEdits(usize),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::InvalidHeader => write!(f, "Invalid patch header"),
+            PatchError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported patch format version {}", version)
+            }
+            PatchError::UnsupportedCompression => {
+                write!(f, "Compressed patches need the `compression` feature")
+            }
+            PatchError::Decode(error) => write!(f, "Invalid patch edits: {}", error),
+            PatchError::SourceMismatch => write!(f, "The patch doesn't apply to this source"),
+            PatchError::TargetMismatch => write!(f, "The patch didn't produce its target"),
+            PatchError::InvalidIndex(idx) => write!(f, "Edit index {} is out of bounds", idx),
+            PatchError::ConflictingEdits(idx) => {
+                write!(f, "Conflicting edits to the item at index {}", idx)
+            }
+        }
+    }
+}
+
+impl Error for PatchError {}
+
+impl From<DecodeError> for PatchError {
+    fn from(error: DecodeError) -> Self {
+        PatchError::Decode(error)
+    }
+}
+
+// Returns the 64-bit FNV-1a hash of the binary encoding of the items. Unlike the hashers in std,
+// this is stable across platforms and Rust versions, so it can be stored.
+fn checksum<T: BinaryItem>(items: &[T]) -> u64 {
+    let mut encoded = Vec::with_capacity(items.len());
+
+    for item in items {
+        item.encode(&mut encoded);
+    }
+
+    encoded.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn read_u64(input: &mut &[u8]) -> Result<u64, PatchError> {
+    if input.len() < 8 {
+        return Err(PatchError::Decode(DecodeError::UnexpectedEnd));
+    }
+
+    let (bytes, rest) = input.split_at(8);
+    *input = rest;
+
+    Ok(u64::from_le_bytes(<[u8; 8]>::try_from(bytes).unwrap()))
+}
+
+/// An edit script bundled with what's needed to check that it applies to the right source.
+///
+/// Besides the edits, a patch records the length and a checksum of the source it was made for,
+/// and a checksum of the target it produces. `Patch::apply` refuses to run against any other
+/// source, instead of silently producing garbage.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{Patch, PatchError};
+///
+/// let patch = Patch::new("SATURDAY".as_bytes(), "SUNDAY".as_bytes());
+///
+/// let bytes = patch.to_bytes();
+/// let patch = Patch::<u8>::from_bytes(&bytes).unwrap();
+///
+/// assert_eq!(patch.apply("SATURDAY".as_bytes()).unwrap(), "SUNDAY".as_bytes());
+/// assert_eq!(patch.apply("THURSDAY".as_bytes()), Err(PatchError::SourceMismatch));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Patch<T: PartialEq> {
+    source_len: usize,
+    source_checksum: u64,
+    target_checksum: u64,
+    edits: Vec<Edit<T>>,
+}
+
+impl<T: Clone + PartialEq + BinaryItem> Patch<T> {
+    /// Returns the patch that transforms source into target.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence
+    /// * `target` - The target sequence
+    pub fn new(source: &[T], target: &[T]) -> Self {
+        let edits = diff_edits(source, target);
+
+        Patch {
+            source_len: source.len(),
+            source_checksum: checksum(source),
+            target_checksum: checksum(target),
+            edits,
+        }
+    }
+
+    /// Returns the patch that applies `edits` to source.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence
+    /// * `edits` - The edits, as generated by `generate_edits`
+    pub fn from_edits(source: &[T], edits: Vec<Edit<T>>) -> Self {
+        Patch {
+            source_len: source.len(),
+            source_checksum: checksum(source),
+            target_checksum: checksum(&apply_edits(source, &edits)),
+            edits,
+        }
+    }
+
+    /// Returns the length of the source the patch was made for.
+    pub fn source_len(&self) -> usize {
+        self.source_len
+    }
+
+    /// Returns the edits in the patch.
+    pub fn edits(&self) -> &[Edit<T>] {
+        &self.edits
+    }
+
+    /// Applies the patch to the source sequence, and returns a vector representing the target
+    /// sequence.
+    ///
+    /// Returns an error, without applying anything, if the source isn't the one the patch was
+    /// made for, or if the edits in the patch don't fit it.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence
+    pub fn apply(&self, source: &[T]) -> Result<Vec<T>, PatchError> {
+        if source.len() != self.source_len || checksum(source) != self.source_checksum {
+            return Err(PatchError::SourceMismatch);
+        }
+
+        let target = try_apply_edits(source, &self.edits)?;
+
+        if checksum(&target) != self.target_checksum {
+            return Err(PatchError::TargetMismatch);
+        }
+
+        Ok(target)
+    }
+
+    /// Serializes the patch: a header with the format version, a flags byte that says how the
+    /// edits are compressed, the source length and both checksums, followed by the edits as
+    /// encoded by `encode_edits`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.write(0, encode_edits(&self.edits))
+    }
+
+    /// Serializes the patch like `to_bytes`, but with the edits compressed with deflate. This is
+    /// worth it for large patches, especially of text.
+    ///
+    /// The length of the encoded edits is written in front of them, so that reading the patch
+    /// back never inflates more than that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use levenshtein_diff as levenshtein;
+    /// use levenshtein::Patch;
+    ///
+    /// let source = "the quick brown fox ".repeat(20);
+    /// let target = "the quick brown dog ".repeat(20);
+    ///
+    /// let patch = Patch::new(source.as_bytes(), target.as_bytes());
+    /// let bytes = patch.to_compressed_bytes();
+    ///
+    /// assert!(bytes.len() < patch.to_bytes().len());
+    /// assert_eq!(Patch::from_bytes(&bytes), Ok(patch));
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn to_compressed_bytes(&self) -> Vec<u8> {
+        use flate2::write::DeflateEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+
+        let encoded = encode_edits(&self.edits);
+
+        // Writing to a vector can't fail
+        encoder.write_all(&encoded).unwrap();
+
+        let mut payload = Vec::new();
+        write_varint(&mut payload, encoded.len() as u64);
+        payload.extend(encoder.finish().unwrap());
+
+        self.write(DEFLATED, payload)
+    }
+
+    fn write(&self, flags: u8, payload: Vec<u8>) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(PATCH_FORMAT_VERSION);
+        out.push(flags);
+
+        write_varint(&mut out, self.source_len as u64);
+        out.extend_from_slice(&self.source_checksum.to_le_bytes());
+        out.extend_from_slice(&self.target_checksum.to_le_bytes());
+        out.extend(payload);
+
+        out
+    }
+
+    /// Deserializes a patch written by `to_bytes`, or `to_compressed_bytes` if the `compression`
+    /// feature is enabled.
+    ///
+    /// Returns an error if an edit refers to an index outside the source of the length recorded
+    /// in the patch, so a malformed patch can't make this decode more edits than its source and
+    /// its bytes account for.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized patch
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PatchError> {
+        let mut input = bytes.strip_prefix(MAGIC).ok_or(PatchError::InvalidHeader)?;
+
+        let (&version, rest) = input.split_first().ok_or(PatchError::InvalidHeader)?;
+        input = rest;
+
+        if version != PATCH_FORMAT_VERSION {
+            return Err(PatchError::UnsupportedVersion(version));
+        }
+
+        let (&flags, rest) = input.split_first().ok_or(PatchError::InvalidHeader)?;
+        input = rest;
+
+        let source_len = usize::try_from(read_varint(&mut input)?)
+            .map_err(|_| PatchError::Decode(DecodeError::Overflow))?;
+        let source_checksum = read_u64(&mut input)?;
+        let target_checksum = read_u64(&mut input)?;
+
+        let edits = match flags {
+            0 => decode_edits_within(input, source_len)?,
+            DEFLATED => {
+                let inflated_len = usize::try_from(read_varint(&mut input)?)
+                    .map_err(|_| PatchError::Decode(DecodeError::Overflow))?;
+
+                decode_edits_within(&inflate(input, inflated_len)?, source_len)?
+            }
+            _ => return Err(PatchError::InvalidHeader),
+        };
+
+        Ok(Patch {
+            source_len,
+            source_checksum,
+            target_checksum,
+            edits,
+        })
+    }
+}
+
+// Inflates the payload, which must inflate to exactly len bytes. Anything past that isn't
+// inflated at all, so a small payload can't claim a lot of memory.
+#[cfg(feature = "compression")]
+fn inflate(payload: &[u8], len: usize) -> Result<Vec<u8>, PatchError> {
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    let mut inflated = Vec::new();
+
+    DeflateDecoder::new(payload)
+        .take(len as u64 + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|_| PatchError::Decode(DecodeError::UnexpectedEnd))?;
+
+    match inflated.len() {
+        inflated_len if inflated_len < len => Err(PatchError::Decode(DecodeError::UnexpectedEnd)),
+        inflated_len if inflated_len > len => Err(PatchError::Decode(DecodeError::Overflow)),
+        _ => Ok(inflated),
+    }
+}
+
+#[cfg(not(feature = "compression"))]
+fn inflate(_payload: &[u8], _len: usize) -> Result<Vec<u8>, PatchError> {
+    Err(PatchError::UnsupportedCompression)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::patch::*;
+
+    #[test]
+    fn patch_test() {
+        let patch = Patch::new("SATURDAY".as_bytes(), "SUNDAY".as_bytes());
+
+        assert_eq!(patch.source_len(), 8);
+        assert_eq!(patch.edits().len(), 3);
+        assert_eq!(Patch::from_bytes(&patch.to_bytes()), Ok(patch.clone()));
+
+        // A source of the right length but with the wrong contents
+        assert_eq!(
+            patch.apply("SATURDAZ".as_bytes()),
+            Err(PatchError::SourceMismatch)
+        );
+    }
+
+    #[test]
+    fn invalid_patch_test() {
+        let mut bytes = Patch::new("SATURDAY".as_bytes(), "SUNDAY".as_bytes()).to_bytes();
+
+        assert_eq!(
+            Patch::<u8>::from_bytes(&bytes[..10]),
+            Err(PatchError::Decode(DecodeError::UnexpectedEnd))
+        );
+
+        // The edits take the last 5 bytes. Replace them with a deletion past the end of the source.
+        let mut outside = bytes[..bytes.len() - 5].to_vec();
+        outside.extend(encode_edits(&[Edit::<u8>::Delete(9)]));
+        assert_eq!(
+            Patch::<u8>::from_bytes(&outside),
+            Err(PatchError::Decode(DecodeError::InvalidIndex))
+        );
+
+        bytes[3] = 3;
+        assert_eq!(
+            Patch::<u8>::from_bytes(&bytes),
+            Err(PatchError::UnsupportedVersion(3))
+        );
+
+        bytes[0] = b'X';
+        assert_eq!(
+            Patch::<u8>::from_bytes(&bytes),
+            Err(PatchError::InvalidHeader)
+        );
+    }
+
+    #[cfg(not(feature = "compression"))]
+    #[test]
+    fn compressed_patch_without_feature_test() {
+        let mut bytes = Patch::new("SATURDAY".as_bytes(), "SUNDAY".as_bytes()).to_bytes();
+        bytes[4] = DEFLATED;
+
+        assert_eq!(
+            Patch::<u8>::from_bytes(&bytes),
+            Err(PatchError::UnsupportedCompression)
+        );
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn compressed_patch_test() {
+        let source = "the quick brown fox ".repeat(20);
+        let target = "the quick brown dog ".repeat(20);
+
+        let patch = Patch::new(source.as_bytes(), target.as_bytes());
+        let bytes = patch.to_compressed_bytes();
+        assert_eq!(Patch::from_bytes(&bytes), Ok(patch.clone()));
+
+        // The length of the encoded edits comes right after the header of 23 bytes, and takes a
+        // byte. Claiming more or fewer of them than the payload inflates to is an error.
+        let encoded_len = encode_edits(patch.edits()).len() as u8;
+        assert_eq!(bytes[23], encoded_len);
+
+        let mut longer = bytes.clone();
+        longer[23] = encoded_len + 1;
+        assert_eq!(
+            Patch::<u8>::from_bytes(&longer),
+            Err(PatchError::Decode(DecodeError::UnexpectedEnd))
+        );
+
+        let mut shorter = bytes;
+        shorter[23] = encoded_len - 1;
+        assert_eq!(
+            Patch::<u8>::from_bytes(&shorter),
+            Err(PatchError::Decode(DecodeError::Overflow))
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use std::cmp::max;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::distance::bounded_distance;
+use crate::util::length_lower_bound;
+
+/// A source sequence that has been preprocessed for repeated comparisons against many targets.
+///
+/// Building a `Pattern` computes everything that only depends on the source once: a histogram of
+/// its elements (used for cheap lower bounds), and the bit-vectors used by Myers' bit-parallel
+/// algorithm. Sources longer than a machine word are split into blocks of 64 elements, so a
+/// distance takes O(⌈m/64⌉ * n) word operations instead of O(m * n) cell updates.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff::Pattern;
+///
+/// let pattern = Pattern::new("SATURDAY".as_bytes());
+///
+/// assert_eq!(pattern.distance_to("SUNDAY".as_bytes()), 3);
+/// assert_eq!(pattern.distance_within("SUNDAY".as_bytes(), 3), Some(3));
+/// assert_eq!(pattern.distance_within("MONDAY".as_bytes(), 3), None);
+/// ```
+pub struct Pattern<'a, T> {
+    source: &'a [T],
+    // How often each element occurs in source
+    histogram: HashMap<&'a T, usize>,
+    // For every element of source, a bit-vector of the positions it occurs at, cut into blocks
+    // of 64 positions. Only the blocks it occurs in are stored, in ascending order of block.
+    peq: HashMap<&'a T, Vec<(usize, u64)>>,
+}
+
+impl<'a, T: Eq + Hash> Pattern<'a, T> {
+    /// Preprocesses the source sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence
+    pub fn new(source: &'a [T]) -> Self {
+        let mut histogram = HashMap::new();
+
+        for item in source {
+            *histogram.entry(item).or_insert(0) += 1;
+        }
+
+        let mut peq = HashMap::<&T, Vec<(usize, u64)>>::new();
+
+        for (i, item) in source.iter().enumerate() {
+            let (block, bit) = (i / 64, 1u64 << (i % 64));
+            let blocks = peq.entry(item).or_default();
+
+            match blocks.last_mut() {
+                Some((last, bits)) if *last == block => *bits |= bit,
+                _ => blocks.push((block, bit)),
+            }
+        }
+
+        Pattern {
+            source,
+            histogram,
+            peq,
+        }
+    }
+
+    /// Returns the source sequence this pattern was built from.
+    pub fn source(&self) -> &'a [T] {
+        self.source
+    }
+
+    /// Returns the Levenshtein distance between the source sequence and target.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target sequence
+    pub fn distance_to(&self, target: &[T]) -> usize {
+        self.bit_parallel_distance(target)
+    }
+
+    /// Returns the Levenshtein distance between the source sequence and target if it is at most
+    /// `k`, and `None` otherwise.
+    ///
+    /// Cheap lower bounds are checked first, so targets that are obviously too far away are
+    /// rejected without computing their distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target sequence
+    /// * `k` - The maximum allowed distance
+    pub fn distance_within(&self, target: &[T], k: usize) -> Option<usize> {
+        if length_lower_bound(self.source, target) > k || self.histogram_lower_bound(target) > k {
+            return None;
+        }
+
+        // Larger bounds than the longer sequence all give the same answer, and would overflow
+        // the size of the band below
+        let k = k.min(self.source.len().max(target.len()));
+
+        // A band of 2k + 1 diagonals is cheaper than every block when k is small
+        let blocks = self.source.len().div_ceil(64);
+
+        if blocks * target.len() <= (2 * k + 1) * self.source.len() {
+            Some(self.bit_parallel_distance(target)).filter(|d| *d <= k)
+        } else {
+            bounded_distance(self.source, target, k)
+        }
+    }
+
+    // The same bound as util::histogram_lower_bound, reusing the histogram of source
+    fn histogram_lower_bound(&self, target: &[T]) -> usize {
+        let mut used = HashMap::<&T, usize>::new();
+        let mut common = 0;
+
+        for item in target {
+            let available = self.histogram.get(item).copied().unwrap_or(0);
+            let used = used.entry(item).or_insert(0);
+
+            if *used < available {
+                *used += 1;
+                common += 1;
+            }
+        }
+
+        max(self.source.len(), target.len()) - common
+    }
+
+    // Myers' bit-parallel algorithm, processing a whole column of the distance matrix per step,
+    // in Hyyrö's blocked form for sources longer than a machine word. Pv and Mv hold the positive
+    // and negative vertical deltas of each block of the current column, and the horizontal delta
+    // out of the last row of a block is carried into the first row of the next.
+    fn bit_parallel_distance(&self, target: &[T]) -> usize {
+        let m = self.source.len();
+
+        if m == 0 {
+            return target.len();
+        }
+
+        let blocks = m.div_ceil(64);
+        let last = 1u64 << ((m - 1) % 64);
+
+        let mut pv = vec![!0u64; blocks];
+        let mut mv = vec![0u64; blocks];
+        let mut score = m;
+
+        for item in target {
+            let bits = self.peq.get(item).map_or(&[][..], |bits| bits.as_slice());
+            let mut next_bits = bits.iter().peekable();
+
+            // The first row of the matrix increases by one in every column
+            let (mut ph_in, mut mh_in) = (1u64, 0u64);
+
+            for block in 0..blocks {
+                let mut eq = match next_bits.peek() {
+                    Some(&&(b, eq)) if b == block => {
+                        next_bits.next();
+                        eq
+                    }
+                    _ => 0,
+                };
+
+                let (p, m) = (pv[block], mv[block]);
+
+                let xv = eq | m;
+                eq |= mh_in;
+                let xh = ((eq & p).wrapping_add(p) ^ p) | eq;
+
+                let mut ph = m | !(xh | p);
+                let mut mh = p & xh;
+
+                let high = if block + 1 == blocks {
+                    last
+                } else {
+                    1u64 << 63
+                };
+                let (ph_out, mh_out) = ((ph & high != 0) as u64, (mh & high != 0) as u64);
+
+                ph = (ph << 1) | ph_in;
+                mh = (mh << 1) | mh_in;
+
+                pv[block] = mh | !(xv | ph);
+                mv[block] = ph & xv;
+
+                ph_in = ph_out;
+                mh_in = mh_out;
+            }
+
+            // What leaves the last row of the last block is the change to the distance
+            score = score + ph_in as usize - mh_in as usize;
+        }
+
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pattern::*;
+
+    #[test]
+    fn pattern_distance_to_test() {
+        let pattern = Pattern::new("LAWN".as_bytes());
+
+        assert_eq!(pattern.distance_to("FFLAWANN".as_bytes()), 4);
+        assert_eq!(pattern.distance_to("LAWN".as_bytes()), 0);
+        assert_eq!(pattern.distance_to("".as_bytes()), 4);
+
+        let empty = Pattern::new("".as_bytes());
+
+        assert_eq!(empty.distance_to("LAWN".as_bytes()), 4);
+    }
+
+    #[test]
+    fn pattern_distance_within_test() {
+        let pattern = Pattern::new("LAWN".as_bytes());
+
+        assert_eq!(pattern.distance_within("FFLAWANN".as_bytes(), 4), Some(4));
+        assert_eq!(pattern.distance_within("FFLAWANN".as_bytes(), 3), None);
+        assert_eq!(pattern.distance_within("NWAL".as_bytes(), 3), None);
+        assert_eq!(
+            pattern.distance_within("FFLAWANN".as_bytes(), usize::MAX),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn pattern_long_source_test() {
+        // Sources that span several blocks, with matches on either side of the block boundaries
+        let source: Vec<u8> = (0..300).map(|i| b"abcde"[(i * i + i / 7) % 5]).collect();
+
+        for &len in &[64, 65, 127, 128, 129, 300] {
+            let pattern = Pattern::new(&source[..len]);
+
+            for target_len in [0, 1, 63, 64, 65, 200, 300].iter() {
+                let target: Vec<u8> = source.iter().rev().take(*target_len).copied().collect();
+                let expected =
+                    crate::distance::levenshtein_two_rows(&source[..len], target.as_slice());
+
+                assert_eq!(pattern.distance_to(&target), expected);
+                assert_eq!(pattern.distance_within(&target, expected), Some(expected));
+            }
+
+            let mut edited = source[..len].to_vec();
+            edited[len / 2] = b'z';
+            edited.remove(len - 1);
+            assert_eq!(pattern.distance_to(&edited), 2);
+            assert_eq!(pattern.distance_within(&edited, 1), None);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::borrow::Cow;
+
+use regex::Regex;
+
+use crate::distance::levenshtein_two_rows;
+use crate::edit::Edit;
+use crate::keyed::stripped_edit_ops;
+use crate::lines::{line_edits, LineEdit, LineOptions};
+
+/// Rewrites strings with regular expressions before they are compared, so that parts that always
+/// differ, like timestamps or UUIDs in logs, don't count as changes.
+///
+/// The rules only change what is compared: the edits and line edits still hold the original
+/// strings, with whatever the rules masked. Rules are applied in the order they were added, each
+/// to the result of the ones before it.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::RegexNormalizer;
+///
+/// let mut normalizer = RegexNormalizer::new();
+/// normalizer
+///     .replace(r"\d{2}:\d{2}:\d{2}", "<time>")
+///     .unwrap()
+///     .replace(r"[0-9a-f]{8}(-[0-9a-f]{4}){3}-[0-9a-f]{12}", "<uuid>")
+///     .unwrap();
+///
+/// let line = "12:00:01 started job 3f2b8c1e-0d4a-4e6b-9a7c-1b2c3d4e5f60";
+/// assert_eq!(normalizer.normalize(line), "<time> started job <uuid>");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RegexNormalizer {
+    // The rules, with their replacements
+    rules: Vec<(Regex, String)>,
+}
+
+impl RegexNormalizer {
+    /// Returns a normalizer with no rules, which leaves strings as they are.
+    pub fn new() -> Self {
+        RegexNormalizer { rules: Vec::new() }
+    }
+
+    /// Adds a rule that replaces every match of a pattern with a replacement.
+    ///
+    /// The replacement can refer to the groups of the match, as `$1` or `$name`, like in
+    /// `Regex::replace_all`. An empty replacement removes the matches.
+    ///
+    /// Returns the error of the `regex` crate if the pattern isn't a valid regular expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The regular expression to match
+    /// * `replacement` - What to replace its matches with
+    pub fn replace(&mut self, pattern: &str, replacement: &str) -> Result<&mut Self, regex::Error> {
+        let regex = Regex::new(pattern)?;
+
+        Ok(self.replace_regex(regex, replacement))
+    }
+
+    /// Adds a rule that replaces every match of a compiled regular expression with a replacement.
+    /// See `replace`.
+    ///
+    /// # Arguments
+    ///
+    /// * `regex` - The regular expression to match
+    /// * `replacement` - What to replace its matches with
+    pub fn replace_regex(&mut self, regex: Regex, replacement: &str) -> &mut Self {
+        self.rules.push((regex, replacement.to_string()));
+        self
+    }
+
+    /// Returns the string as it is compared, after every rule is applied to it. It is borrowed
+    /// from the string unless a rule matched.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The string to normalize
+    pub fn normalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut normalized = Cow::Borrowed(text);
+
+        for (regex, replacement) in &self.rules {
+            let replaced = match regex.replace_all(&normalized, replacement.as_str()) {
+                Cow::Owned(replaced) => replaced,
+                Cow::Borrowed(_) => continue,
+            };
+
+            normalized = Cow::Owned(replaced);
+        }
+
+        normalized
+    }
+
+    // The normalized strings of a sequence
+    fn keys<'a, S: AsRef<str>>(&self, items: &'a [S]) -> Vec<Cow<'a, str>> {
+        items
+            .iter()
+            .map(|item| self.normalize(item.as_ref()))
+            .collect()
+    }
+
+    /// Returns the Levenshtein distance between two sequences of strings, comparing them once
+    /// they are normalized.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence
+    /// * `target` - The target sequence
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use levenshtein_diff as levenshtein;
+    /// use levenshtein::RegexNormalizer;
+    ///
+    /// let mut normalizer = RegexNormalizer::new();
+    /// normalizer.replace(r"id=\d+", "id=N").unwrap();
+    ///
+    /// let source = ["GET /users id=17", "GET /posts id=3"];
+    /// let target = ["GET /users id=42", "DELETE /posts id=3"];
+    ///
+    /// assert_eq!(normalizer.distance(&source, &target), 1);
+    /// ```
+    pub fn distance<S: AsRef<str>>(&self, source: &[S], target: &[S]) -> usize {
+        levenshtein_two_rows(&self.keys(source), &self.keys(target))
+    }
+
+    /// Generate a vector of edits that transform the source sequence of strings into the target
+    /// sequence, comparing them once they are normalized.
+    ///
+    /// The edits hold the original strings of the target, so applying them keeps the strings of
+    /// the source wherever their normalized strings are the same as those of the target.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence
+    /// * `target` - The target sequence
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use levenshtein_diff as levenshtein;
+    /// use levenshtein::{Edit, RegexNormalizer};
+    ///
+    /// let mut normalizer = RegexNormalizer::new();
+    /// normalizer.replace(r"id=\d+", "id=N").unwrap();
+    ///
+    /// let source = ["GET /users id=17", "GET /posts id=3"];
+    /// let target = ["GET /users id=42", "DELETE /posts id=3"];
+    ///
+    /// let edits = normalizer.generate_edits(&source, &target);
+    /// assert_eq!(edits, vec![Edit::Substitute(2, "DELETE /posts id=3")]);
+    /// ```
+    pub fn generate_edits<S: Clone + PartialEq + AsRef<str>>(
+        &self,
+        source: &[S],
+        target: &[S],
+    ) -> Vec<Edit<S>> {
+        stripped_edit_ops(&self.keys(source), &self.keys(target))
+            .iter()
+            .map(|op| op.to_edit(target))
+            .collect()
+    }
+
+    /// Generate the line edits that transform the source text into the target text, comparing
+    /// the lines once they are normalized. See `diff_lines`.
+    ///
+    /// The rules are applied to the text of each line, without its terminator, before the
+    /// whitespace options, so patterns can rely on the whitespace of the original lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source text
+    /// * `target` - The target text
+    /// * `options` - How the lines are compared once they are normalized
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use levenshtein_diff as levenshtein;
+    /// use levenshtein::{LineEdit, LineOptions, RegexNormalizer};
+    ///
+    /// let mut normalizer = RegexNormalizer::new();
+    /// normalizer.replace(r"^\[[^\]]*\] ", "").unwrap();
+    ///
+    /// let source = "[09:00:00] boot\n[09:00:01] load config\n[09:00:02] ready\n";
+    /// let target = "[17:30:00] boot\n[17:30:04] load config\n[17:30:05] failed\n";
+    ///
+    /// let edits = normalizer.diff_lines(source, target, LineOptions::default());
+    /// assert_eq!(edits.len(), 1);
+    ///
+    /// match edits[0] {
+    ///     LineEdit::Substitute { source, target } => {
+    ///         assert_eq!(source.text, "[09:00:02] ready");
+    ///         assert_eq!(target.text, "[17:30:05] failed");
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn diff_lines<'a>(
+        &self,
+        source: &'a str,
+        target: &'a str,
+        options: LineOptions,
+    ) -> Vec<LineEdit<'a>> {
+        line_edits(source, target, |line| {
+            let text = match self.normalize(line.text) {
+                Cow::Borrowed(text) => options.whitespace.normalize(text),
+                Cow::Owned(text) => Cow::Owned(options.whitespace.normalize(&text).into_owned()),
+            };
+
+            (text, options.ending(line))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::regex_normalizer::*;
+
+    #[test]
+    fn normalize_test() {
+        let mut normalizer = RegexNormalizer::new();
+        assert_eq!(normalizer.normalize("a1"), "a1");
+
+        normalizer
+            .replace(r"\d+", "#")
+            .unwrap()
+            .replace(r"(?P<key>\w+)=#", "$key=?")
+            .unwrap();
+
+        // The second rule sees what the first one left
+        assert_eq!(normalizer.normalize("x=12, y=3"), "x=?, y=?");
+        assert!(matches!(
+            normalizer.normalize("none"),
+            Cow::Borrowed("none")
+        ));
+
+        assert!(normalizer.replace("(", "").is_err());
+    }
+
+    #[test]
+    fn generate_edits_test() {
+        let mut normalizer = RegexNormalizer::new();
+        normalizer.replace(r"\d", "").unwrap();
+
+        let source = vec!["a1".to_string(), "b2".to_string(), "c3".to_string()];
+        let target = vec!["a9".to_string(), "c8".to_string(), "d".to_string()];
+
+        let edits = normalizer.generate_edits(&source, &target);
+        assert_eq!(edits.len(), normalizer.distance(&source, &target));
+        assert_eq!(edits.len(), 2);
+
+        // The unchanged strings keep their own digits
+        assert_eq!(crate::apply_edits(&source, &edits), vec!["a1", "c3", "d"]);
+        assert!(normalizer.generate_edits(&source, &source).is_empty());
+    }
+
+    #[test]
+    fn diff_lines_test() {
+        use crate::whitespace::Whitespace;
+
+        let mut normalizer = RegexNormalizer::new();
+        normalizer.replace(r"took \d+ms", "took Nms").unwrap();
+
+        let source = "query took 12ms\ndone\n";
+        let target = "query  took 7ms \ndone\n";
+
+        assert_eq!(
+            normalizer
+                .diff_lines(source, target, LineOptions::default())
+                .len(),
+            1
+        );
+
+        let options = LineOptions {
+            whitespace: Whitespace::Collapse,
+            ..Default::default()
+        };
+        assert!(normalizer.diff_lines(source, target, options).is_empty());
+    }
+}
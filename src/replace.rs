@@ -0,0 +1,274 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::edit::Edit;
+
+/// Replaces a contiguous range of the source sequence with a run of new items.
+///
+/// Unlike `Edit`, ranges are 0-indexed and half-open, like slice ranges: `range` is the part of the
+/// source that is removed and `with` is what takes its place. An empty range is a pure insertion,
+/// and an empty `with` is a pure deletion.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replace<T> {
+    pub range: Range<usize>,
+    pub with: Vec<T>,
+}
+
+/// Coalesces a sequence of single item edits into as few range replacements as possible.
+///
+/// Adjacent insertions, deletions and substitutions (i.e. those not separated by an unchanged
+/// source item) are merged into a single `Replace`. The replacements are returned in ascending
+/// order, don't overlap, and all refer to indices in the original source sequence.
+///
+/// # Arguments
+///
+/// * `edits` - The edits, as generated by `generate_edits`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Replace;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+///
+/// // The adjacent deletions of A and T are merged, the substitution of R with N is not
+/// let replacements = levenshtein::coalesce_edits(&edits);
+/// assert_eq!(
+///     replacements,
+///     vec![
+///         Replace { range: 1..3, with: vec![] },
+///         Replace { range: 4..5, with: "N".as_bytes().to_vec() },
+///     ]
+/// );
+/// ```
+pub fn coalesce_edits<T: Clone + PartialEq>(edits: &[Edit<T>]) -> Vec<Replace<T>> {
+    // Every gap between source items and every source item gets a slot: gap g is slot 2g and item
+    // i is slot 2i + 1, so iterating over slots in order visits them in sequence order. A changed
+    // item maps to Some(value) for a substitution and None for a deletion.
+    let mut inserts = BTreeMap::<usize, Vec<T>>::new();
+    let mut changes = BTreeMap::<usize, Option<T>>::new();
+
+    // Inserts at the same index end up in the reverse order of the edits (see `apply_edits`)
+    for edit in edits.iter().rev() {
+        match edit {
+            Edit::Insert(idx, val) => inserts.entry(2 * idx).or_default().push(val.clone()),
+            Edit::Delete(idx) => {
+                changes.insert(2 * (idx - 1) + 1, None);
+            }
+            Edit::Substitute(idx, val) => {
+                changes.insert(2 * (idx - 1) + 1, Some(val.clone()));
+            }
+        }
+    }
+
+    let mut slots: Vec<usize> = inserts.keys().chain(changes.keys()).copied().collect();
+    slots.sort_unstable();
+
+    let mut replacements = Vec::<Replace<T>>::new();
+    let mut previous_slot: Option<usize> = None;
+
+    for slot in slots {
+        // Two slots belong to the same replacement unless an unchanged item (an odd slot) lies
+        // between them
+        let contiguous = match previous_slot {
+            Some(previous) => slot - previous == 1 || (slot - previous == 2 && previous % 2 == 1),
+            None => false,
+        };
+
+        if !contiguous {
+            replacements.push(Replace {
+                range: slot / 2..slot / 2,
+                with: Vec::new(),
+            });
+        }
+
+        let current = replacements.last_mut().unwrap();
+
+        if slot % 2 == 0 {
+            current.with.extend(inserts[&slot].iter().cloned());
+        } else {
+            current.range.end = slot / 2 + 1;
+            current.with.extend(changes[&slot].iter().cloned());
+        }
+
+        previous_slot = Some(slot);
+    }
+
+    replacements
+}
+
+/// Applies a sequence of range replacements to the source sequence, and returns a vector
+/// representing the target sequence.
+///
+/// The replacements must be sorted, must not overlap, and must refer to indices in the original
+/// source sequence, as returned by `coalesce_edits`.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `replacements` - The replacements to apply
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Replace;
+///
+/// let replacements = vec![Replace { range: 1..5, with: "UN".as_bytes().to_vec() }];
+///
+/// let target = levenshtein::apply_replacements("SATURDAY".as_bytes(), &replacements);
+/// assert_eq!(target, "SUNDAY".as_bytes());
+/// ```
+pub fn apply_replacements<T: Clone>(source: &[T], replacements: &[Replace<T>]) -> Vec<T> {
+    let mut target = Vec::<T>::with_capacity(source.len());
+    let mut copied_up_to = 0;
+
+    for replacement in replacements {
+        target.extend_from_slice(&source[copied_up_to..replacement.range.start]);
+        target.extend_from_slice(&replacement.with);
+        copied_up_to = replacement.range.end;
+    }
+
+    target.extend_from_slice(&source[copied_up_to..]);
+
+    target
+}
+
+/// Expands a sequence of range replacements into single item edits, the inverse of
+/// `coalesce_edits`.
+///
+/// Within each replacement, removed items are substituted with new ones for as long as both
+/// last, and the remainder is deleted or inserted. The edits are returned in the same descending
+/// order as `generate_edits` produces, so they can be applied with `apply_edits`.
+///
+/// # Arguments
+///
+/// * `replacements` - The replacements, sorted and non-overlapping
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Replace;
+///
+/// let replacements = vec![Replace { range: 1..5, with: "UN".as_bytes().to_vec() }];
+///
+/// let edits = levenshtein::replacements_to_edits(&replacements);
+/// assert_eq!(edits.len(), 4);
+///
+/// let target = levenshtein::apply_edits("SATURDAY".as_bytes(), &edits);
+/// assert_eq!(target, "SUNDAY".as_bytes());
+/// ```
+pub fn replacements_to_edits<T: Clone + PartialEq>(replacements: &[Replace<T>]) -> Vec<Edit<T>> {
+    let mut edits = Vec::<Edit<T>>::new();
+
+    for replacement in replacements.iter().rev() {
+        let start = replacement.range.start;
+        let removed = replacement.range.len();
+        let substituted = core::cmp::min(removed, replacement.with.len());
+
+        // Edits are 1-indexed, and inserts at the same index are listed last to first
+        for val in replacement.with[substituted..].iter().rev() {
+            edits.push(Edit::Insert(replacement.range.end, val.clone()));
+        }
+
+        for i in (substituted..removed).rev() {
+            edits.push(Edit::Delete(start + i + 1));
+        }
+
+        for i in (0..substituted).rev() {
+            edits.push(Edit::Substitute(start + i + 1, replacement.with[i].clone()));
+        }
+    }
+
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::replace::*;
+
+    #[test]
+    fn coalesce_edits_test() {
+        // Edits that convert AB to XAYBZ: insertions before, between and after both items
+        let edits = vec![
+            Edit::<u8>::Insert(2, b'Z'),
+            Edit::<u8>::Insert(1, b'Y'),
+            Edit::<u8>::Insert(0, b'X'),
+        ];
+
+        let expected = vec![
+            Replace {
+                range: 0..0,
+                with: vec![b'X'],
+            },
+            Replace {
+                range: 1..1,
+                with: vec![b'Y'],
+            },
+            Replace {
+                range: 2..2,
+                with: vec![b'Z'],
+            },
+        ];
+
+        assert_eq!(coalesce_edits(&edits), expected);
+    }
+
+    #[test]
+    fn coalesce_adjacent_edits_test() {
+        // Edits that turn the RDAY of SATURDAY into DNE, none of which are separated
+        let edits = vec![
+            Edit::<u8>::Substitute(8, b'E'),
+            Edit::<u8>::Delete(7),
+            Edit::<u8>::Delete(6),
+            Edit::<u8>::Insert(5, b'N'),
+            Edit::<u8>::Insert(5, b'D'),
+            Edit::<u8>::Delete(5),
+        ];
+
+        let replacements = coalesce_edits(&edits);
+
+        assert_eq!(
+            replacements,
+            vec![Replace {
+                range: 4..8,
+                with: "DNE".as_bytes().to_vec(),
+            }]
+        );
+        assert_eq!(
+            apply_replacements("SATURDAY".as_bytes(), &replacements),
+            crate::edit::apply_edits("SATURDAY".as_bytes(), &edits)
+        );
+    }
+
+    #[test]
+    fn replacements_to_edits_test() {
+        let replacements = vec![
+            Replace {
+                range: 0..0,
+                with: "XY".as_bytes().to_vec(),
+            },
+            Replace {
+                range: 1..3,
+                with: "Z".as_bytes().to_vec(),
+            },
+        ];
+
+        let edits = replacements_to_edits(&replacements);
+
+        assert_eq!(edits.len(), 4);
+        assert_eq!(coalesce_edits(&edits), replacements);
+        assert_eq!(
+            crate::edit::apply_edits("ABCD".as_bytes(), &edits),
+            "XYAZD".as_bytes()
+        );
+    }
+}
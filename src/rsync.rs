@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use crate::bsdiff::{gap_op, DeltaOp};
+
+// The weak checksum rsync rolls over every window of the target: the sum of the bytes, and the sum
+// of the bytes weighted by their distance from the end of the window, each kept to 16 bits
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct RollingChecksum {
+    a: u32,
+    b: u32,
+    len: u32,
+}
+
+impl RollingChecksum {
+    fn new(window: &[u8]) -> Self {
+        let len = window.len() as u32;
+        let mut a: u32 = 0;
+        let mut b: u32 = 0;
+
+        for (i, &byte) in window.iter().enumerate() {
+            a = a.wrapping_add(byte as u32);
+            b = b.wrapping_add((len - i as u32).wrapping_mul(byte as u32));
+        }
+
+        RollingChecksum { a, b, len }
+    }
+
+    // Slides the window one byte to the right
+    fn roll(&mut self, out: u8, into: u8) {
+        self.a = self.a.wrapping_sub(out as u32).wrapping_add(into as u32);
+        self.b = self
+            .b
+            .wrapping_sub(self.len.wrapping_mul(out as u32))
+            .wrapping_add(self.a);
+    }
+
+    fn value(&self) -> u32 {
+        (self.a & 0xffff) | (self.b << 16)
+    }
+}
+
+/// Returns a binary delta that transforms the source into the target, in the style of rsync.
+///
+/// The source is cut into blocks of `block_size` bytes, and a rolling checksum is slid over the
+/// target one byte at a time to find them wherever they moved to, so every matching block is
+/// found in a single pass over the target. Matched blocks are copied, and Levenshtein edits are
+/// only computed for the gaps between them, in the same way as `binary_delta` does, so this
+/// scales to files far too large for a distance matrix. Compared to `binary_delta`, it needs less
+/// memory and time, but only finds matches that contain whole blocks of the source.
+///
+/// The delta is applied with `apply_binary_delta`.
+///
+/// # Arguments
+///
+/// * `source` - The source bytes
+/// * `target` - The target bytes
+/// * `block_size` - The size of the blocks of the source to look for. Smaller blocks find more
+///   matches, at the cost of a bigger index and more false hits of the checksum.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::DeltaOp;
+///
+/// let source = "the quick brown fox jumps over the lazy dog".as_bytes();
+/// let target = "a quick brown fox jumps over the lazy dog!".as_bytes();
+///
+/// let delta = levenshtein::rsync_delta(source, target, 4);
+///
+/// // "the " was replaced, every other block of the source is still there
+/// assert!(matches!(delta[1], DeltaOp::Copy { from: 4, len: 36 }));
+/// assert_eq!(levenshtein::apply_binary_delta(source, &delta), target);
+/// ```
+pub fn rsync_delta(source: &[u8], target: &[u8], block_size: usize) -> Vec<DeltaOp> {
+    let block_size = std::cmp::max(block_size, 1);
+
+    // The starts of the blocks of the source with each checksum
+    let mut blocks = HashMap::<u32, Vec<usize>>::new();
+    for (i, block) in source.chunks_exact(block_size).enumerate() {
+        blocks
+            .entry(RollingChecksum::new(block).value())
+            .or_default()
+            .push(i * block_size);
+    }
+
+    let mut ops = Vec::new();
+
+    // Where the previous match ended, in the source and in the target
+    let mut source_end = 0;
+    let mut gap_start = 0;
+    let mut pos = 0;
+
+    let mut checksum = None;
+
+    while pos + block_size <= target.len() {
+        let window = &target[pos..pos + block_size];
+        let value = checksum
+            .get_or_insert_with(|| RollingChecksum::new(window))
+            .value();
+
+        // Prefer the block right after the previous match, so that consecutive copies merge
+        let matched = blocks.get(&value).and_then(|starts| {
+            let same = |from: &&usize| &source[**from..**from + block_size] == window;
+
+            starts
+                .iter()
+                .filter(|&&from| from == source_end)
+                .find(same)
+                .or_else(|| starts.iter().find(same))
+                .copied()
+        });
+
+        let from = match matched {
+            Some(from) => from,
+            None => {
+                if let Some(&into) = target.get(pos + block_size) {
+                    checksum.as_mut().unwrap().roll(target[pos], into);
+                }
+
+                pos += 1;
+                continue;
+            }
+        };
+
+        if gap_start < pos {
+            ops.push(gap_op(
+                source,
+                source_end,
+                Some(from),
+                &target[gap_start..pos],
+            ));
+        }
+
+        match ops.last_mut() {
+            Some(DeltaOp::Copy {
+                from: previous,
+                len,
+            }) if *previous + *len == from => {
+                *len += block_size;
+            }
+            _ => ops.push(DeltaOp::Copy {
+                from,
+                len: block_size,
+            }),
+        }
+
+        source_end = from + block_size;
+        pos += block_size;
+        gap_start = pos;
+        checksum = None;
+    }
+
+    if gap_start < target.len() {
+        ops.push(gap_op(source, source_end, None, &target[gap_start..]));
+    }
+
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bsdiff::apply_binary_delta;
+    use crate::rsync::*;
+
+    #[test]
+    fn rolling_checksum_test() {
+        let bytes = "the quick brown fox".as_bytes();
+        let mut checksum = RollingChecksum::new(&bytes[..5]);
+
+        for start in 1..bytes.len() - 4 {
+            checksum.roll(bytes[start - 1], bytes[start + 4]);
+            assert_eq!(checksum, RollingChecksum::new(&bytes[start..start + 5]));
+        }
+    }
+
+    #[test]
+    fn rsync_delta_test() {
+        let source = crate::util::random_bytes(100_000);
+
+        // A few bytes changed, a stretch removed and another moved to the front
+        let mut target = source[60_000..61_000].to_vec();
+        target.extend_from_slice(&source[..30_000]);
+        target.extend_from_slice(b"NEW");
+        target.extend_from_slice(&source[30_010..60_000]);
+        target.extend_from_slice(&source[70_000..]);
+
+        let delta = rsync_delta(&source, &target, 64);
+        assert_eq!(apply_binary_delta(&source, &delta), target);
+
+        let copied: usize = delta
+            .iter()
+            .map(|op| match op {
+                DeltaOp::Copy { len, .. } => *len,
+                _ => 0,
+            })
+            .sum();
+        assert!(copied > target.len() - 4 * 64);
+
+        // Inputs shorter than a block are inserted whole
+        assert_eq!(
+            rsync_delta(&source[..10], b"abc", 64),
+            vec![DeltaOp::Insert(b"abc".to_vec())]
+        );
+        assert!(rsync_delta(&source, &[], 64).is_empty());
+    }
+}
@@ -0,0 +1,327 @@
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::slice::Iter;
+
+use crate::edit::{apply_edits, Edit};
+use crate::replace::{coalesce_edits, Replace};
+use crate::weighted::{edit_costs, Cost, CostModel};
+
+/// A sequence of edits that transforms a source sequence into a target sequence.
+///
+/// This wraps the `Vec<Edit<T>>` returned by `generate_edits` with the helpers most callers end up
+/// writing themselves. The edits are kept in the order they were generated in.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::EditScript;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+///
+/// let script = EditScript::from(edits);
+///
+/// assert_eq!(script.len(), 3);
+/// assert_eq!(script.apply(s1.as_bytes()), s2.as_bytes());
+/// ```
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct EditScript<T: PartialEq> {
+    edits: Vec<Edit<T>>,
+}
+
+impl<T: PartialEq> EditScript<T> {
+    /// Returns an empty edit script, which leaves any source unchanged.
+    pub fn new() -> Self {
+        EditScript { edits: Vec::new() }
+    }
+
+    /// Returns the number of edits in the script.
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// Returns true if the script contains no edits.
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Returns the cost of the script, where every edit costs 1. For a script produced by
+    /// `generate_edits` this is the Levenshtein distance.
+    pub fn cost(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// Returns the cost of the script under a cost model.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence the script applies to
+    /// * `costs` - The cost model, e.g. `Weights`
+    ///
+    /// # Panics
+    ///
+    /// Panics if a deletion or substitution refers to an index outside the source, i.e. if the
+    /// script wasn't generated for this source.
+    pub fn cost_with<C: Cost, M: CostModel<T, C>>(&self, source: &[T], costs: &M) -> C {
+        edit_costs(source, &self.edits, costs)
+            .into_iter()
+            .fold(C::zero(), |total, cost| total + cost)
+    }
+
+    /// Returns an iterator over the edits in the script.
+    pub fn iter_ops(&self) -> Iter<'_, Edit<T>> {
+        self.edits.iter()
+    }
+
+    /// Returns the edits as a slice.
+    pub fn as_slice(&self) -> &[Edit<T>] {
+        &self.edits
+    }
+
+    /// Consumes the script and returns the underlying vector of edits.
+    pub fn into_vec(self) -> Vec<Edit<T>> {
+        self.edits
+    }
+}
+
+impl<T: Clone + PartialEq> EditScript<T> {
+    /// Applies the script to the source sequence, and returns a vector representing the target
+    /// sequence. See `apply_edits`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source sequence
+    pub fn apply(&self, source: &[T]) -> Vec<T> {
+        apply_edits(source, &self.edits)
+    }
+
+    /// Coalesces the script into range replacements. See `coalesce_edits`.
+    pub fn coalesce(&self) -> Vec<Replace<T>> {
+        coalesce_edits(&self.edits)
+    }
+
+    /// Summarizes the script. See `EditStats`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_len` - The length of the source sequence the script applies to
+    pub fn stats(&self, source_len: usize) -> EditStats {
+        EditStats::new(source_len, &self.edits)
+    }
+}
+
+impl<T: PartialEq> Default for EditScript<T> {
+    fn default() -> Self {
+        EditScript::new()
+    }
+}
+
+impl<T: PartialEq> From<Vec<Edit<T>>> for EditScript<T> {
+    fn from(edits: Vec<Edit<T>>) -> Self {
+        EditScript { edits }
+    }
+}
+
+impl<T: PartialEq> From<EditScript<T>> for Vec<Edit<T>> {
+    fn from(script: EditScript<T>) -> Self {
+        script.edits
+    }
+}
+
+impl<T: PartialEq> FromIterator<Edit<T>> for EditScript<T> {
+    fn from_iter<I: IntoIterator<Item = Edit<T>>>(iter: I) -> Self {
+        EditScript {
+            edits: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T: PartialEq> IntoIterator for EditScript<T> {
+    type Item = Edit<T>;
+    type IntoIter = alloc::vec::IntoIter<Edit<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.edits.into_iter()
+    }
+}
+
+impl<'a, T: PartialEq> IntoIterator for &'a EditScript<T> {
+    type Item = &'a Edit<T>;
+    type IntoIter = Iter<'a, Edit<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.edits.iter()
+    }
+}
+
+/// A summary of an edit script.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::EditStats;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+///
+/// let stats = EditStats::new(s1.len(), &edits);
+///
+/// assert_eq!(stats.deletes, 2);
+/// assert_eq!(stats.substitutes, 1);
+/// assert_eq!(stats.cost, 3);
+/// assert_eq!(stats.similarity, 1.0 - 3.0 / 8.0);
+/// assert_eq!(stats.longest_unchanged_run, 3); // DAY
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EditStats {
+    /// The number of insertions
+    pub inserts: usize,
+    /// The number of deletions
+    pub deletes: usize,
+    /// The number of substitutions
+    pub substitutes: usize,
+    /// The total cost, where every edit costs 1
+    pub cost: usize,
+    /// How similar source and target are, from 0.0 (nothing in common) to 1.0 (identical): one
+    /// minus the cost divided by the length of the longer of the two
+    pub similarity: f64,
+    /// The length of the longest run of source items that are kept as they are, without any
+    /// insertions in between
+    pub longest_unchanged_run: usize,
+}
+
+impl EditStats {
+    /// Computes the summary of a sequence of edits.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_len` - The length of the source sequence the edits apply to
+    /// * `edits` - The edits, as generated by `generate_edits`
+    pub fn new<T: Clone + PartialEq>(source_len: usize, edits: &[Edit<T>]) -> Self {
+        let mut inserts = 0;
+        let mut deletes = 0;
+        let mut substitutes = 0;
+
+        for edit in edits {
+            match edit {
+                Edit::Insert(..) => inserts += 1,
+                Edit::Delete(..) => deletes += 1,
+                Edit::Substitute(..) => substitutes += 1,
+            }
+        }
+
+        let cost = edits.len();
+        let target_len = source_len + inserts - deletes;
+
+        let similarity = match core::cmp::max(source_len, target_len) {
+            0 => 1.0,
+            longest => 1.0 - cost as f64 / longest as f64,
+        };
+
+        // The unchanged runs lie between the replacements
+        let mut longest_unchanged_run = 0;
+        let mut run_start = 0;
+
+        for replacement in coalesce_edits(edits) {
+            longest_unchanged_run =
+                core::cmp::max(longest_unchanged_run, replacement.range.start - run_start);
+            run_start = replacement.range.end;
+        }
+
+        longest_unchanged_run = core::cmp::max(longest_unchanged_run, source_len - run_start);
+
+        EditStats {
+            inserts,
+            deletes,
+            substitutes,
+            cost,
+            similarity,
+            longest_unchanged_run,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::script::*;
+    use crate::weighted::Weights;
+
+    #[test]
+    fn edit_script_test() {
+        let s1 = "SATURDAY";
+        let expected_s2 = "SUNDAY";
+
+        // Edits that convert SATURDAY to SUNDAY
+        let edits = vec![
+            Edit::<u8>::Substitute(5, 78),
+            Edit::<u8>::Delete(3),
+            Edit::<u8>::Delete(2),
+        ];
+
+        let script = EditScript::from(edits.clone());
+
+        assert_eq!(script.len(), 3);
+        assert!(!script.is_empty());
+        assert_eq!(script.cost(), 3);
+        assert_eq!(script.iter_ops().count(), 3);
+        assert_eq!(script.apply(s1.as_bytes()), expected_s2.as_bytes());
+
+        let weights = Weights {
+            insert: 1,
+            delete: 2,
+            substitute: 3,
+        };
+        assert_eq!(script.cost_with(s1.as_bytes(), &weights), 7);
+
+        assert!(Vec::from(script) == edits);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cost_with_out_of_range_test() {
+        let script = EditScript::from(vec![Edit::<u8>::Delete(7)]);
+
+        script.cost_with("SUNDAY".as_bytes(), &Weights::uniform(1));
+    }
+
+    #[test]
+    fn empty_edit_script_test() {
+        let script = EditScript::<u8>::new();
+
+        assert!(script.is_empty());
+        assert_eq!(script.apply("SUNDAY".as_bytes()), "SUNDAY".as_bytes());
+    }
+
+    #[test]
+    fn edit_stats_test() {
+        // Edits that convert AB to XAYBZ: insertions before, between and after both items
+        let edits = vec![
+            Edit::<u8>::Insert(2, b'Z'),
+            Edit::<u8>::Insert(1, b'Y'),
+            Edit::<u8>::Insert(0, b'X'),
+        ];
+
+        let stats = EditScript::from(edits).stats(2);
+
+        assert_eq!(stats.inserts, 3);
+        assert_eq!(stats.deletes, 0);
+        assert_eq!(stats.cost, 3);
+        assert_eq!(stats.similarity, 1.0 - 3.0 / 5.0);
+        assert_eq!(stats.longest_unchanged_run, 1);
+
+        let stats = EditScript::<u8>::new().stats(0);
+        assert_eq!(stats.similarity, 1.0);
+        assert_eq!(stats.longest_unchanged_run, 0);
+    }
+}
@@ -0,0 +1,146 @@
+use std::io::{self, Read, Write};
+
+use crate::edit::{in_source_order, Edit};
+use crate::patch::PatchError;
+
+// Copies exactly `len` bytes from the source to the sink
+fn copy_exact<R: Read, W: Write>(source: &mut R, sink: &mut W, len: usize) -> io::Result<()> {
+    let copied = io::copy(&mut source.by_ref().take(len as u64), sink)?;
+
+    if copied < len as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the source is shorter than the edits expect",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Applies a sequence of edits on a source of bytes read from `source`, and writes the target to
+/// `sink`.
+///
+/// The source is read and the target written in a single pass, so neither has to fit in memory;
+/// only the edits do. Bytes are written as they are produced, so wrapping a sink that isn't
+/// buffered in a `BufWriter` is a good idea.
+///
+/// Returns an error if reading or writing fails, if the source ends before an edit that refers to
+/// it, if an edit deletes or substitutes byte 0, or if two edits delete or substitute the same
+/// byte. The last two are errors of kind `InvalidInput` that wrap `PatchError::InvalidIndex` and
+/// `PatchError::ConflictingEdits`, and are only found once the bytes in front of the edits have
+/// been written.
+///
+/// # Arguments
+///
+/// * `source` - The source bytes
+/// * `edits` - The edits, as generated by `generate_edits`
+/// * `sink` - Where the target bytes are written
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY".as_bytes();
+/// let s2 = "SUNDAY".as_bytes();
+///
+/// let (_, matrix) = levenshtein::distance(s1, s2);
+/// let edits = levenshtein::generate_edits(s1, s2, &matrix).unwrap();
+///
+/// let mut target = Vec::new();
+/// levenshtein::apply_edits_streaming(s1, &edits, &mut target).unwrap();
+///
+/// assert_eq!(target, s2);
+/// ```
+pub fn apply_edits_streaming<R: Read, W: Write>(
+    mut source: R,
+    edits: &[Edit<u8>],
+    mut sink: W,
+) -> io::Result<()> {
+    // The first source byte that hasn't been copied or edited yet
+    let mut next = 0;
+
+    for edit in in_source_order(edits, 1) {
+        match edit {
+            Edit::Insert(idx, val) => {
+                if *idx > next {
+                    copy_exact(&mut source, &mut sink, idx - next)?;
+                    next = *idx;
+                }
+                sink.write_all(&[*val])?;
+            }
+            Edit::Delete(idx) | Edit::Substitute(idx, _) => {
+                // Indices of deleted and substituted bytes start at 1
+                if *idx == 0 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        PatchError::InvalidIndex(0),
+                    ));
+                }
+
+                let byte = idx - 1;
+
+                // The byte was already deleted or substituted
+                if byte < next {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        PatchError::ConflictingEdits(*idx),
+                    ));
+                }
+
+                copy_exact(&mut source, &mut sink, byte - next)?;
+                copy_exact(&mut source, &mut io::sink(), 1)?;
+                next = byte + 1;
+
+                if let Edit::Substitute(_, val) = edit {
+                    sink.write_all(&[*val])?;
+                }
+            }
+        }
+    }
+
+    io::copy(&mut source, &mut sink)?;
+
+    sink.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stream::*;
+
+    #[test]
+    fn apply_edits_streaming_test() {
+        let source = "kitten sitting on the mitten".as_bytes();
+        let target = "sitting kitten in the mittens".as_bytes();
+
+        let (_, matrix) = crate::distance(source, target);
+        let edits = crate::generate_edits(source, target, &matrix).unwrap();
+
+        let mut out = Vec::new();
+        apply_edits_streaming(source, &edits, &mut out).unwrap();
+        assert_eq!(out, target);
+
+        // The source is too short for the edits
+        let error = apply_edits_streaming(&source[..10], &edits, io::sink()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+
+        // Two edits of the same byte
+        let edits = vec![Edit::Substitute(3, b'M'), Edit::Delete(3)];
+        let error = apply_edits_streaming(source, &edits, io::sink()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(
+            error.into_inner().unwrap().downcast_ref::<PatchError>(),
+            Some(&PatchError::ConflictingEdits(3))
+        );
+
+        // Bytes are counted from 1
+        for edits in [vec![Edit::Delete(0)], vec![Edit::Substitute(0, b'M')]] {
+            let error = apply_edits_streaming(source, &edits, io::sink()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+            assert_eq!(
+                error.into_inner().unwrap().downcast_ref::<PatchError>(),
+                Some(&PatchError::InvalidIndex(0))
+            );
+        }
+    }
+}
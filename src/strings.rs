@@ -0,0 +1,450 @@
+#[cfg(any(feature = "unicode-segmentation", feature = "unicode-normalization"))]
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::UnicodeNormalization;
+#[cfg(feature = "unicode-segmentation")]
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::case_fold::fold_char;
+use crate::distance::levenshtein_two_rows;
+#[cfg(feature = "unicode-segmentation")]
+use crate::edit::apply_edits;
+pub use crate::edit::apply_edits_str;
+use crate::edit::{generate_edits_stripped, Edit};
+use crate::keyed::{distance_by_key, generate_edits_by_key};
+use crate::util::strip_common_affix;
+
+// The edits generate_edits finds between two sequences, once their common prefix and suffix are
+// stripped
+fn stripped_edits<T: Clone + PartialEq>(source: &[T], target: &[T]) -> Vec<Edit<T>> {
+    let stripped = strip_common_affix(source, target);
+    let (_, matrix) = crate::distance(stripped.source, stripped.target);
+
+    generate_edits_stripped(&stripped, &matrix)
+        .expect("the matrix was computed for these sequences")
+}
+
+/// Returns the Levenshtein distance between two strings, counting characters rather than bytes.
+///
+/// Comparing the bytes of UTF-8 strings counts a change to a character outside ASCII as several
+/// edits, one per byte, while this counts it as one. Strings that are both ASCII are compared
+/// byte by byte, without collecting their characters first.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// assert_eq!(levenshtein::distance_str("SATURDAY", "SUNDAY"), 3);
+///
+/// // ß and e are two and one bytes long, but a single substitution
+/// assert_eq!(levenshtein::distance_str("Grüße", "Grüee"), 1);
+/// assert_eq!(levenshtein::distance_only("Grüße".as_bytes(), "Grüee".as_bytes()), 2);
+/// ```
+pub fn distance_str(source: &str, target: &str) -> usize {
+    if source.is_ascii() && target.is_ascii() {
+        return levenshtein_two_rows(source.as_bytes(), target.as_bytes());
+    }
+
+    let source: Vec<char> = source.chars().collect();
+    let target: Vec<char> = target.chars().collect();
+
+    levenshtein_two_rows(&source, &target)
+}
+
+/// Generate a vector of edits that transform the source string into the target string, on their
+/// characters rather than their bytes.
+///
+/// The indices of the edits count characters, like those `generate_edits` returns for the
+/// characters of the strings, so applying them with `apply_edits_str` always gives a valid
+/// string: edits of the bytes of UTF-8 strings can split up the bytes of a character. The edits
+/// are the ones `generate_edits_stripped` finds once the common prefix and suffix are stripped.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+///
+/// let edits = levenshtein::generate_edits_str("Grüße", "Grüezi");
+///
+/// // ß, the 4th character, is deleted, and z and i are added after the e that follows it
+/// assert_eq!(edits, vec![Edit::Insert(5, 'i'), Edit::Insert(5, 'z'), Edit::Delete(4)]);
+/// assert_eq!(levenshtein::apply_edits_str("Grüße", &edits), "Grüezi");
+/// ```
+pub fn generate_edits_str(source: &str, target: &str) -> Vec<Edit<char>> {
+    let source: Vec<char> = source.chars().collect();
+    let target: Vec<char> = target.chars().collect();
+
+    stripped_edits(&source, &target)
+}
+
+/// Returns the Levenshtein distance between two strings, counting characters, ignoring case.
+///
+/// Characters are compared by their full Unicode case folding, see `fold_case`, so "ß" and "ẞ",
+/// or "ς", "σ" and "Σ" are equal, not just ASCII letters. A character that folds to several, like
+/// "ß" to "ss", is still a single character: "Straße" is one substitution and an insertion away
+/// from "STRASSE".
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// assert_eq!(levenshtein::distance_str_caseless("Saturday", "SUNDAY"), 3);
+/// assert_eq!(levenshtein::distance_str_caseless("ΣΊΣΥΦΟΣ", "σίσυφος"), 0);
+/// ```
+pub fn distance_str_caseless(source: &str, target: &str) -> usize {
+    let source: Vec<char> = source.chars().collect();
+    let target: Vec<char> = target.chars().collect();
+
+    distance_by_key(&source, &target, |&c| fold_char(c))
+}
+
+/// Generate a vector of edits that transform the source string into the target string, on their
+/// characters, ignoring case. See `distance_str_caseless`.
+///
+/// The characters are compared by their case folding, but the edits hold the characters of the
+/// target as they are, and apply to the source itself: applying them with `apply_edits_str` gives
+/// a string that only differs from the target in case, and keeps the case of the source wherever
+/// no edit was needed.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+///
+/// let edits = levenshtein::generate_edits_str_caseless("Hello World", "HELLO WORLDS");
+/// assert_eq!(edits, vec![Edit::Insert(11, 'S')]);
+/// assert_eq!(levenshtein::apply_edits_str("Hello World", &edits), "Hello WorldS");
+/// ```
+pub fn generate_edits_str_caseless(source: &str, target: &str) -> Vec<Edit<char>> {
+    let source: Vec<char> = source.chars().collect();
+    let target: Vec<char> = target.chars().collect();
+
+    generate_edits_by_key(&source, &target, |&c| fold_char(c))
+}
+
+/// Returns the Levenshtein distance between two strings, counting extended grapheme clusters
+/// rather than characters.
+///
+/// A grapheme cluster is what a reader sees as a single character: a letter with its combining
+/// marks, an emoji with its skin tone modifier, or a family of emoji joined with zero width
+/// joiners can all be several characters, which `distance_str` counts separately.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// // e followed by a combining acute accent, and a thumbs up with a skin tone
+/// let source = "cafe\u{301} \u{1F44D}\u{1F3FD}";
+/// let target = "cafe \u{1F44D}";
+///
+/// assert_eq!(levenshtein::distance_graphemes(source, target), 2);
+/// assert_eq!(levenshtein::distance_str(source, target), 2);
+///
+/// // Each cluster is a single substitution, whatever its length in characters
+/// assert_eq!(levenshtein::distance_graphemes(source, "cafa \u{1F44E}"), 2);
+/// assert_eq!(levenshtein::distance_str(source, "cafa \u{1F44E}"), 4);
+/// ```
+#[cfg(feature = "unicode-segmentation")]
+pub fn distance_graphemes(source: &str, target: &str) -> usize {
+    let source: Vec<&str> = source.graphemes(true).collect();
+    let target: Vec<&str> = target.graphemes(true).collect();
+
+    levenshtein_two_rows(&source, &target)
+}
+
+/// Generate a vector of edits that transform the source string into the target string, on their
+/// extended grapheme clusters rather than their characters. See `distance_graphemes`.
+///
+/// The indices of the edits count grapheme clusters, and the new clusters are borrowed from the
+/// target, so a cluster is always inserted, deleted or substituted whole: edits of the
+/// characters can separate an accent from its letter, or break up an emoji sequence.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Edit;
+///
+/// let source = "cafe\u{301}";
+/// let target = "cafe";
+///
+/// let edits = levenshtein::generate_edits_graphemes(source, target);
+/// assert_eq!(edits, vec![Edit::Substitute(4, "e")]);
+/// assert_eq!(levenshtein::apply_edits_graphemes(source, &edits), target);
+///
+/// // On the characters, the accent is deleted on its own
+/// assert_eq!(levenshtein::generate_edits_str(source, target), vec![Edit::Delete(5)]);
+/// ```
+#[cfg(feature = "unicode-segmentation")]
+pub fn generate_edits_graphemes<'a>(source: &'a str, target: &'a str) -> Vec<Edit<&'a str>> {
+    let source: Vec<&str> = source.graphemes(true).collect();
+    let target: Vec<&str> = target.graphemes(true).collect();
+
+    stripped_edits(&source, &target)
+}
+
+/// Applies a sequence of edits on the extended grapheme clusters of a string, and returns the
+/// target string.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `edits` - The edits, as generated by `generate_edits_graphemes`
+#[cfg(feature = "unicode-segmentation")]
+pub fn apply_edits_graphemes(source: &str, edits: &[Edit<&str>]) -> String {
+    let source: Vec<&str> = source.graphemes(true).collect();
+
+    apply_edits(&source, edits).concat()
+}
+
+/// A Unicode normalization form.
+///
+/// The same text can be encoded as different sequences of characters: "é" can be a single
+/// character, or an "e" followed by a combining acute accent. Such canonically equivalent strings
+/// look the same, but their characters differ, so putting both in the same normalization form
+/// before comparing them keeps that difference from counting as an edit.
+#[cfg(feature = "unicode-normalization")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Normalization {
+    /// Canonical composition: characters are composed wherever they can be, so "e" followed by a
+    /// combining acute accent becomes "é"
+    Nfc,
+    /// Canonical decomposition: characters are fully decomposed, so "é" becomes "e" followed by a
+    /// combining acute accent
+    Nfd,
+}
+
+#[cfg(feature = "unicode-normalization")]
+impl Normalization {
+    /// Returns the string in this normalization form.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The string to normalize
+    pub fn normalize(self, text: &str) -> String {
+        self.chars(text).into_iter().collect()
+    }
+
+    // The characters of the string in this normalization form
+    fn chars(self, text: &str) -> Vec<char> {
+        match self {
+            Normalization::Nfc => text.nfc().collect(),
+            Normalization::Nfd => text.nfd().collect(),
+        }
+    }
+}
+
+/// Returns the Levenshtein distance between two strings, counting characters, once both are put
+/// in the given normalization form. See `Normalization`.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+/// * `form` - The normalization form to compare the strings in
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Normalization;
+///
+/// let composed = "caf\u{e9}";
+/// let decomposed = "cafe\u{301}";
+///
+/// assert_eq!(levenshtein::distance_str(composed, decomposed), 2);
+/// assert_eq!(levenshtein::distance_str_normalized(composed, decomposed, Normalization::Nfc), 0);
+/// assert_eq!(levenshtein::distance_str_normalized(composed, decomposed, Normalization::Nfd), 0);
+/// ```
+#[cfg(feature = "unicode-normalization")]
+pub fn distance_str_normalized(source: &str, target: &str, form: Normalization) -> usize {
+    levenshtein_two_rows(&form.chars(source), &form.chars(target))
+}
+
+/// Generate a vector of edits that transform the source string into the target string, on their
+/// characters once both are put in the given normalization form. See `Normalization`.
+///
+/// The edits apply to the normalized source, not to the source itself, since normalizing can
+/// change its number of characters: apply them to `form.normalize(source)` with
+/// `apply_edits_str`, which gives the normalized target.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+/// * `form` - The normalization form to compare the strings in
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{Edit, Normalization};
+///
+/// let source = "cafe\u{301}s";
+/// let target = "caf\u{e9}";
+///
+/// let edits = levenshtein::generate_edits_str_normalized(source, target, Normalization::Nfc);
+/// assert_eq!(edits, vec![Edit::Delete(5)]);
+///
+/// let normalized = Normalization::Nfc.normalize(source);
+/// assert_eq!(levenshtein::apply_edits_str(&normalized, &edits), target);
+/// ```
+#[cfg(feature = "unicode-normalization")]
+pub fn generate_edits_str_normalized(
+    source: &str,
+    target: &str,
+    form: Normalization,
+) -> Vec<Edit<char>> {
+    stripped_edits(&form.chars(source), &form.chars(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::strings::*;
+
+    #[test]
+    fn distance_str_test() {
+        assert_eq!(distance_str("kitten", "sitting"), 3);
+        assert_eq!(distance_str("", "日本語"), 3);
+        assert_eq!(distance_str("日本語", "日本"), 1);
+        assert_eq!(distance_str("naïve", "naive"), 1);
+        assert_eq!(distance_str("🦀🦀", "🦀"), 1);
+    }
+
+    #[test]
+    fn generate_edits_str_test() {
+        let pairs = [
+            ("kitten", "sitting"),
+            ("日本語", "日本"),
+            ("", "🦀 crab"),
+            ("naïve café", "naive cafe"),
+            ("same", "same"),
+        ];
+
+        for (source, target) in pairs.iter() {
+            let edits = generate_edits_str(source, target);
+
+            assert_eq!(edits.len(), distance_str(source, target));
+            assert_eq!(apply_edits_str(source, &edits), *target);
+        }
+    }
+
+    #[test]
+    fn caseless_test() {
+        assert_eq!(distance_str_caseless("kitten", "SITTING"), 3);
+        assert_eq!(distance_str_caseless("STRAẞE", "straße"), 0);
+        assert_eq!(distance_str_caseless("Straße", "STRASSE"), 2);
+        assert_eq!(distance_str_caseless("", "ÉTÉ"), 3);
+
+        let pairs = [
+            ("Ünïcödé", "üNICODE"),
+            ("ΣΊΣΥΦΟΣ", "σίσυφοι"),
+            ("same", "SAME"),
+        ];
+
+        for (source, target) in pairs.iter() {
+            let edits = generate_edits_str_caseless(source, target);
+            let edited = apply_edits_str(source, &edits);
+
+            assert_eq!(edits.len(), distance_str_caseless(source, target));
+            assert_eq!(distance_str_caseless(&edited, target), 0);
+        }
+
+        // The edits keep the case of the source, and carry the case of the target
+        let edits = generate_edits_str_caseless("ΣΊΣΥΦΟΣ", "σίσυφοι");
+        assert_eq!(apply_edits_str("ΣΊΣΥΦΟΣ", &edits), "ΣΊΣΥΦΟι");
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-segmentation")]
+    fn generate_edits_graphemes_test() {
+        // A family joined with zero width joiners, a flag, and a letter with two combining marks
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let source = format!("{} and \u{1F1EB}\u{1F1F7}", family);
+        let target = "o\u{302}\u{301} and \u{1F1E9}\u{1F1EA}";
+
+        assert_eq!(distance_graphemes(&source, target), 2);
+
+        let edits = generate_edits_graphemes(&source, target);
+        assert_eq!(edits.len(), 2);
+        assert_eq!(apply_edits_graphemes(&source, &edits), target);
+
+        assert!(generate_edits_graphemes(target, target).is_empty());
+        assert_eq!(distance_graphemes("", family), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-normalization")]
+    fn normalization_test() {
+        // Å as one character, as A and a combining ring, and as the Angstrom sign
+        let forms = ["\u{c5}", "A\u{30a}", "\u{212b}"];
+
+        for source in forms.iter() {
+            assert_eq!(Normalization::Nfc.normalize(source), "\u{c5}");
+            assert_eq!(Normalization::Nfd.normalize(source), "A\u{30a}");
+
+            for target in forms.iter() {
+                assert_eq!(
+                    distance_str_normalized(source, target, Normalization::Nfc),
+                    0
+                );
+                assert_eq!(
+                    distance_str_normalized(source, target, Normalization::Nfd),
+                    0
+                );
+            }
+        }
+
+        let source = "Ha\u{308}user";
+        let target = "H\u{e4}user";
+
+        for form in [Normalization::Nfc, Normalization::Nfd].iter() {
+            let edits = generate_edits_str_normalized(source, target, *form);
+            assert!(edits.is_empty());
+
+            let edits = generate_edits_str_normalized(source, "Hauser", *form);
+            assert_eq!(
+                edits.len(),
+                distance_str_normalized(source, "Hauser", *form)
+            );
+            assert_eq!(apply_edits_str(&form.normalize(source), &edits), "Hauser");
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::pattern::Pattern;
+
+// Returns every sequence that can be made by deleting at most `k` items of the sequence,
+// including the sequence itself
+fn deletion_variants<T: Clone + Eq + Hash>(items: &[T], k: usize) -> HashSet<Vec<T>> {
+    let mut variants = HashSet::new();
+    variants.insert(items.to_vec());
+
+    let mut level = vec![items.to_vec()];
+
+    for _ in 0..k {
+        let mut next = Vec::new();
+
+        for variant in &level {
+            for i in 0..variant.len() {
+                let mut shorter = variant.clone();
+                shorter.remove(i);
+
+                if variants.insert(shorter.clone()) {
+                    next.push(shorter);
+                }
+            }
+        }
+
+        level = next;
+    }
+
+    variants
+}
+
+/// An index of a corpus of sequences that finds the ones within a small distance of a query with
+/// a handful of hash lookups, as in the SymSpell spelling corrector.
+///
+/// Two sequences are within distance k only if deleting at most k items from each makes them
+/// the same, since a substitution can be undone by deleting the item from both, and an insertion
+/// by deleting it from the other. So the index stores every variant of every sequence with up to
+/// `max_distance` items deleted, and a search looks up the variants of the query instead of
+/// comparing it to the corpus. The candidates found are then checked with their exact distance.
+///
+/// This makes lookups extremely fast, at the cost of memory: a sequence of n items has about
+/// n^k / k! variants, so the index is only practical for short sequences, like words, and a
+/// `max_distance` of 1 to 3. See `TrigramIndex` and `BkTree` for longer sequences.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::SymSpellIndex;
+///
+/// let words = ["SATURDAY", "SUNDAY", "MONDAY", "SATURN"];
+/// let index = SymSpellIndex::new(words.iter().map(|word| word.as_bytes().to_vec()), 2);
+///
+/// assert_eq!(index.search("SUNDAE".as_bytes(), 2), vec![(1, 1)]);
+/// assert_eq!(index.search("MUNDAY".as_bytes(), 1), vec![(1, 1), (2, 1)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SymSpellIndex<T> {
+    items: Vec<Vec<T>>,
+    max_distance: usize,
+    // For every deletion variant, the sequences it was made from
+    variants: HashMap<Vec<T>, Vec<usize>>,
+}
+
+impl<T: Clone + Eq + Hash> SymSpellIndex<T> {
+    /// Returns an index of the sequences, which are numbered in order from 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `corpus` - The sequences to index
+    /// * `max_distance` - The largest distance searches will be able to use
+    pub fn new<I: IntoIterator<Item = Vec<T>>>(corpus: I, max_distance: usize) -> Self {
+        let mut index = SymSpellIndex {
+            items: Vec::new(),
+            max_distance,
+            variants: HashMap::new(),
+        };
+
+        for item in corpus {
+            index.insert(item);
+        }
+
+        index
+    }
+
+    /// Adds a sequence to the index, and returns its number.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The sequence to add
+    pub fn insert(&mut self, item: Vec<T>) -> usize {
+        let id = self.items.len();
+
+        for variant in deletion_variants(&item, self.max_distance) {
+            self.variants.entry(variant).or_default().push(id);
+        }
+
+        self.items.push(item);
+        id
+    }
+
+    /// Returns the number of sequences in the index.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if there are no sequences in the index.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the largest distance searches can use.
+    pub fn max_distance(&self) -> usize {
+        self.max_distance
+    }
+
+    /// Returns the sequence with the given number, if there is one.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The number `insert` returned for the sequence
+    pub fn get(&self, id: usize) -> Option<&[T]> {
+        self.items.get(id).map(|item| item.as_slice())
+    }
+
+    /// Returns the number and distance of every sequence within distance `k` of the query,
+    /// closest first, and in ascending order of number for the same distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The sequence to look for
+    /// * `k` - The maximum distance
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is larger than the `max_distance` the index was built for.
+    pub fn search(&self, query: &[T], k: usize) -> Vec<(usize, usize)> {
+        assert!(
+            k <= self.max_distance,
+            "the index only has variants for distances up to {}",
+            self.max_distance
+        );
+
+        let mut candidates: Vec<usize> = deletion_variants(query, k)
+            .iter()
+            .filter_map(|variant| self.variants.get(variant))
+            .flatten()
+            .copied()
+            .collect();
+
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let pattern = Pattern::new(query);
+
+        let mut matches: Vec<(usize, usize)> = candidates
+            .into_iter()
+            .filter_map(|id| {
+                pattern
+                    .distance_within(&self.items[id], k)
+                    .map(|distance| (id, distance))
+            })
+            .collect();
+
+        matches.sort_by_key(|&(id, distance)| (distance, id));
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::symspell::*;
+
+    #[test]
+    fn deletion_variants_test() {
+        let variants = deletion_variants("abc".as_bytes(), 2);
+
+        // abc, ab, ac, bc, a, b, c
+        assert_eq!(variants.len(), 7);
+        assert!(variants.contains("c".as_bytes()));
+        assert!(!variants.contains("".as_bytes()));
+
+        // Repeated items give fewer distinct variants
+        assert_eq!(deletion_variants("aaa".as_bytes(), 3).len(), 4);
+    }
+
+    #[test]
+    fn symspell_index_test() {
+        let words = ["kitten", "sitting", "mitten", "kit", "", "smitten"];
+        let index = SymSpellIndex::new(words.iter().map(|word| word.as_bytes().to_vec()), 3);
+        assert_eq!(index.len(), 6);
+
+        assert_eq!(
+            index.search("kitten".as_bytes(), 2),
+            vec![(0, 0), (2, 1), (5, 2)]
+        );
+        assert_eq!(index.search("".as_bytes(), 3), vec![(4, 0), (3, 3)]);
+        assert_eq!(index.search("kitten".as_bytes(), 0), vec![(0, 0)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn symspell_index_distance_too_large() {
+        let index = SymSpellIndex::new(vec!["kitten".as_bytes().to_vec()], 1);
+        index.search("sitting".as_bytes(), 2);
+    }
+}
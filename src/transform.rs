@@ -0,0 +1,324 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use crate::edit::Edit;
+use crate::replace::{coalesce_edits, replacements_to_edits, Replace};
+
+// What an edit script does to its source: the items it deletes, and the items it inserts in
+// front of each source item (or at the very end, for the gap after the last item)
+struct Changes<T> {
+    deleted: BTreeSet<usize>,
+    inserted: BTreeMap<usize, Vec<T>>,
+}
+
+impl<T: Clone + PartialEq> Changes<T> {
+    fn new(replacements: Vec<Replace<T>>) -> Self {
+        let mut deleted = BTreeSet::new();
+        let mut inserted = BTreeMap::new();
+
+        for replacement in replacements {
+            deleted.extend(replacement.range.clone());
+
+            if !replacement.with.is_empty() {
+                inserted.insert(replacement.range.start, replacement.with);
+            }
+        }
+
+        Changes { deleted, inserted }
+    }
+
+    // The last gap or item index that is touched
+    fn extent(&self) -> usize {
+        let deleted = self.deleted.iter().next_back().copied().unwrap_or(0);
+        let inserted = self.inserted.keys().next_back().copied().unwrap_or(0);
+
+        core::cmp::max(deleted, inserted)
+    }
+
+    fn inserted_at(&self, gap: usize) -> &[T] {
+        self.inserted.get(&gap).map_or(&[], |items| &items[..])
+    }
+}
+
+// Returns the edits that, applied after `applied`, also carry out `pending`. At every gap, the
+// items inserted by `pending` come first if `pending_first` is true, and last otherwise.
+fn transform_against<T: Clone + PartialEq>(
+    pending: &Changes<T>,
+    applied: &Changes<T>,
+    pending_first: bool,
+    extent: usize,
+) -> Vec<Edit<T>> {
+    let mut replacements = Vec::<Replace<T>>::new();
+
+    // The current position in the sequence that `applied` produced
+    let mut pos = 0;
+
+    for gap in 0..extent + 1 {
+        let already_inserted = applied.inserted_at(gap).len();
+        let to_insert = pending.inserted_at(gap);
+
+        if !to_insert.is_empty() {
+            let at = if pending_first {
+                pos
+            } else {
+                pos + already_inserted
+            };
+
+            replacements.push(Replace {
+                range: at..at,
+                with: to_insert.to_vec(),
+            });
+        }
+
+        pos += already_inserted;
+
+        // The source item after this gap. Deleting an item twice is the same as deleting it once.
+        if applied.deleted.contains(&gap) {
+            continue;
+        }
+
+        if pending.deleted.contains(&gap) {
+            match replacements.last_mut() {
+                Some(last) if last.range.end == pos => last.range.end += 1,
+                _ => replacements.push(Replace {
+                    range: pos..pos + 1,
+                    with: Vec::new(),
+                }),
+            }
+        }
+
+        pos += 1;
+    }
+
+    replacements_to_edits(&replacements)
+}
+
+/// Transforms two edit scripts generated against the same source so that each can be applied
+/// after the other.
+///
+/// Returns `(ours_prime, theirs_prime)`, where `ours_prime` applies to the result of `theirs`, and
+/// `theirs_prime` applies to the result of `ours`. Both orders converge on the same sequence:
+/// items deleted by either side are deleted, and items inserted by both sides are kept. When both
+/// sides insert at the same position, our items come first.
+///
+/// # Arguments
+///
+/// * `ours` - Our edits to the common source
+/// * `theirs` - Their edits to the common source
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let base = "SUNDAY".as_bytes();
+///
+/// let (_, matrix) = levenshtein::distance(base, "MONDAY".as_bytes());
+/// let ours = levenshtein::generate_edits(base, "MONDAY".as_bytes(), &matrix).unwrap();
+///
+/// let (_, matrix) = levenshtein::distance(base, "SUNDAYS".as_bytes());
+/// let theirs = levenshtein::generate_edits(base, "SUNDAYS".as_bytes(), &matrix).unwrap();
+///
+/// let (ours_prime, theirs_prime) = levenshtein::transform(&ours, &theirs);
+///
+/// let ours_then_theirs =
+///     levenshtein::apply_edits(&levenshtein::apply_edits(base, &ours), &theirs_prime);
+/// let theirs_then_ours =
+///     levenshtein::apply_edits(&levenshtein::apply_edits(base, &theirs), &ours_prime);
+///
+/// assert_eq!(ours_then_theirs, "MONDAYS".as_bytes());
+/// assert_eq!(theirs_then_ours, "MONDAYS".as_bytes());
+/// ```
+pub fn transform<T: Clone + PartialEq>(
+    ours: &[Edit<T>],
+    theirs: &[Edit<T>],
+) -> (Vec<Edit<T>>, Vec<Edit<T>>) {
+    let ours = Changes::new(coalesce_edits(ours));
+    let theirs = Changes::new(coalesce_edits(theirs));
+
+    // Beyond this index, neither side changes anything
+    let extent = core::cmp::max(ours.extent(), theirs.extent());
+
+    let ours_prime = transform_against(&ours, &theirs, true, extent);
+    let theirs_prime = transform_against(&theirs, &ours, false, extent);
+
+    (ours_prime, theirs_prime)
+}
+
+/// Represents a conflict found while rebasing an edit script. Indices follow the same 1-indexed
+/// convention as `Edit`.
+#[derive(Debug, PartialEq)]
+pub enum RebaseError {
+    // Both scripts change the source item at this index
+    ConflictingChange(usize),
+    // Both scripts insert items at this index, or one inserts where the other removes items
+    ConflictingInsert(usize),
+}
+
+impl fmt::Display for RebaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RebaseError::ConflictingChange(idx) => {
+                write!(f, "Conflicting changes to the item at index {}", idx)
+            }
+            RebaseError::ConflictingInsert(idx) => {
+                write!(f, "Conflicting insertions at index {}", idx)
+            }
+        }
+    }
+}
+
+impl Error for RebaseError {}
+
+// Returns the conflict between two replacements of the same source, if there is one
+fn conflict<T>(ours: &Replace<T>, theirs: &Replace<T>) -> Option<RebaseError> {
+    // Deleting the same items twice is harmless
+    if ours.with.is_empty() && theirs.with.is_empty() {
+        return None;
+    }
+
+    // A pure insertion conflicts with another insertion at the same place, or with a replacement
+    // of the items around it
+    for (insert, other) in [(ours, theirs), (theirs, ours)] {
+        if insert.range.is_empty() {
+            let at = insert.range.start;
+
+            if other.range.start == at && other.range.is_empty()
+                || other.range.start < at && at < other.range.end
+            {
+                return Some(RebaseError::ConflictingInsert(at));
+            }
+
+            return None;
+        }
+    }
+
+    if ours.range.start < theirs.range.end && theirs.range.start < ours.range.end {
+        let first = core::cmp::max(ours.range.start, theirs.range.start);
+        return Some(RebaseError::ConflictingChange(first + 1));
+    }
+
+    None
+}
+
+/// Rebases an edit script onto a source that has since been modified.
+///
+/// `edits` were generated against some source, and `source_changes` transform that same source
+/// into its current version. The returned edits carry out `edits` on the current version, with
+/// all indices shifted accordingly. Changes that were made on both sides are only applied once.
+///
+/// Returns an error if both scripts change the same items in different ways, or insert items at
+/// the same place, since there's no way to tell which should win.
+///
+/// # Arguments
+///
+/// * `edits` - The edits to rebase
+/// * `source_changes` - The edits that were applied to the source since `edits` were generated
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let old_source = "SATURDAY".as_bytes();
+/// let new_source = "ON SATURDAY".as_bytes();
+///
+/// let (_, matrix) = levenshtein::distance(old_source, "SUNDAY".as_bytes());
+/// let edits = levenshtein::generate_edits(old_source, "SUNDAY".as_bytes(), &matrix).unwrap();
+///
+/// let (_, matrix) = levenshtein::distance(old_source, new_source);
+/// let source_changes = levenshtein::generate_edits(old_source, new_source, &matrix).unwrap();
+///
+/// let rebased = levenshtein::rebase_edits(&edits, &source_changes).unwrap();
+/// assert_eq!(levenshtein::apply_edits(new_source, &rebased), "ON SUNDAY".as_bytes());
+/// ```
+pub fn rebase_edits<T: Clone + PartialEq>(
+    edits: &[Edit<T>],
+    source_changes: &[Edit<T>],
+) -> Result<Vec<Edit<T>>, RebaseError> {
+    let ours = coalesce_edits(edits);
+    let theirs = coalesce_edits(source_changes);
+
+    let mut pending = Vec::<Replace<T>>::with_capacity(ours.len());
+
+    for replacement in ours {
+        if theirs.contains(&replacement) {
+            continue;
+        }
+
+        if let Some(error) = theirs.iter().find_map(|t| conflict(&replacement, t)) {
+            return Err(error);
+        }
+
+        pending.push(replacement);
+    }
+
+    let pending = Changes::new(pending);
+    let applied = Changes::new(theirs);
+
+    let extent = core::cmp::max(pending.extent(), applied.extent());
+
+    Ok(transform_against(&pending, &applied, false, extent))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edit::apply_edits;
+    use crate::transform::*;
+
+    #[test]
+    fn transform_test() {
+        let base = "ABCD".as_bytes();
+
+        // Both sides delete B, and insert at the same position
+        let ours = vec![Edit::<u8>::Insert(2, b'X'), Edit::<u8>::Delete(2)];
+        let theirs = vec![
+            Edit::<u8>::Substitute(4, b'Z'),
+            Edit::<u8>::Insert(2, b'Y'),
+            Edit::<u8>::Delete(2),
+        ];
+
+        let (ours_prime, theirs_prime) = transform(&ours, &theirs);
+
+        let ours_then_theirs = apply_edits(&apply_edits(base, &ours), &theirs_prime);
+        let theirs_then_ours = apply_edits(&apply_edits(base, &theirs), &ours_prime);
+
+        assert_eq!(ours_then_theirs, "AXYCZ".as_bytes());
+        assert_eq!(theirs_then_ours, "AXYCZ".as_bytes());
+    }
+
+    #[test]
+    fn rebase_edits_test() {
+        let old_source = "ABCD".as_bytes();
+
+        // Replace B with X, and delete D
+        let edits = vec![Edit::<u8>::Delete(4), Edit::<u8>::Substitute(2, b'X')];
+
+        // Meanwhile, Y was inserted at the start and D was deleted
+        let source_changes = vec![Edit::<u8>::Delete(4), Edit::<u8>::Insert(0, b'Y')];
+        let new_source = apply_edits(old_source, &source_changes);
+
+        let rebased = rebase_edits(&edits, &source_changes).unwrap();
+
+        assert_eq!(apply_edits(&new_source, &rebased), "YAXC".as_bytes());
+    }
+
+    #[test]
+    fn rebase_edits_conflict_test() {
+        let edits = vec![Edit::<u8>::Substitute(2, b'X')];
+
+        let changed = vec![Edit::<u8>::Substitute(2, b'Y')];
+        let inserted = vec![Edit::<u8>::Insert(2, b'Y')];
+
+        assert!(matches!(
+            rebase_edits(&edits, &changed),
+            Err(RebaseError::ConflictingChange(2))
+        ));
+        assert!(rebase_edits(&edits, &inserted).is_ok());
+
+        // The same change on both sides is only applied once
+        assert!(rebase_edits(&inserted, &inserted).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::automaton::{AutomatonState, LevenshteinAutomaton};
+
+#[derive(Clone, Debug)]
+struct TrieNode<T> {
+    children: BTreeMap<T, usize>,
+    // True if a sequence ends at this node
+    terminal: bool,
+}
+
+impl<T> TrieNode<T> {
+    fn new() -> Self {
+        TrieNode {
+            children: BTreeMap::new(),
+            terminal: false,
+        }
+    }
+}
+
+/// A dictionary of sequences stored as a trie, so that sequences with a common prefix share the
+/// nodes for it.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Trie;
+///
+/// let words = ["SATURDAY", "SATURN", "SUNDAY", "MONDAY"];
+/// let trie = Trie::new(words.iter().map(|word| word.as_bytes().to_vec()));
+///
+/// let found = trie.fuzzy_search("SATURNE".as_bytes(), 2);
+/// assert_eq!(found, vec![("SATURN".as_bytes().to_vec(), 1)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Trie<T> {
+    // The root is the first node
+    nodes: Vec<TrieNode<T>>,
+    len: usize,
+}
+
+impl<T: Clone + Ord> Trie<T> {
+    /// Returns a trie of the sequences.
+    ///
+    /// # Arguments
+    ///
+    /// * `corpus` - The sequences to add to the trie
+    pub fn new<I: IntoIterator<Item = Vec<T>>>(corpus: I) -> Self {
+        let mut trie = Trie {
+            nodes: vec![TrieNode::new()],
+            len: 0,
+        };
+
+        for item in corpus {
+            trie.insert(item);
+        }
+
+        trie
+    }
+
+    /// Adds a sequence to the trie. Returns true if it was added, and false if it was already
+    /// there.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The sequence to add
+    pub fn insert(&mut self, item: Vec<T>) -> bool {
+        let mut current = 0;
+
+        for value in item {
+            current = match self.nodes[current].children.get(&value) {
+                Some(&child) => child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(TrieNode::new());
+                    self.nodes[current].children.insert(value, child);
+                    child
+                }
+            };
+        }
+
+        let added = !self.nodes[current].terminal;
+        self.nodes[current].terminal = true;
+        self.len += added as usize;
+
+        added
+    }
+
+    /// Returns the number of sequences in the trie.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no sequences in the trie.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if the sequence is in the trie.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The sequence to look for
+    pub fn contains(&self, item: &[T]) -> bool {
+        let mut current = 0;
+
+        for value in item {
+            match self.nodes[current].children.get(value) {
+                Some(&child) => current = child,
+                None => return false,
+            }
+        }
+
+        self.nodes[current].terminal
+    }
+
+    /// Returns every sequence in the trie that the automaton accepts, with its distance, in
+    /// ascending order of sequence.
+    ///
+    /// The trie and the automaton are walked together, so every node is visited at most once,
+    /// and the branches where the automaton can no longer match are skipped altogether. This
+    /// usually visits a small fraction of a large dictionary.
+    ///
+    /// # Arguments
+    ///
+    /// * `automaton` - The automaton to run
+    pub fn search(&self, automaton: &LevenshteinAutomaton<'_, T>) -> Vec<(Vec<T>, usize)> {
+        let mut found = Vec::new();
+
+        // The item leading to each node, so the sequences can be rebuilt on the way
+        let mut path = Vec::new();
+        let mut pending: Vec<(usize, usize, Option<&T>, AutomatonState)> =
+            vec![(0, 0, None, automaton.start())];
+
+        while let Some((current, depth, item, state)) = pending.pop() {
+            path.truncate(depth);
+            path.extend(item.cloned());
+
+            let node = &self.nodes[current];
+
+            if node.terminal {
+                if let Some(distance) = automaton.distance(&state) {
+                    found.push((path.clone(), distance));
+                }
+            }
+
+            if !automaton.can_match(&state) {
+                continue;
+            }
+
+            // The children are pushed last to first, so they are visited in order
+            for (value, &child) in node.children.iter().rev() {
+                let next = automaton.step(&state, value);
+                pending.push((child, path.len(), Some(value), next));
+            }
+        }
+
+        found
+    }
+
+    /// Returns every sequence in the trie within distance `k` of the query, with its distance,
+    /// closest first, and in ascending order of sequence for the same distance. See `search`.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The sequence to look for
+    /// * `k` - The maximum distance
+    pub fn fuzzy_search(&self, query: &[T], k: usize) -> Vec<(Vec<T>, usize)> {
+        let mut found = self.search(&LevenshteinAutomaton::new(query, k));
+
+        // Sorting is stable, and the sequences are already in order
+        found.sort_by_key(|&(_, distance)| distance);
+        found
+    }
+
+    /// Returns the distance between the query and every sequence in the trie, in ascending order
+    /// of sequence.
+    ///
+    /// Each row of the distance matrix only depends on the items of the sequence so far, so the
+    /// rows for a prefix are computed once and shared by every sequence that starts with it: the
+    /// work is proportional to the number of nodes in the trie rather than to the total length of
+    /// the sequences, which makes scoring a query against a dictionary of similar words much
+    /// cheaper than calling `distance` for each. Use `fuzzy_search` if only the close ones are
+    /// needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query sequence
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use levenshtein_diff as levenshtein;
+    /// use levenshtein::Trie;
+    ///
+    /// let words = ["SATURDAY", "SATURN", "SUNDAY"];
+    /// let trie = Trie::new(words.iter().map(|word| word.as_bytes().to_vec()));
+    ///
+    /// let distances: Vec<usize> = trie
+    ///     .distances("SATURNE".as_bytes())
+    ///     .into_iter()
+    ///     .map(|(_, distance)| distance)
+    ///     .collect();
+    ///
+    /// // SATURDAY, SATURN and SUNDAY
+    /// assert_eq!(distances, vec![3, 1, 6]);
+    /// ```
+    pub fn distances(&self, query: &[T]) -> Vec<(Vec<T>, usize)> {
+        // An automaton that accepts everything never prunes a branch
+        self.search(&LevenshteinAutomaton::new(query, usize::MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trie::*;
+
+    #[test]
+    fn trie_test() {
+        let words = ["kitten", "sitting", "mitten", "kit", "", "smitten"];
+        let mut trie = Trie::new(words.iter().map(|word| word.as_bytes().to_vec()));
+        assert_eq!(trie.len(), 6);
+
+        assert!(!trie.insert("kit".as_bytes().to_vec()));
+        assert!(trie.contains("kit".as_bytes()));
+        assert!(trie.contains("".as_bytes()));
+        assert!(!trie.contains("kitt".as_bytes()));
+
+        let found = trie.fuzzy_search("kitten".as_bytes(), 2);
+        let found: Vec<(&[u8], usize)> = found.iter().map(|(w, d)| (w.as_slice(), *d)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("kitten".as_bytes(), 0),
+                ("mitten".as_bytes(), 1),
+                ("smitten".as_bytes(), 2)
+            ]
+        );
+
+        // Sequences in ascending order, including the empty one
+        let found = trie.search(&LevenshteinAutomaton::new("".as_bytes(), 3));
+        assert_eq!(found, vec![(Vec::new(), 0), ("kit".as_bytes().to_vec(), 3)]);
+    }
+
+    #[test]
+    fn trie_distances_test() {
+        let mut words = vec![
+            "kitten", "sitting", "mitten", "kit", "", "smitten", "kitchen",
+        ];
+        let trie = Trie::new(words.iter().map(|word| word.as_bytes().to_vec()));
+        words.sort_unstable();
+
+        let distances = trie.distances("sitting".as_bytes());
+        assert_eq!(distances.len(), words.len());
+
+        for ((word, distance), expected) in distances.iter().zip(&words) {
+            assert_eq!(word.as_slice(), expected.as_bytes());
+            assert_eq!(
+                *distance,
+                crate::distance_only("sitting".as_bytes(), expected.as_bytes())
+            );
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::pattern::Pattern;
+
+// Every sequence is padded with two `None`s on either side, so that sequences shorter than three
+// items have trigrams too and the first and last items are in as many trigrams as the others
+type Trigram<T> = [Option<T>; 3];
+
+// Returns how often each trigram of the padded sequence occurs in it. A sequence of n items has
+// n + 2 trigrams.
+fn trigram_counts<T: Clone + Eq + Hash>(items: &[T]) -> HashMap<Trigram<T>, usize> {
+    let padding = || std::iter::repeat_with(|| None).take(2);
+    let padded: Vec<Option<T>> = padding()
+        .chain(items.iter().cloned().map(Some))
+        .chain(padding())
+        .collect();
+
+    let mut counts = HashMap::new();
+    for window in padded.windows(3) {
+        *counts
+            .entry([window[0].clone(), window[1].clone(), window[2].clone()])
+            .or_insert(0) += 1;
+    }
+
+    counts
+}
+
+/// An index of a corpus of sequences, for finding the ones within a distance of a query without
+/// comparing the query to all of them.
+///
+/// Every sequence is indexed by its trigrams, the runs of three consecutive items. A single edit
+/// changes at most three trigrams, so a sequence within distance k of the query shares all but 3k
+/// of its trigrams with it, and the index only returns sequences that share enough (the q-gram
+/// lemma). Only these candidates have their distance computed, which makes searching a large
+/// dictionary for misspellings fast, since few words share many trigrams with the query.
+///
+/// The filter is only selective when 3k is well below the length of the query: there's no
+/// trigram a query within distance k of every other short sequence has to share with it, so
+/// short queries with a large k are compared to every sequence of a suitable length.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::TrigramIndex;
+///
+/// let words = ["SATURDAY", "SUNDAY", "MONDAY", "SATURN"];
+/// let index = TrigramIndex::new(words.iter().map(|word| word.as_bytes().to_vec()));
+///
+/// assert_eq!(index.search("SATRUDAY".as_bytes(), 2), vec![(0, 2)]);
+/// assert_eq!(index.get(0), Some("SATURDAY".as_bytes()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TrigramIndex<T> {
+    items: Vec<Vec<T>>,
+    // For every trigram, the sequences it occurs in and how often
+    postings: HashMap<Trigram<T>, Vec<(usize, usize)>>,
+}
+
+impl<T: Clone + Eq + Hash> TrigramIndex<T> {
+    /// Returns an index of the sequences, which are numbered in order from 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `corpus` - The sequences to index
+    pub fn new<I: IntoIterator<Item = Vec<T>>>(corpus: I) -> Self {
+        let mut index = TrigramIndex {
+            items: Vec::new(),
+            postings: HashMap::new(),
+        };
+
+        for item in corpus {
+            index.insert(item);
+        }
+
+        index
+    }
+
+    /// Adds a sequence to the index, and returns its number.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The sequence to add
+    pub fn insert(&mut self, item: Vec<T>) -> usize {
+        let id = self.items.len();
+
+        for (trigram, count) in trigram_counts(&item) {
+            self.postings.entry(trigram).or_default().push((id, count));
+        }
+
+        self.items.push(item);
+        id
+    }
+
+    /// Returns the number of sequences in the index.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if there are no sequences in the index.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the sequence with the given number, if there is one.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The number `insert` returned for the sequence
+    pub fn get(&self, id: usize) -> Option<&[T]> {
+        self.items.get(id).map(|item| item.as_slice())
+    }
+
+    /// Returns the numbers of the sequences that could be within distance `k` of the query, in
+    /// ascending order.
+    ///
+    /// Every sequence within distance `k` is returned, but some of the others may be too, so
+    /// their distances still have to be checked. See `search`.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The sequence to look for
+    /// * `k` - The maximum distance
+    pub fn candidates(&self, query: &[T], k: usize) -> Vec<usize> {
+        let m = query.len();
+
+        // Sequences of n items within distance k share at least max(m, n) + 2 - 3k trigrams
+        let required = |n: usize| (m.max(n) + 2).saturating_sub(k.saturating_mul(3));
+        let close_in_length = |n: usize| m.abs_diff(n) <= k;
+
+        // Then every candidate shares at least one trigram with the query
+        if required(0) > 0 {
+            let mut shared = HashMap::<usize, usize>::new();
+
+            for (trigram, count) in trigram_counts(query) {
+                for &(id, item_count) in self.postings.get(&trigram).into_iter().flatten() {
+                    *shared.entry(id).or_insert(0) += count.min(item_count);
+                }
+            }
+
+            let mut candidates: Vec<usize> = shared
+                .into_iter()
+                .filter(|&(id, common)| {
+                    let n = self.items[id].len();
+                    close_in_length(n) && common >= required(n)
+                })
+                .map(|(id, _)| id)
+                .collect();
+
+            candidates.sort_unstable();
+            return candidates;
+        }
+
+        (0..self.items.len())
+            .filter(|&id| close_in_length(self.items[id].len()))
+            .collect()
+    }
+
+    /// Returns the number and distance of every sequence within distance `k` of the query,
+    /// closest first, and in ascending order of number for the same distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The sequence to look for
+    /// * `k` - The maximum distance
+    pub fn search(&self, query: &[T], k: usize) -> Vec<(usize, usize)> {
+        let pattern = Pattern::new(query);
+
+        let mut matches: Vec<(usize, usize)> = self
+            .candidates(query, k)
+            .into_iter()
+            .filter_map(|id| {
+                pattern
+                    .distance_within(&self.items[id], k)
+                    .map(|distance| (id, distance))
+            })
+            .collect();
+
+        matches.sort_by_key(|&(id, distance)| (distance, id));
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trigram::*;
+
+    #[test]
+    fn trigram_counts_test() {
+        let counts = trigram_counts("aaaa".as_bytes());
+        assert_eq!(counts.values().sum::<usize>(), 6);
+        assert_eq!(counts[&[Some(b'a'); 3]], 2);
+
+        // Even the empty sequence has trigrams
+        assert_eq!(trigram_counts::<u8>(&[]).values().sum::<usize>(), 2);
+    }
+
+    #[test]
+    fn trigram_index_test() {
+        let words = ["kitten", "sitting", "mitten", "kit", "", "smitten"];
+        let index = TrigramIndex::new(words.iter().map(|word| word.as_bytes().to_vec()));
+        assert_eq!(index.len(), 6);
+
+        // Candidates are a superset of the matches, and far fewer than the corpus
+        assert_eq!(index.candidates("kitten".as_bytes(), 1), vec![0, 2]);
+        assert_eq!(
+            index.search("kitten".as_bytes(), 2),
+            vec![(0, 0), (2, 1), (5, 2)]
+        );
+
+        // Short queries with a large k can't be filtered by trigrams
+        assert_eq!(index.candidates("kt".as_bytes(), 2), vec![3, 4]);
+        assert_eq!(index.search("".as_bytes(), 3), vec![(4, 0), (3, 3)]);
+        assert_eq!(index.search("kitten".as_bytes(), usize::MAX).len(), 6);
+    }
+}
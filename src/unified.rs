@@ -0,0 +1,332 @@
+use alloc::string::String;
+use core::ops::Range;
+
+use crate::edit::Edit;
+use crate::hunk::{group_into_hunks, Hunk, HunkPart};
+use crate::replace::coalesce_edits;
+
+// Formats a range for a hunk header: 1-indexed, with the length left out if it's 1, and empty
+// ranges starting at the line before them
+pub(crate) fn hunk_range(range: &Range<usize>) -> String {
+    match range.len() {
+        0 => format!("{},0", range.start),
+        1 => format!("{}", range.start + 1),
+        len => format!("{},{}", range.start + 1, len),
+    }
+}
+
+/// Returns the unified diff of an edit script over lines, as produced by `diff -u`.
+///
+/// Changes whose context would overlap are grouped into a single hunk, headed by the line ranges
+/// it covers in the source and the target. The lines must not include their line terminators,
+/// since every line is written followed by a newline. An empty edit script gives an empty diff.
+///
+/// # Arguments
+///
+/// * `source_name` - The name of the source, for the `---` header
+/// * `target_name` - The name of the target, for the `+++` header
+/// * `source` - The lines of the source
+/// * `edits` - The edits that transform the source lines into the target lines
+/// * `context` - The number of unchanged lines to show around each change
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let source = vec!["one", "two", "three", "four"];
+/// let target = vec!["one", "2", "three", "four", "five"];
+///
+/// let (_, matrix) = levenshtein::distance(&source, &target);
+/// let edits = levenshtein::generate_edits(&source, &target, &matrix).unwrap();
+///
+/// let diff = levenshtein::unified_diff("a.txt", "b.txt", &source, &edits, 1);
+///
+/// assert_eq!(
+///     diff,
+///     "--- a.txt\n+++ b.txt\n@@ -1,4 +1,5 @@\n one\n-two\n+2\n three\n four\n+five\n"
+/// );
+/// ```
+pub fn unified_diff<S: AsRef<str> + Clone + PartialEq>(
+    source_name: &str,
+    target_name: &str,
+    source: &[S],
+    edits: &[Edit<S>],
+    context: usize,
+) -> String {
+    let hunks = group_into_hunks(source, edits, context);
+
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", source_name, target_name);
+
+    for hunk in hunks {
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(&hunk.source),
+            hunk_range(&hunk.target)
+        ));
+
+        for part in &hunk.parts {
+            match part {
+                HunkPart::Context(lines) => write_lines(&mut out, " ", lines),
+                HunkPart::Change { removed, added } => {
+                    write_lines(&mut out, "-", removed);
+                    write_lines(&mut out, "+", added);
+                }
+            }
+        }
+    }
+
+    out
+}
+
+// Writes each line prefixed with the marker
+pub(crate) fn write_lines<S: AsRef<str>>(out: &mut String, marker: &str, lines: &[S]) {
+    for line in lines {
+        out.push_str(&format!("{}{}\n", marker, line.as_ref()));
+    }
+}
+
+// Formats a range for a context diff header: 1-indexed and inclusive, with a single number for
+// ranges of one line, and empty ranges as the line before them
+fn context_range(range: &Range<usize>) -> String {
+    match range.len() {
+        0 | 1 => format!("{}", range.end),
+        _ => format!("{},{}", range.start + 1, range.end),
+    }
+}
+
+// Writes one side of a context diff hunk
+fn write_context_side<S: AsRef<str>>(out: &mut String, hunk: &Hunk<S>, is_source: bool) {
+    for part in &hunk.parts {
+        let (removed, added) = match part {
+            HunkPart::Context(lines) => {
+                write_lines(out, "  ", lines);
+                continue;
+            }
+            HunkPart::Change { removed, added } => (removed, added),
+        };
+
+        // Lines that are both removed and added are marked as changed on both sides
+        let (lines, marker) = if is_source {
+            (removed, "- ")
+        } else {
+            (added, "+ ")
+        };
+        let marker = if removed.is_empty() || added.is_empty() {
+            marker
+        } else {
+            "! "
+        };
+
+        write_lines(out, marker, lines);
+    }
+}
+
+/// Returns the context diff of an edit script over lines, as produced by `diff -c`.
+///
+/// Hunks are grouped like in `unified_diff`, but each one shows the source lines and then the
+/// target lines, with removed lines marked `-`, added lines marked `+` and changed lines marked
+/// `!`. A side with no removed or added lines is left out, keeping only its header.
+///
+/// # Arguments
+///
+/// * `source_name` - The name of the source, for the `***` header
+/// * `target_name` - The name of the target, for the `---` header
+/// * `source` - The lines of the source
+/// * `edits` - The edits that transform the source lines into the target lines
+/// * `context` - The number of unchanged lines to show around each change
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let source = vec!["one", "two", "three"];
+/// let target = vec!["one", "2", "three", "four"];
+///
+/// let (_, matrix) = levenshtein::distance(&source, &target);
+/// let edits = levenshtein::generate_edits(&source, &target, &matrix).unwrap();
+///
+/// let diff = levenshtein::context_diff("a.txt", "b.txt", &source, &edits, 1);
+///
+/// assert_eq!(
+///     diff,
+///     "*** a.txt\n--- b.txt\n***************\n*** 1,3 ****\n  one\n! two\n  three\n\
+///      --- 1,4 ----\n  one\n! 2\n  three\n+ four\n"
+/// );
+/// ```
+pub fn context_diff<S: AsRef<str> + Clone + PartialEq>(
+    source_name: &str,
+    target_name: &str,
+    source: &[S],
+    edits: &[Edit<S>],
+    context: usize,
+) -> String {
+    let hunks = group_into_hunks(source, edits, context);
+
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!("*** {}\n--- {}\n", source_name, target_name);
+
+    for hunk in hunks {
+        out.push_str("***************\n");
+        out.push_str(&format!("*** {} ****\n", context_range(&hunk.source)));
+
+        if hunk.changes().iter().any(|change| !change.range.is_empty()) {
+            write_context_side(&mut out, &hunk, true);
+        }
+
+        out.push_str(&format!("--- {} ----\n", context_range(&hunk.target)));
+
+        if hunk.changes().iter().any(|change| !change.with.is_empty()) {
+            write_context_side(&mut out, &hunk, false);
+        }
+    }
+
+    out
+}
+
+/// Returns an ed script that transforms the source lines into the target lines, as produced by
+/// `diff -e`.
+///
+/// The commands are written from the end of the source to its start, so the line numbers of each
+/// command still refer to the source when it runs. Like `diff -e`, the script doesn't end with a
+/// `w` command. A target line consisting of a single `.` would end the text of a command, so it's
+/// written as `..` and fixed up with a substitution afterwards.
+///
+/// # Arguments
+///
+/// * `edits` - The edits that transform the source lines into the target lines
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let source = vec!["one", "two", "three", "four"];
+/// let target = vec!["one", "2", "three"];
+///
+/// let (_, matrix) = levenshtein::distance(&source, &target);
+/// let edits = levenshtein::generate_edits(&source, &target, &matrix).unwrap();
+///
+/// assert_eq!(levenshtein::ed_script(&edits), "4d\n2c\n2\n.\n");
+/// ```
+pub fn ed_script<S: AsRef<str> + Clone + PartialEq>(edits: &[Edit<S>]) -> String {
+    let mut out = String::new();
+
+    for change in coalesce_edits(edits).iter().rev() {
+        let range = &change.range;
+
+        let lines = match range.len() {
+            0 => format!("{}", range.start),
+            1 => format!("{}", range.end),
+            _ => format!("{},{}", range.start + 1, range.end),
+        };
+
+        if change.with.is_empty() {
+            out.push_str(&format!("{}d\n", lines));
+            continue;
+        }
+
+        let command = if range.is_empty() { 'a' } else { 'c' };
+        out.push_str(&format!("{}{}\n", lines, command));
+
+        let mut inserting = true;
+
+        for line in &change.with {
+            if !inserting {
+                out.push_str("a\n");
+                inserting = true;
+            }
+
+            if line.as_ref() == "." {
+                out.push_str("..\n.\ns/.//\n");
+                inserting = false;
+            } else {
+                out.push_str(&format!("{}\n", line.as_ref()));
+            }
+        }
+
+        if inserting {
+            out.push_str(".\n");
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::unified::*;
+
+    fn edits<'a>(source: &[&'a str], target: &[&'a str]) -> Vec<Edit<&'a str>> {
+        let (_, matrix) = crate::distance(source, target);
+        crate::generate_edits(source, target, &matrix).unwrap()
+    }
+
+    #[test]
+    fn distant_changes_get_separate_hunks() {
+        let source: Vec<&str> = vec!["a", "b", "c", "d", "e", "f", "g", "h"];
+        let mut target = source.clone();
+        target[0] = "A";
+        target.remove(6);
+
+        let diff = unified_diff("a", "b", &source, &edits(&source, &target), 1);
+
+        assert_eq!(
+            diff,
+            "--- a\n+++ b\n@@ -1,2 +1,2 @@\n-a\n+A\n b\n@@ -6,3 +6,2 @@\n f\n-g\n h\n"
+        );
+
+        // With more context, the hunks merge
+        let diff = unified_diff("a", "b", &source, &edits(&source, &target), 3);
+        assert!(diff.starts_with("--- a\n+++ b\n@@ -1,8 +1,7 @@\n"));
+    }
+
+    #[test]
+    fn empty_ranges_start_at_the_line_before() {
+        let source: Vec<&str> = vec![];
+        let target = vec!["a"];
+
+        let diff = unified_diff("a", "b", &source, &edits(&source, &target), 3);
+        assert_eq!(diff, "--- a\n+++ b\n@@ -0,0 +1 @@\n+a\n");
+
+        assert!(unified_diff("a", "b", &target, &edits(&target, &target), 3).is_empty());
+    }
+
+    #[test]
+    fn context_diff_test() {
+        let source: Vec<&str> = vec!["a", "b", "c", "d", "e", "f", "g", "h"];
+        let mut target = source.clone();
+        target.insert(1, "x");
+        target.remove(7);
+
+        let diff = context_diff("a", "b", &source, &edits(&source, &target), 1);
+
+        // The first hunk only adds lines and the second only removes them, so each has one side
+        assert_eq!(
+            diff,
+            "*** a\n--- b\n***************\n*** 1,2 ****\n--- 1,3 ----\n  a\n+ x\n  b\n\
+             ***************\n*** 6,8 ****\n  f\n- g\n  h\n--- 7,8 ----\n"
+        );
+    }
+
+    #[test]
+    fn ed_script_test() {
+        let source: Vec<&str> = vec!["a", "b", "c"];
+        let target = vec![".", "a", "b", "c", "d", ".", "e"];
+
+        assert_eq!(
+            ed_script(&edits(&source, &target)),
+            "3a\nd\n..\n.\ns/.//\na\ne\n.\n0a\n..\n.\ns/.//\n"
+        );
+
+        assert!(ed_script(&edits(&source, &source)).is_empty());
+    }
+}
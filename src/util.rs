@@ -1,11 +1,520 @@
-pub type DistanceMatrix = Vec<Vec<usize>>;
+use alloc::vec::Vec;
+use core::cmp::{max, min};
+use core::convert::TryFrom;
+use core::fmt::{Debug, Display};
+#[cfg(feature = "std")]
+use core::hash::Hash;
+use core::ops::{Add, Index};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
 
-pub fn print_table(table: &DistanceMatrix) {
-    for row in table {
+use crate::edit::LevenshteinError;
+
+/// The distances between every prefix of a source and every prefix of a target, as computed by
+/// `distance`.
+///
+/// The cell in row i and column j holds the distance between the first i items of the source and
+/// the first j items of the target, so there is one more row than the source has items and one
+/// more column than the target has. The matrix is always rectangular and has at least one cell.
+///
+/// A matrix can be made from its rows with `try_from`, which checks their shape, and turned back
+/// into rows with `into`.
+///
+/// A banded matrix, as computed by `levenshtein_banded`, only stores the cells near the diagonal;
+/// all the cells outside the band have the same value, one more than the bound.
+///
+/// With the `serde` feature, a matrix can be serialized and reloaded later, e.g. to generate edits
+/// in another process without computing the distances again. It is serialized as its rows, so a
+/// banded matrix comes back as a full one, with the same cells.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let (_, matrix) = levenshtein::distance("FLAW".as_bytes(), "LAWN".as_bytes());
+///
+/// assert_eq!(matrix.dims(), (5, 5));
+/// assert_eq!(matrix.distance(), 2);
+/// assert_eq!(matrix.get(1, 0), Some(1)); // Delete the F
+/// assert_eq!(matrix.get(5, 0), None);
+///
+/// let first_row: Vec<usize> = matrix.rows().next().unwrap().collect();
+/// assert_eq!(first_row, vec![0, 1, 2, 3, 4]);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "Vec<Vec<C>>",
+        into = "Vec<Vec<C>>",
+        bound(serialize = "C: Copy + serde::Serialize")
+    )
+)]
+pub struct DistanceMatrix<C = usize> {
+    // The stored cells, row after row. Every row of a banded matrix holds the same number of
+    // cells, the 2k + 1 from k columns left of the diagonal to k columns right of it, or all of
+    // them if there are fewer columns, shifted to stay inside the matrix.
+    cells: Vec<C>,
+    rows: usize,
+    cols: usize,
+    // The width k of the band on either side of the diagonal, and the value of the cells outside
+    // it, for a banded matrix
+    band: Option<(usize, C)>,
+}
+
+impl<C: Copy> DistanceMatrix<C> {
+    // Returns a matrix of the given dimensions with every cell set to value
+    pub(crate) fn filled(rows: usize, cols: usize, value: C) -> Self {
+        DistanceMatrix {
+            cells: vec![value; rows * cols],
+            rows,
+            cols,
+            band: None,
+        }
+    }
+
+    // Turns the matrix into one of the given dimensions with every cell set to value, reusing its
+    // allocation
+    pub(crate) fn refill(&mut self, rows: usize, cols: usize, value: C) {
+        self.cells.clear();
+        self.cells.resize(rows * cols, value);
+        self.rows = rows;
+        self.cols = cols;
+        self.band = None;
+    }
+
+    // Adds rows to the bottom of the matrix, with every new cell set to value. Only for matrices
+    // that aren't banded.
+    pub(crate) fn add_rows(&mut self, rows: usize, value: C) {
+        assert!(self.band.is_none());
+
+        self.cells.resize((self.rows + rows) * self.cols, value);
+        self.rows += rows;
+    }
+
+    // Adds columns to the right of the matrix, with every new cell set to value. Only for
+    // matrices that aren't banded.
+    pub(crate) fn add_cols(&mut self, cols: usize, value: C) {
+        assert!(self.band.is_none());
+
+        let mut cells = Vec::with_capacity(self.rows * (self.cols + cols));
+
+        for row in self.cells.chunks(self.cols) {
+            cells.extend_from_slice(row);
+            cells.extend(core::iter::repeat_n(value, cols));
+        }
+
+        self.cells = cells;
+        self.cols += cols;
+    }
+
+    pub(crate) fn is_banded(&self) -> bool {
+        self.band.is_some()
+    }
+
+    // Returns a matrix of the given dimensions that only stores the cells at most k columns away
+    // from the diagonal, with every cell set to value
+    pub(crate) fn banded(rows: usize, cols: usize, k: usize, value: C) -> Self {
+        // A band wider than the matrix holds every cell already
+        let k = k.min(max(rows, cols));
+
+        DistanceMatrix {
+            cells: vec![value; rows * min(2 * k + 1, cols)],
+            rows,
+            cols,
+            band: Some((k, value)),
+        }
+    }
+
+    // Returns the position of a cell in the stored cells, or None if it's outside the band
+    fn position(&self, i: usize, j: usize) -> Option<usize> {
+        assert!(
+            i < self.rows && j < self.cols,
+            "cell ({}, {}) is outside the matrix",
+            i,
+            j
+        );
+
+        match self.band {
+            None => Some(i * self.cols + j),
+            Some((k, _)) if j + k < i || j > i + k => None,
+            Some((k, _)) => {
+                let width = min(2 * k + 1, self.cols);
+                let first = min(i.saturating_sub(k), self.cols - width);
+
+                Some(i * width + j - first)
+            }
+        }
+    }
+
+    pub(crate) fn set(&mut self, i: usize, j: usize, value: C) {
+        let position = self.position(i, j).expect("the cell is outside the band");
+        self.cells[position] = value;
+    }
+
+    // Returns row i - 1, and row i for writing. Only for matrices that aren't banded.
+    pub(crate) fn row_pair_mut(&mut self, i: usize) -> (&[C], &mut [C]) {
+        assert!(self.band.is_none());
+
+        let (before, rest) = self.cells.split_at_mut(i * self.cols);
+
+        (&before[(i - 1) * self.cols..], &mut rest[..self.cols])
+    }
+
+    // Returns the cells from row i to the end, row after row, for writing. Only for matrices that
+    // aren't banded.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn rows_from_mut(&mut self, i: usize) -> &mut [C] {
+        assert!(self.band.is_none());
+
+        &mut self.cells[i * self.cols..]
+    }
+
+    /// Returns the cell in row i and column j, or `None` if it's outside the matrix.
+    pub fn get(&self, i: usize, j: usize) -> Option<C> {
+        if i < self.rows && j < self.cols {
+            Some(self[(i, j)])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of rows and the number of columns, i.e. the length of the source and
+    /// the length of the target, each plus one.
+    pub fn dims(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Returns the distance between the whole source and the whole target, i.e. the last cell.
+    pub fn distance(&self) -> C {
+        self[(self.rows - 1, self.cols - 1)]
+    }
+
+    /// Returns an iterator over the rows of the matrix, each of which is an iterator over its
+    /// cells.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = C> + '_> + '_ {
+        (0..self.rows).map(move |i| (0..self.cols).map(move |j| self[(i, j)]))
+    }
+}
+
+impl<C: Copy + Display> DistanceMatrix<C> {
+    /// Writes the matrix as CSV, with the items of the source labelling the rows and the items of
+    /// the target labelling the columns, e.g. to open it in a spreadsheet.
+    ///
+    /// The first row holds the target labels and the first column the source labels, after an
+    /// empty cell for the row and the column of the empty prefixes. Labels that contain a comma,
+    /// a quote or a line break are quoted.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where to write the CSV
+    /// * `source_labels` - The labels of the rows, usually the items of the source
+    /// * `target_labels` - The labels of the columns, usually the items of the target
+    ///
+    /// # Panics
+    ///
+    /// Panics if there isn't one label for every item of the source and the target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use levenshtein_diff as levenshtein;
+    ///
+    /// let s1: Vec<char> = "AB".chars().collect();
+    /// let s2: Vec<char> = "B".chars().collect();
+    ///
+    /// let (_, matrix) = levenshtein::distance(&s1, &s2);
+    ///
+    /// let mut csv = Vec::new();
+    /// matrix.write_csv(&mut csv, &s1, &s2).unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(csv).unwrap(), ",,B\n,0,1\nA,1,1\nB,2,1\n");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_csv<W: Write, L: Display>(
+        &self,
+        writer: W,
+        source_labels: &[L],
+        target_labels: &[L],
+    ) -> io::Result<()> {
+        self.write_delimited(writer, ',', source_labels, target_labels)
+    }
+
+    /// Writes the matrix as TSV, with the items of the source labelling the rows and the items of
+    /// the target labelling the columns. See `write_csv`.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where to write the TSV
+    /// * `source_labels` - The labels of the rows, usually the items of the source
+    /// * `target_labels` - The labels of the columns, usually the items of the target
+    ///
+    /// # Panics
+    ///
+    /// Panics if there isn't one label for every item of the source and the target.
+    #[cfg(feature = "std")]
+    pub fn write_tsv<W: Write, L: Display>(
+        &self,
+        writer: W,
+        source_labels: &[L],
+        target_labels: &[L],
+    ) -> io::Result<()> {
+        self.write_delimited(writer, '\t', source_labels, target_labels)
+    }
+
+    // Writes the labels and the cells with fields separated by delimiter, one row per line
+    #[cfg(feature = "std")]
+    fn write_delimited<W: Write, L: Display>(
+        &self,
+        mut writer: W,
+        delimiter: char,
+        source_labels: &[L],
+        target_labels: &[L],
+    ) -> io::Result<()> {
+        assert_eq!(
+            (source_labels.len() + 1, target_labels.len() + 1),
+            self.dims(),
+            "there must be a label for every row and column but the first"
+        );
+
+        // Quotes a label if it would otherwise be read as more than one field or record
+        let field = |label: &L| {
+            let label = label.to_string();
+
+            if label.contains([delimiter, '"', '\n', '\r']) {
+                format!("\"{}\"", label.replace('"', "\"\""))
+            } else {
+                label
+            }
+        };
+
+        write!(writer, "{}", delimiter)?;
+        for label in target_labels {
+            write!(writer, "{}{}", delimiter, field(label))?;
+        }
+        writeln!(writer)?;
+
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                write!(writer, "{}", field(&source_labels[i - 1]))?;
+            }
+
+            for cell in row {
+                write!(writer, "{}{}", delimiter, cell)?;
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The matrix between two empty sequences, with a single cell of 0. Handy as the buffer to pass to
+/// `distance_with_buffer` the first time.
+impl<C: Copy + Default> Default for DistanceMatrix<C> {
+    fn default() -> Self {
+        DistanceMatrix::filled(1, 1, C::default())
+    }
+}
+
+impl<C: Copy> Index<(usize, usize)> for DistanceMatrix<C> {
+    type Output = C;
+
+    fn index(&self, (i, j): (usize, usize)) -> &C {
+        match (self.position(i, j), &self.band) {
+            (Some(position), _) => &self.cells[position],
+            (None, Some((_, outside))) => outside,
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+impl<C: Copy + PartialEq> PartialEq for DistanceMatrix<C> {
+    // Matrices are equal if their cells are, regardless of how they are stored
+    fn eq(&self, other: &Self) -> bool {
+        self.dims() == other.dims()
+            && self
+                .rows()
+                .flatten()
+                .zip(other.rows().flatten())
+                .all(|(a, b)| a == b)
+    }
+}
+
+impl<C: Copy + Eq> Eq for DistanceMatrix<C> {}
+
+impl<C> TryFrom<Vec<Vec<C>>> for DistanceMatrix<C> {
+    type Error = LevenshteinError;
+
+    fn try_from(rows: Vec<Vec<C>>) -> Result<Self, Self::Error> {
+        let cols = rows.first().map_or(0, |row| row.len());
+
+        if cols == 0 || rows.iter().any(|row| row.len() != cols) {
+            return Err(LevenshteinError::InvalidDistanceMatrixError);
+        }
+
+        Ok(DistanceMatrix {
+            rows: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+            cols,
+            band: None,
+        })
+    }
+}
+
+impl<C: Copy> From<DistanceMatrix<C>> for Vec<Vec<C>> {
+    fn from(matrix: DistanceMatrix<C>) -> Self {
+        matrix.rows().map(|row| row.collect()).collect()
+    }
+}
+
+/// An unsigned integer type that can hold the cells of a `DistanceMatrix`, or the indices of an
+/// `Edit`.
+///
+/// No distance is larger than the length of the longer sequence, so a matrix of `u32` cells works
+/// for sequences of up to 4 billion items, in half the memory of the default `usize` cells. The
+/// same goes for the indices of edits.
+pub trait DistanceCell: Copy + Ord + Add<Output = Self> + Debug + Display {
+    /// Converts a distance into a cell, or returns `None` if it doesn't fit.
+    fn from_usize(value: usize) -> Option<Self>;
+
+    /// Converts the cell into a distance, saturating at `usize::MAX`.
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_distance_cell {
+    ($($t:ty),*) => {
+        $(
+            impl DistanceCell for $t {
+                fn from_usize(value: usize) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+
+                fn to_usize(self) -> usize {
+                    usize::try_from(self).unwrap_or(usize::MAX)
+                }
+            }
+        )*
+    };
+}
+
+impl_distance_cell!(u8, u16, u32, u64, usize);
+
+/// Writes a distance matrix as a table for reading, with the items of the source labelling the
+/// rows and the items of the target labelling the columns.
+///
+/// Every column is as wide as its widest entry, with the cells aligned to the right, so the table
+/// lines up in a terminal or a text file as long as the labels are one character wide per `char`.
+/// The row and the column of the empty prefixes have no label. Use `DistanceMatrix::write_csv`
+/// for a format spreadsheets can read.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write the table
+/// * `table` - The distance matrix
+/// * `source_labels` - The labels of the rows, usually the items of the source
+/// * `target_labels` - The labels of the columns, usually the items of the target
+///
+/// # Panics
+///
+/// Panics if there isn't one label for every item of the source and the target.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::util::write_table;
+///
+/// let s1: Vec<char> = "FLAW".chars().collect();
+/// let s2: Vec<char> = "LAWN".chars().collect();
+///
+/// let (_, matrix) = levenshtein::distance(&s1, &s2);
+///
+/// let mut table = Vec::new();
+/// write_table(&mut table, &matrix, &s1, &s2).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(table).unwrap(),
+///     "    L A W N\n  0 1 2 3 4\nF 1 1 2 3 4\nL 2 1 2 3 4\nA 3 2 1 2 3\nW 4 3 2 1 2\n"
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn write_table<W: Write, C: Copy + Display, L: Display>(
+    mut writer: W,
+    table: &DistanceMatrix<C>,
+    source_labels: &[L],
+    target_labels: &[L],
+) -> io::Result<()> {
+    assert_eq!(
+        (source_labels.len() + 1, target_labels.len() + 1),
+        table.dims(),
+        "there must be a label for every row and column but the first"
+    );
+
+    let width = |text: &str| text.chars().count();
+
+    // The header row and the label column, with empty labels for the empty prefixes
+    let header: Vec<String> = core::iter::once(String::new())
+        .chain(target_labels.iter().map(|label| label.to_string()))
+        .collect();
+    let labels: Vec<String> = core::iter::once(String::new())
+        .chain(source_labels.iter().map(|label| label.to_string()))
+        .collect();
+    let cells: Vec<Vec<String>> = table
+        .rows()
+        .map(|row| row.map(|cell| cell.to_string()).collect())
+        .collect();
+
+    let label_width = labels.iter().map(|label| width(label)).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..header.len())
+        .map(|j| {
+            cells
+                .iter()
+                .map(|row| width(&row[j]))
+                .chain(core::iter::once(width(&header[j])))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let write_row = |writer: &mut W, label: &str, row: &[String]| -> io::Result<()> {
+        write!(writer, "{}", label)?;
+        write!(writer, "{}", " ".repeat(label_width - width(label)))?;
+
+        for (entry, &column_width) in row.iter().zip(&widths) {
+            write!(
+                writer,
+                " {}{}",
+                " ".repeat(column_width - width(entry)),
+                entry
+            )?;
+        }
+
+        writeln!(writer)
+    };
+
+    write_row(&mut writer, "", &header)?;
+
+    for (label, row) in labels.iter().zip(&cells) {
+        write_row(&mut writer, label, row)?;
+    }
+
+    Ok(())
+}
+
+/// Prints a distance matrix to stdout, one row per line, without labels. See `write_table` for a
+/// labelled table that can be written anywhere.
+#[cfg(feature = "std")]
+pub fn print_table<C: Copy + Display>(table: &DistanceMatrix<C>) {
+    for row in table.rows() {
         for item in row {
             print!("{} ", item);
         }
-        println!("");
+        println!();
     }
 }
 
@@ -14,16 +523,17 @@ pub fn print_table(table: &DistanceMatrix) {
 // The First column is 0..m+1
 // And the rest of the values are usize::MAX
 pub fn get_distance_table(m: usize, n: usize) -> DistanceMatrix {
-    let mut distances = Vec::with_capacity(m + 1);
+    // initialize the whole table to sentinel
+    let mut distances = DistanceMatrix::filled(m + 1, n + 1, usize::MAX);
 
     // The first row
-    distances.push((0..n + 1).collect());
+    for j in 0..n + 1 {
+        distances.set(0, j, j);
+    }
 
+    // The first column
     for i in 1..m + 1 {
-        // initialize the whole row to sentinel
-        distances.push(vec![usize::MAX; n + 1]);
-        // update the first item in the row
-        distances[i][0] = i;
+        distances.set(i, 0, i);
     }
 
     distances
@@ -32,3 +542,303 @@ pub fn get_distance_table(m: usize, n: usize) -> DistanceMatrix {
 pub fn up_to_last<T>(slice: &[T]) -> &[T] {
     slice.split_last().map_or(&[], |(_, rest)| rest)
 }
+
+/// Two sequences with their common prefix and suffix stripped off, as returned by
+/// `strip_common_affix`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommonAffix<'a, T> {
+    /// The length of the prefix both sequences start with
+    pub prefix: usize,
+    /// The length of the suffix both sequences end with, after the prefix
+    pub suffix: usize,
+    /// What's left of the source in between
+    pub source: &'a [T],
+    /// What's left of the target in between
+    pub target: &'a [T],
+}
+
+/// Strips the longest common prefix and suffix off source and target, and returns what's left of
+/// them along with the lengths of the prefix and the suffix.
+///
+/// The common prefix and suffix never contribute to the distance, so the remaining slices have
+/// the same distance as the originals, and their distance matrix can be much smaller. Edits
+/// generated from that matrix refer to the remaining slices; `generate_edits_stripped` shifts
+/// them by the length of the prefix so they apply to the original source.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff::util::strip_common_affix;
+///
+/// let stripped = strip_common_affix("SATURDAY".as_bytes(), "SUNDAY".as_bytes());
+///
+/// assert_eq!(stripped.prefix, 1);
+/// assert_eq!(stripped.suffix, 3);
+/// assert_eq!(stripped.source, "ATUR".as_bytes());
+/// assert_eq!(stripped.target, "UN".as_bytes());
+/// ```
+pub fn strip_common_affix<'a, T: PartialEq>(
+    source: &'a [T],
+    target: &'a [T],
+) -> CommonAffix<'a, T> {
+    let prefix = source
+        .iter()
+        .zip(target.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let source = &source[prefix..];
+    let target = &target[prefix..];
+
+    let suffix = source
+        .iter()
+        .rev()
+        .zip(target.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    CommonAffix {
+        prefix,
+        suffix,
+        source: &source[..source.len() - suffix],
+        target: &target[..target.len() - suffix],
+    }
+}
+
+/// Returns a lower bound on the Levenshtein distance between source and target based only on
+/// their lengths.
+///
+/// Every edit changes the length by at most one, so the distance can never be smaller than the
+/// difference in lengths. This is O(1) and is the cheapest way to discard a candidate.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff::util::length_lower_bound;
+///
+/// assert_eq!(length_lower_bound("SATURDAY".as_bytes(), "SUNDAY".as_bytes()), 2);
+/// ```
+pub fn length_lower_bound<T>(source: &[T], target: &[T]) -> usize {
+    if source.len() > target.len() {
+        source.len() - target.len()
+    } else {
+        target.len() - source.len()
+    }
+}
+
+/// Returns a lower bound on the Levenshtein distance between source and target based on how often
+/// each element occurs in either sequence.
+///
+/// Elements that occur more often in source than in target must be deleted or substituted, and
+/// those that occur more often in target must be inserted or substituted. A single edit can fix at
+/// most one surplus on each side, so the distance is at least the larger of the two surpluses.
+/// This bound is never weaker than `length_lower_bound`, and runs in O(m + n).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff::util::histogram_lower_bound;
+///
+/// // FLAW and LAWN share L, A and W, but F must become N
+/// assert_eq!(histogram_lower_bound("FLAW".as_bytes(), "LAWN".as_bytes()), 1);
+/// ```
+#[cfg(feature = "std")]
+pub fn histogram_lower_bound<T: Eq + Hash>(source: &[T], target: &[T]) -> usize {
+    let mut counts = HashMap::<&T, isize>::new();
+
+    for item in source {
+        *counts.entry(item).or_insert(0) += 1;
+    }
+
+    for item in target {
+        *counts.entry(item).or_insert(0) -= 1;
+    }
+
+    let mut surplus = 0;
+    let mut deficit = 0;
+
+    for count in counts.values() {
+        if *count > 0 {
+            surplus += *count as usize;
+        } else {
+            deficit += (-*count) as usize;
+        }
+    }
+
+    if surplus > deficit {
+        surplus
+    } else {
+        deficit
+    }
+}
+
+// Returns pseudo-random bytes from a fixed seed, for tests that need long inputs in which blocks
+// and chunks don't repeat
+#[cfg(test)]
+pub(crate) fn random_bytes(len: usize) -> Vec<u8> {
+    let mut state = 1u32;
+
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) as u8
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::*;
+
+    #[test]
+    fn distance_matrix_test() {
+        let matrix = DistanceMatrix::try_from(vec![vec![0, 1], vec![1, 1]]).unwrap();
+
+        assert_eq!(matrix.dims(), (2, 2));
+        assert_eq!(matrix.distance(), 1);
+        assert_eq!(matrix.get(0, 1), Some(1));
+        assert_eq!(matrix.get(0, 2), None);
+        assert_eq!(matrix.rows().count(), 2);
+
+        // Matrices must be rectangular and not empty
+        assert!(DistanceMatrix::try_from(vec![vec![0, 1], vec![1]]).is_err());
+        assert!(DistanceMatrix::<usize>::try_from(vec![]).is_err());
+        assert!(DistanceMatrix::<usize>::try_from(vec![vec![]]).is_err());
+    }
+
+    #[test]
+    fn distance_matrix_indexing_test() {
+        let rows = vec![vec![0, 1, 2], vec![3, 4, 5]];
+        let mut matrix = DistanceMatrix::try_from(rows.clone()).unwrap();
+
+        // The cells are stored row after row
+        assert_eq!(matrix.dims(), (2, 3));
+        assert_eq!(matrix[(0, 2)], 2);
+        assert_eq!(matrix[(1, 0)], 3);
+        assert_eq!(matrix.distance(), 5);
+        assert_eq!(Vec::<Vec<usize>>::from(matrix.clone()), rows);
+
+        matrix.set(1, 1, 7);
+        assert_eq!(matrix[(1, 1)], 7);
+        assert_eq!(matrix[(0, 1)], 1);
+        assert_eq!(matrix[(1, 2)], 5);
+    }
+
+    #[test]
+    fn banded_matrix_test() {
+        let mut matrix = DistanceMatrix::banded(4, 6, 1, 99);
+
+        for i in 0usize..4 {
+            for j in i.saturating_sub(1)..core::cmp::min(i + 2, 6) {
+                matrix.set(i, j, 10 * i + j);
+            }
+        }
+
+        // The cells outside the band all have the band's value
+        let rows: Vec<Vec<usize>> = matrix.into();
+        assert_eq!(
+            rows,
+            vec![
+                vec![0, 1, 99, 99, 99, 99],
+                vec![10, 11, 12, 99, 99, 99],
+                vec![99, 21, 22, 23, 99, 99],
+                vec![99, 99, 32, 33, 34, 99],
+            ]
+        );
+
+        // Rows hold at most every column, however wide the band
+        let wide = DistanceMatrix::banded(3, 2, usize::MAX, 0);
+        assert_eq!(wide.cells.len(), 6);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_csv_test() {
+        let s1 = ["a,b", "c"];
+        let s2 = ["say \"hi\""];
+        let (_, matrix) = crate::distance(&s1, &s2);
+
+        let mut csv = Vec::new();
+        matrix.write_csv(&mut csv, &s1, &s2).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            ",,\"say \"\"hi\"\"\"\n,0,1\n\"a,b\",1,1\nc,2,2\n"
+        );
+
+        // Commas only need quoting in CSV
+        let mut tsv = Vec::new();
+        matrix.write_tsv(&mut tsv, &s1, &s2).unwrap();
+        assert_eq!(
+            String::from_utf8(tsv).unwrap(),
+            "\t\t\"say \"\"hi\"\"\"\n\t0\t1\na,b\t1\t1\nc\t2\t2\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_table_test() {
+        let s1 = ["héllo", "a"];
+        let s2 = ["b"];
+        let (_, matrix) = crate::distance(&s1, &s2);
+
+        // Columns are as wide as their widest entry, counted in chars
+        let mut table = Vec::new();
+        write_table(&mut table, &matrix, &s1, &s2).unwrap();
+        assert_eq!(
+            String::from_utf8(table).unwrap(),
+            "        b\n      0 1\nhéllo 1 1\na     2 2\n"
+        );
+    }
+
+    #[test]
+    fn strip_common_affix_test() {
+        let stripped = strip_common_affix("kitten".as_bytes(), "sitting".as_bytes());
+        assert_eq!((stripped.prefix, stripped.suffix), (0, 0));
+
+        // The suffix doesn't overlap the prefix
+        let stripped = strip_common_affix("aaa".as_bytes(), "aa".as_bytes());
+        assert_eq!((stripped.prefix, stripped.suffix), (2, 0));
+        assert_eq!(stripped.source, "a".as_bytes());
+        assert!(stripped.target.is_empty());
+    }
+
+    #[test]
+    fn length_lower_bound_test() {
+        let s1 = "LAWN";
+        let s2 = "FFLAWANN";
+
+        assert_eq!(length_lower_bound(s1.as_bytes(), s2.as_bytes()), 4);
+        assert_eq!(length_lower_bound(s2.as_bytes(), s1.as_bytes()), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn histogram_lower_bound_test() {
+        let s1 = "SATURDAY";
+        let s2 = "SUNDAY";
+
+        // S, U, D, A and Y are shared, A, T and R are left over in SATURDAY and N in SUNDAY
+        assert_eq!(histogram_lower_bound(s1.as_bytes(), s2.as_bytes()), 3);
+
+        // Anagrams can't be told apart by their histograms
+        assert_eq!(
+            histogram_lower_bound("LISTEN".as_bytes(), "SILENT".as_bytes()),
+            0
+        );
+    }
+}
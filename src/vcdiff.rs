@@ -0,0 +1,235 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::distance::diff_edits;
+use crate::edit::Edit;
+use crate::replace::coalesce_edits;
+
+// The magic bytes and version that start every VCDIFF file
+const HEADER: [u8; 4] = [0xd6, 0xc3, 0xc4, 0x00];
+
+// Win_Indicator flag for windows that copy from the source
+const VCD_SOURCE: u8 = 0x01;
+
+// Instruction codes of the default code table whose size follows as an integer
+const ADD: u8 = 1;
+const COPY_SELF: u8 = 19;
+
+// Writes a VCDIFF integer: base 128, most significant digit first, with the high bit set on every
+// byte but the last
+fn write_integer(out: &mut Vec<u8>, value: usize) {
+    let mut digits = vec![(value & 0x7f) as u8];
+    let mut value = value >> 7;
+
+    while value > 0 {
+        digits.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+
+    out.extend(digits.iter().rev());
+}
+
+/// Returns a VCDIFF (RFC 3284) delta that transforms the source into the target.
+///
+/// The delta consists of a single window that copies unchanged runs from the source and adds
+/// everything else, using the default code table and no secondary compression, so it can be
+/// decoded by any VCDIFF decoder (e.g. `xdelta3 -d -s source`).
+///
+/// # Arguments
+///
+/// * `source` - The source bytes
+/// * `target` - The target bytes
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let delta = levenshtein::vcdiff_delta("SATURDAY".as_bytes(), "SUNDAY".as_bytes());
+/// assert_eq!(&delta[..4], &[0xd6, 0xc3, 0xc4, 0x00]);
+/// ```
+pub fn vcdiff_delta(source: &[u8], target: &[u8]) -> Vec<u8> {
+    let edits = diff_edits(source, target);
+
+    vcdiff_from_edits(source, &edits)
+}
+
+/// Returns a VCDIFF (RFC 3284) delta that applies the edits to the source. See `vcdiff_delta`.
+///
+/// # Arguments
+///
+/// * `source` - The source bytes
+/// * `edits` - The edits, as generated by `generate_edits`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// let (_, matrix) = levenshtein::distance(s1.as_bytes(), s2.as_bytes());
+/// let edits = levenshtein::generate_edits(s1.as_bytes(), s2.as_bytes(), &matrix).unwrap();
+///
+/// let delta = levenshtein::vcdiff_from_edits(s1.as_bytes(), &edits);
+/// assert_eq!(delta, levenshtein::vcdiff_delta(s1.as_bytes(), s2.as_bytes()));
+/// ```
+pub fn vcdiff_from_edits(source: &[u8], edits: &[Edit<u8>]) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut instructions = Vec::new();
+    let mut addresses = Vec::new();
+
+    // Unchanged runs are copied from the source, replaced ones are added
+    let mut copy = |range: Range<usize>, instructions: &mut Vec<u8>| {
+        if !range.is_empty() {
+            instructions.push(COPY_SELF);
+            write_integer(instructions, range.len());
+            write_integer(&mut addresses, range.start);
+        }
+    };
+
+    let mut copied_up_to = 0;
+    let mut target_len = source.len();
+
+    for replacement in coalesce_edits(edits) {
+        copy(copied_up_to..replacement.range.start, &mut instructions);
+        target_len = target_len - replacement.range.len() + replacement.with.len();
+
+        if !replacement.with.is_empty() {
+            instructions.push(ADD);
+            write_integer(&mut instructions, replacement.with.len());
+            data.extend_from_slice(&replacement.with);
+        }
+
+        copied_up_to = replacement.range.end;
+    }
+
+    copy(copied_up_to..source.len(), &mut instructions);
+
+    // The delta encoding, whose length has to be written before it
+    let mut delta = Vec::new();
+    write_integer(&mut delta, target_len);
+    delta.push(0); // No secondary compression
+    write_integer(&mut delta, data.len());
+    write_integer(&mut delta, instructions.len());
+    write_integer(&mut delta, addresses.len());
+    delta.extend(data);
+    delta.extend(instructions);
+    delta.extend(addresses);
+
+    let mut out = HEADER.to_vec();
+    out.push(0); // Hdr_Indicator: no secondary compressor or custom code table
+
+    if source.is_empty() {
+        out.push(0);
+    } else {
+        out.push(VCD_SOURCE);
+        write_integer(&mut out, source.len());
+        write_integer(&mut out, 0);
+    }
+
+    write_integer(&mut out, delta.len());
+    out.extend(delta);
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vcdiff::*;
+
+    // Reads a VCDIFF integer
+    fn read_integer(input: &mut &[u8]) -> usize {
+        let mut value = 0;
+
+        loop {
+            let byte = input[0];
+            *input = &input[1..];
+            value = value << 7 | (byte & 0x7f) as usize;
+
+            if byte & 0x80 == 0 {
+                return value;
+            }
+        }
+    }
+
+    // Decodes the subset of VCDIFF that `vcdiff_from_edits` produces
+    fn decode(source: &[u8], delta: &[u8]) -> Vec<u8> {
+        assert_eq!(&delta[..5], &[0xd6, 0xc3, 0xc4, 0x00, 0x00]);
+        let mut input = &delta[5..];
+
+        if input[0] == VCD_SOURCE {
+            input = &input[1..];
+            assert_eq!(read_integer(&mut input), source.len());
+            assert_eq!(read_integer(&mut input), 0);
+        } else {
+            input = &input[1..];
+        }
+
+        assert_eq!(read_integer(&mut input), input.len());
+
+        let target_len = read_integer(&mut input);
+        assert_eq!(input[0], 0);
+        input = &input[1..];
+
+        let data_len = read_integer(&mut input);
+        let instructions_len = read_integer(&mut input);
+        let addresses_len = read_integer(&mut input);
+
+        let (mut data, rest) = input.split_at(data_len);
+        let (mut instructions, mut addresses) = rest.split_at(instructions_len);
+        assert_eq!(addresses.len(), addresses_len);
+
+        let mut target = Vec::new();
+
+        while !instructions.is_empty() {
+            let code = instructions[0];
+            instructions = &instructions[1..];
+            let size = read_integer(&mut instructions);
+
+            match code {
+                ADD => {
+                    target.extend_from_slice(&data[..size]);
+                    data = &data[size..];
+                }
+                COPY_SELF => {
+                    let address = read_integer(&mut addresses);
+                    target.extend_from_slice(&source[address..address + size]);
+                }
+                _ => panic!("unexpected instruction {}", code),
+            }
+        }
+
+        assert_eq!(target.len(), target_len);
+
+        target
+    }
+
+    #[test]
+    fn write_integer_test() {
+        // The example from section 2 of RFC 3284
+        let mut out = Vec::new();
+        write_integer(&mut out, 123456789);
+        assert_eq!(out, vec![0xba, 0xef, 0x9a, 0x15]);
+
+        let mut out = Vec::new();
+        write_integer(&mut out, 0);
+        assert_eq!(out, vec![0]);
+    }
+
+    #[test]
+    fn vcdiff_delta_test() {
+        let pairs = [
+            ("SATURDAY", "SUNDAY"),
+            ("", "SUNDAY"),
+            ("SUNDAY", ""),
+            ("SUNDAY", "SUNDAY"),
+        ];
+
+        for (source, target) in pairs {
+            let delta = vcdiff_delta(source.as_bytes(), target.as_bytes());
+            assert_eq!(decode(source.as_bytes(), &delta), target.as_bytes());
+        }
+    }
+}
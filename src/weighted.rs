@@ -0,0 +1,449 @@
+use alloc::vec::Vec;
+use core::ops::{Add, Mul};
+
+use crate::edit::{Edit, LevenshteinError};
+use crate::util::DistanceMatrix;
+
+/// A value that can be used as the cost of an edit.
+///
+/// Costs only need to be added together and compared, so fractional weights (`f32`, `f64`) work
+/// just as well as integers. Implement this trait for your own type to use, for example, a
+/// saturating integer.
+pub trait Cost: Copy + Add<Output = Self> + PartialOrd {
+    /// The cost of doing nothing.
+    fn zero() -> Self;
+}
+
+macro_rules! impl_cost {
+    ($($t:ty),*) => {
+        $(
+            impl Cost for $t {
+                fn zero() -> Self {
+                    0 as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_cost!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+/// The cost of each kind of edit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Weights<C: Cost> {
+    pub insert: C,
+    pub delete: C,
+    pub substitute: C,
+}
+
+impl<C: Cost> Weights<C> {
+    /// Returns weights where every kind of edit costs the same.
+    ///
+    /// # Arguments
+    ///
+    /// * `cost` - The cost of any edit
+    pub fn uniform(cost: C) -> Self {
+        Weights {
+            insert: cost,
+            delete: cost,
+            substitute: cost,
+        }
+    }
+}
+
+/// Decides how much each individual edit costs.
+///
+/// Positions follow the same 1-indexed convention as `Edit`: `source_idx` is the index of the
+/// deleted or substituted item, or the number of source items before an insertion. The item
+/// being inserted, deleted or substituted is passed along, so costs can depend on both where an
+/// edit happens and what it touches.
+pub trait CostModel<T, C: Cost> {
+    /// Returns the cost of inserting `item` after the first `source_idx` items of the source.
+    fn insert_cost(&self, source_idx: usize, item: &T) -> C;
+
+    /// Returns the cost of deleting `item`, the item at `source_idx`.
+    fn delete_cost(&self, source_idx: usize, item: &T) -> C;
+
+    /// Returns the cost of substituting `from`, the item at `source_idx`, with `to`.
+    fn substitute_cost(&self, source_idx: usize, from: &T, to: &T) -> C;
+}
+
+impl<T, C: Cost> CostModel<T, C> for Weights<C> {
+    fn insert_cost(&self, _: usize, _: &T) -> C {
+        self.insert
+    }
+
+    fn delete_cost(&self, _: usize, _: &T) -> C {
+        self.delete
+    }
+
+    fn substitute_cost(&self, _: usize, _: &T, _: &T) -> C {
+        self.substitute
+    }
+}
+
+/// Weights that are scaled by a function of the position of each edit.
+///
+/// The cost of an edit is its weight multiplied by `factor(source_idx)`, which makes it easy to,
+/// for example, penalize edits near the beginning of a sequence more than those near its end.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{PositionWeights, Weights};
+///
+/// // Edits in the first three positions cost ten times as much
+/// let costs = PositionWeights {
+///     weights: Weights::uniform(1),
+///     factor: |idx: usize| if idx <= 3 { 10 } else { 1 },
+/// };
+///
+/// let (prefix, _) = levenshtein::weighted_distance("SUNDAY".as_bytes(), "MUNDAY".as_bytes(), &costs);
+/// let (suffix, _) = levenshtein::weighted_distance("SUNDAY".as_bytes(), "SUNDAE".as_bytes(), &costs);
+///
+/// assert_eq!(prefix, 10);
+/// assert_eq!(suffix, 1);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct PositionWeights<C: Cost, F> {
+    pub weights: Weights<C>,
+    pub factor: F,
+}
+
+impl<T, C, F> CostModel<T, C> for PositionWeights<C, F>
+where
+    C: Cost + Mul<Output = C>,
+    F: Fn(usize) -> C,
+{
+    fn insert_cost(&self, source_idx: usize, _: &T) -> C {
+        self.weights.insert * (self.factor)(source_idx)
+    }
+
+    fn delete_cost(&self, source_idx: usize, _: &T) -> C {
+        self.weights.delete * (self.factor)(source_idx)
+    }
+
+    fn substitute_cost(&self, source_idx: usize, _: &T, _: &T) -> C {
+        self.weights.substitute * (self.factor)(source_idx)
+    }
+}
+
+// Returns the smaller of two partially ordered values, preferring the first one
+fn min_cost<C: Cost>(a: C, b: C) -> C {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+/// Returns the weighted Levenshtein distance and the distance matrix between source and target
+/// using dynamic programming with tabulation.
+///
+/// Every edit costs whatever `costs` says it does rather than 1. With `Weights::uniform(1)` this
+/// computes the same values as `levenshtein_tabulation`.
+///
+/// This implementation has a time complexity of O(n^2) and a space complexity of O(n^2).
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `costs` - The cost model, e.g. `Weights` or `PositionWeights`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Weights;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+///
+/// // Substituting is as expensive as deleting and inserting
+/// let weights = Weights { insert: 1.0, delete: 1.0, substitute: 2.0 };
+///
+/// let (distance, _) = levenshtein::weighted_distance(s1.as_bytes(), s2.as_bytes(), &weights);
+/// assert_eq!(distance, 4.0);
+/// ```
+pub fn weighted_distance<T: PartialEq, C: Cost, M: CostModel<T, C>>(
+    source: &[T],
+    target: &[T],
+    costs: &M,
+) -> (C, DistanceMatrix<C>) {
+    let m = source.len();
+    let n = target.len();
+
+    let mut distances = DistanceMatrix::filled(m + 1, n + 1, C::zero());
+
+    // The first row and column are the costs of inserting or deleting everything so far
+    for j in 1..n + 1 {
+        distances.set(
+            0,
+            j,
+            distances[(0, j - 1)] + costs.insert_cost(0, &target[j - 1]),
+        );
+    }
+
+    for i in 1..m + 1 {
+        distances.set(
+            i,
+            0,
+            distances[(i - 1, 0)] + costs.delete_cost(i, &source[i - 1]),
+        );
+    }
+
+    for i in 1..m + 1 {
+        for j in 1..n + 1 {
+            let delete = distances[(i - 1, j)] + costs.delete_cost(i, &source[i - 1]);
+            let insert = distances[(i, j - 1)] + costs.insert_cost(i, &target[j - 1]);
+            let substitute = if source[i - 1] == target[j - 1] {
+                distances[(i - 1, j - 1)]
+            } else {
+                distances[(i - 1, j - 1)] + costs.substitute_cost(i, &source[i - 1], &target[j - 1])
+            };
+
+            distances.set(i, j, min_cost(min_cost(delete, insert), substitute));
+        }
+    }
+
+    (distances.distance(), distances)
+}
+
+/// Generate a vector of edits that, when applied to the source sequence, transform it into the
+/// target sequence, using a matrix computed by `weighted_distance`.
+///
+/// The edits use the same representation as those returned by `generate_edits`, so they can be
+/// applied with `apply_edits`.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence
+/// * `target` - The target sequence
+/// * `costs` - The cost model the matrix was computed with
+/// * `distances` - A reference to the weighted `DistanceMatrix` for converting source to target
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::Weights;
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+/// let weights = Weights { insert: 1.0, delete: 1.0, substitute: 2.0 };
+///
+/// let (_, matrix) = levenshtein::weighted_distance(s1.as_bytes(), s2.as_bytes(), &weights);
+///
+/// let edits =
+///     levenshtein::generate_edits_weighted(s1.as_bytes(), s2.as_bytes(), &weights, &matrix)
+///         .unwrap();
+///
+/// let target = levenshtein::apply_edits(s1.as_bytes(), &edits);
+/// assert_eq!(target, s2.as_bytes());
+/// ```
+pub fn generate_edits_weighted<T: Clone + PartialEq, C: Cost, M: CostModel<T, C>>(
+    source: &[T],
+    target: &[T],
+    costs: &M,
+    distances: &DistanceMatrix<C>,
+) -> Result<Vec<Edit<T>>, LevenshteinError> {
+    let mut source_idx = source.len();
+    let mut target_idx = target.len();
+
+    if (source_idx + 1, target_idx + 1) != distances.dims() {
+        return Err(LevenshteinError::InvalidDistanceMatrixError);
+    }
+
+    let mut edits = Vec::<Edit<T>>::new();
+
+    // Costs are recomputed exactly as they were when the matrix was filled, so comparing them for
+    // equality is safe even for floating point weights
+    while source_idx != 0 || target_idx != 0 {
+        let current_item = distances[(source_idx, target_idx)];
+
+        if source_idx > 0 && target_idx > 0 {
+            let diagonal = distances[(source_idx - 1, target_idx - 1)];
+
+            if source[source_idx - 1] == target[target_idx - 1] && diagonal == current_item {
+                source_idx -= 1;
+                target_idx -= 1;
+                continue;
+            }
+        }
+
+        let inserted = target_idx > 0 && {
+            let item = &target[target_idx - 1];
+            distances[(source_idx, target_idx - 1)] + costs.insert_cost(source_idx, item)
+                == current_item
+        };
+
+        let deleted = !inserted && source_idx > 0 && {
+            let item = &source[source_idx - 1];
+            distances[(source_idx - 1, target_idx)] + costs.delete_cost(source_idx, item)
+                == current_item
+        };
+
+        let substituted = !inserted && !deleted && source_idx > 0 && target_idx > 0 && {
+            let from = &source[source_idx - 1];
+            let to = &target[target_idx - 1];
+            distances[(source_idx - 1, target_idx - 1)]
+                + costs.substitute_cost(source_idx, from, to)
+                == current_item
+        };
+
+        if inserted {
+            edits.push(Edit::Insert(source_idx, target[target_idx - 1].clone()));
+            target_idx -= 1;
+        } else if deleted {
+            edits.push(Edit::Delete(source_idx));
+            source_idx -= 1;
+        } else if substituted {
+            edits.push(Edit::Substitute(source_idx, target[target_idx - 1].clone()));
+            source_idx -= 1;
+            target_idx -= 1;
+        } else {
+            return Err(LevenshteinError::InvalidDistanceMatrixError);
+        }
+    }
+
+    Ok(edits)
+}
+
+/// Returns the cost of every edit under a cost model, in the same order as the edits.
+///
+/// The costs add up to the weighted distance for edits generated by `generate_edits_weighted`
+/// with the same cost model, which makes it possible to tell which edits a distance comes from.
+///
+/// # Arguments
+///
+/// * `source` - The source sequence the edits apply to
+/// * `edits` - The edits, e.g. as generated by `generate_edits_weighted`
+/// * `costs` - The cost model, e.g. `Weights` or `PositionWeights`
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{Edit, Weights};
+///
+/// let s1 = "SATURDAY";
+/// let s2 = "SUNDAY";
+/// let weights = Weights { insert: 2, delete: 1, substitute: 2 };
+///
+/// let (distance, matrix) = levenshtein::weighted_distance(s1.as_bytes(), s2.as_bytes(), &weights);
+/// let edits =
+///     levenshtein::generate_edits_weighted(s1.as_bytes(), s2.as_bytes(), &weights, &matrix)
+///         .unwrap();
+///
+/// let costs = levenshtein::edit_costs(s1.as_bytes(), &edits, &weights);
+///
+/// assert_eq!(edits[0], Edit::Substitute(5, b'N'));
+/// assert_eq!(costs, vec![2, 1, 1]);
+/// assert_eq!(costs.iter().sum::<i32>(), distance);
+/// ```
+pub fn edit_costs<T: PartialEq, C: Cost, M: CostModel<T, C>>(
+    source: &[T],
+    edits: &[Edit<T>],
+    costs: &M,
+) -> Vec<C> {
+    edits
+        .iter()
+        .map(|edit| match edit {
+            Edit::Insert(idx, val) => costs.insert_cost(*idx, val),
+            Edit::Delete(idx) => costs.delete_cost(*idx, &source[idx - 1]),
+            Edit::Substitute(idx, val) => costs.substitute_cost(*idx, &source[idx - 1], val),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::weighted::*;
+
+    #[test]
+    fn weighted_distance_test() {
+        let s1 = String::from("LAWN");
+        let s2 = String::from("FFLAWANN");
+
+        let (dist, _) = weighted_distance(s1.as_bytes(), s2.as_bytes(), &Weights::uniform(1));
+        assert_eq!(dist, 4);
+
+        // Only insertions are needed, so substitution weights don't matter
+        let weights = Weights {
+            insert: 0.5,
+            delete: 2.0,
+            substitute: 10.0,
+        };
+
+        let (dist, _) = weighted_distance(s1.as_bytes(), s2.as_bytes(), &weights);
+        assert_eq!(dist, 2.0);
+    }
+
+    #[test]
+    fn generate_edits_weighted_test() {
+        let s1 = "SATURDAY";
+        let s2 = "SUNDAY";
+
+        // Substitution is so expensive that it's never used
+        let weights = Weights {
+            insert: 1,
+            delete: 1,
+            substitute: 5,
+        };
+
+        let (dist, matrix) = weighted_distance(s1.as_bytes(), s2.as_bytes(), &weights);
+        assert_eq!(dist, 4);
+
+        let edits =
+            generate_edits_weighted(s1.as_bytes(), s2.as_bytes(), &weights, &matrix).unwrap();
+
+        assert_eq!(edits.len(), 4);
+        assert!(!edits.iter().any(|e| matches!(e, Edit::Substitute(..))));
+        assert_eq!(
+            crate::edit::apply_edits(s1.as_bytes(), &edits),
+            s2.as_bytes()
+        );
+    }
+
+    #[test]
+    fn position_weights_test() {
+        let s1 = "SATURDAY";
+        let s2 = "SUNDAY";
+
+        // Edits are free after the first position, and the S is shared
+        let costs = PositionWeights {
+            weights: Weights::uniform(1),
+            factor: |idx: usize| if idx <= 1 { 1 } else { 0 },
+        };
+
+        let (dist, matrix) = weighted_distance(s1.as_bytes(), s2.as_bytes(), &costs);
+        assert_eq!(dist, 0);
+
+        let edits = generate_edits_weighted(s1.as_bytes(), s2.as_bytes(), &costs, &matrix).unwrap();
+
+        assert_eq!(
+            crate::edit::apply_edits(s1.as_bytes(), &edits),
+            s2.as_bytes()
+        );
+    }
+
+    #[test]
+    fn edit_costs_test() {
+        let s1 = "kitten sitting";
+        let s2 = "sitting kitten";
+
+        let costs = PositionWeights {
+            weights: Weights::uniform(1),
+            factor: |idx: usize| idx + 1,
+        };
+
+        let (dist, matrix) = weighted_distance(s1.as_bytes(), s2.as_bytes(), &costs);
+        let edits = generate_edits_weighted(s1.as_bytes(), s2.as_bytes(), &costs, &matrix).unwrap();
+
+        let edit_costs = edit_costs(s1.as_bytes(), &edits, &costs);
+        assert_eq!(edit_costs.len(), edits.len());
+        assert_eq!(edit_costs.iter().sum::<usize>(), dist);
+    }
+}
@@ -0,0 +1,118 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+/// How whitespace is compared, like the whitespace options of `diff`.
+///
+/// Whitespace is what `char::is_whitespace` says it is. The options only change which texts are
+/// taken for equal: edits still hold the original texts, whitespace and all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Whitespace {
+    /// Whitespace is compared like any other character.
+    #[default]
+    Exact,
+    /// Whitespace at the end of a text is ignored, like `diff --ignore-trailing-space`.
+    IgnoreTrailing,
+    /// Whitespace at the end of a text is ignored, and every other run of whitespace is the same
+    /// as a single space, like `diff -b`. A run of whitespace is still different from none.
+    Collapse,
+    /// All whitespace is ignored, like `diff -w`.
+    IgnoreAll,
+}
+
+impl Whitespace {
+    /// Returns the text as it is compared with this option, which is borrowed from the text
+    /// unless whitespace had to be replaced or removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to normalize
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use levenshtein_diff as levenshtein;
+    /// use levenshtein::Whitespace;
+    ///
+    /// let line = "  let x =\t1;  ";
+    ///
+    /// assert_eq!(Whitespace::Exact.normalize(line), line);
+    /// assert_eq!(Whitespace::IgnoreTrailing.normalize(line), "  let x =\t1;");
+    /// assert_eq!(Whitespace::Collapse.normalize(line), " let x = 1;");
+    /// assert_eq!(Whitespace::IgnoreAll.normalize(line), "letx=1;");
+    /// ```
+    pub fn normalize(self, text: &str) -> Cow<'_, str> {
+        match self {
+            Whitespace::Exact => Cow::Borrowed(text),
+            Whitespace::IgnoreTrailing => Cow::Borrowed(text.trim_end()),
+            Whitespace::Collapse => collapse(text.trim_end()),
+            Whitespace::IgnoreAll if !text.contains(char::is_whitespace) => Cow::Borrowed(text),
+            Whitespace::IgnoreAll => {
+                Cow::Owned(text.chars().filter(|c| !c.is_whitespace()).collect())
+            }
+        }
+    }
+}
+
+// The text with every run of whitespace replaced with a single space
+fn collapse(text: &str) -> Cow<'_, str> {
+    // Runs that are already a single space don't need a copy
+    let mut in_run = false;
+    let collapsed = text.chars().all(|c| {
+        let single_space = !c.is_whitespace() || (c == ' ' && !in_run);
+        in_run = c.is_whitespace();
+        single_space
+    });
+
+    if collapsed {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut in_run = false;
+
+    for c in text.chars() {
+        if !c.is_whitespace() {
+            result.push(c);
+        } else if !in_run {
+            result.push(' ');
+        }
+
+        in_run = c.is_whitespace();
+    }
+
+    Cow::Owned(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::whitespace::*;
+
+    #[test]
+    fn normalize_test() {
+        assert!(matches!(
+            Whitespace::Collapse.normalize("a b c "),
+            Cow::Borrowed("a b c")
+        ));
+        assert!(matches!(
+            Whitespace::IgnoreAll.normalize("abc"),
+            Cow::Borrowed("abc")
+        ));
+
+        assert_eq!(Whitespace::Collapse.normalize("a \t b\n"), "a b");
+        assert_eq!(Whitespace::Collapse.normalize("\u{a0}a  b"), " a b");
+        assert_eq!(Whitespace::Collapse.normalize("   "), "");
+        assert_eq!(Whitespace::IgnoreTrailing.normalize(" a \r"), " a");
+        assert_eq!(Whitespace::IgnoreAll.normalize(" a\u{3000}b "), "ab");
+
+        // Collapsing never joins words
+        assert_ne!(
+            Whitespace::Collapse.normalize("a b"),
+            Whitespace::Collapse.normalize("ab")
+        );
+        assert_eq!(
+            Whitespace::IgnoreAll.normalize("a b"),
+            Whitespace::IgnoreAll.normalize("ab")
+        );
+    }
+}
@@ -0,0 +1,455 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+#[cfg(feature = "unicode-segmentation")]
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::distance::levenshtein_two_rows;
+use crate::edit::Edit;
+use crate::edit_op::EditOp;
+use crate::keyed::stripped_edit_ops;
+use crate::replace::coalesce_edits;
+
+/// Splits a string into the tokens that a word diff compares.
+///
+/// Tokens are returned as byte ranges of the string, in order and without overlapping. They don't
+/// have to cover all of it: the bytes between tokens are never compared, and are kept from the
+/// source when changes are applied. Any `Fn(&str) -> Vec<Range<usize>>` is a tokenizer.
+pub trait Tokenizer {
+    /// Returns the byte ranges of the tokens of a string.
+    fn tokenize(&self, text: &str) -> Vec<Range<usize>>;
+}
+
+impl<F: Fn(&str) -> Vec<Range<usize>>> Tokenizer for F {
+    fn tokenize(&self, text: &str) -> Vec<Range<usize>> {
+        self(text)
+    }
+}
+
+// The byte ranges of the runs of characters of the same class, where a class of None puts every
+// character in a token of its own
+fn runs<C: PartialEq, F: Fn(char) -> Option<C>>(text: &str, class: F) -> Vec<Range<usize>> {
+    let mut tokens: Vec<Range<usize>> = Vec::new();
+    let mut previous = None;
+
+    for (idx, c) in text.char_indices() {
+        let current = class(c);
+        let end = idx + c.len_utf8();
+
+        match tokens.last_mut() {
+            Some(last) if current.is_some() && current == previous => last.end = end,
+            _ => tokens.push(idx..end),
+        }
+
+        previous = current;
+    }
+
+    tokens
+}
+
+/// Splits a string into words, runs of whitespace, and punctuation.
+///
+/// Words are runs of alphanumeric characters, and every other character that isn't whitespace is
+/// a token of its own, so a changed comma doesn't make the word before it change too. Whitespace
+/// is kept as tokens, so the tokens cover the whole string.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{Tokenizer, WordTokenizer};
+///
+/// let text = "Hello, world!";
+/// let tokens: Vec<&str> = WordTokenizer
+///     .tokenize(text)
+///     .into_iter()
+///     .map(|range| &text[range])
+///     .collect();
+///
+/// assert_eq!(tokens, vec!["Hello", ",", " ", "world", "!"]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WordTokenizer;
+
+impl Tokenizer for WordTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<Range<usize>> {
+        runs(text, |c| {
+            if c.is_alphanumeric() {
+                Some(true)
+            } else if c.is_whitespace() {
+                Some(false)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Splits a string into runs of whitespace and runs of everything else.
+///
+/// Punctuation stays attached to the words around it, like in `git diff --word-diff`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WhitespaceTokenizer;
+
+impl Tokenizer for WhitespaceTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<Range<usize>> {
+        runs(text, |c| Some(c.is_whitespace()))
+    }
+}
+
+/// Splits a string into single characters, for diffs of the characters of strings that map
+/// their changes to bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CharTokenizer;
+
+impl Tokenizer for CharTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<Range<usize>> {
+        text.char_indices()
+            .map(|(idx, c)| idx..idx + c.len_utf8())
+            .collect()
+    }
+}
+
+/// Splits a string at the word boundaries of Unicode Standard Annex #29.
+///
+/// Unlike `WordTokenizer`, this keeps words like "can't" and "3.14" whole, and splits text
+/// without spaces between words, like Chinese, into single characters.
+#[cfg(feature = "unicode-segmentation")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnicodeWordTokenizer;
+
+#[cfg(feature = "unicode-segmentation")]
+impl Tokenizer for UnicodeWordTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<Range<usize>> {
+        text.split_word_bound_indices()
+            .map(|(idx, word)| idx..idx + word.len())
+            .collect()
+    }
+}
+
+// The tokens that aren't only whitespace
+pub(crate) fn without_whitespace(text: &str, tokens: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    tokens
+        .into_iter()
+        .filter(|range| !text[range.clone()].chars().all(char::is_whitespace))
+        .collect()
+}
+
+/// Leaves out the tokens of another tokenizer that are only whitespace, so that changes to the
+/// whitespace between words are ignored, like `git diff --word-diff -w`.
+///
+/// The whitespace is still there when changes are applied, since the bytes between tokens are
+/// kept from the source.
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{SkipWhitespace, WordTokenizer};
+///
+/// let source = "x = 1;";
+/// let target = "x  =\t1 ;";
+///
+/// assert_eq!(levenshtein::distance_words(source, target, &WordTokenizer), 3);
+/// assert_eq!(levenshtein::distance_words(source, target, &SkipWhitespace(WordTokenizer)), 0);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SkipWhitespace<K>(pub K);
+
+impl<K: Tokenizer> Tokenizer for SkipWhitespace<K> {
+    fn tokenize(&self, text: &str) -> Vec<Range<usize>> {
+        without_whitespace(text, self.0.tokenize(text))
+    }
+}
+
+/// Replaces a range of bytes of the source string with a range of bytes of the target string.
+///
+/// Ranges are 0-indexed and half-open, like slice ranges. An empty `source` range is a pure
+/// insertion, and an empty `target` range a pure deletion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextChange {
+    pub source: Range<usize>,
+    pub target: Range<usize>,
+}
+
+// The tokens of a string, as slices of it
+fn token_slices<'a>(text: &'a str, ranges: &[Range<usize>]) -> Vec<&'a str> {
+    ranges.iter().map(|range| &text[range.clone()]).collect()
+}
+
+// The bytes a range of tokens covers, where the gap before token g is at the start of token g
+fn byte_range(ranges: &[Range<usize>], len: usize, tokens: Range<usize>) -> Range<usize> {
+    if tokens.is_empty() {
+        let offset = ranges.get(tokens.start).map_or(len, |range| range.start);
+        return offset..offset;
+    }
+
+    ranges[tokens.start].start..ranges[tokens.end - 1].end
+}
+
+/// Returns the Levenshtein distance between two strings, counting the tokens the tokenizer splits
+/// them into.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+/// * `tokenizer` - Splits the strings into tokens
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{WhitespaceTokenizer, WordTokenizer};
+///
+/// let source = "The quick brown fox.";
+/// let target = "The quick red fox!";
+///
+/// assert_eq!(levenshtein::distance_words(source, target, &WordTokenizer), 2);
+/// assert_eq!(levenshtein::distance_words(source, target, &WhitespaceTokenizer), 2);
+/// assert_eq!(levenshtein::distance_words(source, "The quick red fox.", &WhitespaceTokenizer), 1);
+/// ```
+pub fn distance_words<K: Tokenizer + ?Sized>(source: &str, target: &str, tokenizer: &K) -> usize {
+    let source = token_slices(source, &tokenizer.tokenize(source));
+    let target = token_slices(target, &tokenizer.tokenize(target));
+
+    levenshtein_two_rows(&source, &target)
+}
+
+/// Generate the changes that turn the source string into the target string, diffing the tokens
+/// the tokenizer splits them into, and returning them as ranges of bytes of both strings.
+///
+/// Runs of adjacent changed tokens are merged into a single change, so the changes are in
+/// ascending order, don't overlap, and are separated by unchanged tokens: `&source[change.source]`
+/// is replaced with `&target[change.target]`. An insertion before token g is placed at the start
+/// of token g.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+/// * `tokenizer` - Splits the strings into tokens
+///
+/// # Examples
+///
+/// ```
+/// use levenshtein_diff as levenshtein;
+/// use levenshtein::{TextChange, WordTokenizer};
+///
+/// let source = "The quick brown fox jumps.";
+/// let target = "The quick red fox leaps.";
+///
+/// let changes = levenshtein::diff_words(source, target, &WordTokenizer);
+/// assert_eq!(
+///     changes,
+///     vec![
+///         TextChange { source: 10..15, target: 10..13 },
+///         TextChange { source: 20..25, target: 18..23 },
+///     ]
+/// );
+/// assert_eq!(&source[10..15], "brown");
+/// assert_eq!(&target[18..23], "leaps");
+///
+/// assert_eq!(levenshtein::apply_text_changes(source, target, &changes), target);
+/// ```
+pub fn diff_words<K: Tokenizer + ?Sized>(
+    source: &str,
+    target: &str,
+    tokenizer: &K,
+) -> Vec<TextChange> {
+    let source_ranges = tokenizer.tokenize(source);
+    let target_ranges = tokenizer.tokenize(target);
+
+    let source_tokens = token_slices(source, &source_ranges);
+    let target_tokens = token_slices(target, &target_ranges);
+
+    // Edits whose new items are the indices of target tokens, so that coalescing them gives the
+    // target tokens each change turns into
+    let edits: Vec<Edit<usize>> = stripped_edit_ops(&source_tokens, &target_tokens)
+        .into_iter()
+        .map(|op| match op {
+            EditOp::Delete(idx) => Edit::Delete(idx),
+            EditOp::Insert(idx, target_idx) => Edit::Insert(idx, target_idx),
+            EditOp::Substitute(idx, target_idx) => Edit::Substitute(idx, target_idx),
+        })
+        .collect();
+
+    // The tokens between two changes are kept, so they are as many in the source as in the target
+    let mut source_end = 0;
+    let mut target_end = 0;
+
+    coalesce_edits(&edits)
+        .into_iter()
+        .map(|replace| {
+            let target_start = target_end + (replace.range.start - source_end);
+
+            source_end = replace.range.end;
+            target_end = target_start + replace.with.len();
+
+            TextChange {
+                source: byte_range(&source_ranges, source.len(), replace.range),
+                target: byte_range(&target_ranges, target.len(), target_start..target_end),
+            }
+        })
+        .collect()
+}
+
+/// Applies a sequence of text changes to the source string, taking the new text from the target,
+/// and returns the resulting string.
+///
+/// The changes must be sorted and must not overlap, as returned by `diff_words`. If the tokenizer
+/// covers the whole strings, the result is the target; otherwise the bytes between unchanged
+/// tokens are kept from the source.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string the changes refer to
+/// * `changes` - The changes to apply
+pub fn apply_text_changes(source: &str, target: &str, changes: &[TextChange]) -> String {
+    let mut result = String::with_capacity(target.len());
+    let mut pos = 0;
+
+    for change in changes {
+        result.push_str(&source[pos..change.source.start]);
+        result.push_str(&target[change.target.clone()]);
+        pos = change.source.end;
+    }
+
+    result.push_str(&source[pos..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::words::*;
+
+    fn tokens<'a, K: Tokenizer>(text: &'a str, tokenizer: &K) -> Vec<&'a str> {
+        token_slices(text, &tokenizer.tokenize(text))
+    }
+
+    #[test]
+    fn tokenizers_test() {
+        let text = "  Don't panic, 42 times!\n";
+
+        assert_eq!(
+            tokens(text, &WordTokenizer),
+            vec!["  ", "Don", "'", "t", " ", "panic", ",", " ", "42", " ", "times", "!", "\n"]
+        );
+        assert_eq!(
+            tokens(text, &WhitespaceTokenizer),
+            vec!["  ", "Don't", " ", "panic,", " ", "42", " ", "times!", "\n"]
+        );
+        assert!(WordTokenizer.tokenize("").is_empty());
+        assert_eq!(tokens("añb", &CharTokenizer), vec!["a", "ñ", "b"]);
+
+        // Punctuation runs aren't merged
+        assert_eq!(tokens("?!", &WordTokenizer), vec!["?", "!"]);
+        assert_eq!(
+            tokens("日本語 ok", &WordTokenizer),
+            vec!["日本語", " ", "ok"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-segmentation")]
+    fn unicode_word_tokenizer_test() {
+        assert_eq!(
+            tokens("Don't pay 3.14!", &UnicodeWordTokenizer),
+            vec!["Don't", " ", "pay", " ", "3.14", "!"]
+        );
+    }
+
+    #[test]
+    fn diff_words_test() {
+        let source = "one two three four";
+        let target = "one 2 three four five";
+
+        let changes = diff_words(source, target, &WordTokenizer);
+        assert_eq!(
+            changes,
+            vec![
+                TextChange {
+                    source: 4..7,
+                    target: 4..5
+                },
+                TextChange {
+                    source: 18..18,
+                    target: 16..21
+                },
+            ]
+        );
+        assert_eq!(apply_text_changes(source, target, &changes), target);
+        assert_eq!(distance_words(source, target, &WordTokenizer), 3);
+
+        let changes = diff_words("b c", "a b c", &WordTokenizer);
+        assert_eq!(
+            changes,
+            vec![TextChange {
+                source: 0..0,
+                target: 0..2
+            }]
+        );
+
+        assert!(diff_words(source, source, &WordTokenizer).is_empty());
+        assert_eq!(
+            diff_words("", "new text", &WordTokenizer),
+            vec![TextChange {
+                source: 0..0,
+                target: 0..8
+            }]
+        );
+    }
+
+    #[test]
+    fn skip_whitespace_test() {
+        let skipping = SkipWhitespace(WhitespaceTokenizer);
+        assert_eq!(tokens(" a  b\n", &skipping), vec!["a", "b"]);
+
+        let source = "let  x = 1;\n";
+        let target = "let x = 2;";
+
+        let changes = diff_words(source, target, &SkipWhitespace(WordTokenizer));
+        assert_eq!(
+            changes,
+            vec![TextChange {
+                source: 9..10,
+                target: 8..9
+            }]
+        );
+        assert_eq!(
+            apply_text_changes(source, target, &changes),
+            "let  x = 2;\n"
+        );
+    }
+
+    #[test]
+    fn diff_words_custom_tokenizer_test() {
+        // Only the words, skipping the whitespace between them
+        let words = |text: &str| -> Vec<Range<usize>> {
+            WordTokenizer
+                .tokenize(text)
+                .into_iter()
+                .filter(|range| !text[range.clone()].trim().is_empty())
+                .collect()
+        };
+
+        let source = "a  b c";
+        let target = "a b  d";
+
+        let changes = diff_words(source, target, &words);
+        assert_eq!(
+            changes,
+            vec![TextChange {
+                source: 5..6,
+                target: 5..6
+            }]
+        );
+
+        // The whitespace comes from the source
+        assert_eq!(apply_text_changes(source, target, &changes), "a  b d");
+        assert_eq!(distance_words(source, target, &words), 1);
+    }
+}
@@ -3,6 +3,13 @@ use rand::{thread_rng, Rng};
 
 use levenshtein_diff as levenshtein;
 
+// Returns a random sequence of up to `max_len` items from a small alphabet, so that sequences
+// often share items
+fn random_bytes(rng: &mut impl Rng, max_len: usize) -> Vec<u8> {
+    let len = rng.gen_range(0..=max_len);
+    (0..len).map(|_| rng.gen_range(b'a'..b'e')).collect()
+}
+
 #[test]
 fn test_regeneration_of_target() {
     let source = "SATURDAY";
@@ -55,12 +62,18 @@ fn test_strings_have_same_distances() {
         levenshtein::levenshtein_tabulation(random_str_1.as_bytes(), random_str_2.as_bytes());
     let (leven_memo, _) =
         levenshtein::levenshtein_memoization(random_str_1.as_bytes(), random_str_2.as_bytes());
+    let leven_two_rows =
+        levenshtein::levenshtein_two_rows(random_str_1.as_bytes(), random_str_2.as_bytes());
+    let leven_const =
+        levenshtein::levenshtein_const(random_str_1.as_bytes(), random_str_2.as_bytes());
 
     // Putting all three assertions here though one would be redundant to easily identify the
     // broken function if the test fails
     assert_eq!(leven_naive, leven_tab);
     assert_eq!(leven_naive, leven_memo);
     assert_eq!(leven_tab, leven_memo);
+    assert_eq!(leven_naive, leven_two_rows);
+    assert_eq!(leven_naive, leven_const);
 }
 
 #[test]
@@ -82,3 +95,1401 @@ fn test_for_cases_that_differ_by_one_item() {
     // Just a sanity check
     assert_eq!(target_collection.len(), generated_target_vec.len())
 }
+
+#[test]
+fn test_is_within_distance_agrees_with_distance() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        // A small alphabet makes matches (and therefore interesting bands) more likely
+        let s1 = random_bytes(&mut rng, 12);
+        let s2 = random_bytes(&mut rng, 12);
+
+        let (dist, _) = levenshtein::distance(&s1, &s2);
+
+        for k in 0..14 {
+            assert_eq!(levenshtein::is_within_distance(&s1, &s2, k), dist <= k);
+        }
+    }
+}
+
+#[test]
+fn test_prefilters_are_lower_bounds() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 12);
+        let s2 = random_bytes(&mut rng, 12);
+
+        let dist = levenshtein::distance_only(&s1, &s2);
+        let length_bound = levenshtein::util::length_lower_bound(&s1, &s2);
+        let histogram_bound = levenshtein::util::histogram_lower_bound(&s1, &s2);
+
+        assert!(length_bound <= histogram_bound);
+        assert!(histogram_bound <= dist);
+    }
+}
+
+#[test]
+fn test_one_to_many_agrees_with_distance() {
+    let mut rng = thread_rng();
+
+    // Sources on both sides of the 64 element blocks of the bit-parallel algorithm
+    for source_len in [0usize, 5, 64, 65, 80, 128, 129, 300] {
+        let source: Vec<u8> = (0..source_len).map(|_| rng.gen_range(b'a'..b'e')).collect();
+
+        let targets: Vec<Vec<u8>> = (0..50)
+            .map(|_| {
+                let len = rng.gen_range(source_len.saturating_sub(5)..source_len + 5);
+                (0..len).map(|_| rng.gen_range(b'a'..b'e')).collect()
+            })
+            .collect();
+        let target_slices: Vec<&[u8]> = targets.iter().map(|t| t.as_slice()).collect();
+
+        let unbounded = levenshtein::distances_one_to_many(&source, &target_slices, None);
+        let bounded = levenshtein::distances_one_to_many(&source, &target_slices, Some(10));
+
+        for (i, target) in targets.iter().enumerate() {
+            let dist = levenshtein::distance_only(&source, target);
+
+            assert_eq!(unbounded[i], Some(dist));
+            assert_eq!(bounded[i], if dist <= 10 { Some(dist) } else { None });
+        }
+    }
+}
+
+#[test]
+fn test_uniform_weights_match_unweighted_distance() {
+    let mut rng = thread_rng();
+
+    for _ in 0..50 {
+        let s1 = random_bytes(&mut rng, 12);
+        let s2 = random_bytes(&mut rng, 12);
+
+        let weights = levenshtein::Weights::uniform(1.0);
+
+        let (dist, _) = levenshtein::distance(&s1, &s2);
+        let (weighted_dist, matrix) = levenshtein::weighted_distance(&s1, &s2, &weights);
+
+        assert_eq!(dist as f64, weighted_dist);
+
+        let edits = levenshtein::generate_edits_weighted(&s1, &s2, &weights, &matrix).unwrap();
+
+        assert_eq!(edits.len(), dist);
+        assert_eq!(levenshtein::apply_edits(&s1, &edits), s2);
+    }
+}
+
+#[test]
+fn test_coalesced_edits_regenerate_target() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 12);
+        let s2 = random_bytes(&mut rng, 12);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+
+        let replacements = levenshtein::coalesce_edits(&edits);
+
+        assert!(replacements.len() <= edits.len());
+        assert_eq!(levenshtein::apply_replacements(&s1, &replacements), s2);
+
+        let block_edits = levenshtein::detect_moves(&s1, &replacements, 1, true);
+
+        assert_eq!(levenshtein::apply_block_edits(&s1, &block_edits), s2);
+    }
+}
+
+#[test]
+fn test_composed_edits_regenerate_target() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let a = random_bytes(&mut rng, 12);
+        let b = random_bytes(&mut rng, 12);
+        let c = random_bytes(&mut rng, 12);
+
+        let (_, matrix) = levenshtein::distance(&a, &b);
+        let a_to_b = levenshtein::generate_edits(&a, &b, &matrix).unwrap();
+
+        let (_, matrix) = levenshtein::distance(&b, &c);
+        let b_to_c = levenshtein::generate_edits(&b, &c, &matrix).unwrap();
+
+        let a_to_c = levenshtein::compose_edits(&a_to_b, &b_to_c);
+
+        assert_eq!(levenshtein::apply_edits(&a, &a_to_c), c);
+    }
+}
+
+#[test]
+fn test_transformed_edits_converge() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let base = random_bytes(&mut rng, 12);
+        let ours_target = random_bytes(&mut rng, 12);
+        let theirs_target = random_bytes(&mut rng, 12);
+
+        let (_, matrix) = levenshtein::distance(&base, &ours_target);
+        let ours = levenshtein::generate_edits(&base, &ours_target, &matrix).unwrap();
+
+        let (_, matrix) = levenshtein::distance(&base, &theirs_target);
+        let theirs = levenshtein::generate_edits(&base, &theirs_target, &matrix).unwrap();
+
+        let (ours_prime, theirs_prime) = levenshtein::transform(&ours, &theirs);
+
+        let ours_then_theirs = levenshtein::apply_edits(&ours_target, &theirs_prime);
+        let theirs_then_ours = levenshtein::apply_edits(&theirs_target, &ours_prime);
+
+        assert_eq!(ours_then_theirs, theirs_then_ours);
+    }
+}
+
+#[test]
+fn test_index_map_finds_unchanged_items() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 12);
+        let s2 = random_bytes(&mut rng, 12);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+
+        let map = levenshtein::IndexMap::new(&edits);
+
+        let mut unchanged = 0;
+        for (i, item) in s1.iter().enumerate() {
+            if let Some(j) = map.map_index(i) {
+                assert_eq!(s2[j], *item);
+                unchanged += 1;
+            }
+        }
+
+        // Every item that isn't deleted or substituted is kept
+        assert_eq!(
+            unchanged
+                + edits
+                    .iter()
+                    .filter(|e| !matches!(e, levenshtein::Edit::Insert(..)))
+                    .count(),
+            s1.len()
+        );
+        assert!(map.map_position(s1.len()) <= s2.len());
+    }
+}
+
+#[test]
+fn test_forward_edits_regenerate_target() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 12);
+        let s2 = random_bytes(&mut rng, 12);
+
+        let (dist, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits_forward(&s1, &s2, &matrix).unwrap();
+
+        assert_eq!(edits.len(), dist);
+        assert_eq!(levenshtein::apply_edits_forward(&s1, &edits), s2);
+    }
+}
+
+#[test]
+fn test_zero_based_edits_regenerate_target() {
+    use levenshtein::EditIndexing;
+
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 12);
+        let s2 = random_bytes(&mut rng, 12);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits_indexed(&s1, &s2, &matrix, EditIndexing::ZeroBased)
+            .unwrap();
+
+        assert_eq!(
+            levenshtein::apply_edits_indexed(&s1, &edits, EditIndexing::ZeroBased),
+            s2
+        );
+    }
+}
+
+#[test]
+fn test_backpointer_edits_match_generate_edits() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 12);
+        let s2 = random_bytes(&mut rng, 12);
+
+        let (leven, matrix, backpointers) =
+            levenshtein::levenshtein_tabulation_backpointers(&s1, &s2);
+        assert_eq!(leven, levenshtein::distance(&s1, &s2).0);
+
+        let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+        let backpointer_edits =
+            levenshtein::generate_edits_from_backpointers(&s1, &s2, &backpointers).unwrap();
+
+        assert!(edits == backpointer_edits);
+    }
+}
+
+#[test]
+fn test_tiebreak_policies_regenerate_target() {
+    use levenshtein::TiebreakPolicy;
+
+    let mut rng = thread_rng();
+
+    let policies = [
+        TiebreakPolicy::PreferInsertion,
+        TiebreakPolicy::PreferDeletion,
+        TiebreakPolicy::PreferSubstitution,
+        TiebreakPolicy::PreferContiguity,
+    ];
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 12);
+        let s2 = random_bytes(&mut rng, 12);
+
+        let (distance, matrix) = levenshtein::distance(&s1, &s2);
+
+        for policy in policies {
+            let edits = levenshtein::generate_edits_with_policy(&s1, &s2, &matrix, policy).unwrap();
+
+            assert_eq!(edits.len(), distance);
+            assert_eq!(levenshtein::apply_edits(&s1, &edits), s2);
+        }
+    }
+}
+
+#[test]
+fn test_semantic_cleanup_regenerates_target() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 20);
+        let s2 = random_bytes(&mut rng, 20);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+        let replacements = levenshtein::coalesce_edits(&edits);
+
+        let cleaned = levenshtein::cleanup_semantic(&s1, &replacements, |c| *c == b'a');
+
+        assert!(cleaned.len() <= replacements.len());
+        assert_eq!(levenshtein::apply_replacements(&s1, &cleaned), s2);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialized_edits_round_trip() {
+    use levenshtein::util::DistanceMatrix;
+    use levenshtein::{EditIndexing, EditScript, TiebreakPolicy};
+
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 12);
+        let s2 = random_bytes(&mut rng, 12);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let script = EditScript::from(levenshtein::generate_edits(&s1, &s2, &matrix).unwrap());
+
+        let json = serde_json::to_string(&script).unwrap();
+        let deserialized: EditScript<u8> = serde_json::from_str(&json).unwrap();
+        assert!(deserialized == script);
+
+        let json = serde_json::to_string(&matrix).unwrap();
+        let deserialized: DistanceMatrix = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, matrix);
+    }
+
+    // The options edits are generated with can be stored along with them
+    let options = (EditIndexing::ZeroBased, TiebreakPolicy::PreferContiguity);
+    let json = serde_json::to_string(&options).unwrap();
+    let deserialized: (EditIndexing, TiebreakPolicy) = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, options);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_reloaded_matrices_generate_the_same_edits() {
+    use levenshtein::util::DistanceMatrix;
+
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 30);
+        let s2 = random_bytes(&mut rng, 30);
+
+        let (dist, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+
+        let json = serde_json::to_string(&matrix).unwrap();
+        let reloaded: DistanceMatrix = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            levenshtein::generate_edits(&s1, &s2, &reloaded).unwrap(),
+            edits
+        );
+
+        let (_, narrow) = levenshtein::distance_as::<_, u32>(&s1, &s2);
+        let json = serde_json::to_string(&narrow).unwrap();
+        let reloaded: DistanceMatrix<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            levenshtein::generate_edits(&s1, &s2, &reloaded).unwrap(),
+            edits
+        );
+
+        // A banded matrix is reloaded as a full one
+        let (_, banded) = levenshtein::levenshtein_banded(&s1, &s2, dist).unwrap();
+        let json = serde_json::to_string(&banded).unwrap();
+        let reloaded: DistanceMatrix = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded, banded);
+        assert_eq!(
+            levenshtein::generate_edits(&s1, &s2, &reloaded).unwrap(),
+            edits
+        );
+    }
+}
+
+#[test]
+fn test_encoded_edits_round_trip() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 30);
+        let s2 = random_bytes(&mut rng, 30);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+
+        let encoded = levenshtein::encode_edits(&edits);
+        assert!(encoded.len() <= 3 * edits.len());
+
+        let decoded = levenshtein::decode_edits::<u8>(&encoded).unwrap();
+        assert_eq!(decoded, edits);
+        assert_eq!(levenshtein::apply_edits(&s1, &decoded), s2);
+    }
+}
+
+#[test]
+fn test_patches_only_apply_to_their_source() {
+    use levenshtein::{Patch, PatchError};
+
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let len_1: usize = rng.gen_range(1..20);
+
+        let s1: Vec<u8> = (0..len_1).map(|_| rng.gen_range(b'a'..b'e')).collect();
+        let s2 = random_bytes(&mut rng, 20);
+
+        let patch = Patch::<u8>::from_bytes(&Patch::new(&s1, &s2).to_bytes()).unwrap();
+        assert_eq!(patch.apply(&s1), Ok(s2));
+
+        // Change one item of the source
+        let mut other = s1.clone();
+        let idx = rng.gen_range(0..len_1);
+        other[idx] = b'z';
+
+        assert_eq!(patch.apply(&other), Err(PatchError::SourceMismatch));
+    }
+}
+
+#[test]
+fn test_binary_delta_regenerates_target() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let len_1: usize = rng.gen_range(0..200);
+        let s1: Vec<u8> = (0..len_1).map(|_| rng.gen_range(b'a'..b'e')).collect();
+
+        // Copy random chunks of the source, with random changes sprinkled in
+        let mut s2 = Vec::new();
+        while s2.len() < len_1 && len_1 > 10 {
+            let from = rng.gen_range(0..len_1 - 10);
+            s2.extend_from_slice(&s1[from..from + 10]);
+            s2.push(rng.gen_range(b'a'..b'f'));
+        }
+
+        let delta = levenshtein::binary_delta(&s1, &s2, 6);
+        assert_eq!(levenshtein::apply_binary_delta(&s1, &delta), s2);
+    }
+}
+
+#[test]
+fn test_rsync_delta_regenerates_target() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let len_1: usize = rng.gen_range(0..200);
+        let s1: Vec<u8> = (0..len_1).map(|_| rng.gen_range(b'a'..b'e')).collect();
+
+        // Copy random chunks of the source, with random changes sprinkled in
+        let mut s2 = Vec::new();
+        while s2.len() < len_1 && len_1 > 10 {
+            let from = rng.gen_range(0..len_1 - 10);
+            s2.extend_from_slice(&s1[from..from + 10]);
+            s2.push(rng.gen_range(b'a'..b'f'));
+        }
+
+        let block_size = rng.gen_range(1..8);
+        let delta = levenshtein::rsync_delta(&s1, &s2, block_size);
+        assert_eq!(levenshtein::apply_binary_delta(&s1, &delta), s2);
+    }
+}
+
+#[test]
+fn test_chunked_edits_regenerate_target() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 2500);
+
+        // Long enough that the chunks are compared before the bytes
+        let mut s2 = s1.clone();
+        for _ in 0..rng.gen_range(0..5) {
+            let at = rng.gen_range(0..s2.len() + 1);
+            let end = std::cmp::min(at + rng.gen_range(0..20), s2.len());
+            let with: Vec<u8> = (0..rng.gen_range(0..20))
+                .map(|_| rng.gen_range(b'a'..b'f'))
+                .collect();
+            s2.splice(at..end, with);
+        }
+
+        let edits = levenshtein::chunked_edits(&s1, &s2, 64);
+        assert_eq!(levenshtein::apply_edits(&s1, &edits), s2);
+    }
+}
+
+#[test]
+fn test_git_delta_regenerates_target() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 30);
+        let s2 = random_bytes(&mut rng, 30);
+
+        let delta = levenshtein::git_delta(&s1, &s2);
+        assert_eq!(levenshtein::apply_git_delta(&s1, &delta).unwrap(), s2);
+    }
+}
+
+#[test]
+fn test_dmp_patch_round_trip() {
+    let mut rng = thread_rng();
+    let alphabet = ['a', 'b', ' ', '%', '\n', '\u{e9}', '\u{1f600}'];
+
+    for _ in 0..100 {
+        let len_1: usize = rng.gen_range(0..40);
+        let len_2: usize = rng.gen_range(0..40);
+
+        let s1: String = (0..len_1).map(|_| alphabet[rng.gen_range(0..7)]).collect();
+        let s2: String = (0..len_2).map(|_| alphabet[rng.gen_range(0..7)]).collect();
+
+        let edits = levenshtein::parse_dmp_patch(&levenshtein::dmp_patch(&s1, &s2)).unwrap();
+        let source: Vec<char> = s1.chars().collect();
+
+        let target: String = levenshtein::apply_edits(&source, &edits)
+            .into_iter()
+            .collect();
+        assert_eq!(target, s2);
+    }
+}
+
+#[test]
+fn test_hunks_cover_all_changes() {
+    use levenshtein::HunkPart;
+
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let context_len: usize = rng.gen_range(0..4);
+
+        let s1 = random_bytes(&mut rng, 30);
+        let s2 = random_bytes(&mut rng, 30);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+
+        let hunks = levenshtein::group_into_hunks(&s1, &edits, context_len);
+
+        // Each hunk turns its part of the source into its part of the target
+        for hunk in &hunks {
+            let mut target = Vec::new();
+
+            for part in &hunk.parts {
+                match part {
+                    HunkPart::Context(items) => target.extend_from_slice(items),
+                    HunkPart::Change { added, .. } => target.extend_from_slice(added),
+                }
+            }
+
+            assert_eq!(target, &s2[hunk.target.clone()]);
+        }
+
+        let changes: Vec<_> = hunks.iter().flat_map(|hunk| hunk.changes()).collect();
+        assert_eq!(levenshtein::apply_replacements(&s1, &changes), s2);
+    }
+}
+
+#[test]
+fn test_fuzzy_hunks_follow_drifted_source() {
+    use rand::seq::SliceRandom;
+
+    let mut rng = thread_rng();
+    let alphabet: Vec<u8> = (b'a'..=b'z').collect();
+
+    for _ in 0..100 {
+        let len_1: usize = rng.gen_range(1..alphabet.len());
+        let context_len: usize = rng.gen_range(1..4);
+
+        // The items of the source are distinct, so the items a hunk covers occur only once in it,
+        // and there's only one place to apply it
+        let s1: Vec<u8> = alphabet.choose_multiple(&mut rng, len_1).cloned().collect();
+        let s2 = random_bytes(&mut rng, 30);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+        let hunks = levenshtein::group_into_hunks(&s1, &edits, context_len);
+
+        let result = levenshtein::apply_hunks_fuzzy(&s1, &hunks, 0, 0);
+        assert_eq!(result.target, s2);
+
+        // Items that can't be part of any hunk, added before all of them
+        let prefix_len = rng.gen_range(1..5);
+        let mut drifted = vec![b'X'; prefix_len];
+        drifted.extend_from_slice(&s1);
+
+        let result = levenshtein::apply_hunks_fuzzy(&drifted, &hunks, prefix_len, 0);
+        assert_eq!(result.target[prefix_len..], s2[..]);
+        assert!(result.rejected().is_empty());
+    }
+}
+
+#[test]
+fn test_in_place_apply_matches_apply() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 30);
+        let s2 = random_bytes(&mut rng, 30);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+
+        let mut sequence = s1.clone();
+        levenshtein::apply_edits_in_place(&mut sequence, &edits).unwrap();
+        assert_eq!(sequence, s2);
+    }
+}
+
+#[test]
+fn test_streaming_apply_matches_apply() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 30);
+        let s2 = random_bytes(&mut rng, 30);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+
+        let mut target = Vec::new();
+        levenshtein::apply_edits_streaming(&s1[..], &edits, &mut target).unwrap();
+        assert_eq!(target, s2);
+    }
+}
+
+#[test]
+fn test_edit_ops_regenerate_target() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 30);
+        let s2 = random_bytes(&mut rng, 30);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let ops = levenshtein::generate_edit_ops(&s1, &s2, &matrix).unwrap();
+
+        assert_eq!(levenshtein::apply_edit_ops(&s1, &s2, &ops), s2);
+    }
+}
+
+#[test]
+fn test_inverted_edits_restore_source() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 30);
+        let s2 = random_bytes(&mut rng, 30);
+
+        let (_, matrix) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits(&s1, &s2, &matrix).unwrap();
+
+        let undo = levenshtein::invert_edits(&s1, &edits);
+        assert_eq!(levenshtein::apply_edits(&s2, &undo), s1);
+    }
+}
+
+#[test]
+fn test_merge_takes_one_sided_changes() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let base = random_bytes(&mut rng, 30);
+        let side = random_bytes(&mut rng, 30);
+
+        assert_eq!(levenshtein::merge(&base, &side, &base), Ok(side.clone()));
+        assert_eq!(levenshtein::merge(&base, &base, &side), Ok(side.clone()));
+        assert_eq!(levenshtein::merge(&base, &side, &side), Ok(side.clone()));
+    }
+}
+
+#[test]
+fn test_multiple_alignment_keeps_sequences() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let count: usize = rng.gen_range(1..6);
+        let sequences: Vec<Vec<u8>> = (0..count).map(|_| random_bytes(&mut rng, 30)).collect();
+        let slices: Vec<&[u8]> = sequences.iter().map(|s| s.as_slice()).collect();
+
+        let rows = levenshtein::multiple_alignment(&slices);
+
+        assert_eq!(rows.len(), count);
+        for (row, sequence) in rows.iter().zip(&sequences) {
+            assert_eq!(row.len(), rows[0].len());
+            assert_eq!(
+                &row.iter().flatten().copied().collect::<Vec<u8>>(),
+                sequence
+            );
+        }
+    }
+}
+
+#[test]
+fn test_banded_edits_match_generate_edits() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 30);
+        let s2 = random_bytes(&mut rng, 30);
+
+        let (dist, mat) = levenshtein::distance(&s1, &s2);
+        let edits = levenshtein::generate_edits(&s1, &s2, &mat).unwrap();
+
+        for k in 0..32 {
+            match levenshtein::levenshtein_banded(&s1, &s2, k) {
+                Some((banded_dist, banded_mat)) => {
+                    assert_eq!(banded_dist, dist);
+                    assert_eq!(
+                        levenshtein::generate_edits(&s1, &s2, &banded_mat).unwrap(),
+                        edits
+                    );
+                }
+                None => assert!(dist > k),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_greedy_edits_are_optimal() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 30);
+        let s2 = random_bytes(&mut rng, 30);
+
+        let dist = levenshtein::distance_only(&s1, &s2);
+        assert_eq!(levenshtein::levenshtein_greedy(&s1, &s2), dist);
+
+        let edits = levenshtein::generate_edits_greedy(&s1, &s2);
+        assert_eq!(edits.len(), dist);
+        assert_eq!(levenshtein::apply_edits(&s1, &edits), s2);
+    }
+}
+
+#[test]
+fn test_stripped_edits_regenerate_target() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let s1 = random_bytes(&mut rng, 30);
+        let s2 = random_bytes(&mut rng, 30);
+
+        let (dist, mat) = levenshtein::distance(&s1, &s2);
+
+        let stripped = levenshtein::util::strip_common_affix(&s1, &s2);
+        let (stripped_dist, stripped_mat) = levenshtein::distance(stripped.source, stripped.target);
+        let edits = levenshtein::generate_edits_stripped(&stripped, &stripped_mat).unwrap();
+
+        // The edits are as few as with the full matrix, though ties may go another way
+        assert_eq!(stripped_dist, dist);
+        assert_eq!(
+            edits.len(),
+            levenshtein::generate_edits(&s1, &s2, &mat).unwrap().len()
+        );
+        assert_eq!(levenshtein::apply_edits(&s1, &edits), s2);
+    }
+}
+
+#[test]
+fn test_online_distance_matches_closest_stretch() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let pattern = random_bytes(&mut rng, 8);
+        let stream = random_bytes(&mut rng, 30);
+
+        let mut tracker = levenshtein::OnlineDistance::new(pattern.clone());
+
+        for end in 1..stream.len() + 1 {
+            let closest = (0..end + 1)
+                .map(|start| levenshtein::distance_only(&pattern, &stream[start..end]))
+                .min()
+                .unwrap();
+
+            assert_eq!(tracker.push(&stream[end - 1]), closest);
+        }
+    }
+}
+
+#[test]
+fn test_pattern_distance_on_long_sequences() {
+    let mut rng = thread_rng();
+
+    for _ in 0..20 {
+        let source = random_bytes(&mut rng, 2000);
+        let target = random_bytes(&mut rng, 2000);
+
+        let pattern = levenshtein::Pattern::new(&source);
+        let dist = levenshtein::distance_only(&source, &target);
+
+        assert_eq!(pattern.distance_to(&target), dist);
+        assert_eq!(pattern.distance_within(&target, dist), Some(dist));
+        assert_eq!(
+            pattern.distance_within(&target, 10),
+            Some(dist).filter(|&d| d <= 10)
+        );
+    }
+}
+
+#[test]
+fn test_interned_edits_are_optimal() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let source_len: usize = rng.gen_range(0..30);
+        let target_len: usize = rng.gen_range(0..30);
+
+        // Lines drawn from a small set, so many of them repeat
+        let source: Vec<String> = (0..source_len)
+            .map(|_| "line ".repeat(rng.gen_range(1..5)))
+            .collect();
+        let target: Vec<String> = (0..target_len)
+            .map(|_| "line ".repeat(rng.gen_range(1..5)))
+            .collect();
+
+        let dist = levenshtein::distance_only(&source, &target);
+        let edits = levenshtein::generate_edits_interned(&source, &target);
+
+        assert_eq!(levenshtein::distance_interned(&source, &target), dist);
+        assert_eq!(edits.len(), dist);
+        assert_eq!(levenshtein::apply_edits(&source, &edits), target);
+    }
+}
+
+#[test]
+fn test_hashed_edits_are_optimal() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let source = random_bytes(&mut rng, 40);
+        let target = random_bytes(&mut rng, 40);
+
+        let dist = levenshtein::distance_only(&source, &target);
+        let edits = levenshtein::generate_edits_hashed(&source, &target, true);
+
+        assert_eq!(levenshtein::distance_hashed(&source, &target), dist);
+        assert_eq!(edits.len(), dist);
+        assert_eq!(levenshtein::apply_edits(&source, &edits), target);
+    }
+}
+
+#[test]
+fn test_string_edits_are_optimal() {
+    let mut rng = thread_rng();
+    let alphabet = ['a', 'é', 'ß', '日', '🦀'];
+
+    for _ in 0..100 {
+        let source_len: usize = rng.gen_range(0..20);
+        let target_len: usize = rng.gen_range(0..20);
+
+        let source: String = (0..source_len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect();
+        let target: String = (0..target_len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect();
+
+        let source_chars: Vec<char> = source.chars().collect();
+        let target_chars: Vec<char> = target.chars().collect();
+        let dist = levenshtein::distance_only(&source_chars, &target_chars);
+
+        let edits = levenshtein::generate_edits_str(&source, &target);
+
+        assert_eq!(levenshtein::distance_str(&source, &target), dist);
+        assert_eq!(edits.len(), dist);
+        assert_eq!(levenshtein::apply_edits_str(&source, &edits), target);
+    }
+}
+
+#[test]
+fn test_caseless_edits_are_optimal() {
+    let mut rng = thread_rng();
+    let alphabet = ['a', 'A', 'ß', 'ẞ', 'σ', 'ς', 'Σ', 'é', 'É'];
+
+    for _ in 0..100 {
+        let source_len: usize = rng.gen_range(0..20);
+        let target_len: usize = rng.gen_range(0..20);
+
+        let source: String = (0..source_len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect();
+        let target: String = (0..target_len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect();
+
+        // Each character compared by its own folding, which can be several characters
+        let fold = |text: &str| -> Vec<String> {
+            text.chars()
+                .map(|c| levenshtein::fold_case(&c.to_string()))
+                .collect()
+        };
+        let source_folded = fold(&source);
+        let target_folded = fold(&target);
+
+        let dist = levenshtein::distance_only(&source_folded, &target_folded);
+        let edits = levenshtein::generate_edits_str_caseless(&source, &target);
+        let edited = levenshtein::apply_edits_str(&source, &edits);
+
+        assert_eq!(levenshtein::distance_str_caseless(&source, &target), dist);
+        assert_eq!(edits.len(), dist);
+        assert_eq!(
+            levenshtein::fold_case(&edited),
+            levenshtein::fold_case(&target)
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "collation")]
+fn test_collated_edits_are_optimal() {
+    use icu_collator::{Collator, CollatorOptions, Strength};
+    use std::cmp::Ordering;
+
+    // A name compared with the collator
+    #[derive(Clone)]
+    struct Collated<'a>(&'a str, &'a Collator);
+
+    impl PartialEq for Collated<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            self.1.compare(self.0, other.0) == Ordering::Equal
+        }
+    }
+
+    let mut rng = thread_rng();
+    let names = [
+        "Müller", "Muller", "MÜLLER", "Straße", "STRASSE", "Weiß", "Weiss", "Wei",
+    ];
+
+    let mut options = CollatorOptions::new();
+    options.strength = Some(Strength::Secondary);
+    let collator = Collator::try_new(&Default::default(), options).unwrap();
+
+    for _ in 0..100 {
+        let source_len: usize = rng.gen_range(0..10);
+        let target_len: usize = rng.gen_range(0..10);
+
+        let source: Vec<&str> = (0..source_len)
+            .map(|_| names[rng.gen_range(0..names.len())])
+            .collect();
+        let target: Vec<&str> = (0..target_len)
+            .map(|_| names[rng.gen_range(0..names.len())])
+            .collect();
+
+        let collated = |names: &[&'static str]| -> Vec<Collated> {
+            names.iter().map(|name| Collated(name, &collator)).collect()
+        };
+        let dist = levenshtein::distance_only(&collated(&source), &collated(&target));
+
+        let edits = levenshtein::generate_edits_collated(&source, &target, &collator);
+        let edited = levenshtein::apply_edits(&source, &edits);
+
+        assert_eq!(
+            levenshtein::distance_collated(&source, &target, &collator),
+            dist
+        );
+        assert_eq!(edits.len(), dist);
+        assert_eq!(
+            levenshtein::distance_collated(&edited, &target, &collator),
+            0
+        );
+    }
+}
+
+#[test]
+fn test_word_changes_rebuild_target() {
+    use levenshtein::{Tokenizer, WordTokenizer};
+
+    let mut rng = thread_rng();
+    let words = [
+        "the", "cat", "sat", "on", "mat", "日本", ",", ".", " ", "\n",
+    ];
+
+    for _ in 0..100 {
+        let source_len: usize = rng.gen_range(0..20);
+        let target_len: usize = rng.gen_range(0..20);
+
+        let source: String = (0..source_len)
+            .map(|_| words[rng.gen_range(0..words.len())])
+            .collect();
+        let target: String = (0..target_len)
+            .map(|_| words[rng.gen_range(0..words.len())])
+            .collect();
+
+        let tokens = |text: &String| -> Vec<String> {
+            WordTokenizer
+                .tokenize(text)
+                .into_iter()
+                .map(|range| text[range].to_string())
+                .collect()
+        };
+        let dist = levenshtein::distance_only(&tokens(&source), &tokens(&target));
+
+        let changes = levenshtein::diff_words(&source, &target, &WordTokenizer);
+
+        assert_eq!(
+            levenshtein::distance_words(&source, &target, &WordTokenizer),
+            dist
+        );
+        assert!(changes.len() <= dist);
+        assert!(changes
+            .windows(2)
+            .all(|pair| pair[0].source.end < pair[1].source.start));
+        assert_eq!(
+            levenshtein::apply_text_changes(&source, &target, &changes),
+            target
+        );
+    }
+}
+
+#[test]
+fn test_line_edits_are_optimal() {
+    use levenshtein::{LineEdit, LineEndings, LineOptions};
+
+    let mut rng = thread_rng();
+    let lines = ["a\n", "b\n", "a\r\n", "b\r\n", "c\n", "\n"];
+    let normalized_endings = LineOptions {
+        endings: LineEndings::Normalize,
+        ..Default::default()
+    };
+
+    for _ in 0..100 {
+        let source_len: usize = rng.gen_range(0..20);
+        let target_len: usize = rng.gen_range(0..20);
+
+        let source: String = (0..source_len)
+            .map(|_| lines[rng.gen_range(0..lines.len())])
+            .collect();
+        let target: String = (0..target_len)
+            .map(|_| lines[rng.gen_range(0..lines.len())])
+            .collect();
+
+        let source_lines: Vec<&str> = source.split_inclusive('\n').collect();
+        let target_lines: Vec<&str> = target.split_inclusive('\n').collect();
+        let dist = levenshtein::distance_only(&source_lines, &target_lines);
+
+        let edits = levenshtein::diff_lines(&source, &target, LineOptions::default());
+
+        assert_eq!(
+            levenshtein::distance_lines(&source, &target, LineOptions::default()),
+            dist
+        );
+        assert_eq!(edits.len(), dist);
+
+        // Every line carries its own text, number and offset
+        for edit in &edits {
+            if let LineEdit::Delete { source: line } | LineEdit::Substitute { source: line, .. } =
+                edit
+            {
+                assert_eq!(source_lines[line.number - 1], &source[line.bytes()]);
+            }
+            if let LineEdit::Insert { target: line, .. }
+            | LineEdit::Substitute { target: line, .. } = edit
+            {
+                assert_eq!(target_lines[line.number - 1], &target[line.bytes()]);
+            }
+        }
+
+        // With normalized terminators, the edits only rebuild the target up to them
+        let normalized: Vec<&str> = target.lines().collect();
+        let edits: Vec<levenshtein::Edit<&str>> =
+            levenshtein::diff_lines(&source, &target, normalized_endings)
+                .iter()
+                .map(LineEdit::to_edit)
+                .collect();
+        let source_texts: Vec<&str> = source.lines().collect();
+
+        assert_eq!(levenshtein::apply_edits(&source_texts, &edits), normalized);
+    }
+}
+
+#[test]
+fn test_refined_line_changes_rebuild_lines() {
+    use levenshtein::{CharTokenizer, LineEdit, LineOptions, WordTokenizer};
+
+    let mut rng = thread_rng();
+    let words = ["let", "x", "=", "1", ";", " ", "\n"];
+
+    for _ in 0..100 {
+        let source_len: usize = rng.gen_range(0..30);
+        let target_len: usize = rng.gen_range(0..30);
+
+        let source: String = (0..source_len)
+            .map(|_| words[rng.gen_range(0..words.len())])
+            .collect();
+        let target: String = (0..target_len)
+            .map(|_| words[rng.gen_range(0..words.len())])
+            .collect();
+
+        let edits = levenshtein::diff_lines(&source, &target, LineOptions::default());
+        let by_words = levenshtein::diff_lines_refined(
+            &source,
+            &target,
+            LineOptions::default(),
+            &WordTokenizer,
+        );
+        let by_chars = levenshtein::diff_lines_refined(
+            &source,
+            &target,
+            LineOptions::default(),
+            &CharTokenizer,
+        );
+
+        for refined in by_words.iter().chain(by_chars.iter()) {
+            if let LineEdit::Substitute { source, target } = refined.edit {
+                let changes = &refined.changes;
+                let rebuilt = levenshtein::apply_text_changes(source.text, target.text, changes);
+
+                assert_eq!(rebuilt, target.text);
+            } else {
+                assert!(refined.changes.is_empty());
+            }
+        }
+
+        let unrefined: Vec<LineEdit> = by_words.into_iter().map(|refined| refined.edit).collect();
+        assert_eq!(unrefined, edits);
+    }
+}
+
+#[test]
+fn test_whitespace_insensitive_line_edits_are_optimal() {
+    use levenshtein::{LineEdit, LineOptions, Whitespace};
+
+    let mut rng = thread_rng();
+    let lines = ["a b\n", "a  b\n", "a\tb \n", "ab\n", " a b\n", "\n", "  \n"];
+    let options = [
+        Whitespace::Exact,
+        Whitespace::IgnoreTrailing,
+        Whitespace::Collapse,
+        Whitespace::IgnoreAll,
+    ];
+
+    for _ in 0..100 {
+        let source_len: usize = rng.gen_range(0..20);
+        let target_len: usize = rng.gen_range(0..20);
+
+        let source: String = (0..source_len)
+            .map(|_| lines[rng.gen_range(0..lines.len())])
+            .collect();
+        let target: String = (0..target_len)
+            .map(|_| lines[rng.gen_range(0..lines.len())])
+            .collect();
+
+        for &whitespace in options.iter() {
+            let normalize = |text: &str| -> Vec<String> {
+                text.lines()
+                    .map(|line| whitespace.normalize(line).into_owned())
+                    .collect()
+            };
+            let dist = levenshtein::distance_only(&normalize(&source), &normalize(&target));
+
+            let options = LineOptions {
+                whitespace,
+                ..Default::default()
+            };
+            let edits: Vec<levenshtein::Edit<&str>> =
+                levenshtein::diff_lines(&source, &target, options)
+                    .iter()
+                    .map(LineEdit::to_edit)
+                    .collect();
+
+            assert_eq!(levenshtein::distance_lines(&source, &target, options), dist);
+            assert_eq!(edits.len(), dist);
+
+            // The result keeps the source lines that were equal, so it's only equal to the target
+            // up to whitespace
+            let source_lines: Vec<&str> = source.lines().collect();
+            let result: Vec<String> = levenshtein::apply_edits(&source_lines, &edits)
+                .iter()
+                .map(|line| whitespace.normalize(line).into_owned())
+                .collect();
+            assert_eq!(result, normalize(&target));
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn test_regex_normalized_edits_are_optimal() {
+    use levenshtein::RegexNormalizer;
+
+    let mut rng = thread_rng();
+    let mut normalizer = RegexNormalizer::new();
+    normalizer.replace(r"\d+", "N").unwrap();
+
+    let lines = ["id 1", "id 22", "id 333", "name", "id", "name 4"];
+
+    for _ in 0..100 {
+        let source_len: usize = rng.gen_range(0..20);
+        let target_len: usize = rng.gen_range(0..20);
+
+        let source: Vec<&str> = (0..source_len)
+            .map(|_| lines[rng.gen_range(0..lines.len())])
+            .collect();
+        let target: Vec<&str> = (0..target_len)
+            .map(|_| lines[rng.gen_range(0..lines.len())])
+            .collect();
+
+        let normalize = |lines: &[&str]| -> Vec<String> {
+            lines
+                .iter()
+                .map(|line| normalizer.normalize(line).into_owned())
+                .collect()
+        };
+        let dist = levenshtein::distance_only(&normalize(&source), &normalize(&target));
+
+        let edits = normalizer.generate_edits(&source, &target);
+        assert_eq!(normalizer.distance(&source, &target), dist);
+        assert_eq!(edits.len(), dist);
+
+        // The result keeps the source lines that were equal, so it's only equal to the target
+        // once it is normalized
+        let result = levenshtein::apply_edits(&source, &edits);
+        assert_eq!(normalize(&result), normalize(&target));
+
+        let text =
+            |lines: &[&str]| -> String { lines.iter().map(|line| format!("{}\n", line)).collect() };
+        let (source_text, target_text) = (text(&source), text(&target));
+        let options = levenshtein::LineOptions::default();
+        assert_eq!(
+            normalizer
+                .diff_lines(&source_text, &target_text, options)
+                .len(),
+            dist
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "unicode-segmentation")]
+fn test_grapheme_edits_are_optimal() {
+    let mut rng = thread_rng();
+
+    // A letter with a combining mark, a flag, an emoji with a skin tone, and plain letters
+    let alphabet = [
+        "e\u{301}",
+        "\u{1F1EB}\u{1F1F7}",
+        "\u{1F44D}\u{1F3FD}",
+        "a",
+        "e",
+    ];
+
+    for _ in 0..100 {
+        let source_len: usize = rng.gen_range(0..20);
+        let target_len: usize = rng.gen_range(0..20);
+
+        let source: Vec<&str> = (0..source_len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect();
+        let target: Vec<&str> = (0..target_len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect();
+
+        let dist = levenshtein::distance_only(&source, &target);
+        let (source, target) = (source.concat(), target.concat());
+
+        let edits = levenshtein::generate_edits_graphemes(&source, &target);
+
+        assert_eq!(levenshtein::distance_graphemes(&source, &target), dist);
+        assert_eq!(edits.len(), dist);
+        assert_eq!(levenshtein::apply_edits_graphemes(&source, &edits), target);
+    }
+}
+
+#[test]
+fn test_window_matcher_matches_last_items() {
+    let mut rng = thread_rng();
+
+    for _ in 0..100 {
+        let size: usize = rng.gen_range(0..12);
+
+        // Patterns longer than a machine word use several blocks of the bit-parallel algorithm
+        let pattern = random_bytes(&mut rng, 80);
+        let stream: Vec<u8> = (0..30).map(|_| rng.gen_range(b'a'..b'e')).collect();
+
+        let mut matcher = levenshtein::WindowMatcher::new(&pattern, size);
+
+        for end in 1..stream.len() + 1 {
+            let window = &stream[end.saturating_sub(size)..end];
+
+            assert_eq!(
+                matcher.push(stream[end - 1]),
+                levenshtein::distance_only(&pattern, window)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_trigram_index_finds_every_match() {
+    let mut rng = thread_rng();
+
+    let words: Vec<Vec<u8>> = (0..200).map(|_| random_bytes(&mut rng, 12)).collect();
+    let index = levenshtein::TrigramIndex::new(words.clone());
+
+    for _ in 0..100 {
+        let query = random_bytes(&mut rng, 12);
+        let k: usize = rng.gen_range(0..4);
+
+        let mut expected: Vec<(usize, usize)> = words
+            .iter()
+            .enumerate()
+            .map(|(id, word)| (id, levenshtein::distance_only(&query, word)))
+            .filter(|&(_, distance)| distance <= k)
+            .collect();
+        expected.sort_by_key(|&(id, distance)| (distance, id));
+
+        assert_eq!(index.search(&query, k), expected);
+    }
+}
+
+#[test]
+fn test_bk_tree_finds_every_match() {
+    let mut rng = thread_rng();
+
+    let mut words: Vec<Vec<u8>> = (0..200).map(|_| random_bytes(&mut rng, 12)).collect();
+    let tree = levenshtein::BkTree::new(words.clone());
+
+    words.sort();
+    words.dedup();
+    assert_eq!(tree.len(), words.len());
+
+    for _ in 0..100 {
+        let query = random_bytes(&mut rng, 12);
+        let k: usize = rng.gen_range(0..4);
+
+        let mut expected: Vec<(&[u8], usize)> = words
+            .iter()
+            .map(|word| (word.as_slice(), levenshtein::distance_only(&query, word)))
+            .filter(|&(_, distance)| distance <= k)
+            .collect();
+        expected.sort_by_key(|&(word, distance)| (distance, word));
+
+        let mut found = tree.search(&query, k);
+        found.sort_by_key(|&(word, distance)| (distance, word));
+
+        assert_eq!(found, expected);
+    }
+}
+
+#[test]
+fn test_symspell_index_finds_every_match() {
+    let mut rng = thread_rng();
+
+    let words: Vec<Vec<u8>> = (0..200).map(|_| random_bytes(&mut rng, 12)).collect();
+    let index = levenshtein::SymSpellIndex::new(words.clone(), 3);
+
+    for _ in 0..100 {
+        let query = random_bytes(&mut rng, 12);
+        let k: usize = rng.gen_range(0..4);
+
+        let mut expected: Vec<(usize, usize)> = words
+            .iter()
+            .enumerate()
+            .map(|(id, word)| (id, levenshtein::distance_only(&query, word)))
+            .filter(|&(_, distance)| distance <= k)
+            .collect();
+        expected.sort_by_key(|&(id, distance)| (distance, id));
+
+        assert_eq!(index.search(&query, k), expected);
+    }
+}
+
+#[test]
+fn test_near_duplicates_are_within_distance() {
+    let mut rng = thread_rng();
+
+    let mut documents: Vec<Vec<u8>> = (0..100).map(|_| random_bytes(&mut rng, 40)).collect();
+
+    // Exact copies always share every band
+    documents.push(documents[0].clone());
+
+    let items: Vec<&[u8]> = documents
+        .iter()
+        .map(|document| document.as_slice())
+        .collect();
+    let k: usize = rng.gen_range(0..4);
+    let pairs = levenshtein::near_duplicates(&items, 3, 16, 2, k);
+
+    assert!(pairs.contains(&(0, 100, 0)));
+
+    for (i, j, distance) in pairs {
+        assert!(i < j && distance <= k);
+        assert_eq!(levenshtein::distance_only(items[i], items[j]), distance);
+    }
+}
+
+#[test]
+fn test_trie_fuzzy_search_finds_every_match() {
+    let mut rng = thread_rng();
+
+    let mut words: Vec<Vec<u8>> = (0..200).map(|_| random_bytes(&mut rng, 12)).collect();
+    let trie = levenshtein::Trie::new(words.clone());
+
+    words.sort();
+    words.dedup();
+    assert_eq!(trie.len(), words.len());
+
+    for _ in 0..100 {
+        let query = random_bytes(&mut rng, 12);
+        let k: usize = rng.gen_range(0..4);
+
+        let mut expected: Vec<(Vec<u8>, usize)> = words
+            .iter()
+            .map(|word| (word.clone(), levenshtein::distance_only(&query, word)))
+            .filter(|&(_, distance)| distance <= k)
+            .collect();
+        expected.sort_by_key(|&(_, distance)| distance);
+
+        assert_eq!(trie.fuzzy_search(&query, k), expected);
+    }
+}